@@ -0,0 +1,35 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sweeper_rust::categories::FileCategory;
+use sweeper_rust::config::Config;
+use sweeper_rust::fixture::{self, FixtureSpec};
+use sweeper_rust::scanner::Scanner;
+
+fn scan(c: &mut Criterion) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fixture::generate(dir.path(), &FixtureSpec::bench(7)).expect("fixture");
+    let root = dir.path().canonicalize().expect("canonical root");
+
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(20);
+    group.bench_function("scoped", |b| {
+        b.iter(|| {
+            let scanner = Scanner::with_scope(Config::default(), vec![root.clone()]);
+            scanner.scan().expect("scan")
+        })
+    });
+    group.bench_function("scoped_with_duplicates", |b| {
+        let mut config = Config::default();
+        config.enabled_categories.insert(FileCategory::Duplicate);
+        b.iter(|| {
+            let scanner = Scanner::with_scope(config.clone(), vec![root.clone()]);
+            scanner.scan().expect("scan")
+        })
+    });
+    group.bench_function("dir_size", |b| {
+        b.iter(|| Scanner::dir_size_parallel(&root).expect("dir size"))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scan);
+criterion_main!(benches);
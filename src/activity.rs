@@ -0,0 +1,399 @@
+use crate::audit;
+use crate::cleaner::{CleanJob, CleanReport, CleanTarget};
+use crate::config::{Config, DeleteMode};
+use crate::error::{self, SweeperError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub const TARGETED_RESTORE: bool = true;
+#[cfg(not(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub const TARGETED_RESTORE: bool = false;
+
+/// Whether the most recent cleanup can be undone. On macOS this relies on
+/// the items still being in ~/.Trash under their own names.
+pub const UNDO_RESTORE: bool = TARGETED_RESTORE || cfg!(target_os = "macos");
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+const MATCH_WINDOW_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrashStatus {
+    #[default]
+    InTrash,
+    Restored,
+    Unrecoverable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashedEntry {
+    pub batch: u64,
+    pub path: PathBuf,
+    pub size: u64,
+    pub trashed_at: DateTime<Utc>,
+    #[serde(default)]
+    pub status: TrashStatus,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityLog {
+    pub entries: Vec<TrashedEntry>,
+    pub mode_changes: Vec<ModeChange>,
+    pub scheduled_runs: Vec<ScheduledRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModeChange {
+    pub mode: DeleteMode,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledRun {
+    pub schedule: String,
+    pub at: DateTime<Utc>,
+    pub items_found: usize,
+    pub found_bytes: u64,
+    pub items_cleaned: usize,
+    pub freed_bytes: u64,
+    #[serde(default)]
+    pub refused: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreError {
+    ParentMissing,
+    Unrecoverable,
+    Failed(String),
+}
+
+impl std::fmt::Display for RestoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ParentMissing => write!(f, "the original folder no longer exists"),
+            Self::Unrecoverable => write!(f, "it is no longer in the trash"),
+            Self::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl ActivityLog {
+    pub fn path() -> PathBuf {
+        Config::config_dir().join("activity.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn record_trashed(&mut self, targets: &[CleanTarget], at: DateTime<Utc>) -> Option<u64> {
+        if targets.is_empty() {
+            return None;
+        }
+        let batch = self
+            .entries
+            .iter()
+            .map(|entry| entry.batch)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        self.entries
+            .extend(targets.iter().map(|target| TrashedEntry {
+                batch,
+                path: target.path.clone(),
+                size: target.size,
+                trashed_at: at,
+                status: TrashStatus::InTrash,
+            }));
+        Some(batch)
+    }
+
+    pub fn record_mode(&mut self, mode: DeleteMode, at: DateTime<Utc>) -> bool {
+        if self.mode_changes.last().map(|change| change.mode) == Some(mode) {
+            return false;
+        }
+        self.mode_changes.push(ModeChange { mode, at });
+        true
+    }
+
+    pub fn record_scheduled(&mut self, run: ScheduledRun) {
+        self.scheduled_runs.push(run);
+    }
+
+    pub fn last_scheduled_runs(&self) -> HashMap<String, DateTime<Utc>> {
+        let mut last_runs: HashMap<String, DateTime<Utc>> = HashMap::new();
+        for run in &self.scheduled_runs {
+            let last = last_runs.entry(run.schedule.clone()).or_insert(run.at);
+            *last = (*last).max(run.at);
+        }
+        last_runs
+    }
+
+    pub fn set_status(&mut self, batch: u64, path: &Path, status: TrashStatus) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.batch == batch && entry.path == path)
+        {
+            entry.status = status;
+        }
+    }
+
+    pub fn batch(&self, batch: u64) -> Vec<TrashedEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.batch == batch)
+            .cloned()
+            .collect()
+    }
+
+    pub fn batches(&self) -> Vec<(u64, Vec<&TrashedEntry>)> {
+        let mut batches: Vec<(u64, Vec<&TrashedEntry>)> = Vec::new();
+        for entry in &self.entries {
+            match batches.iter_mut().find(|(batch, _)| *batch == entry.batch) {
+                Some((_, entries)) => entries.push(entry),
+                None => batches.push((entry.batch, vec![entry])),
+            }
+        }
+        batches.sort_by_key(|(batch, _)| std::cmp::Reverse(*batch));
+        batches
+    }
+
+    pub fn mark_unrecoverable(&mut self) -> usize {
+        let mut marked = 0;
+        for entry in self
+            .entries
+            .iter_mut()
+            .filter(|entry| entry.status == TrashStatus::InTrash)
+        {
+            if still_in_trash(entry) == Some(false) {
+                entry.status = TrashStatus::Unrecoverable;
+                marked += 1;
+            }
+        }
+        marked
+    }
+}
+
+pub fn run_logged(job: CleanJob) -> CleanReport {
+    let mut report = job.run();
+    if !report.trashed.is_empty() {
+        let mut log = ActivityLog::load();
+        report.batch = log.record_trashed(&report.trashed, Utc::now());
+        if let Err(e) = log.save() {
+            log::warn!("Failed to save activity log: {}", e);
+        }
+    }
+    report
+}
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn find_in_trash(entry: &TrashedEntry) -> Result<Option<trash::TrashItem>, String> {
+    let trashed_at = entry.trashed_at.timestamp();
+    Ok(trash::os_limited::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| item.original_path() == entry.path)
+        .filter(|item| (item.time_deleted - trashed_at).abs() <= MATCH_WINDOW_SECS)
+        .min_by_key(|item| (item.time_deleted - trashed_at).abs()))
+}
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn still_in_trash(entry: &TrashedEntry) -> Option<bool> {
+    find_in_trash(entry).ok().map(|item| item.is_some())
+}
+
+#[cfg(target_os = "macos")]
+fn still_in_trash(entry: &TrashedEntry) -> Option<bool> {
+    let name = entry.path.file_name()?.to_string_lossy().to_string();
+    let trash = trash_dir().ok()?;
+    let entries = std::fs::read_dir(trash).ok()?;
+    Some(
+        entries
+            .flatten()
+            .any(|item| item.file_name().to_string_lossy().starts_with(&name)),
+    )
+}
+
+#[cfg(not(any(
+    windows,
+    target_os = "macos",
+    all(unix, not(target_os = "ios"), not(target_os = "android"))
+)))]
+fn still_in_trash(_entry: &TrashedEntry) -> Option<bool> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+pub fn trash_dir() -> error::Result<PathBuf> {
+    dirs::home_dir()
+        .map(|home| home.join(".Trash"))
+        .ok_or(SweeperError::Unsupported(
+            "finding the Trash without a home folder",
+        ))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn trash_dir() -> error::Result<PathBuf> {
+    Err(SweeperError::Unsupported("opening the Trash folder"))
+}
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore(entry: &TrashedEntry, destination: Option<&Path>) -> Result<PathBuf, RestoreError> {
+    audit::ensure_writable().map_err(|e| RestoreError::Failed(e.to_string()))?;
+    let parent_exists = entry.path.parent().is_none_or(|parent| parent.is_dir());
+    if !parent_exists && destination.is_none() {
+        return Err(RestoreError::ParentMissing);
+    }
+    let item = find_in_trash(entry)
+        .map_err(RestoreError::Failed)?
+        .ok_or(RestoreError::Unrecoverable)?;
+
+    let created = missing_ancestors(&entry.path);
+    trash::os_limited::restore_all([item]).map_err(|e| RestoreError::Failed(e.to_string()))?;
+    let Some(destination) = destination.filter(|_| !parent_exists) else {
+        return Ok(entry.path.clone());
+    };
+
+    let target = destination.join(entry.path.file_name().unwrap_or_default());
+    std::fs::rename(&entry.path, &target).map_err(|e| {
+        RestoreError::Failed(format!(
+            "restored to {} but could not move it to {}: {}",
+            entry.path.display(),
+            target.display(),
+            e
+        ))
+    })?;
+    for dir in created.iter().rev() {
+        if std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+    Ok(target)
+}
+
+#[cfg(target_os = "macos")]
+pub fn restore(entry: &TrashedEntry, destination: Option<&Path>) -> Result<PathBuf, RestoreError> {
+    audit::ensure_writable().map_err(|e| RestoreError::Failed(e.to_string()))?;
+    let parent_exists = entry.path.parent().is_none_or(|parent| parent.is_dir());
+    if !parent_exists && destination.is_none() {
+        return Err(RestoreError::ParentMissing);
+    }
+    let name = entry.path.file_name().ok_or(RestoreError::Unrecoverable)?;
+    let trashed = trash_dir()
+        .map_err(|e| RestoreError::Failed(e.to_string()))?
+        .join(name);
+    if std::fs::symlink_metadata(&trashed).is_err() {
+        return Err(RestoreError::Unrecoverable);
+    }
+
+    let target = match destination.filter(|_| !parent_exists) {
+        Some(destination) => destination.join(name),
+        None => entry.path.clone(),
+    };
+    if std::fs::symlink_metadata(&target).is_ok() {
+        return Err(RestoreError::Failed(format!(
+            "{} already exists",
+            target.display()
+        )));
+    }
+    std::fs::rename(&trashed, &target).map_err(|e| RestoreError::Failed(e.to_string()))?;
+    Ok(target)
+}
+
+#[cfg(not(any(
+    windows,
+    target_os = "macos",
+    all(unix, not(target_os = "ios"), not(target_os = "android"))
+)))]
+pub fn restore(
+    _entry: &TrashedEntry,
+    _destination: Option<&Path>,
+) -> Result<PathBuf, RestoreError> {
+    Err(RestoreError::Failed(
+        SweeperError::Unsupported("restoring single items from the trash").to_string(),
+    ))
+}
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn missing_ancestors(path: &Path) -> Vec<PathBuf> {
+    let mut missing: Vec<PathBuf> = path
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| !dir.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    missing.reverse();
+    missing
+}
@@ -0,0 +1,23 @@
+use crate::error::{self, SweeperError};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Switches the process into read-only audit mode. There is deliberately no
+/// way to switch it back off.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Gate for every code path that deletes, moves or restores user files.
+pub fn ensure_writable() -> error::Result<()> {
+    if is_enabled() {
+        Err(SweeperError::AuditMode)
+    } else {
+        Ok(())
+    }
+}
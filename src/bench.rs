@@ -0,0 +1,61 @@
+use crate::config::Config;
+use crate::scanner::Scanner;
+use bytesize::ByteSize;
+use std::path::Path;
+use std::time::Instant;
+
+pub fn run(dir: &Path) -> i32 {
+    if !dir.is_dir() {
+        eprintln!("bench directory is not a directory: {}", dir.display());
+        return crate::ci::EXIT_ERROR;
+    }
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+
+    let scanner = Scanner::with_scope(Config::default(), vec![dir.clone()]);
+    let started = Instant::now();
+    if let Err(e) = scanner.scan() {
+        eprintln!("scan failed: {}", e);
+        return crate::ci::EXIT_ERROR;
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+    let store = scanner.snapshot();
+
+    let files = scanner.files_scanned();
+    let items = store.len();
+    println!("Scanned {} in {:.3}s", dir.display(), elapsed);
+    println!(
+        "  {} files ({})",
+        files,
+        per_second(files as f64, elapsed, "files")
+    );
+    println!(
+        "  {} items, {} ({})",
+        items,
+        ByteSize(store.stats().total_size),
+        per_second(items as f64, elapsed, "items")
+    );
+
+    println!("Passes (run in parallel):");
+    let mut timings = store.pass_timings().to_vec();
+    timings.sort_by_key(|(_, duration_ms)| std::cmp::Reverse(*duration_ms));
+    let width = timings
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, duration_ms) in timings {
+        println!("  {:<width$}  {:>8} ms", name, duration_ms, width = width);
+    }
+
+    for warning in store.warnings() {
+        println!("warning: {}", warning);
+    }
+    crate::ci::EXIT_OK
+}
+
+fn per_second(count: f64, seconds: f64, unit: &str) -> String {
+    if seconds <= 0.0 {
+        return format!("- {}/sec", unit);
+    }
+    format!("{:.0} {}/sec", count / seconds, unit)
+}
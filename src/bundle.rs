@@ -0,0 +1,141 @@
+use crate::scanner::persist::{self, PersistError};
+use crate::scanner::{ItemStore, ScanStats, ScannedItem};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("could not read bundle: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("bundle is not valid JSON: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("{0}")]
+    Items(#[from] PersistError),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostInfo {
+    pub hostname: String,
+    pub os: String,
+    pub total_disk: u64,
+    pub free_disk: u64,
+}
+
+impl HostInfo {
+    pub fn current() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        let disk = disks
+            .list()
+            .iter()
+            .filter(|disk| home.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len());
+
+        Self {
+            hostname: sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string()),
+            os: sysinfo::System::long_os_version()
+                .unwrap_or_else(|| std::env::consts::OS.to_string()),
+            total_disk: disk.map(|disk| disk.total_space()).unwrap_or(0),
+            free_disk: disk.map(|disk| disk.available_space()).unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleHeader {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub host: HostInfo,
+    pub stats: ScanStats,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanBundle {
+    pub header: BundleHeader,
+    pub items: Vec<ScannedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawBundle {
+    #[serde(default)]
+    version: u32,
+    #[serde(default = "Utc::now")]
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    host: HostInfo,
+    #[serde(default)]
+    stats: ScanStats,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedBundle {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub host: HostInfo,
+    pub store: ItemStore,
+    pub skipped: usize,
+}
+
+impl LoadedBundle {
+    pub fn is_newer(&self) -> bool {
+        self.version > BUNDLE_VERSION
+    }
+}
+
+impl ScanBundle {
+    pub fn capture(store: &ItemStore) -> Self {
+        Self {
+            header: BundleHeader {
+                version: BUNDLE_VERSION,
+                created_at: Utc::now(),
+                host: HostInfo::current(),
+                stats: store.stats().clone(),
+            },
+            items: store.items().to_vec(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BundleError> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        persist::write_items(&mut file, &self.header, &self.items)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}.sweeper.json",
+            self.header.host.hostname,
+            self.header.created_at.format("%Y%m%d-%H%M")
+        )
+    }
+
+    pub fn load(path: &Path) -> Result<LoadedBundle, BundleError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_json(&contents)
+    }
+
+    pub fn from_json(contents: &str) -> Result<LoadedBundle, BundleError> {
+        let raw: RawBundle = serde_json::from_str(contents)?;
+        let loaded = persist::read_items(contents.as_bytes())?;
+
+        let mut store = ItemStore::default();
+        for item in loaded.items {
+            store.push(item);
+        }
+        store.set_duration_ms(raw.stats.duration_ms);
+
+        Ok(LoadedBundle {
+            version: raw.version,
+            created_at: raw.created_at,
+            host: raw.host,
+            store,
+            skipped: loaded.skipped,
+        })
+    }
+}
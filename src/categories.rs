@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FileCategory {
@@ -13,9 +15,27 @@ pub enum FileCategory {
     OldDownload,
     Duplicate,
     Unused,
+    AppContainer,
+    GlobalPackage,
 }
 
 impl FileCategory {
+    pub const ALL: [FileCategory; 13] = [
+        Self::DevArtifact,
+        Self::PackageCache,
+        Self::IdeCache,
+        Self::BrowserCache,
+        Self::SystemCache,
+        Self::LogFile,
+        Self::TempFile,
+        Self::LargeFile,
+        Self::OldDownload,
+        Self::Duplicate,
+        Self::Unused,
+        Self::AppContainer,
+        Self::GlobalPackage,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             Self::DevArtifact => "Dev Artifacts",
@@ -29,6 +49,26 @@ impl FileCategory {
             Self::OldDownload => "Old Downloads",
             Self::Duplicate => "Duplicates",
             Self::Unused => "Unused Files",
+            Self::AppContainer => "App Containers",
+            Self::GlobalPackage => "Global Packages",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::DevArtifact => "This is build output or installed dependencies for a project, not your source code.",
+            Self::PackageCache => "This is a package manager's download cache; packages are fetched again when a project needs them.",
+            Self::IdeCache => "This is an editor's cache of indexes and compiled extensions; the editor recreates it when you open it.",
+            Self::BrowserCache => "This is a web browser's cache of pages and images; the browser downloads them again as you browse.",
+            Self::SystemCache => "This is a cache kept by the system or an app to speed things up; it is recreated when needed.",
+            Self::LogFile => "This is a log file that records what a program did; programs don't need old logs to run.",
+            Self::TempFile => "This is a temporary file that a program left behind.",
+            Self::LargeFile => "This is a large file that Sweeper can't identify; it may be something you want to keep.",
+            Self::OldDownload => "This is a file in your Downloads folder that you haven't opened in a while.",
+            Self::Duplicate => "This file has the same contents as another file that stays on disk.",
+            Self::Unused => "This file hasn't been opened in a long time.",
+            Self::AppContainer => "This folder holds an app's private data, which can include documents and settings.",
+            Self::GlobalPackage => "This is a command-line tool installed globally with npm or yarn; removing it uninstalls the command.",
         }
     }
 
@@ -45,13 +85,837 @@ impl FileCategory {
             Self::LargeFile => 0.70,
             Self::Duplicate => 0.70,
             Self::Unused => 0.70,
+            Self::AppContainer => 0.40,
+            Self::GlobalPackage => 0.60,
+        }
+    }
+
+    pub fn age_curve(&self) -> Option<AgeCurve> {
+        match self {
+            Self::DevArtifact => Some(AgeCurve {
+                knee_days: 30,
+                midpoint_days: 90,
+                full_days: 365,
+                max_bonus: 0.15,
+                cap: 0.98,
+            }),
+            Self::OldDownload => Some(AgeCurve {
+                knee_days: 30,
+                midpoint_days: 90,
+                full_days: 365,
+                max_bonus: 0.15,
+                cap: 0.95,
+            }),
+            Self::LogFile => Some(AgeCurve {
+                knee_days: 7,
+                midpoint_days: 45,
+                full_days: 180,
+                max_bonus: 0.10,
+                cap: 0.95,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AgeCurve {
+    pub knee_days: u64,
+    pub midpoint_days: u64,
+    pub full_days: u64,
+    pub max_bonus: f32,
+    pub cap: f32,
+}
+
+impl AgeCurve {
+    const STEEPNESS: f64 = 10.0;
+
+    pub fn bonus(&self, age_days: u64) -> f32 {
+        if age_days <= self.knee_days {
+            return 0.0;
+        }
+        if age_days >= self.full_days {
+            return self.max_bonus;
+        }
+
+        let span = (self.full_days - self.knee_days) as f64;
+        let midpoint = self.midpoint_days as f64;
+        let logistic =
+            |days: f64| 1.0 / (1.0 + (-Self::STEEPNESS * (days - midpoint) / span).exp());
+        let low = logistic(self.knee_days as f64);
+        let high = logistic(self.full_days as f64);
+        let weight = (logistic(age_days as f64) - low) / (high - low);
+        self.max_bonus * weight.clamp(0.0, 1.0) as f32
+    }
+
+    pub fn apply(&self, base: f32, age_days: u64) -> f32 {
+        (base + self.bonus(age_days)).min(self.cap.max(base))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RulesetChange {
+    pub version: u32,
+    pub added: &'static [&'static str],
+    pub changed: &'static [&'static str],
+}
+
+pub const RULESET_CHANGES: &[RulesetChange] = &[
+    RulesetChange {
+        version: 1,
+        added: &["App containers", "CACHEDIR.TAG caches"],
+        changed: &[
+            "dev artifacts, logs and old downloads gain confidence as they age",
+            "folders with a .sweeper-keep file are skipped",
+            "project folders are scanned deeper than other folders",
+        ],
+    },
+    RulesetChange {
+        version: 2,
+        added: &[
+            "Global npm and yarn packages",
+            "Large files",
+            "Leftover tool temp folders",
+        ],
+        changed: &["per-tool temp folders in /tmp and %TEMP% are grouped into one item per tool"],
+    },
+    RulesetChange {
+        version: 3,
+        added: &[
+            "Windows.old",
+            "Delivery Optimization cache",
+            "Windows error reports and crash dumps",
+            "INetCache and Explorer thumbnail caches",
+        ],
+        changed: &["locations that need administrator rights are shown but can't be selected"],
+    },
+];
+
+pub const RULESET_VERSION: u32 = 3;
+
+pub fn ruleset_changes_since(seen: u32) -> Vec<&'static RulesetChange> {
+    RULESET_CHANGES
+        .iter()
+        .filter(|change| change.version > seen && change.version <= RULESET_VERSION)
+        .collect()
+}
+
+pub fn summarize_ruleset_changes(changes: &[&RulesetChange]) -> Option<String> {
+    let join = |pick: fn(&RulesetChange) -> &'static [&'static str]| {
+        changes
+            .iter()
+            .flat_map(|change| pick(change).iter().copied())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let parts: Vec<String> = [
+        ("New", join(|change| change.added)),
+        ("Changed", join(|change| change.changed)),
+    ]
+    .into_iter()
+    .filter(|(_, list)| !list.is_empty())
+    .map(|(label, list)| format!("{}: {}", label, list))
+    .collect();
+    (!parts.is_empty()).then(|| parts.join("; "))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManagedStore {
+    pub tool: &'static str,
+    pub command: &'static str,
+    root: &'static str,
+}
+
+impl ManagedStore {
+    const NIX: Self = Self {
+        tool: "Nix",
+        command: "nix-collect-garbage -d",
+        root: "/nix",
+    };
+    const GUIX: Self = Self {
+        tool: "Guix",
+        command: "guix gc",
+        root: "/gnu/store",
+    };
+
+    fn installed(&self) -> bool {
+        static INSTALLED: std::sync::OnceLock<(bool, bool)> = std::sync::OnceLock::new();
+        let (nix, guix) = *INSTALLED.get_or_init(|| {
+            (
+                Path::new(Self::NIX.root).is_dir(),
+                Path::new(Self::GUIX.root).is_dir(),
+            )
+        });
+        if *self == Self::NIX {
+            nix
+        } else {
+            guix
         }
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct KnownCache {
+    pub marker: &'static str,
+    pub regenerates_quickly: bool,
+    pub owner: Option<&'static str>,
+    pub caution: Option<&'static str>,
+    pub elevation: Option<&'static str>,
+}
+
+impl KnownCache {
+    pub fn matches(&self, path: &Path) -> bool {
+        path.ends_with(self.marker)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rebuilder {
+    pub marker: &'static str,
+    pub tool: &'static str,
+    pub command: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TempFamily {
+    pub pattern: &'static str,
+    pub label: &'static str,
+    prefixes: &'static [&'static str],
+}
+
+impl TempFamily {
+    pub fn matches(&self, name: &str) -> bool {
+        self.prefixes
+            .iter()
+            .any(|prefix| name.len() > prefix.len() && name.starts_with(prefix))
+    }
+}
+
+/// Leftovers of interrupted package downloads inside a tool's cache. Files
+/// up to `depth` levels below `root` ending in one of `suffixes` are
+/// partials, and so is everything inside a directory named in `dirs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialDownload {
+    pub tool: &'static str,
+    pub pattern: &'static str,
+    pub root: PathBuf,
+    pub depth: usize,
+    suffixes: &'static [&'static str],
+    dirs: &'static [&'static str],
+}
+
+impl PartialDownload {
+    pub fn matches_file(&self, name: &str) -> bool {
+        self.suffixes
+            .iter()
+            .chain(PARTIAL_DOWNLOAD_SUFFIXES)
+            .any(|suffix| name.len() > suffix.len() && name.ends_with(suffix))
+    }
+
+    pub fn holds_partials(&self, dir_name: &str) -> bool {
+        self.dirs.contains(&dir_name)
+    }
+}
+
+/// Suffixes browsers and download helpers give unfinished files, checked in
+/// every partial download root.
+const PARTIAL_DOWNLOAD_SUFFIXES: &[&str] = &[".crdownload", ".part", ".partial"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalPackageRoot {
+    pub modules: PathBuf,
+    pub bin: PathBuf,
+    pub node_version: Option<String>,
+}
+
+impl GlobalPackageRoot {
+    pub fn node_installed(&self) -> bool {
+        self.node_version.is_none()
+            || self.bin.join("node").exists()
+            || self.bin.join("node.exe").exists()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VersionedCache {
+    pub id: &'static str,
+    pub root: String,
+    pub category: FileCategory,
+    pub default_keep: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FolderVersion {
+    pub product: String,
+    pub parts: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunableVersion {
+    pub name: String,
+    pub kept: Vec<String>,
+}
+
+pub fn parse_folder_version(name: &str) -> Option<FolderVersion> {
+    let start = name.find(|c: char| c.is_ascii_digit())?;
+    let product = name[..start]
+        .trim_end_matches(['-', '_', '.', ' '])
+        .to_lowercase();
+    let end = name[start..]
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '_')))
+        .map(|offset| start + offset)
+        .unwrap_or(name.len());
+
+    let parts: Vec<u64> = name[start..end]
+        .split(['.', '-', '_'])
+        .filter(|part| !part.is_empty())
+        .map_while(|part| part.parse().ok())
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+
+    Some(FolderVersion { product, parts })
+}
+
+pub fn prunable_versions(names: &[String], keep_newest: usize) -> Vec<PrunableVersion> {
+    let mut by_product: HashMap<String, Vec<(Vec<u64>, &String)>> = HashMap::new();
+    for name in names {
+        if let Some(version) = parse_folder_version(name) {
+            by_product
+                .entry(version.product)
+                .or_default()
+                .push((version.parts, name));
+        }
+    }
+
+    let mut prunable = Vec::new();
+    for mut versions in by_product.into_values() {
+        versions.sort_by(|a, b| b.cmp(a));
+        let keep = keep_newest.min(versions.len());
+        let kept: Vec<String> = versions[..keep]
+            .iter()
+            .map(|(_, name)| (*name).clone())
+            .collect();
+        prunable.extend(versions[keep..].iter().map(|(_, name)| PrunableVersion {
+            name: (*name).clone(),
+            kept: kept.clone(),
+        }));
+    }
+    prunable.sort_by(|a, b| a.name.cmp(&b.name));
+    prunable
+}
+
 pub struct CategoryPatterns;
 
 impl CategoryPatterns {
+    pub fn known_caches() -> &'static [KnownCache] {
+        &[
+            KnownCache {
+                marker: "Code/CachedData",
+                regenerates_quickly: true,
+                owner: Some("VS Code"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Code/CachedExtensions",
+                regenerates_quickly: false,
+                owner: Some("VS Code"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "com.microsoft.VSCode",
+                regenerates_quickly: true,
+                owner: Some("VS Code"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "GPUCache",
+                regenerates_quickly: true,
+                owner: None,
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "ShaderCache",
+                regenerates_quickly: true,
+                owner: None,
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "GrShaderCache",
+                regenerates_quickly: true,
+                owner: None,
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Code Cache",
+                regenerates_quickly: true,
+                owner: None,
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "fontconfig",
+                regenerates_quickly: true,
+                owner: Some("Fontconfig"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "mesa_shader_cache",
+                regenerates_quickly: true,
+                owner: Some("Mesa"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "thumbnails",
+                regenerates_quickly: true,
+                owner: None,
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "com.apple.QuickLook.thumbnailcache",
+                regenerates_quickly: true,
+                owner: Some("Quick Look"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Homebrew",
+                regenerates_quickly: false,
+                owner: Some("Homebrew"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "pip",
+                regenerates_quickly: false,
+                owner: Some("pip"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "go-build",
+                regenerates_quickly: false,
+                owner: Some("Go"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "JetBrains",
+                regenerates_quickly: false,
+                owner: Some("JetBrains IDEs"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Windows.old",
+                regenerates_quickly: false,
+                owner: Some("Windows"),
+                caution: Some("It holds the previous Windows installation, needed to roll back an upgrade"),
+                elevation: Some("Remove it with Disk Cleanup: run cleanmgr as administrator, choose Clean up system files, then tick Previous Windows installation(s)"),
+            },
+            KnownCache {
+                marker: "DeliveryOptimization/Cache",
+                regenerates_quickly: false,
+                owner: Some("Windows Update"),
+                caution: None,
+                elevation: Some("Run `Delete-DeliveryOptimizationCache -Force` in an administrator PowerShell, or use Settings > System > Storage > Temporary files"),
+            },
+            KnownCache {
+                marker: "SoftwareDistribution/DeliveryOptimization",
+                regenerates_quickly: false,
+                owner: Some("Windows Update"),
+                caution: None,
+                elevation: Some("Run `Delete-DeliveryOptimizationCache -Force` in an administrator PowerShell, or use Settings > System > Storage > Temporary files"),
+            },
+            KnownCache {
+                marker: "ProgramData/Microsoft/Windows/WER/ReportQueue",
+                regenerates_quickly: false,
+                owner: Some("Windows Error Reporting"),
+                caution: None,
+                elevation: Some("Remove it with Disk Cleanup: run cleanmgr as administrator, choose Clean up system files, then tick System error memory dump files and Windows error reports"),
+            },
+            KnownCache {
+                marker: "Local/Microsoft/Windows/WER/ReportQueue",
+                regenerates_quickly: false,
+                owner: Some("Windows Error Reporting"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Local/Microsoft/Windows/WER/ReportArchive",
+                regenerates_quickly: false,
+                owner: Some("Windows Error Reporting"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Local/CrashDumps",
+                regenerates_quickly: false,
+                owner: Some("Windows Error Reporting"),
+                caution: Some("Crash dumps help diagnose app crashes; keep them while you are reporting a bug"),
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Microsoft/Windows/INetCache",
+                regenerates_quickly: true,
+                owner: Some("Windows internet components"),
+                caution: None,
+                elevation: None,
+            },
+            KnownCache {
+                marker: "Microsoft/Windows/Explorer",
+                regenerates_quickly: true,
+                owner: Some("File Explorer"),
+                caution: Some("File Explorer keeps its thumbnail databases open, so some files may be skipped until you sign out"),
+                elevation: None,
+            },
+        ]
+    }
+
+    pub fn rebuilders() -> &'static [Rebuilder] {
+        &[
+            Rebuilder {
+                marker: "DerivedData",
+                tool: "Xcode",
+                command: None,
+            },
+            Rebuilder {
+                marker: "target",
+                tool: "Cargo",
+                command: None,
+            },
+            Rebuilder {
+                marker: ".build",
+                tool: "Swift Package Manager",
+                command: None,
+            },
+            Rebuilder {
+                marker: ".gradle",
+                tool: "Gradle",
+                command: None,
+            },
+            Rebuilder {
+                marker: "__pycache__",
+                tool: "Python",
+                command: None,
+            },
+            Rebuilder {
+                marker: ".pytest_cache",
+                tool: "pytest",
+                command: None,
+            },
+            Rebuilder {
+                marker: ".mypy_cache",
+                tool: "mypy",
+                command: None,
+            },
+            Rebuilder {
+                marker: ".next",
+                tool: "Next.js",
+                command: None,
+            },
+            Rebuilder {
+                marker: ".nuxt",
+                tool: "Nuxt",
+                command: None,
+            },
+            Rebuilder {
+                marker: "node_modules",
+                tool: "npm",
+                command: Some("npm install"),
+            },
+            Rebuilder {
+                marker: "bower_components",
+                tool: "Bower",
+                command: Some("bower install"),
+            },
+            Rebuilder {
+                marker: "Pods",
+                tool: "CocoaPods",
+                command: Some("pod install"),
+            },
+            Rebuilder {
+                marker: "venv",
+                tool: "Python",
+                command: Some("python -m venv venv"),
+            },
+            Rebuilder {
+                marker: ".venv",
+                tool: "Python",
+                command: Some("python -m venv .venv"),
+            },
+        ]
+    }
+
+    pub fn rebuilder(path: &Path) -> Option<&'static Rebuilder> {
+        let name = path.file_name()?.to_str()?;
+        Self::rebuilders()
+            .iter()
+            .find(|rebuilder| rebuilder.marker == name)
+    }
+
+    pub fn known_cache(path: &Path) -> Option<&'static KnownCache> {
+        Self::known_caches()
+            .iter()
+            .find(|known| known.matches(path))
+    }
+
+    pub fn elevation_required(path: &Path) -> Option<&'static str> {
+        Self::known_cache(path).and_then(|known| known.elevation)
+    }
+
+    pub fn versioned_caches() -> Vec<VersionedCache> {
+        let mut caches = Vec::new();
+        let home = match dirs::home_dir() {
+            Some(h) => h,
+            None => return caches,
+        };
+        let home_str = home.to_string_lossy();
+
+        caches.extend([
+            VersionedCache {
+                id: "gradle",
+                root: format!("{}/.gradle/caches", home_str),
+                category: FileCategory::PackageCache,
+                default_keep: 2,
+            },
+            VersionedCache {
+                id: "gradle-wrapper",
+                root: format!("{}/.gradle/wrapper/dists", home_str),
+                category: FileCategory::PackageCache,
+                default_keep: 2,
+            },
+        ]);
+
+        #[cfg(target_os = "macos")]
+        caches.extend([
+            VersionedCache {
+                id: "jetbrains",
+                root: format!("{}/Library/Caches/JetBrains", home_str),
+                category: FileCategory::IdeCache,
+                default_keep: 1,
+            },
+            VersionedCache {
+                id: "xcode-device-support",
+                root: format!("{}/Library/Developer/Xcode/iOS DeviceSupport", home_str),
+                category: FileCategory::IdeCache,
+                default_keep: 2,
+            },
+        ]);
+
+        #[cfg(target_os = "linux")]
+        caches.push(VersionedCache {
+            id: "jetbrains",
+            root: format!("{}/.cache/JetBrains", home_str),
+            category: FileCategory::IdeCache,
+            default_keep: 1,
+        });
+
+        #[cfg(target_os = "windows")]
+        if let Some(local_app) = dirs::data_local_dir() {
+            caches.push(VersionedCache {
+                id: "jetbrains",
+                root: format!("{}/JetBrains", local_app.to_string_lossy()),
+                category: FileCategory::IdeCache,
+                default_keep: 1,
+            });
+        }
+
+        caches
+    }
+
+    pub const KEEP_MARKER: &'static str = ".sweeper-keep";
+    pub const CACHEDIR_TAG: &'static str = "CACHEDIR.TAG";
+    const CACHEDIR_SIGNATURE: &'static [u8] = b"Signature: 8a477f597d28d172789f06886806bc55";
+
+    pub fn has_keep_marker(dir: &Path) -> bool {
+        std::fs::symlink_metadata(dir.join(Self::KEEP_MARKER)).is_ok()
+    }
+
+    pub fn has_cachedir_tag(dir: &Path) -> bool {
+        use std::io::Read;
+
+        let Ok(mut file) = std::fs::File::open(dir.join(Self::CACHEDIR_TAG)) else {
+            return false;
+        };
+        let mut signature = [0u8; 43];
+        file.read_exact(&mut signature).is_ok() && signature == Self::CACHEDIR_SIGNATURE
+    }
+
+    pub fn container_markers() -> HashMap<String, String> {
+        [
+            ("vault.cryptomator", "Cryptomator"),
+            ("masterkey.cryptomator", "Cryptomator"),
+            ("gocryptfs.conf", "gocryptfs"),
+            (".encfs6.xml", "EncFS"),
+        ]
+        .into_iter()
+        .map(|(marker, owner)| (marker.to_string(), owner.to_string()))
+        .collect()
+    }
+
+    pub fn container_owner(path: &Path, markers: &HashMap<String, String>) -> Option<String> {
+        Self::sandbox_container_owner(path)
+            .or_else(|| Self::encrypted_mount_owner(path))
+            .or_else(|| {
+                markers
+                    .iter()
+                    .find(|(marker, _)| path.join(marker).is_file())
+                    .map(|(_, owner)| owner.clone())
+            })
+    }
+
+    pub fn sandbox_container_owner(path: &Path) -> Option<String> {
+        let name = path.file_name()?.to_str()?;
+        let parent = path.parent()?;
+        if parent.parent()?.file_name()? != "Library" {
+            return None;
+        }
+        match parent.file_name()?.to_str()? {
+            "Containers" => Some(name.to_string()),
+            "Group Containers" => Some(Self::group_container_owner(name)),
+            _ => None,
+        }
+    }
+
+    fn group_container_owner(name: &str) -> String {
+        if let Some(id) = name.strip_prefix("group.") {
+            return id.to_string();
+        }
+        match name.split_once('.') {
+            Some((team, id))
+                if team.len() == 10
+                    && team
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) =>
+            {
+                id.to_string()
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    fn encrypted_mount_owner(path: &Path) -> Option<String> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        let number = name.strip_prefix("veracrypt")?;
+        let mounted = path.ancestors().skip(1).take(2).any(|ancestor| {
+            ["/media", "/mnt", "/Volumes", "/run/media"]
+                .iter()
+                .any(|root| ancestor == Path::new(root))
+        });
+        (mounted && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+            .then(|| "VeraCrypt".to_string())
+    }
+
+    pub fn managed_store(path: &Path) -> Option<ManagedStore> {
+        Self::managed_store_in(path, dirs::home_dir().as_deref()).filter(ManagedStore::installed)
+    }
+
+    pub fn managed_store_in(path: &Path, home: Option<&Path>) -> Option<ManagedStore> {
+        let system: [(&str, ManagedStore); 4] = [
+            ("/nix/store", ManagedStore::NIX),
+            ("/nix/var/nix/profiles", ManagedStore::NIX),
+            ("/gnu/store", ManagedStore::GUIX),
+            ("/var/guix/profiles", ManagedStore::GUIX),
+        ];
+        let user: [(&str, ManagedStore); 6] = [
+            (".nix-profile", ManagedStore::NIX),
+            (".local/state/nix/profiles", ManagedStore::NIX),
+            (".cache/nix", ManagedStore::NIX),
+            (".guix-profile", ManagedStore::GUIX),
+            (".config/guix/current", ManagedStore::GUIX),
+            (".cache/guix", ManagedStore::GUIX),
+        ];
+
+        system
+            .iter()
+            .find(|(root, _)| path.starts_with(root))
+            .or_else(|| {
+                let home = home?;
+                user.iter()
+                    .find(|(relative, _)| path.starts_with(home.join(relative)))
+            })
+            .map(|(_, store)| *store)
+    }
+
+    pub fn global_package_roots() -> Vec<GlobalPackageRoot> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        let nvm_dir = std::env::var_os("NVM_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".nvm"));
+        let versions: Vec<PathBuf> = std::fs::read_dir(nvm_dir.join("versions/node"))
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        Self::global_package_roots_in(&home, &versions)
+    }
+
+    pub fn global_package_roots_in(
+        home: &Path,
+        nvm_versions: &[PathBuf],
+    ) -> Vec<GlobalPackageRoot> {
+        let mut roots = vec![
+            GlobalPackageRoot {
+                modules: home.join(".npm-global/lib/node_modules"),
+                bin: home.join(".npm-global/bin"),
+                node_version: None,
+            },
+            GlobalPackageRoot {
+                modules: home.join(".config/yarn/global/node_modules"),
+                bin: home.join(".yarn/bin"),
+                node_version: None,
+            },
+        ];
+        roots.extend(nvm_versions.iter().filter_map(|version_dir| {
+            let modules = version_dir.join("lib/node_modules");
+            Some(GlobalPackageRoot {
+                node_version: Some(Self::nvm_version_of(&modules)?.to_string()),
+                bin: version_dir.join("bin"),
+                modules,
+            })
+        }));
+        roots
+    }
+
+    pub fn nvm_version_of(path: &Path) -> Option<&str> {
+        let components: Vec<&std::ffi::OsStr> = path.iter().collect();
+        components
+            .windows(5)
+            .find(|window| {
+                window[0] == "versions"
+                    && window[1] == "node"
+                    && window[3] == "lib"
+                    && window[4] == "node_modules"
+            })
+            .and_then(|window| window[2].to_str())
+            .filter(|version| version.starts_with('v'))
+    }
+
+    pub fn bundled_global_packages() -> &'static [&'static str] {
+        &["npm", "corepack"]
+    }
+
+    pub fn project_markers() -> &'static [&'static str] {
+        &[".git", "package.json", "Cargo.toml", "pyproject.toml"]
+    }
+
+    pub fn is_project_root(dir: &Path) -> bool {
+        Self::project_markers()
+            .iter()
+            .any(|marker| dir.join(marker).exists())
+    }
+
     pub fn dev_artifact_dirs() -> &'static [&'static str] {
         &[
             "node_modules",
@@ -263,6 +1127,219 @@ impl CategoryPatterns {
         paths
     }
 
+    #[cfg(target_os = "windows")]
+    pub fn windows_system_paths() -> Vec<String> {
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| format!("{}/Windows", drive));
+        let mut paths = vec![
+            format!("{}/Windows.old", drive),
+            format!(
+                "{}/ServiceProfiles/NetworkService/AppData/Local/Microsoft/Windows/DeliveryOptimization/Cache",
+                root
+            ),
+            format!("{}/SoftwareDistribution/DeliveryOptimization", root),
+        ];
+        if let Some(local_app) = dirs::data_local_dir() {
+            let local_app = local_app.to_string_lossy();
+            paths.push(format!("{}/Microsoft/Windows/INetCache", local_app));
+            paths.push(format!("{}/Microsoft/Windows/Explorer", local_app));
+        }
+        paths
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn windows_system_paths() -> Vec<String> {
+        Vec::new()
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn windows_report_paths() -> Vec<String> {
+        let program_data =
+            std::env::var("ProgramData").unwrap_or_else(|_| "C:/ProgramData".to_string());
+        let mut paths = vec![format!(
+            "{}/Microsoft/Windows/WER/ReportQueue",
+            program_data
+        )];
+        if let Some(local_app) = dirs::data_local_dir() {
+            let local_app = local_app.to_string_lossy();
+            paths.push(format!("{}/CrashDumps", local_app));
+            paths.push(format!("{}/Microsoft/Windows/WER/ReportQueue", local_app));
+            paths.push(format!("{}/Microsoft/Windows/WER/ReportArchive", local_app));
+        }
+        paths
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn windows_report_paths() -> Vec<String> {
+        Vec::new()
+    }
+
+    pub fn log_root_paths() -> Vec<String> {
+        let mut paths = Vec::new();
+
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(home) = dirs::home_dir() {
+                paths.push(format!("{}/Library/Logs", home.to_string_lossy()));
+            }
+            paths.push("/Library/Logs".to_string());
+        }
+
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        {
+            paths.push("/var/log".to_string());
+        }
+
+        paths
+    }
+
+    pub fn app_log_parents() -> Vec<String> {
+        let mut paths = Vec::new();
+
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(home) = dirs::home_dir() {
+                paths.push(format!(
+                    "{}/Library/Application Support",
+                    home.to_string_lossy()
+                ));
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(config) = dirs::config_dir() {
+                paths.push(config.to_string_lossy().to_string());
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            if let Some(local_app) = dirs::data_local_dir() {
+                paths.push(local_app.to_string_lossy().to_string());
+            }
+            if let Some(roaming) = dirs::data_dir() {
+                paths.push(roaming.to_string_lossy().to_string());
+            }
+        }
+
+        paths
+    }
+
+    pub fn partial_downloads() -> Vec<PartialDownload> {
+        match dirs::home_dir() {
+            Some(home) => Self::partial_downloads_in(&home),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn partial_downloads_in(home: &Path) -> Vec<PartialDownload> {
+        let mut roots = vec![
+            PartialDownload {
+                tool: "Cargo",
+                pattern: "*.tmp",
+                root: home.join(".cargo/registry"),
+                depth: 3,
+                suffixes: &[".tmp"],
+                dirs: &[],
+            },
+            PartialDownload {
+                tool: "npm",
+                pattern: "tmp",
+                root: home.join(".npm/_cacache"),
+                depth: 1,
+                suffixes: &[],
+                dirs: &["tmp"],
+            },
+        ];
+
+        #[cfg(target_os = "macos")]
+        roots.push(PartialDownload {
+            tool: "Homebrew",
+            pattern: "*.incomplete",
+            root: home.join("Library/Caches/Homebrew"),
+            depth: 2,
+            suffixes: &[".incomplete"],
+            dirs: &[],
+        });
+
+        #[cfg(target_os = "linux")]
+        roots.extend([
+            PartialDownload {
+                tool: "Homebrew",
+                pattern: "*.incomplete",
+                root: home.join(".cache/Homebrew"),
+                depth: 2,
+                suffixes: &[".incomplete"],
+                dirs: &[],
+            },
+            PartialDownload {
+                tool: "apt",
+                pattern: "partial",
+                root: PathBuf::from("/var/cache/apt/archives"),
+                depth: 1,
+                suffixes: &[],
+                dirs: &["partial"],
+            },
+        ]);
+
+        roots
+    }
+
+    pub fn temp_families() -> &'static [TempFamily] {
+        &[
+            TempFamily {
+                pattern: "pip-*",
+                label: "pip build dirs",
+                prefixes: &[
+                    "pip-install-",
+                    "pip-build-",
+                    "pip-req-build-",
+                    "pip-unpack-",
+                    "pip-wheel-",
+                    "pip-ephem-wheel-cache-",
+                    "pip-modern-metadata-",
+                ],
+            },
+            TempFamily {
+                pattern: "cargo-install*",
+                label: "cargo install dirs",
+                prefixes: &["cargo-install"],
+            },
+            TempFamily {
+                pattern: "electron-download-*",
+                label: "Electron downloads",
+                prefixes: &["electron-download-", "electron-packager-"],
+            },
+            TempFamily {
+                pattern: "npm-*",
+                label: "npm temp dirs",
+                prefixes: &["npm-cache-", "npm-"],
+            },
+            TempFamily {
+                pattern: "yarn--*",
+                label: "yarn temp dirs",
+                prefixes: &["yarn--"],
+            },
+            TempFamily {
+                pattern: "go-build*",
+                label: "Go build dirs",
+                prefixes: &["go-build"],
+            },
+            TempFamily {
+                pattern: "rustc*",
+                label: "rustc temp dirs",
+                prefixes: &["rustc"],
+            },
+        ]
+    }
+
+    pub fn temp_family(name: &str) -> Option<&'static TempFamily> {
+        Self::temp_families()
+            .iter()
+            .find(|family| family.matches(name))
+    }
+
     pub fn temp_extensions() -> &'static [&'static str] {
         &[
             ".tmp",
@@ -287,11 +1364,374 @@ impl CategoryPatterns {
         &[".log", ".logs"]
     }
 
-    pub fn stale_threshold_days() -> u64 {
-        90
-    }
-
     pub fn old_download_days() -> u64 {
         30
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn folder_versions_parse_into_product_and_parts() {
+        type Parsed = Option<(&'static str, &'static [u64])>;
+        let cases: &[(&str, Parsed)] = &[
+            ("7.4.2", Some(("", &[7, 4, 2]))),
+            ("gradle-8.5", Some(("gradle", &[8, 5]))),
+            ("Xcode_15.0.1", Some(("xcode", &[15, 0, 1]))),
+            ("node-v18.19.0", Some(("node-v", &[18, 19, 0]))),
+            ("1.2.3-beta.4", Some(("", &[1, 2, 3]))),
+            ("2.0.0-rc1", Some(("", &[2, 0, 0]))),
+            ("3.1-SNAPSHOT", Some(("", &[3, 1]))),
+            ("1..2", Some(("", &[1, 2]))),
+            ("99999999999999999999.1", None),
+            ("latest", None),
+            ("", None),
+        ];
+        for (name, expected) in cases {
+            let parsed = parse_folder_version(name);
+            let parsed = parsed
+                .as_ref()
+                .map(|version| (version.product.as_str(), version.parts.as_slice()));
+            assert_eq!(parsed, *expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn versions_are_ordered_numerically_not_by_name() {
+        let prunable = prunable_versions(&names(&["1.10.0", "1.9.2", "1.2.0"]), 1);
+        let pruned: Vec<&str> = prunable.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(pruned, ["1.2.0", "1.9.2"]);
+        assert!(prunable.iter().all(|p| p.kept == ["1.10.0"]));
+    }
+
+    #[test]
+    fn prerelease_folders_rank_with_their_release_numbers() {
+        let prunable = prunable_versions(&names(&["2.0.0-rc1", "1.9.0", "2.1.0"]), 2);
+        assert_eq!(prunable.len(), 1);
+        assert_eq!(prunable[0].name, "1.9.0");
+        assert_eq!(prunable[0].kept, ["2.1.0", "2.0.0-rc1"]);
+    }
+
+    #[test]
+    fn products_are_pruned_separately_and_malformed_names_are_left_alone() {
+        let list = names(&["jdk-17", "jdk-11", "node-20", "node-18", "latest", "cache"]);
+        let prunable = prunable_versions(&list, 1);
+        let pruned: Vec<&str> = prunable.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(pruned, ["jdk-11", "node-18"]);
+    }
+
+    #[test]
+    fn age_curves_raise_confidence_smoothly_and_stay_capped() {
+        let dev = FileCategory::DevArtifact.age_curve().unwrap();
+        let log = FileCategory::LogFile.age_curve().unwrap();
+        let cases = [
+            (dev, 0, 0.0),
+            (dev, 30, 0.0),
+            (dev, 31, 0.0006),
+            (dev, 60, 0.0257),
+            (dev, 90, 0.0625),
+            (dev, 120, 0.0993),
+            (dev, 180, 0.1389),
+            (dev, 365, 0.15),
+            (dev, 10_000, 0.15),
+            (log, 7, 0.0),
+            (log, 30, 0.0218),
+            (log, 45, 0.0445),
+            (log, 90, 0.0924),
+            (log, 180, 0.10),
+        ];
+        for (curve, age, expected) in cases {
+            let bonus = curve.bonus(age);
+            assert!(
+                (bonus - expected).abs() < 0.0005,
+                "{} days: {} != {}",
+                age,
+                bonus,
+                expected
+            );
+        }
+
+        // Never decreasing with age.
+        let bonuses: Vec<f32> = (0..400).map(|age| dev.bonus(age)).collect();
+        assert!(bonuses.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        assert!((dev.apply(0.5, 365) - 0.65).abs() < 1e-6);
+        assert_eq!(dev.apply(0.9, 365), 0.98);
+        assert_eq!(
+            FileCategory::OldDownload
+                .age_curve()
+                .unwrap()
+                .apply(0.9, 365),
+            0.95
+        );
+        // A base already above the cap is left alone rather than lowered.
+        assert_eq!(log.apply(0.97, 365), 0.97);
+        assert_eq!(FileCategory::TempFile.age_curve(), None);
+    }
+
+    #[test]
+    fn nvm_versions_come_from_the_node_modules_path() {
+        let cases = [
+            (
+                "/home/me/.nvm/versions/node/v20.11.1/lib/node_modules",
+                Some("v20.11.1"),
+            ),
+            (
+                "/home/me/.nvm/versions/node/v18.0.0/lib/node_modules/typescript",
+                Some("v18.0.0"),
+            ),
+            // Versions without the leading v aren't nvm's.
+            ("/opt/versions/node/20.11.1/lib/node_modules", None),
+            // Missing or reordered components.
+            ("/home/me/.nvm/versions/node/v20.11.1/node_modules", None),
+            ("/home/me/.nvm/versions/node/v20.11.1/lib", None),
+            ("/home/me/.nvm/node/v20.11.1/lib/node_modules", None),
+            ("/usr/local/lib/node_modules", None),
+            ("", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                CategoryPatterns::nvm_version_of(Path::new(path)),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn keeping_more_than_exist_prunes_nothing() {
+        assert!(prunable_versions(&names(&["1.0", "2.0"]), 5).is_empty());
+        assert!(prunable_versions(&[], 1).is_empty());
+    }
+
+    #[test]
+    fn sandbox_and_group_containers_are_named_after_their_app() {
+        let cases = [
+            (
+                "/Users/me/Library/Containers/com.apple.mail",
+                Some("com.apple.mail"),
+            ),
+            (
+                "/Users/me/Library/Group Containers/group.com.apple.notes",
+                Some("com.apple.notes"),
+            ),
+            (
+                "/Users/me/Library/Group Containers/UBF8T346G9.Office",
+                Some("Office"),
+            ),
+            // Not a ten character team id.
+            (
+                "/Users/me/Library/Group Containers/ubf8t346g9.Office",
+                Some("ubf8t346g9.Office"),
+            ),
+            ("/Users/me/Library/Containers", None),
+            ("/Users/me/Library/Containers/com.x/Data", None),
+            ("/Users/me/Containers/com.apple.mail", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                CategoryPatterns::sandbox_container_owner(Path::new(path)).as_deref(),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn vaults_and_encrypted_mounts_are_containers() {
+        let markers = CategoryPatterns::container_markers();
+        let owner = |path: &str| CategoryPatterns::container_owner(Path::new(path), &markers);
+        assert_eq!(owner("/media/veracrypt1").as_deref(), Some("VeraCrypt"));
+        assert_eq!(
+            owner("/run/media/me/veracrypt12").as_deref(),
+            Some("VeraCrypt")
+        );
+        assert_eq!(owner("/home/me/veracrypt1"), None);
+        assert_eq!(owner("/media/veracrypt"), None);
+        assert_eq!(owner("/media/veracrypt1a"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("Secrets");
+        std::fs::create_dir(&vault).unwrap();
+        assert_eq!(owner(vault.to_str().unwrap()), None);
+        std::fs::write(vault.join("vault.cryptomator"), b"").unwrap();
+        assert_eq!(
+            owner(vault.to_str().unwrap()).as_deref(),
+            Some("Cryptomator")
+        );
+
+        // A folder of the marker's name is not a marker.
+        let fake = dir.path().join("Fake");
+        std::fs::create_dir_all(fake.join("gocryptfs.conf")).unwrap();
+        assert_eq!(owner(fake.to_str().unwrap()), None);
+    }
+
+    #[test]
+    fn keep_markers_and_cachedir_tags_are_recognized() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        assert!(!CategoryPatterns::has_keep_marker(root));
+        assert!(!CategoryPatterns::has_cachedir_tag(root));
+
+        std::fs::write(root.join(CategoryPatterns::KEEP_MARKER), b"").unwrap();
+        assert!(CategoryPatterns::has_keep_marker(root));
+
+        let tag = root.join(CategoryPatterns::CACHEDIR_TAG);
+        std::fs::write(
+            &tag,
+            b"Signature: 8a477f597d28d172789f06886806bc55\n# This file is a cache directory tag.\n",
+        )
+        .unwrap();
+        assert!(CategoryPatterns::has_cachedir_tag(root));
+        std::fs::write(&tag, b"Signature: 8a477f597d28d172789f06886806bc5").unwrap();
+        assert!(!CategoryPatterns::has_cachedir_tag(root));
+        std::fs::write(&tag, b"Signature: 0000000000000000000000000000000000").unwrap();
+        assert!(!CategoryPatterns::has_cachedir_tag(root));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_dangling_keep_marker_still_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("missing"),
+            dir.path().join(CategoryPatterns::KEEP_MARKER),
+        )
+        .unwrap();
+        assert!(CategoryPatterns::has_keep_marker(dir.path()));
+    }
+
+    #[test]
+    fn ruleset_versions_are_listed_in_order_up_to_the_current_one() {
+        let versions: Vec<u32> = RULESET_CHANGES
+            .iter()
+            .map(|change| change.version)
+            .collect();
+        assert_eq!(versions, (1..=RULESET_VERSION).collect::<Vec<_>>());
+        assert!(ruleset_changes_since(RULESET_VERSION).is_empty());
+        assert_eq!(ruleset_changes_since(0).len(), RULESET_CHANGES.len());
+        assert_eq!(
+            ruleset_changes_since(RULESET_VERSION - 1)
+                .iter()
+                .map(|change| change.version)
+                .collect::<Vec<_>>(),
+            [RULESET_VERSION]
+        );
+    }
+
+    #[test]
+    fn ruleset_summaries_merge_every_unseen_version() {
+        let first = RulesetChange {
+            version: 1,
+            added: &["A"],
+            changed: &[],
+        };
+        let second = RulesetChange {
+            version: 2,
+            added: &["B"],
+            changed: &["c"],
+        };
+        assert_eq!(
+            summarize_ruleset_changes(&[&first, &second]).as_deref(),
+            Some("New: A, B; Changed: c")
+        );
+        assert_eq!(
+            summarize_ruleset_changes(&[&first]).as_deref(),
+            Some("New: A")
+        );
+        assert_eq!(summarize_ruleset_changes(&[]), None);
+    }
+
+    #[test]
+    fn only_configs_saved_before_a_ruleset_see_its_changes() {
+        use crate::config::Config;
+
+        assert_eq!(Config::default().seen_ruleset_version, RULESET_VERSION);
+        let saved_before_versioning = Config::from_json("{}").unwrap();
+        assert_eq!(saved_before_versioning.seen_ruleset_version, 0);
+    }
+
+    #[test]
+    fn nix_and_guix_paths_map_to_their_store() {
+        let home = Path::new("/home/me");
+        let cases = [
+            ("/nix/store/abc-hello-2.12/bin/hello", Some("Nix")),
+            ("/nix/var/nix/profiles/default", Some("Nix")),
+            ("/gnu/store/xyz-guile-3.0", Some("Guix")),
+            ("/var/guix/profiles/per-user/me", Some("Guix")),
+            ("/home/me/.nix-profile/bin", Some("Nix")),
+            ("/home/me/.cache/nix/eval-cache-v5", Some("Nix")),
+            ("/home/me/.config/guix/current", Some("Guix")),
+            ("/home/me/.cache/guix", Some("Guix")),
+            // Look-alikes that are not the store.
+            ("/nixos/store", None),
+            ("/home/me/.cache/nixpkgs", None),
+            ("/home/other/.nix-profile", None),
+            ("/srv/nix/store", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                CategoryPatterns::managed_store_in(Path::new(path), Some(home))
+                    .map(|store| store.tool),
+                expected,
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            CategoryPatterns::managed_store_in(Path::new("/home/me/.nix-profile"), None),
+            None
+        );
+        assert_eq!(
+            CategoryPatterns::managed_store_in(Path::new("/gnu/store"), None)
+                .map(|store| store.command),
+            Some("guix gc")
+        );
+    }
+
+    #[test]
+    fn temp_folders_are_grouped_by_tool_prefix() {
+        let family = |name: &str| CategoryPatterns::temp_family(name).map(|family| family.pattern);
+        assert_eq!(family("pip-install-abc123"), Some("pip-*"));
+        assert_eq!(family("pip-ephem-wheel-cache-x"), Some("pip-*"));
+        assert_eq!(family("cargo-installAbCd"), Some("cargo-install*"));
+        assert_eq!(family("npm-cache-1"), Some("npm-*"));
+        assert_eq!(family("npm-4711-deadbeef"), Some("npm-*"));
+        assert_eq!(family("rustcQx1"), Some("rustc*"));
+        // The bare prefix or an unrelated name isn't a leftover.
+        assert_eq!(family("pip-install-"), None);
+        assert_eq!(family("rustc"), None);
+        assert_eq!(family("pipeline"), None);
+        assert_eq!(family("my-npm-cache"), None);
+    }
+
+    #[test]
+    fn partial_downloads_match_tool_and_browser_suffixes() {
+        let home = Path::new("/home/u");
+        let roots = CategoryPatterns::partial_downloads_in(home);
+        let root = |tool: &str| roots.iter().find(|root| root.tool == tool).unwrap();
+
+        let cargo = root("Cargo");
+        assert_eq!(cargo.root, home.join(".cargo/registry"));
+        assert!(cargo.matches_file("serde-1.0.0.crate.tmp"));
+        assert!(cargo.matches_file("index.crdownload"));
+        assert!(cargo.matches_file("blob.part"));
+        assert!(!cargo.matches_file("serde-1.0.0.crate"));
+        assert!(!cargo.matches_file(".tmp"));
+        assert!(!cargo.holds_partials("tmp"));
+
+        let npm = root("npm");
+        assert!(npm.holds_partials("tmp"));
+        assert!(!npm.holds_partials("content-v2"));
+        assert!(!npm.matches_file("index.tmp"));
+        assert!(npm.matches_file("tarball.partial"));
+    }
+}
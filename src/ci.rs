@@ -0,0 +1,116 @@
+use crate::audit;
+use crate::cleaner::{CleanJob, CleanTarget};
+use crate::config::Config;
+use crate::scanner::{metrics, Scanner};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_ERROR: i32 = 1;
+pub const EXIT_INSUFFICIENT: i32 = 2;
+
+#[derive(Debug, Serialize)]
+pub struct CiSummary {
+    pub workspace: PathBuf,
+    pub dry_run: bool,
+    pub items_found: usize,
+    pub items_deleted: usize,
+    pub found_bytes: u64,
+    pub freed_bytes: u64,
+    pub required_bytes: Option<u64>,
+    pub duration_ms: u64,
+    pub blocked: Vec<String>,
+    pub failed: Vec<String>,
+    pub success: bool,
+}
+
+pub fn run(
+    workspace: &Path,
+    required_bytes: Option<u64>,
+    dry_run: bool,
+    metrics_out: Option<&Path>,
+) -> i32 {
+    if !workspace.is_dir() {
+        eprintln!("workspace is not a directory: {}", workspace.display());
+        return EXIT_ERROR;
+    }
+    let workspace = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+    // CI mode brings its own settings, but an audit lock in the user's
+    // config still holds: the scan runs and nothing is deleted.
+    match Config::load() {
+        Ok(user) if user.audit_mode => audit::enable(),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("failed to load config: {}", e);
+            return EXIT_ERROR;
+        }
+    }
+
+    let mut config = Config::ci_preset(workspace.clone());
+    config.dry_run = dry_run;
+
+    let scanner = Scanner::with_scope(config.clone(), vec![workspace.clone()]);
+    if let Err(e) = scanner.scan() {
+        eprintln!("scan failed: {}", e);
+        return EXIT_ERROR;
+    }
+    let store = scanner.snapshot();
+    if let Some(path) = metrics_out {
+        if let Err(e) = metrics::write(path, &store) {
+            eprintln!("failed to write metrics to {}: {}", path.display(), e);
+            return EXIT_ERROR;
+        }
+    }
+
+    let targets: Vec<CleanTarget> = store
+        .items()
+        .iter()
+        .filter(|item| config.enabled_categories.contains(&item.category))
+        .flat_map(CleanTarget::for_item)
+        .collect();
+    let items_found = targets.len();
+    let found_bytes = targets.iter().map(|target| target.size).sum();
+
+    let report = match CleanJob::new(targets, config.use_trash, dry_run) {
+        Ok(job) => job
+            .with_quarantine(config.quarantine_dir.clone())
+            .with_manifest_dir(config.manifest_dir.clone())
+            .run(),
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    let failed = report.failures().collect();
+    let success = required_bytes.is_none_or(|required| report.freed >= required);
+    let summary = CiSummary {
+        workspace,
+        dry_run,
+        items_found,
+        items_deleted: report.deleted.len(),
+        found_bytes,
+        freed_bytes: report.freed,
+        required_bytes,
+        duration_ms: store.stats().duration_ms,
+        blocked: report.blocked,
+        failed,
+        success,
+    };
+
+    match serde_json::to_string_pretty(&summary) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("failed to serialize summary: {}", e);
+            return EXIT_ERROR;
+        }
+    }
+
+    if success {
+        EXIT_OK
+    } else {
+        EXIT_INSUFFICIENT
+    }
+}
@@ -0,0 +1,1315 @@
+use crate::audit;
+use crate::categories::{CategoryPatterns, FileCategory, GlobalPackageRoot};
+use crate::config::DeleteMode;
+use crate::error::{self, SweeperError};
+use crate::git::{self, GitRunner, SystemGit, TrackedCheck};
+use crate::manifest::DeletionManifest;
+use crate::scanner::globals;
+use crate::scanner::ScannedItem;
+use crate::volumes::VolumeTable;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const COALESCE_MIN_FILES: usize = 50;
+const TRASH_BATCH_SIZE: usize = 256;
+const FILES_DELETED_PER_SEC: u64 = 5_000;
+
+#[derive(Debug, Clone)]
+pub struct CleanTarget {
+    pub path: PathBuf,
+    pub size: u64,
+    pub check_git: bool,
+}
+
+impl CleanTarget {
+    pub fn for_item(item: &ScannedItem) -> Vec<Self> {
+        if item.is_keeper {
+            return Vec::new();
+        }
+        let check_git = item.category == FileCategory::DevArtifact;
+        if item.members.is_empty() {
+            return vec![Self {
+                path: item.path.clone(),
+                size: item.size,
+                check_git,
+            }];
+        }
+        item.members
+            .iter()
+            .map(|(path, size)| Self {
+                path: path.clone(),
+                size: *size,
+                check_git,
+            })
+            .collect()
+    }
+}
+
+/// Drops targets that repeat another target or sit inside one, since
+/// removing the outer folder removes them too. Returns what is left and how
+/// many targets were merged away.
+pub fn normalize_selection(mut targets: Vec<CleanTarget>) -> (Vec<CleanTarget>, usize) {
+    let before = targets.len();
+    targets.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut kept: Vec<CleanTarget> = Vec::with_capacity(before);
+    for target in targets {
+        // Path ordering is per component, so everything inside a folder sorts
+        // right after it.
+        if kept
+            .last()
+            .is_some_and(|outer| target.path.starts_with(&outer.path))
+        {
+            continue;
+        }
+        kept.push(target);
+    }
+    let merged = before - kept.len();
+    (kept, merged)
+}
+
+// The private field is a witness that the job went through `new` and its
+// audit check, not a placeholder for future fields.
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Debug, Clone)]
+pub struct CleanJob {
+    pub targets: Vec<CleanTarget>,
+    pub use_trash: bool,
+    pub dry_run: bool,
+    pub quarantine: Option<PathBuf>,
+    pub permanent: HashSet<PathBuf>,
+    pub manifest_dir: Option<PathBuf>,
+    _writable: (),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedDeletion {
+    pub path: PathBuf,
+    pub size: u64,
+    pub mode: DeleteMode,
+    pub volume: Option<PathBuf>,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+pub struct CleanReport {
+    pub deleted: HashSet<PathBuf>,
+    /// Targets that were already gone when the cleanup reached them.
+    pub skipped: Vec<PathBuf>,
+    pub freed: u64,
+    pub blocked: Vec<String>,
+    pub unchecked: Vec<String>,
+    pub failed: Vec<SweeperError>,
+    pub untrashable: Vec<CleanTarget>,
+    pub trashed: Vec<CleanTarget>,
+    pub batch: Option<u64>,
+    pub elapsed: Duration,
+}
+
+impl CleanReport {
+    pub fn untrashable_failures(&self) -> impl Iterator<Item = String> + '_ {
+        self.untrashable
+            .iter()
+            .map(|target| format!("{}: cannot be moved to the trash", target.path.display()))
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = String> + '_ {
+        self.failed
+            .iter()
+            .map(|e| e.to_string())
+            .chain(self.untrashable_failures())
+    }
+}
+
+impl CleanJob {
+    /// The only way to build a job; refuses in audit mode so nothing above the
+    /// cleaner can get hold of one.
+    pub fn new(targets: Vec<CleanTarget>, use_trash: bool, dry_run: bool) -> error::Result<Self> {
+        audit::ensure_writable()?;
+        let (targets, _) = normalize_selection(targets);
+        Ok(Self {
+            targets,
+            use_trash,
+            dry_run,
+            quarantine: None,
+            permanent: HashSet::new(),
+            manifest_dir: None,
+            _writable: (),
+        })
+    }
+
+    pub fn with_quarantine(mut self, quarantine: Option<PathBuf>) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
+    pub fn with_permanent(mut self, permanent: HashSet<PathBuf>) -> Self {
+        self.permanent = permanent;
+        self
+    }
+
+    /// Writes a manifest of every real cleanup into `dir` once the safety
+    /// checks have passed and before anything is removed.
+    pub fn with_manifest_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.manifest_dir = dir;
+        self
+    }
+
+    fn mode_for(&self, target: &CleanTarget) -> DeleteMode {
+        if self.dry_run {
+            DeleteMode::DryRun
+        } else if !self.use_trash || self.permanent.contains(&target.path) {
+            DeleteMode::Permanent
+        } else {
+            DeleteMode::Trash
+        }
+    }
+
+    /// Every target in the order `run` attempts it, with the mode it will be
+    /// removed in. Safety checks at run time may still skip some of them.
+    pub fn plan(&self) -> Vec<PlannedDeletion> {
+        let volumes = VolumeTable::current();
+        self.targets
+            .iter()
+            .map(|target| PlannedDeletion {
+                path: target.path.clone(),
+                size: target.size,
+                mode: self.mode_for(target),
+                volume: volumes
+                    .volume_for(&target.path)
+                    .map(|volume| volume.mount_point.clone()),
+                modified: std::fs::symlink_metadata(&target.path)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+                    .map(DateTime::<Utc>::from),
+            })
+            .collect()
+    }
+
+    pub fn coalescible(&self, min_files: usize) -> Vec<(PathBuf, usize)> {
+        let mut by_parent: HashMap<&Path, Vec<usize>> = HashMap::new();
+        for (index, target) in self.targets.iter().enumerate() {
+            if let Some(parent) = target.path.parent() {
+                by_parent.entry(parent).or_default().push(index);
+            }
+        }
+
+        let targeted: HashSet<&Path> = self.targets.iter().map(|t| t.path.as_path()).collect();
+        let mut groups: Vec<(PathBuf, usize)> = by_parent
+            .into_iter()
+            .filter(|(parent, members)| {
+                members.len() >= min_files
+                    && !targeted.contains(parent)
+                    && members.iter().all(|&i| self.targets[i].path.is_file())
+                    && std::fs::read_dir(parent)
+                        .map(|entries| entries.count() == members.len())
+                        .unwrap_or(false)
+            })
+            .map(|(parent, members)| (parent.to_path_buf(), members.len()))
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    pub fn coalesce(&mut self, min_files: usize) -> usize {
+        let groups = self.coalescible(min_files);
+        for (parent, _) in &groups {
+            let mut size = 0;
+            let mut check_git = false;
+            self.targets.retain(|target| {
+                if target.path.parent() == Some(parent.as_path()) {
+                    size += target.size;
+                    check_git |= target.check_git;
+                    false
+                } else {
+                    true
+                }
+            });
+            self.targets.push(CleanTarget {
+                path: parent.clone(),
+                size,
+                check_git,
+            });
+        }
+        groups.len()
+    }
+
+    pub fn run(self) -> CleanReport {
+        self.run_with(
+            &SystemGit,
+            &SystemTrash,
+            &InUsePaths::current(),
+            &CategoryPatterns::global_package_roots(),
+        )
+    }
+
+    fn run_with(
+        mut self,
+        git: &impl GitRunner,
+        trash: &impl Trasher,
+        in_use: &InUsePaths,
+        package_roots: &[GlobalPackageRoot],
+    ) -> CleanReport {
+        let started = Instant::now();
+        let mut report = CleanReport::default();
+        if let Err(e) = audit::ensure_writable() {
+            report.blocked.extend(
+                self.targets
+                    .iter()
+                    .map(|target| format!("{}: {}", target.path.display(), e)),
+            );
+            return report;
+        }
+        let mut ready = Vec::with_capacity(self.targets.len());
+        for target in std::mem::take(&mut self.targets) {
+            if let Some(store) = CategoryPatterns::managed_store(&target.path) {
+                report.blocked.push(format!(
+                    "{} is managed by {}; run `{}` instead",
+                    target.path.display(),
+                    store.tool,
+                    store.command
+                ));
+                continue;
+            }
+
+            if let Some(instructions) = CategoryPatterns::elevation_required(&target.path) {
+                report.blocked.push(format!(
+                    "{} needs administrator rights. {}",
+                    target.path.display(),
+                    instructions
+                ));
+                continue;
+            }
+
+            if let Some(reason) = in_use.conflict(&target.path) {
+                report
+                    .blocked
+                    .push(format!("{} {}", target.path.display(), reason));
+                continue;
+            }
+
+            if target.check_git {
+                match git::check_tracked(git, &target.path, git::TRACKED_CHECK_TIMEOUT) {
+                    TrackedCheck::Tracked(count) => {
+                        report.blocked.push(format!(
+                            "{} contains {} tracked files",
+                            target.path.display(),
+                            count
+                        ));
+                        continue;
+                    }
+                    TrackedCheck::TimedOut => {
+                        report
+                            .unchecked
+                            .push(format!("{} (git timed out)", target.path.display()));
+                    }
+                    TrackedCheck::Failed(reason) | TrackedCheck::Unavailable(reason) => {
+                        report
+                            .unchecked
+                            .push(format!("{} ({})", target.path.display(), reason));
+                    }
+                    TrackedCheck::Clean | TrackedCheck::NotInRepo => {}
+                }
+            }
+
+            ready.push(target);
+        }
+
+        // Written once the safety checks have run, so it lists exactly the
+        // paths that are about to be attempted.
+        self.targets = ready;
+        if let Some(dir) = self.manifest_dir.as_deref().filter(|_| !self.dry_run) {
+            if let Err(e) = DeletionManifest::from_job(&self).save_in(dir) {
+                report.blocked.extend(self.targets.iter().map(|target| {
+                    format!(
+                        "{}: could not write the deletion manifest to {}: {}",
+                        target.path.display(),
+                        dir.display(),
+                        e
+                    )
+                }));
+                return report;
+            }
+        }
+
+        let (permanent, trashable): (Vec<CleanTarget>, Vec<CleanTarget>) =
+            std::mem::take(&mut self.targets)
+                .into_iter()
+                .partition(|target| self.mode_for(target) != DeleteMode::Trash);
+        for batch in trashable.chunks(TRASH_BATCH_SIZE) {
+            trash_batch(trash, batch, self.quarantine.as_deref(), &mut report);
+        }
+        let mut removed = HashSet::new();
+        for target in permanent {
+            match delete_path(&target.path, self.dry_run) {
+                Ok(()) => {
+                    report.freed += target.size;
+                    removed.insert(target.path.clone());
+                    report.deleted.insert(target.path);
+                }
+                Err(e) => report.failed.push(e),
+            }
+        }
+        if !self.dry_run {
+            remove_dangling_links(&mut report, &removed, package_roots, trash);
+        }
+
+        report.elapsed = started.elapsed();
+        report
+    }
+}
+
+/// Removes the bin links left pointing at deleted global packages, the same
+/// way their package went: links of a permanently deleted package are
+/// deleted, links of a trashed one are trashed and logged with it so Undo
+/// brings both back. Links of a quarantined package are left alone.
+fn remove_dangling_links(
+    report: &mut CleanReport,
+    removed: &HashSet<PathBuf>,
+    roots: &[GlobalPackageRoot],
+    trash: &impl Trasher,
+) {
+    let trashed: HashSet<&Path> = report.trashed.iter().map(|t| t.path.as_path()).collect();
+    let links: Vec<(PathBuf, bool)> = report
+        .deleted
+        .iter()
+        .filter(|package| removed.contains(*package) || trashed.contains(package.as_path()))
+        .flat_map(|package| {
+            let permanent = removed.contains(package);
+            globals::dangling_links(roots, package)
+                .into_iter()
+                .map(move |link| (link, permanent))
+        })
+        .collect();
+    for (link, permanent) in links {
+        let result = if permanent {
+            std::fs::remove_file(&link).map_err(|e| SweeperError::io(&link, e))
+        } else {
+            trash
+                .delete(&link)
+                .map_err(|e| SweeperError::trash(&link, &e))
+        };
+        match result {
+            Ok(()) => {
+                if !permanent {
+                    report.trashed.push(CleanTarget {
+                        path: link.clone(),
+                        size: 0,
+                        check_git: false,
+                    });
+                }
+                report.deleted.insert(link);
+            }
+            Err(e) => report.failed.push(e),
+        }
+    }
+}
+
+/// Where trashed items go. `SystemTrash` is the platform trash; the seam
+/// lets tests make it fail.
+trait Trasher {
+    fn delete_all(&self, paths: &[&Path]) -> Result<(), trash::Error>;
+    fn delete(&self, path: &Path) -> Result<(), trash::Error>;
+}
+
+struct SystemTrash;
+
+impl Trasher for SystemTrash {
+    fn delete_all(&self, paths: &[&Path]) -> Result<(), trash::Error> {
+        trash::delete_all(paths)
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), trash::Error> {
+        trash::delete(path)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InUsePaths {
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl InUsePaths {
+    pub fn current() -> Self {
+        let resolve = |path: PathBuf| path.canonicalize().unwrap_or(path);
+        Self {
+            exe: std::env::current_exe().ok().map(resolve),
+            cwd: std::env::current_dir().ok().map(resolve),
+        }
+    }
+
+    pub fn conflict(&self, target: &Path) -> Option<&'static str> {
+        let target = target
+            .canonicalize()
+            .unwrap_or_else(|_| target.to_path_buf());
+        if self
+            .exe
+            .as_deref()
+            .is_some_and(|exe| exe.starts_with(&target))
+        {
+            return Some("contains the running Sweeper executable");
+        }
+        if self
+            .cwd
+            .as_deref()
+            .is_some_and(|cwd| cwd.starts_with(&target))
+        {
+            return Some("contains the current working directory");
+        }
+        None
+    }
+}
+
+fn trash_batch(
+    trash: &impl Trasher,
+    batch: &[CleanTarget],
+    quarantine: Option<&Path>,
+    report: &mut CleanReport,
+) {
+    let exists = |target: &CleanTarget| std::fs::symlink_metadata(&target.path).is_ok();
+    let (present, missing): (Vec<&CleanTarget>, Vec<&CleanTarget>) =
+        batch.iter().partition(|target| exists(target));
+    report
+        .skipped
+        .extend(missing.into_iter().map(|target| target.path.clone()));
+    if present.is_empty() {
+        return;
+    }
+
+    let paths: Vec<&Path> = present.iter().map(|target| target.path.as_path()).collect();
+    let moved = trash.delete_all(&paths).is_ok();
+    for target in present {
+        // A batch can fail partway; whatever it already moved is gone now
+        // and is in the trash like the rest.
+        let result = if moved || !exists(target) {
+            Ok(())
+        } else {
+            trash.delete(&target.path)
+        };
+        match result {
+            Ok(()) => {
+                report.freed += target.size;
+                report.deleted.insert(target.path.clone());
+                report.trashed.push(target.clone());
+            }
+            Err(e) if needs_trash_fallback(&e) => match quarantine {
+                Some(dir) => match quarantine_path(&target.path, dir) {
+                    Ok(()) => {
+                        report.freed += target.size;
+                        report.deleted.insert(target.path.clone());
+                    }
+                    Err(e) => report.failed.push(e),
+                },
+                None => report.untrashable.push(target.clone()),
+            },
+            Err(e) => report.failed.push(SweeperError::trash(&target.path, &e)),
+        }
+    }
+}
+
+pub fn needs_trash_fallback(err: &trash::Error) -> bool {
+    match err {
+        trash::Error::Os { code, description } => {
+            io_needs_fallback(&std::io::Error::from_raw_os_error(*code))
+                || describes_unsupported(description)
+        }
+        trash::Error::Unknown { description } => describes_unsupported(description),
+        #[cfg(all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        ))]
+        trash::Error::FileSystem { path, source } => {
+            io_needs_fallback(source)
+                || (source.kind() == std::io::ErrorKind::PermissionDenied && is_trash_dir(path))
+        }
+        _ => false,
+    }
+}
+
+fn io_needs_fallback(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::CrossesDevices | std::io::ErrorKind::Unsupported
+    )
+}
+
+fn describes_unsupported(description: &str) -> bool {
+    let description = description.to_lowercase();
+    [
+        "cross-device",
+        "not supported",
+        "unsupported",
+        "valid 'home trash'",
+        "no trash",
+    ]
+    .iter()
+    .any(|phrase| description.contains(phrase))
+}
+
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+))]
+fn is_trash_dir(path: &Path) -> bool {
+    path.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        name == "Trash" || name.starts_with(".Trash")
+    })
+}
+
+fn quarantine_path(path: &Path, dir: &Path) -> error::Result<()> {
+    quarantine_with(path, dir, |from, to| std::fs::rename(from, to))
+}
+
+/// Moves `path` into the quarantine folder with `rename`. The trash usually
+/// refuses a target because it sits on another volume, and so may the
+/// quarantine folder, so a rename across devices falls back to a copy
+/// followed by removing the original.
+fn quarantine_with(
+    path: &Path,
+    dir: &Path,
+    rename: impl Fn(&Path, &Path) -> std::io::Result<()>,
+) -> error::Result<()> {
+    std::fs::create_dir_all(dir).map_err(|e| SweeperError::io(dir, e))?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "item".to_string());
+    let mut destination = dir.join(&name);
+    let mut suffix = 1;
+    while std::fs::symlink_metadata(&destination).is_ok() {
+        destination = dir.join(format!("{}.{}", name, suffix));
+        suffix += 1;
+    }
+    match rename(path, &destination) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            move_by_copy(path, &destination)
+        }
+        result => result,
+    }
+    .map_err(|e| SweeperError::io(path, e))
+}
+
+/// Copies `from` to `to` and removes `from`. A failed copy removes what it
+/// wrote and leaves the original alone.
+fn move_by_copy(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Err(e) = copy_tree(from, to) {
+        let _ = remove_tree(to);
+        return Err(e);
+    }
+    remove_tree(from)
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    for entry in walkdir::WalkDir::new(from) {
+        let entry = entry.map_err(std::io::Error::other)?;
+        let relative = entry.path().strip_prefix(from).unwrap_or(Path::new(""));
+        let destination = to.join(relative);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            std::fs::create_dir(&destination)?;
+        } else if file_type.is_symlink() {
+            copy_link(entry.path(), &destination)?;
+        } else {
+            std::fs::copy(entry.path(), &destination)?;
+            let modified = entry
+                .metadata()
+                .map_err(std::io::Error::other)?
+                .modified()?;
+            std::fs::File::options()
+                .write(true)
+                .open(&destination)?
+                .set_modified(modified)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_link(link: &Path, destination: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(link)?, destination)
+}
+
+#[cfg(not(unix))]
+fn copy_link(link: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::copy(link, destination).map(|_| ())
+}
+
+fn remove_tree(path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingClean {
+    job: CleanJob,
+    due: Instant,
+}
+
+impl PendingClean {
+    pub fn new(job: CleanJob, delay: Duration) -> Self {
+        Self {
+            job,
+            due: Instant::now() + delay,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.job.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.job.targets.is_empty()
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.due.saturating_duration_since(now)
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.due
+    }
+
+    pub fn into_job(self) -> CleanJob {
+        self.job
+    }
+}
+
+pub fn estimated_duration(files: u64) -> Duration {
+    Duration::from_secs(files / FILES_DELETED_PER_SEC)
+}
+
+pub fn duration_hint(estimate: Duration) -> Option<&'static str> {
+    match estimate.as_secs() {
+        0..=9 => None,
+        10..=59 => Some("This may take up to a minute"),
+        60..=599 => Some("This may take a few minutes"),
+        _ => Some("This may take a long time"),
+    }
+}
+
+fn delete_path(path: &Path, dry_run: bool) -> error::Result<()> {
+    if dry_run {
+        return Ok(());
+    }
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+    .map_err(|e| SweeperError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture;
+    use crate::git::GitOutput;
+    use std::cell::RefCell;
+
+    /// Moves trashed paths into a folder of its own, or fails the way it
+    /// is told to.
+    struct FakeTrash {
+        dir: PathBuf,
+        fail_batches: bool,
+        /// Moves this many paths of a batch, then fails the rest of it.
+        partial_batch: Option<usize>,
+        fail: Option<fn() -> trash::Error>,
+        /// Paths refused one at a time with a permission error.
+        refuse: Vec<PathBuf>,
+        trashed: RefCell<Vec<PathBuf>>,
+    }
+
+    impl FakeTrash {
+        fn new(dir: &Path) -> Self {
+            Self {
+                dir: dir.to_path_buf(),
+                fail_batches: false,
+                partial_batch: None,
+                fail: None,
+                refuse: Vec::new(),
+                trashed: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Trasher for FakeTrash {
+        fn delete_all(&self, paths: &[&Path]) -> Result<(), trash::Error> {
+            if let Some(moved) = self.partial_batch {
+                paths
+                    .iter()
+                    .take(moved)
+                    .try_for_each(|path| self.delete(path))?;
+                return Err(trash::Error::Unknown {
+                    description: "batch interrupted".to_string(),
+                });
+            }
+            if self.fail_batches || self.fail.is_some() || !self.refuse.is_empty() {
+                return Err(trash::Error::Unknown {
+                    description: "batch refused".to_string(),
+                });
+            }
+            paths.iter().try_for_each(|path| self.delete(path))
+        }
+
+        fn delete(&self, path: &Path) -> Result<(), trash::Error> {
+            if let Some(fail) = self.fail {
+                return Err(fail());
+            }
+            if self.refuse.iter().any(|refused| refused == path) {
+                return Err(permission_denied());
+            }
+            let mut trashed = self.trashed.borrow_mut();
+            let name = format!(
+                "{}-{}",
+                trashed.len(),
+                path.file_name().unwrap().to_string_lossy()
+            );
+            std::fs::create_dir_all(&self.dir).unwrap();
+            std::fs::rename(path, self.dir.join(name)).unwrap();
+            trashed.push(path.to_path_buf());
+            Ok(())
+        }
+    }
+
+    /// Answers every git call with the same canned output.
+    struct FakeGit(fn() -> std::io::Result<GitOutput>);
+
+    impl GitRunner for FakeGit {
+        fn run(
+            &self,
+            _cwd: &Path,
+            _args: &[&std::ffi::OsStr],
+            _timeout: Duration,
+        ) -> std::io::Result<GitOutput> {
+            (self.0)()
+        }
+    }
+
+    fn not_in_repo() -> std::io::Result<GitOutput> {
+        Ok(GitOutput::Failed {
+            stderr: "fatal: not a git repository (or any of the parent directories): .git"
+                .to_string(),
+        })
+    }
+
+    fn cross_device() -> trash::Error {
+        trash::Error::Unknown {
+            description: "Invalid cross-device link".to_string(),
+        }
+    }
+
+    fn permission_denied() -> trash::Error {
+        trash::Error::Unknown {
+            description: "permission denied".to_string(),
+        }
+    }
+
+    /// Three temp files under `dir`, as clean targets.
+    fn files(dir: &Path) -> Vec<CleanTarget> {
+        let folder = dir.join("files");
+        std::fs::create_dir_all(&folder).unwrap();
+        (0..3)
+            .map(|i| {
+                let path = folder.join(format!("{}.tmp", i));
+                std::fs::write(&path, b"data").unwrap();
+                target(&path)
+            })
+            .collect()
+    }
+
+    fn run(job: CleanJob, trash: &FakeTrash) -> CleanReport {
+        job.run_with(&FakeGit(not_in_repo), trash, &InUsePaths::default(), &[])
+    }
+
+    #[test]
+    fn failed_batch_falls_back_to_one_at_a_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let targets = files(dir.path());
+        let trash = FakeTrash {
+            fail_batches: true,
+            ..FakeTrash::new(&dir.path().join("trash"))
+        };
+
+        let report = run(CleanJob::new(targets.clone(), true, false).unwrap(), &trash);
+        assert_eq!(report.deleted.len(), 3);
+        assert_eq!(report.trashed.len(), 3);
+        assert_eq!(trash.trashed.borrow().len(), 3);
+    }
+
+    #[test]
+    fn interrupted_batch_keeps_what_it_moved_in_the_trash_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut targets = files(dir.path());
+        let missing = dir.path().join("files").join("gone.tmp");
+        targets.push(target(&missing));
+        let trash = FakeTrash {
+            partial_batch: Some(1),
+            ..FakeTrash::new(&dir.path().join("trash"))
+        };
+
+        let report = run(CleanJob::new(targets.clone(), true, false).unwrap(), &trash);
+        assert_eq!(report.deleted.len(), 3);
+        assert_eq!(report.freed, 3);
+        assert_eq!(report.skipped, vec![missing.clone()]);
+        let logged: HashSet<&Path> = report.trashed.iter().map(|t| t.path.as_path()).collect();
+        assert_eq!(logged.len(), 3);
+        assert!(!logged.contains(missing.as_path()));
+        assert_eq!(trash.trashed.borrow().len(), 3);
+    }
+
+    #[test]
+    fn unsupported_trash_falls_back_to_quarantine() {
+        let dir = tempfile::tempdir().unwrap();
+        let targets = files(dir.path());
+        let quarantine = dir.path().join("quarantine");
+        let trash = FakeTrash {
+            fail: Some(cross_device),
+            ..FakeTrash::new(&dir.path().join("trash"))
+        };
+
+        let job = CleanJob::new(targets.clone(), true, false)
+            .unwrap()
+            .with_quarantine(Some(quarantine.clone()));
+        let report = run(job, &trash);
+        assert_eq!(report.failures().count(), 0);
+        assert_eq!(report.deleted.len(), 3);
+        // Quarantined items are not in the trash, so Undo must not expect them.
+        assert!(report.trashed.is_empty());
+        for target in &targets {
+            assert!(!target.path.exists());
+            assert!(quarantine.join(target.path.file_name().unwrap()).exists());
+        }
+    }
+
+    #[test]
+    fn unsupported_trash_without_quarantine_leaves_files_for_the_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let targets = files(dir.path());
+        let trash = FakeTrash {
+            fail: Some(cross_device),
+            ..FakeTrash::new(&dir.path().join("trash"))
+        };
+
+        let report = run(CleanJob::new(targets.clone(), true, false).unwrap(), &trash);
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.untrashable.len(), 3);
+        assert!(targets.iter().all(|target| target.path.exists()));
+    }
+
+    #[test]
+    fn other_trash_errors_fail_without_deleting() {
+        let dir = tempfile::tempdir().unwrap();
+        let targets = files(dir.path());
+        let trash = FakeTrash {
+            fail: Some(permission_denied),
+            ..FakeTrash::new(&dir.path().join("trash"))
+        };
+
+        let job = CleanJob::new(targets.clone(), true, false)
+            .unwrap()
+            .with_quarantine(Some(dir.path().join("quarantine")));
+        let report = run(job, &trash);
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.failed.len(), 3);
+        assert!(targets.iter().all(|target| target.path.exists()));
+    }
+
+    #[test]
+    fn manifest_lists_exactly_the_paths_attempted() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture =
+            fixture::generate(&dir.path().join("home"), &fixture::FixtureSpec::small(7)).unwrap();
+        let manifests = dir.path().join("manifests");
+        let mut targets: Vec<CleanTarget> = fixture
+            .artifacts
+            .iter()
+            .chain(&fixture.temp_files)
+            .map(|path| target(path))
+            .collect();
+        // One target is blocked before the manifest is written, one vanishes
+        // before its permanent delete and two are refused by the trash.
+        let blocked = fixture.artifacts[0].clone();
+        let vanished = fixture.root.join("scratch").join("gone.tmp");
+        targets.push(target(&vanished));
+        let refused = vec![fixture.temp_files[0].clone(), fixture.temp_files[1].clone()];
+        let trash = FakeTrash {
+            refuse: refused.clone(),
+            ..FakeTrash::new(&dir.path().join("trash"))
+        };
+        let in_use = InUsePaths {
+            exe: None,
+            cwd: Some(blocked.join("pkg-0")),
+        };
+
+        let job = CleanJob::new(targets, true, false)
+            .unwrap()
+            .with_permanent(HashSet::from([vanished.clone()]))
+            .with_manifest_dir(Some(manifests.clone()));
+        let report = job.run_with(&FakeGit(not_in_repo), &trash, &in_use, &[]);
+
+        assert_eq!(report.blocked.len(), 1);
+        assert_eq!(report.failed.len(), 3);
+        let failed: HashSet<PathBuf> = report
+            .failed
+            .iter()
+            .map(|e| match e {
+                SweeperError::Io { path, .. } | SweeperError::Trash { path, .. } => path.clone(),
+                other => panic!("unexpected failure {}", other),
+            })
+            .collect();
+        let executed: HashSet<PathBuf> = report.deleted.union(&failed).cloned().collect();
+
+        let written: Vec<PathBuf> = std::fs::read_dir(&manifests)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(written.len(), 1);
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&written[0]).unwrap()).unwrap();
+        let listed: HashSet<PathBuf> = manifest["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| PathBuf::from(entry["path"].as_str().unwrap()))
+            .collect();
+
+        assert_eq!(listed, executed);
+        assert!(!listed.contains(&blocked));
+        assert!(listed.contains(&vanished));
+        assert!(refused
+            .iter()
+            .all(|path| listed.contains(path) && path.exists()));
+    }
+
+    #[test]
+    fn trash_errors_that_fall_back_to_quarantine() {
+        let cases = [
+            (cross_device(), true),
+            (permission_denied(), false),
+            (
+                trash::Error::Unknown {
+                    description: "Operation not supported".to_string(),
+                },
+                true,
+            ),
+            (
+                trash::Error::Os {
+                    // EXDEV on Linux and macOS.
+                    code: 18,
+                    description: String::new(),
+                },
+                true,
+            ),
+            (
+                trash::Error::Os {
+                    code: 13,
+                    description: "Permission denied".to_string(),
+                },
+                false,
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(needs_trash_fallback(&error), expected, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn quarantine_copies_across_devices() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("cache");
+        std::fs::create_dir_all(source.join("nested")).unwrap();
+        std::fs::write(source.join("a.bin"), b"first").unwrap();
+        std::fs::write(source.join("nested").join("b.bin"), b"second").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("a.bin", source.join("link")).unwrap();
+        let before: Vec<(PathBuf, Vec<u8>)> = fixture::snapshot(&source)
+            .unwrap()
+            .into_iter()
+            .map(|(path, bytes)| (path.strip_prefix(&source).unwrap().to_path_buf(), bytes))
+            .collect();
+        let quarantine = dir.path().join("quarantine");
+        let cross_device =
+            |_: &Path, _: &Path| Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices));
+
+        quarantine_with(&source, &quarantine, cross_device).unwrap();
+        assert!(!source.exists());
+        let moved = quarantine.join("cache");
+        let after: Vec<(PathBuf, Vec<u8>)> = fixture::snapshot(&moved)
+            .unwrap()
+            .into_iter()
+            .map(|(path, bytes)| (path.strip_prefix(&moved).unwrap().to_path_buf(), bytes))
+            .collect();
+        assert_eq!(after, before);
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::read_link(moved.join("link")).unwrap(),
+            Path::new("a.bin")
+        );
+
+        // Any other rename error leaves the original where it was.
+        let file = dir.path().join("keep.bin");
+        std::fs::write(&file, b"x").unwrap();
+        let denied =
+            |_: &Path, _: &Path| Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(quarantine_with(&file, &quarantine, denied).is_err());
+        assert!(file.exists());
+        assert!(!quarantine.join("keep.bin").exists());
+    }
+
+    #[test]
+    fn paths_in_use_are_blocked() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        let build = project.join("target");
+        std::fs::create_dir_all(build.join("debug")).unwrap();
+        std::fs::write(build.join("debug").join("sweeper"), b"binary").unwrap();
+        let in_use = InUsePaths {
+            exe: Some(build.join("debug").join("sweeper")),
+            cwd: Some(project.join("src")),
+        };
+        let trash = FakeTrash::new(&dir.path().join("trash"));
+
+        let job = CleanJob::new(vec![target(&build)], false, false).unwrap();
+        let report = job.run_with(&FakeGit(not_in_repo), &trash, &in_use, &[]);
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.blocked.len(), 1);
+        assert!(report.blocked[0].contains("running Sweeper executable"));
+        assert!(build.join("debug").join("sweeper").exists());
+
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let in_use = InUsePaths {
+            exe: None,
+            ..in_use
+        };
+        let job = CleanJob::new(vec![target(&project)], false, false).unwrap();
+        let report = job.run_with(&FakeGit(not_in_repo), &trash, &in_use, &[]);
+        assert!(report.blocked[0].contains("current working directory"));
+        assert!(project.exists());
+    }
+
+    #[test]
+    fn git_tracked_paths_are_blocked() {
+        let dir = tempfile::tempdir().unwrap();
+        let build = dir.path().join("build");
+        std::fs::create_dir_all(&build).unwrap();
+        std::fs::write(build.join("checked-in.txt"), b"x").unwrap();
+        let trash = FakeTrash::new(&dir.path().join("trash"));
+        let tracked = FakeGit(|| Ok(GitOutput::Success(b"build/a\0build/b\0".to_vec())));
+
+        let checked = CleanTarget {
+            check_git: true,
+            ..target(&build)
+        };
+        let job = CleanJob::new(vec![checked.clone()], false, false).unwrap();
+        let report = job.run_with(&tracked, &trash, &InUsePaths::default(), &[]);
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.blocked.len(), 1);
+        assert!(report.blocked[0].contains("2 tracked files"));
+        assert!(build.join("checked-in.txt").exists());
+
+        // When git can't answer, the clean goes ahead but says so.
+        let broken = FakeGit(|| {
+            Ok(GitOutput::Failed {
+                stderr: "fatal: index file corrupt".to_string(),
+            })
+        });
+        let job = CleanJob::new(vec![checked.clone()], false, false).unwrap();
+        let report = job.run_with(&broken, &trash, &InUsePaths::default(), &[]);
+        assert_eq!(
+            report.unchecked,
+            [format!("{} (fatal: index file corrupt)", build.display())]
+        );
+        assert!(report.deleted.contains(&build));
+
+        std::fs::create_dir_all(&build).unwrap();
+        let timed_out = FakeGit(|| Ok(GitOutput::TimedOut));
+        let job = CleanJob::new(vec![checked], false, false).unwrap();
+        let report = job.run_with(&timed_out, &trash, &InUsePaths::default(), &[]);
+        assert_eq!(report.unchecked.len(), 1);
+        assert!(report.deleted.contains(&build));
+        assert!(!build.exists());
+    }
+
+    /// A global npm package with a bin link, under a fake home.
+    #[cfg(unix)]
+    fn global_package(home: &Path) -> (Vec<GlobalPackageRoot>, PathBuf, PathBuf) {
+        let roots = CategoryPatterns::global_package_roots_in(home, &[]);
+        let package = roots[0].modules.join("left-pad");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(package.join("cli.js"), b"#!/usr/bin/env node").unwrap();
+        std::fs::create_dir_all(&roots[0].bin).unwrap();
+        let link = roots[0].bin.join("left-pad");
+        std::os::unix::fs::symlink("../lib/node_modules/left-pad/cli.js", &link).unwrap();
+        (roots, package, link)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn trashing_a_global_package_trashes_its_bin_link_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let (roots, package, link) = global_package(&home);
+        let trash = FakeTrash::new(&dir.path().join("trash"));
+
+        let job = CleanJob::new(vec![target(&package)], true, false).unwrap();
+        let report = job.run_with(&SystemGit, &trash, &InUsePaths::default(), &roots);
+
+        assert_eq!(report.failures().count(), 0);
+        assert_eq!(*trash.trashed.borrow(), vec![package.clone(), link.clone()]);
+        let logged: Vec<&Path> = report.trashed.iter().map(|t| t.path.as_path()).collect();
+        assert_eq!(logged, vec![package.as_path(), link.as_path()]);
+        assert!(report.deleted.contains(&link));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn deleting_a_global_package_deletes_its_bin_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let (roots, package, link) = global_package(&home);
+        let trash = FakeTrash::new(&dir.path().join("trash"));
+
+        let job = CleanJob::new(vec![target(&package)], false, false).unwrap();
+        let report = job.run_with(&SystemGit, &trash, &InUsePaths::default(), &roots);
+
+        assert!(std::fs::symlink_metadata(&link).is_err());
+        assert!(report.deleted.contains(&link));
+        assert!(report.trashed.is_empty());
+        assert!(trash.trashed.borrow().is_empty());
+    }
+
+    fn target(path: &Path) -> CleanTarget {
+        CleanTarget {
+            path: path.to_path_buf(),
+            size: 1,
+            check_git: false,
+        }
+    }
+
+    #[test]
+    fn cancelling_during_the_countdown_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("files");
+        std::fs::create_dir(&root).unwrap();
+        let files: Vec<PathBuf> = (0..3).map(|i| root.join(format!("{}.tmp", i))).collect();
+        for file in &files {
+            std::fs::write(file, b"keep me").unwrap();
+        }
+        let manifests = dir.path().join("manifests");
+        let before = fixture::snapshot(dir.path()).unwrap();
+
+        let job = CleanJob::new(files.iter().map(|f| target(f)).collect(), false, false)
+            .unwrap()
+            .with_manifest_dir(Some(manifests.clone()));
+        let pending = PendingClean::new(job, Duration::from_secs(10));
+        let now = Instant::now();
+        assert!(!pending.is_due(now));
+        assert_eq!(pending.len(), 3);
+        assert!(pending.remaining(now) > Duration::from_secs(9));
+
+        // Cancelling drops the pending clean without running it.
+        drop(pending);
+        assert_eq!(fixture::snapshot(dir.path()).unwrap(), before);
+        assert!(!manifests.exists());
+    }
+
+    #[test]
+    fn a_due_pending_clean_runs_its_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.tmp");
+        std::fs::write(&file, b"x").unwrap();
+
+        let job = CleanJob::new(vec![target(&file)], false, false).unwrap();
+        let pending = PendingClean::new(job, Duration::from_millis(5));
+        assert!(pending.is_due(Instant::now() + Duration::from_millis(5)));
+        let report = pending.into_job().run();
+        assert!(report.deleted.contains(&file));
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn selection_drops_repeats_and_nested_targets() {
+        let normalized = |paths: &[&str]| {
+            let targets = paths.iter().map(|path| target(Path::new(path))).collect();
+            let (kept, merged) = normalize_selection(targets);
+            let kept: Vec<PathBuf> = kept.into_iter().map(|target| target.path).collect();
+            (kept, merged)
+        };
+        let paths = |list: &[&str]| -> Vec<PathBuf> { list.iter().map(PathBuf::from).collect() };
+
+        let cases: [(&[&str], &[&str], usize); 7] = [
+            (&[], &[], 0),
+            (&["/a/b"], &["/a/b"], 0),
+            (&["/a/b", "/a/b"], &["/a/b"], 1),
+            (&["/a/b/c/d", "/a/b", "/a/b/c"], &["/a/b"], 2),
+            // Sharing a name prefix doesn't make one folder contain the other.
+            (&["/a/bc", "/a/b"], &["/a/b", "/a/bc"], 0),
+            (&["/a/b-x", "/a/b/c", "/a/b"], &["/a/b", "/a/b-x"], 1),
+            (&["/x/y", "/a", "/a/z", "/x"], &["/a", "/x"], 2),
+        ];
+        for (input, expected, merged) in cases {
+            assert_eq!(normalized(input), (paths(expected), merged), "{:?}", input);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn in_use_conflicts_compare_resolved_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let bin = root.join("app/bin");
+        let work = root.join("work");
+        let sibling = root.join("workshop");
+        for path in [&bin, &work, &sibling, &work.join("sub")] {
+            std::fs::create_dir_all(path).unwrap();
+        }
+        std::fs::write(bin.join("sweeper"), "").unwrap();
+        std::os::unix::fs::symlink(root.join("app"), root.join("app-link")).unwrap();
+
+        let in_use = InUsePaths {
+            exe: Some(bin.join("sweeper")),
+            cwd: Some(work.clone()),
+        };
+        let exe = Some("contains the running Sweeper executable");
+        let cwd = Some("contains the current working directory");
+        let cases = [
+            (root.join("app"), exe),
+            (bin.join("sweeper"), exe),
+            // Reached through a symlink, it is still the same folder.
+            (root.join("app-link"), exe),
+            (root.join("app-link/bin/../bin"), exe),
+            (work.clone(), cwd),
+            (root.clone(), exe),
+            (work.join("sub"), None),
+            (sibling, None),
+            (root.join("app/lib"), None),
+        ];
+        for (target, expected) in cases {
+            assert_eq!(in_use.conflict(&target), expected, "{}", target.display());
+        }
+        assert_eq!(InUsePaths::default().conflict(&root), None);
+    }
+
+    #[test]
+    fn deletion_time_hints_scale_with_file_count() {
+        let hint = |files| duration_hint(estimated_duration(files));
+        assert_eq!(hint(0), None);
+        assert_eq!(hint(49_999), None);
+        assert_eq!(hint(50_000), Some("This may take up to a minute"));
+        assert_eq!(hint(299_999), Some("This may take up to a minute"));
+        assert_eq!(hint(300_000), Some("This may take a few minutes"));
+        assert_eq!(hint(3_000_000), Some("This may take a long time"));
+        assert_eq!(estimated_duration(12_500), Duration::from_secs(2));
+    }
+}
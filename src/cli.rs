@@ -0,0 +1,230 @@
+use crate::categories::FileCategory;
+use crate::scanner;
+use bytesize::ByteSize;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "sweeper",
+    version,
+    about,
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 1..,
+        help = "Open directly into a scan of these folders"
+    )]
+    pub scan: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        requires = "scan",
+        help = "Name the app asking for the --scan, and hand it to an open window if there is one"
+    )]
+    pub from: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        conflicts_with = "scan",
+        help = "Scan the folder in a sweeper://scan?path=… link, in the open window if there is one"
+    )]
+    pub open_url: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "WORKSPACE",
+        conflicts_with_all = ["scan", "open_url"],
+        help = "Clean a CI workspace headlessly and print a JSON summary"
+    )]
+    pub ci: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SIZE",
+        requires = "ci",
+        help = "Exit with an error if less than this much space was freed (e.g. 50GB)"
+    )]
+    pub require_free: Option<ByteSize>,
+
+    #[arg(
+        long,
+        requires = "ci",
+        help = "Report what would be deleted without deleting"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "ci",
+        help = "Write scan metrics to FILE (Prometheus text, or JSON for a .json path)"
+    )]
+    pub metrics_out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        hide = true,
+        conflicts_with_all = ["scan", "ci", "open_url"],
+        help = "Run a timed scan of DIR and print a per-pass breakdown"
+    )]
+    pub bench_scan: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["ci", "bench_scan"],
+        help = "Open read-only: report findings but never delete, trash or restore anything"
+    )]
+    pub audit: bool,
+}
+
+#[derive(Debug, Subcommand)]
+#[command(after_help = EXIT_CODES)]
+pub enum Command {
+    #[command(about = "Scan without opening the window and print what was found")]
+    Scan(ScanArgs),
+    #[command(about = "Scan and delete the items matching the given filters")]
+    Clean(CleanArgs),
+    #[command(about = "Scan and print totals per category")]
+    Report(ScanArgs),
+    #[command(
+        about = "Run the scheduled scans that are due; call this hourly from cron or launchd"
+    )]
+    Scheduled,
+}
+
+const EXIT_CODES: &str = "Exit codes: 0 found or cleaned items, 1 error, \
+3 nothing found, 4 some deletions failed";
+
+#[derive(Debug, Args)]
+pub struct ScanArgs {
+    #[arg(value_name = "PATH", help = "Only scan these folders")]
+    pub paths: Vec<PathBuf>,
+
+    #[arg(long, help = "Print machine-readable JSON instead of a table")]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CleanArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    #[arg(
+        long,
+        value_name = "CATEGORY",
+        required = true,
+        value_parser = parse_category,
+        help = "Clean items in this category, e.g. dev-artifacts (repeatable)"
+    )]
+    pub category: Vec<FileCategory>,
+
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        help = "Only clean items untouched for at least this long (e.g. 30d, 4w)"
+    )]
+    pub older_than: Option<u64>,
+
+    #[arg(short, long, help = "Delete without asking for confirmation")]
+    pub yes: bool,
+
+    #[arg(long, conflicts_with = "permanent", help = "Move items to the trash")]
+    pub trash: bool,
+
+    #[arg(long, help = "Delete items permanently instead of using the trash")]
+    pub permanent: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "no_dry_run",
+        help = "Report what would be deleted without deleting"
+    )]
+    pub dry_run: bool,
+
+    #[arg(long, help = "Delete even if dry_run is set in the config")]
+    pub no_dry_run: bool,
+}
+
+impl CleanArgs {
+    pub fn use_trash(&self, configured: bool) -> bool {
+        (configured || self.trash) && !self.permanent
+    }
+
+    pub fn dry_run(&self, configured: bool) -> bool {
+        (configured || self.dry_run) && !self.no_dry_run
+    }
+}
+
+pub fn category_slug(category: FileCategory) -> String {
+    category.name().to_lowercase().replace(' ', "-")
+}
+
+fn parse_category(input: &str) -> Result<FileCategory, String> {
+    let wanted = input.trim().to_lowercase().replace(['_', ' '], "-");
+    FileCategory::ALL
+        .into_iter()
+        .find(|category| category_slug(*category) == wanted)
+        .ok_or_else(|| {
+            let known: Vec<String> = FileCategory::ALL.into_iter().map(category_slug).collect();
+            format!("unknown category, expected one of: {}", known.join(", "))
+        })
+}
+
+fn parse_age(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("\"{}\" is not an age", trimmed))?;
+    let days = match unit {
+        "" | "d" => 1,
+        "w" => 7,
+        "m" => 30,
+        "y" => 365,
+        other => return Err(format!("unknown age unit \"{}\", use d, w, m or y", other)),
+    };
+    Ok(value.saturating_mul(days))
+}
+
+impl Cli {
+    pub fn scan_paths(&self) -> Result<Option<Vec<PathBuf>>, String> {
+        scope(&self.scan)
+    }
+}
+
+impl ScanArgs {
+    pub fn scope(&self) -> Result<Option<Vec<PathBuf>>, String> {
+        scope(&self.paths)
+    }
+}
+
+fn scope(paths: &[PathBuf]) -> Result<Option<Vec<PathBuf>>, String> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    for path in paths {
+        if !path.exists() {
+            return Err(format!("scan path does not exist: {}", path.display()));
+        }
+        if !path.is_dir() {
+            return Err(format!("scan path is not a directory: {}", path.display()));
+        }
+    }
+
+    Ok(Some(scanner::normalize_scope(paths)))
+}
@@ -1,21 +1,79 @@
-use crate::categories::FileCategory;
+use crate::categories::{self, CategoryPatterns, FileCategory, VersionedCache};
+use crate::error::{self, SweeperError};
+use crate::scanner::ScannedItem;
+use crate::schedule::{self, ScheduleEntry};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const CI_CONFIDENCE: f32 = 0.98;
+pub const DISMISSED_PRUNE_AFTER_SCANS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeleteMode {
+    DryRun,
+    Trash,
+    Permanent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReloadDecision {
+    Unchanged,
+    Apply { affects_scan: bool },
+    Conflict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub scan_paths: Vec<PathBuf>,
     pub exclude_patterns: Vec<String>,
     pub enabled_categories: HashSet<FileCategory>,
     pub large_file_threshold: u64,
     pub stale_days_threshold: u64,
+    pub protect_active_days: u64,
     pub old_download_days: u64,
     pub show_hidden: bool,
     pub follow_symlinks: bool,
+    pub same_filesystem_only: bool,
     pub max_depth: u32,
+    pub shallow_depth: u32,
     pub use_trash: bool,
     pub dry_run: bool,
+    pub recommend_quick_regen: bool,
+    pub check_for_updates: bool,
+    pub list_density: ListDensity,
+    pub confidence_overrides: HashMap<FileCategory, f32>,
+    pub explorer_depth: usize,
+    pub min_sizes: HashMap<FileCategory, u64>,
+    pub dismissed: HashMap<PathBuf, u32>,
+    pub show_dismissed: bool,
+    pub ignored_paths: Vec<PathBuf>,
+    pub deletion_grace_period: bool,
+    pub control_server: bool,
+    pub allow_remote_clean: bool,
+    pub keep_newest: HashMap<String, usize>,
+    pub quarantine_dir: Option<PathBuf>,
+    pub manifest_dir: Option<PathBuf>,
+    pub redownloadable_domains: Vec<String>,
+    pub container_markers: HashMap<String, String>,
+    pub metrics_path: Option<PathBuf>,
+    #[serde(default)]
+    pub seen_ruleset_version: u32,
+    pub crash_report_paths: bool,
+    pub full_volume_percent: u8,
+    pub sniff_logs: bool,
+    pub large_item_limit: u64,
+    pub schedule: Vec<ScheduleEntry>,
+    pub audit_mode: bool,
 }
 
 impl Default for Config {
@@ -30,6 +88,8 @@ impl Default for Config {
         enabled_categories.insert(FileCategory::TempFile);
         enabled_categories.insert(FileCategory::LargeFile);
         enabled_categories.insert(FileCategory::OldDownload);
+        enabled_categories.insert(FileCategory::GlobalPackage);
+        enabled_categories.insert(FileCategory::AppContainer);
 
         Self {
             scan_paths: Self::default_scan_paths(),
@@ -37,17 +97,82 @@ impl Default for Config {
             enabled_categories,
             large_file_threshold: 100 * 1024 * 1024,
             stale_days_threshold: 90,
+            protect_active_days: 7,
             old_download_days: 30,
             show_hidden: true,
             follow_symlinks: false,
+            same_filesystem_only: false,
             max_depth: 20,
+            shallow_depth: 6,
             use_trash: true,
             dry_run: false,
+            recommend_quick_regen: false,
+            check_for_updates: false,
+            list_density: ListDensity::default(),
+            confidence_overrides: HashMap::new(),
+            explorer_depth: 3,
+            min_sizes: Self::default_min_sizes(),
+            dismissed: HashMap::new(),
+            show_dismissed: false,
+            ignored_paths: Vec::new(),
+            deletion_grace_period: true,
+            control_server: false,
+            allow_remote_clean: false,
+            keep_newest: HashMap::new(),
+            quarantine_dir: None,
+            manifest_dir: None,
+            redownloadable_domains: Self::default_redownloadable_domains(),
+            container_markers: CategoryPatterns::container_markers(),
+            metrics_path: None,
+            seen_ruleset_version: categories::RULESET_VERSION,
+            crash_report_paths: false,
+            full_volume_percent: 90,
+            sniff_logs: true,
+            large_item_limit: 50 * 1024 * 1024 * 1024,
+            schedule: Vec::new(),
+            audit_mode: false,
         }
     }
 }
 
 impl Config {
+    pub fn delete_mode(&self) -> DeleteMode {
+        if self.dry_run {
+            DeleteMode::DryRun
+        } else if self.use_trash {
+            DeleteMode::Trash
+        } else {
+            DeleteMode::Permanent
+        }
+    }
+
+    pub fn default_redownloadable_domains() -> Vec<String> {
+        [
+            "github.com",
+            "objects.githubusercontent.com",
+            "release-assets.githubusercontent.com",
+            "gitlab.com",
+            "registry.npmjs.org",
+            "files.pythonhosted.org",
+            "static.crates.io",
+            "dl.google.com",
+            "download.mozilla.org",
+            "download.jetbrains.com",
+            "desktop.docker.com",
+            "download.visualstudio.microsoft.com",
+            "download.developer.apple.com",
+            "updates.cdn-apple.com",
+            "releases.ubuntu.com",
+            "cdimage.debian.org",
+            "nodejs.org",
+            "go.dev",
+            "dl.k8s.io",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
     pub fn default_scan_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
 
@@ -136,22 +261,503 @@ impl Config {
         excludes
     }
 
-    pub fn load() -> Self {
-        let config_path = Self::config_path();
-        if config_path.exists() {
-            if let Ok(contents) = std::fs::read_to_string(&config_path) {
-                if let Ok(config) = serde_json::from_str(&contents) {
-                    return config;
-                }
+    pub fn default_min_sizes() -> HashMap<FileCategory, u64> {
+        const MB: u64 = 1024 * 1024;
+        HashMap::from([
+            (FileCategory::DevArtifact, MB),
+            (FileCategory::PackageCache, MB),
+            (FileCategory::IdeCache, MB),
+            (FileCategory::BrowserCache, 10 * MB),
+            (FileCategory::SystemCache, MB),
+            (FileCategory::LogFile, 10 * MB),
+            (FileCategory::TempFile, 0),
+            (FileCategory::OldDownload, MB),
+            (FileCategory::Unused, 10 * MB),
+            (FileCategory::AppContainer, MB),
+            (FileCategory::GlobalPackage, MB),
+        ])
+    }
+
+    pub fn keep_newest(&self, cache: &VersionedCache) -> usize {
+        self.keep_newest
+            .get(cache.id)
+            .copied()
+            .unwrap_or(cache.default_keep)
+            .max(1)
+    }
+
+    pub fn min_size(&self, category: FileCategory) -> u64 {
+        self.min_sizes.get(&category).copied().unwrap_or(0)
+    }
+
+    pub fn ci_preset(workspace: PathBuf) -> Self {
+        let enabled_categories: HashSet<FileCategory> = [
+            FileCategory::DevArtifact,
+            FileCategory::PackageCache,
+            FileCategory::IdeCache,
+            FileCategory::SystemCache,
+            FileCategory::LogFile,
+            FileCategory::TempFile,
+        ]
+        .into_iter()
+        .collect();
+        let confidence_overrides = enabled_categories
+            .iter()
+            .map(|category| (*category, CI_CONFIDENCE))
+            .collect();
+
+        Self {
+            scan_paths: vec![workspace],
+            enabled_categories,
+            use_trash: false,
+            confidence_overrides,
+            ..Self::default()
+        }
+    }
+
+    pub fn confidence_override(&self, category: FileCategory) -> Option<f32> {
+        self.confidence_overrides
+            .get(&category)
+            .filter(|value| value.is_finite())
+            .map(|value| value.clamp(0.0, 1.0))
+    }
+
+    pub fn base_confidence(&self, category: FileCategory) -> f32 {
+        self.confidence_override(category)
+            .unwrap_or_else(|| category.base_confidence())
+    }
+
+    pub fn set_confidence_override(&mut self, category: FileCategory, value: Option<f32>) {
+        match value.filter(|value| value.is_finite()) {
+            Some(value) => {
+                self.confidence_overrides
+                    .insert(category, value.clamp(0.0, 1.0));
+            }
+            None => {
+                self.confidence_overrides.remove(&category);
+            }
+        }
+    }
+
+    pub fn dismissed_key(&self, path: &Path) -> Option<PathBuf> {
+        if self.dismissed.is_empty() {
+            return None;
+        }
+        if self.dismissed.contains_key(path) {
+            return Some(path.to_path_buf());
+        }
+        path.canonicalize()
+            .ok()
+            .filter(|canonical| self.dismissed.contains_key(canonical))
+    }
+
+    pub fn dismiss(&mut self, path: &Path) {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.dismissed.insert(canonical, 0);
+    }
+
+    pub fn undismiss(&mut self, path: &Path) {
+        self.dismissed.remove(path);
+    }
+
+    pub fn reconcile_dismissed(
+        &mut self,
+        seen: &HashSet<PathBuf>,
+        scanned: impl Fn(&Path) -> bool,
+    ) -> usize {
+        for (path, missed) in self.dismissed.iter_mut() {
+            if seen.contains(path) {
+                *missed = 0;
+            } else if scanned(path) {
+                *missed += 1;
+            }
+        }
+        let before = self.dismissed.len();
+        self.dismissed
+            .retain(|_, missed| *missed < DISMISSED_PRUNE_AFTER_SCANS);
+        before - self.dismissed.len()
+    }
+
+    /// Counts a scan of `scope` (everything when `None`) toward forgetting
+    /// the dismissed items it did not find, and returns how many were
+    /// forgotten. Only a scan that ran to the end shows an item is gone, so
+    /// for a cancelled or failed one this changes nothing and returns `None`.
+    pub fn reconcile_scan(
+        &mut self,
+        items: &[ScannedItem],
+        scope: Option<&[PathBuf]>,
+        finished: bool,
+    ) -> Option<usize> {
+        if !finished || self.dismissed.is_empty() {
+            return None;
+        }
+        let seen: HashSet<PathBuf> = items
+            .iter()
+            .filter_map(|item| self.dismissed_key(&item.path))
+            .collect();
+        Some(self.reconcile_dismissed(&seen, |path| {
+            scope.is_none_or(|roots| roots.iter().any(|root| path.starts_with(root)))
+        }))
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignored_paths
+            .iter()
+            .any(|ignored| path.starts_with(ignored))
+    }
+
+    /// Adds `path` to the ignore list, dropping entries it now covers.
+    pub fn ignore(&mut self, path: &Path) {
+        if self.is_ignored(path) {
+            return;
+        }
+        self.ignored_paths
+            .retain(|ignored| !ignored.starts_with(path));
+        self.ignored_paths.push(path.to_path_buf());
+    }
+
+    pub fn unignore(&mut self, path: &Path) {
+        self.ignored_paths.retain(|ignored| ignored != path);
+    }
+
+    pub fn affects_scan(&self, other: &Config) -> bool {
+        self.scan_paths != other.scan_paths
+            || self.exclude_patterns != other.exclude_patterns
+            || self.ignored_paths != other.ignored_paths
+            || self.enabled_categories != other.enabled_categories
+            || self.large_file_threshold != other.large_file_threshold
+            || self.stale_days_threshold != other.stale_days_threshold
+            || self.protect_active_days != other.protect_active_days
+            || self.old_download_days != other.old_download_days
+            || self.show_hidden != other.show_hidden
+            || self.follow_symlinks != other.follow_symlinks
+            || self.max_depth != other.max_depth
+            || self.shallow_depth != other.shallow_depth
+            || self.confidence_overrides != other.confidence_overrides
+            || self.min_sizes != other.min_sizes
+            || self.keep_newest != other.keep_newest
+            || self.redownloadable_domains != other.redownloadable_domains
+            || self.container_markers != other.container_markers
+            || self.sniff_logs != other.sniff_logs
+    }
+
+    pub fn reload_decision(synced: &Config, current: &Config, disk: &Config) -> ReloadDecision {
+        if disk == synced || disk == current {
+            ReloadDecision::Unchanged
+        } else if current != synced {
+            ReloadDecision::Conflict
+        } else {
+            ReloadDecision::Apply {
+                affects_scan: current.affects_scan(disk),
             }
         }
-        Self::default()
     }
 
-    fn config_path() -> PathBuf {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_depth == 0 {
+            return Err("max_depth must be at least 1".to_string());
+        }
+        if self.large_file_threshold == 0 {
+            return Err("large_file_threshold must be greater than 0".to_string());
+        }
+        if self.shallow_depth == 0 {
+            return Err("shallow_depth must be at least 1".to_string());
+        }
+        if self.explorer_depth == 0 {
+            return Err("explorer_depth must be at least 1".to_string());
+        }
+        if !(1..=100).contains(&self.full_volume_percent) {
+            return Err("full_volume_percent must be between 1 and 100".to_string());
+        }
+        if self.large_item_limit == 0 {
+            return Err("large_item_limit must be greater than 0".to_string());
+        }
+        if let Some((id, _)) = self.keep_newest.iter().find(|(_, keep)| **keep == 0) {
+            return Err(format!("keep_newest for {} must be at least 1", id));
+        }
+        schedule::validate(&self.schedule)
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, String> {
+        let config: Self = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn read_from_disk() -> error::Result<Self> {
+        let path = Self::config_path();
+        let contents = std::fs::read_to_string(&path).map_err(|e| SweeperError::io(&path, e))?;
+        Self::from_json(&contents).map_err(SweeperError::Config)
+    }
+
+    pub fn disk_modified() -> Option<SystemTime> {
+        std::fs::metadata(Self::config_path())
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+
+    pub fn load() -> error::Result<Self> {
+        if !Self::config_path().exists() {
+            return Ok(Self::default());
+        }
+        Self::read_from_disk()
+    }
+
+    pub fn save(&self) -> error::Result<()> {
+        let config_path = Self::config_path();
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| SweeperError::io(parent, e))?;
+        }
+        let contents =
+            serde_json::to_string_pretty(self).map_err(|e| SweeperError::Config(e.to_string()))?;
+        std::fs::write(&config_path, contents).map_err(|e| SweeperError::io(&config_path, e))
+    }
+
+    pub fn config_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("sweeper")
-            .join("config.json")
+    }
+
+    pub fn default_metrics_path() -> PathBuf {
+        Self::config_dir().join("sweeper.prom")
+    }
+
+    pub fn config_path() -> PathBuf {
+        Self::config_dir().join("config.json")
+    }
+}
+
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("\"{}\" is not a size", input.trim()))?;
+
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        other => return Err(format!("unknown size unit \"{}\"", other)),
+    };
+
+    let bytes = value * multiplier as f64;
+    if !bytes.is_finite() || bytes >= u64::MAX as f64 {
+        return Err(format!("\"{}\" is too large", input.trim()));
+    }
+    Ok(bytes.round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ScannedItem;
+    use chrono::Utc;
+
+    fn item(path: &str) -> ScannedItem {
+        ScannedItem::new(path.into(), 1, FileCategory::TempFile, Utc::now())
+    }
+
+    fn config_with_dismissed(paths: &[&str]) -> Config {
+        Config {
+            dismissed: paths.iter().map(|path| (PathBuf::from(path), 0)).collect(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn cancelled_or_failed_scans_never_count_toward_forgetting() {
+        let mut config = config_with_dismissed(&["/gone/a.tmp"]);
+        for _ in 0..DISMISSED_PRUNE_AFTER_SCANS * 2 {
+            assert_eq!(config.reconcile_scan(&[], None, false), None);
+        }
+        assert_eq!(config.dismissed[Path::new("/gone/a.tmp")], 0);
+    }
+
+    #[test]
+    fn finished_scans_forget_missing_items_after_the_limit() {
+        let mut config = config_with_dismissed(&["/gone/a.tmp", "/here/b.tmp"]);
+        let found = [item("/here/b.tmp")];
+        for _ in 1..DISMISSED_PRUNE_AFTER_SCANS {
+            assert_eq!(config.reconcile_scan(&found, None, true), Some(0));
+        }
+        assert_eq!(config.reconcile_scan(&found, None, true), Some(1));
+        assert!(config.dismissed.contains_key(Path::new("/here/b.tmp")));
+        assert!(!config.dismissed.contains_key(Path::new("/gone/a.tmp")));
+    }
+
+    #[test]
+    fn scoped_scans_only_count_items_inside_the_scope() {
+        let mut config = config_with_dismissed(&["/a/x.tmp", "/b/y.tmp"]);
+        let scope = [PathBuf::from("/a")];
+        config.reconcile_scan(&[], Some(&scope), true);
+        assert_eq!(config.dismissed[Path::new("/a/x.tmp")], 1);
+        assert_eq!(config.dismissed[Path::new("/b/y.tmp")], 0);
+    }
+
+    #[test]
+    fn seeing_an_item_again_resets_its_count() {
+        let mut config = config_with_dismissed(&["/a/x.tmp"]);
+        config.reconcile_scan(&[], None, true);
+        config.reconcile_scan(&[item("/a/x.tmp")], None, true);
+        assert_eq!(config.dismissed[Path::new("/a/x.tmp")], 0);
+    }
+
+    #[test]
+    fn keeping_zero_versions_is_rejected() {
+        let err = Config::from_json(r#"{"keep_newest": {"gradle": 0}}"#).unwrap_err();
+        assert_eq!(err, "keep_newest for gradle must be at least 1");
+        assert!(Config::from_json(r#"{"keep_newest": {"gradle": 1}}"#).is_ok());
+
+        // A value that skipped validation still keeps the newest version.
+        let config = Config {
+            keep_newest: [("gradle".to_string(), 0)].into(),
+            ..Config::default()
+        };
+        let cache = VersionedCache {
+            id: "gradle",
+            root: String::new(),
+            category: FileCategory::PackageCache,
+            default_keep: 2,
+        };
+        assert_eq!(config.keep_newest(&cache), 1);
+    }
+
+    #[test]
+    fn sizes_parse_with_any_unit_case_and_spacing() {
+        let cases = [
+            ("0", 0),
+            ("512", 512),
+            ("512b", 512),
+            ("1k", 1 << 10),
+            ("1KB", 1 << 10),
+            ("2 MiB", 2 << 20),
+            ("1.5gb", 3 << 29),
+            ("  10 GB  ", 10 << 30),
+            ("1T", 1 << 40),
+            ("0.5 kb", 512),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_size(input), Ok(expected), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn malformed_sizes_are_rejected() {
+        let cases = [
+            ("", "\"\" is not a size"),
+            ("   ", "\"\" is not a size"),
+            ("GB", "\"GB\" is not a size"),
+            ("-1GB", "\"-1GB\" is not a size"),
+            ("1.2.3", "\"1.2.3\" is not a size"),
+            ("10 PB", "unknown size unit \"pb\""),
+            ("1e3", "unknown size unit \"e3\""),
+            (
+                "18446744073709551616",
+                "\"18446744073709551616\" is too large",
+            ),
+            ("99999999999 TB", "\"99999999999 TB\" is too large"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_size(input), Err(expected.to_string()), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn reload_decisions_follow_who_changed_what() {
+        let synced = Config::default();
+        let edited_here = Config {
+            use_trash: !synced.use_trash,
+            ..synced.clone()
+        };
+        let cosmetic_on_disk = Config {
+            show_dismissed: !synced.show_dismissed,
+            ..synced.clone()
+        };
+        let scan_on_disk = Config {
+            max_depth: synced.max_depth + 1,
+            ..synced.clone()
+        };
+
+        let cases = [
+            // Nothing changed anywhere.
+            (&synced, &synced, ReloadDecision::Unchanged),
+            // Only the app changed; the file still holds what was synced.
+            (&edited_here, &synced, ReloadDecision::Unchanged),
+            // The file already holds the app's own edit.
+            (&edited_here, &edited_here, ReloadDecision::Unchanged),
+            // Only the file changed.
+            (
+                &synced,
+                &cosmetic_on_disk,
+                ReloadDecision::Apply {
+                    affects_scan: false,
+                },
+            ),
+            (
+                &synced,
+                &scan_on_disk,
+                ReloadDecision::Apply { affects_scan: true },
+            ),
+            // Both changed, differently.
+            (&edited_here, &scan_on_disk, ReloadDecision::Conflict),
+        ];
+        for (index, (current, disk, expected)) in cases.into_iter().enumerate() {
+            assert_eq!(
+                Config::reload_decision(&synced, current, disk),
+                expected,
+                "case {}",
+                index
+            );
+        }
+    }
+
+    #[test]
+    fn confidence_overrides_are_clamped_to_a_probability() {
+        let mut config = Config::default();
+        config.set_confidence_override(FileCategory::LogFile, Some(1.5));
+        config.set_confidence_override(FileCategory::TempFile, Some(-0.2));
+        config.set_confidence_override(FileCategory::IdeCache, Some(0.4));
+        assert_eq!(config.confidence_overrides[&FileCategory::LogFile], 1.0);
+        assert_eq!(config.confidence_overrides[&FileCategory::TempFile], 0.0);
+        assert_eq!(config.base_confidence(FileCategory::IdeCache), 0.4);
+
+        config.set_confidence_override(FileCategory::IdeCache, Some(f32::NAN));
+        assert_eq!(config.confidence_override(FileCategory::IdeCache), None);
+        assert_eq!(
+            config.base_confidence(FileCategory::IdeCache),
+            FileCategory::IdeCache.base_confidence()
+        );
+        config.set_confidence_override(FileCategory::LogFile, None);
+        assert!(!config
+            .confidence_overrides
+            .contains_key(&FileCategory::LogFile));
+
+        // Hand-edited values outside the range are clamped when read.
+        let edited =
+            Config::from_json(r#"{"confidence_overrides": {"SystemCache": 3.0}}"#).unwrap();
+        assert_eq!(
+            edited.confidence_override(FileCategory::SystemCache),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn confidence_overrides_survive_a_save_and_load() {
+        let mut config = Config::default();
+        config.set_confidence_override(FileCategory::DevArtifact, Some(0.25));
+        config.set_confidence_override(FileCategory::Duplicate, Some(1.0));
+
+        let json = serde_json::to_string_pretty(&config).unwrap();
+        let loaded = Config::from_json(&json).unwrap();
+        assert_eq!(loaded.confidence_overrides, config.confidence_overrides);
+        assert_eq!(loaded.base_confidence(FileCategory::DevArtifact), 0.25);
     }
 }
@@ -0,0 +1,460 @@
+use crate::categories::FileCategory;
+use crate::cleaner::CleanTarget;
+use crate::duplicates;
+use crate::scanner::ScannedItem;
+use crossbeam_channel::{Receiver, Sender};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const SOCKET_NAME: &str = "control.sock";
+pub const TOKEN_NAME: &str = "control.token";
+const STAGING_NAME: &str = ".control.staging";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(600);
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const UNAUTHORIZED: i64 = -32001;
+const CALL_FAILED: i64 = -32000;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ItemFilter {
+    pub categories: Option<Vec<FileCategory>>,
+    pub min_size: Option<u64>,
+    pub min_confidence: Option<f32>,
+    pub stale_only: bool,
+    pub limit: Option<usize>,
+}
+
+impl ItemFilter {
+    pub fn matches(&self, item: &ScannedItem) -> bool {
+        self.categories
+            .as_ref()
+            .is_none_or(|categories| categories.contains(&item.category))
+            && self.min_size.is_none_or(|min| item.size >= min)
+            && self.min_confidence.is_none_or(|min| item.confidence >= min)
+            && (!self.stale_only || item.is_stale)
+    }
+
+    /// Whether the filter narrows anything down. An empty filter matches
+    /// every item, which is never what a clean call means.
+    pub fn has_criteria(&self) -> bool {
+        self.categories.is_some()
+            || self.min_size.is_some()
+            || self.min_confidence.is_some()
+            || self.stale_only
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanMode {
+    Trash,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub enum Call {
+    Status,
+    Scan {
+        scope: Option<Vec<PathBuf>>,
+    },
+    Items(ItemFilter),
+    Clean {
+        paths: Vec<PathBuf>,
+        filter: Option<ItemFilter>,
+        mode: CleanMode,
+    },
+}
+
+pub type Reply = Result<Value, String>;
+
+pub struct ControlRequest {
+    pub call: Call,
+    pub reply: Sender<Reply>,
+}
+
+pub struct ControlServer {
+    pub requests: Receiver<ControlRequest>,
+    socket_path: PathBuf,
+}
+
+impl ControlServer {
+    #[cfg(unix)]
+    pub fn start(dir: &Path) -> std::io::Result<Self> {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        use std::os::unix::net::UnixListener;
+
+        std::fs::create_dir_all(dir)?;
+        let token = load_or_create_token(&dir.join(TOKEN_NAME))?;
+        let socket_path = dir.join(SOCKET_NAME);
+        if socket_path.exists() {
+            std::fs::remove_file(&socket_path)?;
+        }
+
+        // Bound inside a folder only we can enter and moved into place once
+        // it is private, so no other user can connect in between.
+        let staging = dir.join(STAGING_NAME);
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+        let staged = staging.join(SOCKET_NAME);
+        let listener = UnixListener::bind(&staged)?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, &socket_path)?;
+        std::fs::remove_dir(&staging)?;
+
+        let (sender, requests) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                let token = token.clone();
+                std::thread::spawn(move || {
+                    if let Ok(reader) = stream.try_clone() {
+                        serve(BufReader::new(reader), stream, &token, &sender);
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            requests,
+            socket_path,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_dir: &Path) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the control server is only available on Unix",
+        ))
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+#[cfg(unix)]
+fn load_or_create_token(path: &Path) -> std::io::Result<String> {
+    use std::io::Read;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    if let Ok(token) = std::fs::read_to_string(path) {
+        let token = token.trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+
+    let mut bytes = [0u8; 32];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    let token = hex::encode(bytes);
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn serve(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    token: &str,
+    sender: &Sender<ControlRequest>,
+) {
+    let mut authenticated = false;
+
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                if send(&mut writer, error(Value::Null, PARSE_ERROR, e.to_string())).is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            if send(&mut writer, error(id, INVALID_REQUEST, "missing method")).is_err() {
+                return;
+            }
+            continue;
+        };
+
+        let response = if method == "auth" {
+            let given = params.get("token").and_then(Value::as_str).unwrap_or("");
+            authenticated = tokens_match(token, given);
+            if authenticated {
+                success(id, json!({ "authenticated": true }))
+            } else {
+                error(id, UNAUTHORIZED, "invalid token")
+            }
+        } else if !authenticated {
+            error(id, UNAUTHORIZED, "call auth with the token first")
+        } else {
+            match parse_call(method, params) {
+                Ok(call) => dispatch(call, id, &mut writer, sender),
+                Err((code, message)) => error(id, code, message),
+            }
+        };
+
+        if send(&mut writer, response).is_err() {
+            return;
+        }
+    }
+}
+
+fn parse_call(method: &str, params: Value) -> Result<Call, (i64, String)> {
+    let params = if params.is_null() { json!({}) } else { params };
+    let invalid = |e: serde_json::Error| (INVALID_PARAMS, e.to_string());
+
+    match method {
+        "status" => Ok(Call::Status),
+        "scan" => {
+            #[derive(Deserialize)]
+            struct Params {
+                #[serde(default)]
+                scope: Option<Vec<PathBuf>>,
+            }
+            let params: Params = serde_json::from_value(params).map_err(invalid)?;
+            Ok(Call::Scan {
+                scope: params.scope,
+            })
+        }
+        "items" => Ok(Call::Items(
+            serde_json::from_value(params.get("filter").cloned().unwrap_or(params.clone()))
+                .map_err(invalid)?,
+        )),
+        "clean" => {
+            #[derive(Deserialize)]
+            struct Params {
+                #[serde(default)]
+                paths: Vec<PathBuf>,
+                #[serde(default)]
+                filter: Option<ItemFilter>,
+                mode: CleanMode,
+            }
+            let params: Params = serde_json::from_value(params).map_err(invalid)?;
+            if params.paths.is_empty() && params.filter.is_none() {
+                return Err((INVALID_PARAMS, "clean needs paths or a filter".to_string()));
+            }
+            if params
+                .filter
+                .as_ref()
+                .is_some_and(|filter| !filter.has_criteria())
+            {
+                return Err((
+                    INVALID_PARAMS,
+                    "clean filter needs at least one criterion".to_string(),
+                ));
+            }
+            Ok(Call::Clean {
+                paths: params.paths,
+                filter: params.filter,
+                mode: params.mode,
+            })
+        }
+        other => Err((METHOD_NOT_FOUND, format!("unknown method {}", other))),
+    }
+}
+
+fn dispatch(
+    call: Call,
+    id: Value,
+    writer: &mut impl Write,
+    sender: &Sender<ControlRequest>,
+) -> Value {
+    let is_scan = matches!(call, Call::Scan { .. });
+    let started = match request(call, sender) {
+        Ok(result) => result,
+        Err(message) => return error(id, CALL_FAILED, message),
+    };
+    if !is_scan {
+        return success(id, started);
+    }
+
+    loop {
+        std::thread::sleep(PROGRESS_INTERVAL);
+        let status = match request(Call::Status, sender) {
+            Ok(status) => status,
+            Err(message) => return error(id, CALL_FAILED, message),
+        };
+        if status.get("scanning").and_then(Value::as_bool) != Some(true) {
+            return success(id, status);
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "progress",
+            "params": status,
+        });
+        if send(writer, notification).is_err() {
+            return Value::Null;
+        }
+    }
+}
+
+fn request(call: Call, sender: &Sender<ControlRequest>) -> Reply {
+    let (reply, response) = crossbeam_channel::bounded(1);
+    sender
+        .send(ControlRequest { call, reply })
+        .map_err(|_| "Sweeper is shutting down".to_string())?;
+    response
+        .recv_timeout(REPLY_TIMEOUT)
+        .map_err(|_| "Sweeper did not answer".to_string())?
+}
+
+fn send(writer: &mut impl Write, message: Value) -> std::io::Result<()> {
+    if message.is_null() {
+        return Ok(());
+    }
+    writeln!(writer, "{}", message)?;
+    writer.flush()
+}
+
+fn success(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+/// The reply to an `items` call.
+pub fn list_items(items: &[ScannedItem], filter: &ItemFilter) -> Value {
+    Value::Array(
+        items
+            .iter()
+            .filter(|item| filter.matches(item))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .map(item_json)
+            .collect(),
+    )
+}
+
+/// What a `clean` call removes: the listed paths and the items matching the
+/// filter, never the copy a duplicate set keeps.
+pub fn clean_targets(
+    items: &[ScannedItem],
+    paths: &[PathBuf],
+    filter: Option<&ItemFilter>,
+    downloads: Option<&Path>,
+) -> Vec<CleanTarget> {
+    let all: Vec<&ScannedItem> = items.iter().collect();
+    let keepers = duplicates::keepers(&all, downloads);
+    let paths: HashSet<&PathBuf> = paths.iter().collect();
+    items
+        .iter()
+        .filter(|item| !keepers.contains(&item.path) && !duplicates::is_unverified(item))
+        .filter(|item| {
+            paths.contains(&item.path) || filter.is_some_and(|filter| filter.matches(item))
+        })
+        .flat_map(CleanTarget::for_item)
+        .collect()
+}
+
+pub fn item_json(item: &ScannedItem) -> Value {
+    json!({
+        "path": item.path,
+        "size": item.size,
+        "category": item.category,
+        "confidence": item.confidence,
+        "is_stale": item.is_stale,
+        "source": item.source,
+        "origin": item.origin,
+        "managed_by": item.managed_by,
+        "file_count": item.file_count,
+        "modified": item.modified.to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_rejects_an_empty_filter() {
+        let err = parse_call("clean", json!({ "filter": {}, "mode": "trash" })).unwrap_err();
+        assert_eq!(err.0, INVALID_PARAMS);
+        let err = parse_call(
+            "clean",
+            json!({ "filter": { "limit": 5 }, "mode": "trash" }),
+        )
+        .unwrap_err();
+        assert_eq!(err.0, INVALID_PARAMS);
+        assert!(parse_call("clean", json!({ "mode": "trash" })).is_err());
+    }
+
+    #[test]
+    fn clean_accepts_paths_or_a_narrowing_filter() {
+        assert!(matches!(
+            parse_call("clean", json!({ "paths": ["/tmp/x"], "mode": "delete" })),
+            Ok(Call::Clean { .. })
+        ));
+        assert!(matches!(
+            parse_call(
+                "clean",
+                json!({ "filter": { "stale_only": true }, "mode": "trash" })
+            ),
+            Ok(Call::Clean { .. })
+        ));
+    }
+
+    #[test]
+    fn unauthenticated_calls_are_refused() {
+        let (sender, requests) = crossbeam_channel::unbounded();
+        let input = b"{\"id\":1,\"method\":\"status\"}\n{\"id\":2,\"method\":\"auth\",\"params\":{\"token\":\"nope\"}}\n";
+        let mut output = Vec::new();
+        serve(&input[..], &mut output, "secret", &sender);
+        let replies: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(replies.len(), 2);
+        assert!(replies
+            .iter()
+            .all(|reply| reply["error"]["code"] == UNAUTHORIZED));
+        assert!(requests.try_recv().is_err());
+    }
+}
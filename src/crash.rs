@@ -0,0 +1,248 @@
+use crate::activity::ActivityLog;
+use crate::config::Config;
+use chrono::Utc;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+
+const RECENT_ACTIVITY: usize = 5;
+const REDACTED: &str = "<path>";
+
+pub fn report_path() -> PathBuf {
+    Config::config_dir().join("crash-report.txt")
+}
+
+pub fn install() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Scan passes run on worker threads and recover from panics, so only the UI thread's
+        // panics mean the app went down.
+        if std::thread::current().name() != Some("main") {
+            previous(info);
+            return;
+        }
+        let include_paths = Config::read_from_disk().is_ok_and(|config| config.crash_report_paths);
+        let report = build_report(
+            &panic_message(info),
+            &std::backtrace::Backtrace::force_capture().to_string(),
+            &ActivityLog::load(),
+            include_paths,
+        );
+        let path = report_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if std::fs::write(&path, report).is_ok() {
+            eprintln!(
+                "Sweeper crashed; a report was written to {}",
+                path.display()
+            );
+        }
+        previous(info);
+    }));
+}
+
+pub fn pending_report() -> Option<PathBuf> {
+    let path = report_path();
+    path.is_file().then_some(path)
+}
+
+pub fn dismiss_report() -> std::io::Result<PathBuf> {
+    let path = report_path();
+    let archived = path.with_file_name("crash-report.previous.txt");
+    std::fs::rename(&path, &archived)?;
+    Ok(archived)
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string());
+    match info.location() {
+        Some(location) => format!("{} ({}:{})", payload, location.file(), location.line()),
+        None => payload,
+    }
+}
+
+pub fn build_report(
+    message: &str,
+    backtrace: &str,
+    activity: &ActivityLog,
+    include_paths: bool,
+) -> String {
+    let scrub = |text: &str| {
+        if include_paths {
+            text.to_string()
+        } else {
+            redact_paths(text)
+        }
+    };
+
+    let mut report = String::new();
+    let _ = writeln!(report, "Sweeper crash report");
+    let _ = writeln!(report, "Version: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        report,
+        "OS: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(report, "Time: {}", Utc::now().to_rfc3339());
+    let _ = writeln!(report, "Paths included: {}", include_paths);
+    let _ = writeln!(report);
+    let _ = writeln!(report, "Panic: {}", scrub(message));
+    let _ = writeln!(report);
+    let _ = writeln!(report, "Recent activity:");
+    let recent: Vec<_> = activity
+        .entries
+        .iter()
+        .rev()
+        .take(RECENT_ACTIVITY)
+        .collect();
+    if recent.is_empty() {
+        let _ = writeln!(report, "  (none)");
+    }
+    for entry in recent {
+        let path = if include_paths {
+            entry.path.display().to_string()
+        } else {
+            REDACTED.to_string()
+        };
+        let _ = writeln!(
+            report,
+            "  batch {} • {} • {} bytes • {:?} • {}",
+            entry.batch,
+            entry.trashed_at.to_rfc3339(),
+            entry.size,
+            entry.status,
+            path
+        );
+    }
+    let _ = writeln!(report);
+    let _ = writeln!(report, "Backtrace:");
+    let _ = writeln!(report, "{}", scrub(backtrace));
+    report
+}
+
+/// Replaces anything that looks like an absolute filesystem path with `<path>`,
+/// keeping Rust source locations (`…/file.rs:12`) so backtraces stay useful.
+/// The home directory is shortened to `~` first so source paths don't leak the user name.
+pub fn redact_paths(text: &str) -> String {
+    let text = match dirs::home_dir() {
+        Some(home) if home.parent().is_some() => text.replace(home.to_string_lossy().as_ref(), "~"),
+        _ => text.to_string(),
+    };
+    text.split_inclusive(char::is_whitespace)
+        .map(|token| {
+            let trimmed = token.trim_end();
+            let word = trimmed
+                .trim_matches(|c| matches!(c, '"' | '\'' | '(' | ')' | ',' | ';' | ':' | '`'));
+            if looks_like_path(word) && !is_source_location(word) {
+                token.replacen(word, REDACTED, 1)
+            } else {
+                token.to_string()
+            }
+        })
+        .collect()
+}
+
+fn looks_like_path(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    let windows_drive = bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    (word.starts_with('/') && word.len() > 1)
+        || word.starts_with("~/")
+        || word.starts_with("\\\\")
+        || windows_drive
+}
+
+fn is_source_location(word: &str) -> bool {
+    let mut file = word;
+    while let Some((head, tail)) = file.rsplit_once(':') {
+        if tail.is_empty() || !tail.chars().all(|c| c.is_ascii_digit()) {
+            break;
+        }
+        file = head;
+    }
+    file.ends_with(".rs")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::{TrashStatus, TrashedEntry};
+
+    fn activity(paths: &[&str]) -> ActivityLog {
+        ActivityLog {
+            entries: paths
+                .iter()
+                .enumerate()
+                .map(|(index, path)| TrashedEntry {
+                    batch: index as u64,
+                    path: PathBuf::from(path),
+                    size: 10,
+                    trashed_at: Utc::now(),
+                    status: TrashStatus::InTrash,
+                })
+                .collect(),
+            ..ActivityLog::default()
+        }
+    }
+
+    #[test]
+    fn paths_are_redacted_but_source_locations_are_kept() {
+        let cases = [
+            (
+                "failed to open /srv/data/secret.db: denied",
+                "failed to open <path>: denied",
+            ),
+            ("at src/scanner/mod.rs:120:9", "at src/scanner/mod.rs:120:9"),
+            (
+                "at /rustc/abc/library/std/src/panicking.rs:689:5",
+                "at /rustc/abc/library/std/src/panicking.rs:689:5",
+            ),
+            ("copying \"C:\\Users\\me\\a.txt\"", "copying \"<path>\""),
+            ("share \\\\nas\\backups gone", "share <path> gone"),
+            ("(~/notes.txt) missing", "(<path>) missing"),
+            ("divide by zero / oops", "divide by zero / oops"),
+            ("/srv/a.rs.bak:3", "<path>"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(redact_paths(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn the_home_directory_never_appears_in_a_redacted_report() {
+        let Some(home) = dirs::home_dir().filter(|home| home.parent().is_some()) else {
+            return;
+        };
+        let message = format!("no space left writing {}/big.iso", home.display());
+        let report = build_report(&message, "", &activity(&[]), false);
+        assert!(!report.contains(home.to_string_lossy().as_ref()));
+        assert!(report.contains("Panic: no space left writing <path>"));
+    }
+
+    #[test]
+    fn reports_list_recent_activity_newest_first() {
+        let log = activity(&["/a", "/b", "/c", "/d", "/e", "/f", "/g"]);
+
+        let redacted = build_report("boom", "bt", &log, false);
+        assert!(redacted.contains("Paths included: false"));
+        assert_eq!(redacted.matches("• <path>").count(), RECENT_ACTIVITY);
+        assert!(redacted.find("batch 6").unwrap() < redacted.find("batch 2").unwrap());
+        assert!(!redacted.contains("batch 1 "));
+
+        let full = build_report("boom", "bt", &log, true);
+        assert!(full.contains("• /g"));
+        assert!(!full.contains("<path>"));
+
+        let empty = build_report("boom", "bt", &activity(&[]), false);
+        assert!(empty.contains("  (none)"));
+    }
+}
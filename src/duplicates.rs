@@ -0,0 +1,211 @@
+use crate::categories::FileCategory;
+use crate::scanner::ScannedItem;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub struct DuplicateGroup<'a> {
+    pub hash: &'a str,
+    pub members: Vec<&'a ScannedItem>,
+    pub keeper: usize,
+}
+
+impl<'a> DuplicateGroup<'a> {
+    pub fn keeper(&self) -> &'a ScannedItem {
+        self.members[self.keeper]
+    }
+
+    pub fn is_keeper(&self, path: &Path) -> bool {
+        self.keeper().path == path
+    }
+
+    pub fn removable(&self) -> impl Iterator<Item = &'a ScannedItem> + '_ {
+        self.members
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| *i != self.keeper)
+            .map(|(_, item)| *item)
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.members.iter().any(|item| item.sampled_hash)
+    }
+
+    pub fn wasted_size(&self) -> u64 {
+        self.removable().map(|item| item.size).sum()
+    }
+
+    pub fn newest(&self) -> &'a ScannedItem {
+        self.members
+            .iter()
+            .copied()
+            .max_by(|a, b| {
+                a.modified
+                    .cmp(&b.modified)
+                    .then_with(|| b.path.cmp(&a.path))
+            })
+            .unwrap_or(self.keeper())
+    }
+}
+
+pub fn group_duplicates<'a>(
+    items: &[&'a ScannedItem],
+    downloads: Option<&Path>,
+) -> Vec<DuplicateGroup<'a>> {
+    let mut by_hash: HashMap<&'a str, Vec<&'a ScannedItem>> = HashMap::new();
+    for item in items {
+        if item.category != FileCategory::Duplicate {
+            continue;
+        }
+        if let Some(hash) = item.hash.as_deref() {
+            by_hash.entry(hash).or_default().push(item);
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup<'a>> = by_hash
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(hash, mut members)| {
+            members.sort_by(|a, b| a.path.cmp(&b.path));
+            // The scanner already chose one; items loaded from an older
+            // snapshot carry no flag and are picked here the same way.
+            let keeper = members
+                .iter()
+                .position(|item| item.is_keeper)
+                .unwrap_or_else(|| pick_keeper(&members, downloads));
+            DuplicateGroup {
+                hash,
+                members,
+                keeper,
+            }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.wasted_size()
+            .cmp(&a.wasted_size())
+            .then_with(|| a.hash.cmp(b.hash))
+    });
+    groups
+}
+
+/// The copy each duplicate set keeps, for callers that select by filter
+/// rather than through the Duplicates tab.
+pub fn keepers(items: &[&ScannedItem], downloads: Option<&Path>) -> HashSet<PathBuf> {
+    group_duplicates(items, downloads)
+        .iter()
+        .map(|group| group.keeper().path.clone())
+        .collect()
+}
+
+/// A duplicate matched only on sampled chunks of a large file. The copies
+/// may still differ elsewhere, so only the app cleans these, after the user
+/// verifies or confirms them; the CLI and the control socket skip them.
+pub fn is_unverified(item: &ScannedItem) -> bool {
+    item.category == FileCategory::Duplicate && item.sampled_hash
+}
+
+pub fn pick_keeper(members: &[&ScannedItem], downloads: Option<&Path>) -> usize {
+    let in_downloads = |item: &ScannedItem| downloads.is_some_and(|dir| item.path.starts_with(dir));
+
+    members
+        .iter()
+        .enumerate()
+        .max_by(|(ia, a), (ib, b)| {
+            (!in_downloads(a))
+                .cmp(&!in_downloads(b))
+                .then_with(|| b.modified.cmp(&a.modified))
+                .then_with(|| ib.cmp(ia))
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+pub fn fully_selected<'g, 'a>(
+    groups: &'g [DuplicateGroup<'a>],
+    selected: &HashSet<PathBuf>,
+) -> Vec<&'g DuplicateGroup<'a>> {
+    groups
+        .iter()
+        .filter(|group| {
+            group
+                .members
+                .iter()
+                .all(|item| selected.contains(&item.path))
+        })
+        .collect()
+}
+
+pub fn safe_selection(groups: &[DuplicateGroup]) -> Vec<PathBuf> {
+    groups
+        .iter()
+        .flat_map(|group| group.removable().map(|item| item.path.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::CleanTarget;
+    use chrono::{Duration, Utc};
+
+    fn copy(path: &str, days_old: i64) -> ScannedItem {
+        let modified = Utc::now() - Duration::days(days_old);
+        let mut item = ScannedItem::new(
+            PathBuf::from(path),
+            2 * 1024 * 1024,
+            FileCategory::Duplicate,
+            modified,
+        );
+        item.hash = Some("abc".to_string());
+        item
+    }
+
+    #[test]
+    fn keeper_prefers_copies_outside_downloads_then_the_oldest() {
+        let items = [
+            copy("/home/u/Downloads/a.bin", 30),
+            copy("/home/u/docs/a.bin", 1),
+            copy("/home/u/media/a.bin", 5),
+        ];
+        let refs: Vec<&ScannedItem> = items.iter().collect();
+        let groups = group_duplicates(&refs, Some(Path::new("/home/u/Downloads")));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].keeper().path, Path::new("/home/u/media/a.bin"));
+        assert_eq!(groups[0].removable().count(), 2);
+    }
+
+    #[test]
+    fn flagged_keeper_wins_over_the_heuristic() {
+        let mut items = [
+            copy("/home/u/docs/a.bin", 1),
+            copy("/home/u/media/a.bin", 5),
+        ];
+        items[0].is_keeper = true;
+        let refs: Vec<&ScannedItem> = items.iter().collect();
+        let kept = keepers(&refs, None);
+        assert_eq!(kept, HashSet::from([PathBuf::from("/home/u/docs/a.bin")]));
+    }
+
+    #[test]
+    fn safe_selection_never_includes_a_keeper() {
+        let items = [
+            copy("/a/1.bin", 3),
+            copy("/b/1.bin", 2),
+            copy("/c/1.bin", 1),
+        ];
+        let refs: Vec<&ScannedItem> = items.iter().collect();
+        let groups = group_duplicates(&refs, None);
+        let selection: HashSet<PathBuf> = safe_selection(&groups).into_iter().collect();
+        assert_eq!(selection.len(), 2);
+        assert!(!selection.contains(&groups[0].keeper().path));
+        assert!(fully_selected(&groups, &selection).is_empty());
+    }
+
+    #[test]
+    fn keepers_produce_no_clean_targets() {
+        let mut item = copy("/a/1.bin", 1);
+        assert_eq!(CleanTarget::for_item(&item).len(), 1);
+        item.is_keeper = true;
+        assert!(CleanTarget::for_item(&item).is_empty());
+    }
+}
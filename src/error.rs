@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SweeperError {
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{}: permission denied", path.display())]
+    PermissionDenied { path: PathBuf },
+    #[error("{0} isn't supported on this platform")]
+    Unsupported(&'static str),
+    #[error("{}: could not move to the trash: {reason}", path.display())]
+    Trash { path: PathBuf, reason: String },
+    #[error("invalid config: {0}")]
+    Config(String),
+    #[error("cancelled")]
+    Cancelled,
+    #[error("Sweeper is running in audit mode; nothing can be deleted")]
+    AuditMode,
+}
+
+impl SweeperError {
+    pub fn io(path: &Path, source: std::io::Error) -> Self {
+        let path = path.to_path_buf();
+        if source.kind() == std::io::ErrorKind::PermissionDenied {
+            Self::PermissionDenied { path }
+        } else {
+            Self::Io { path, source }
+        }
+    }
+
+    pub fn trash(path: &Path, err: &trash::Error) -> Self {
+        Self::Trash {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, SweeperError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn io_errors_keep_the_path_and_single_out_permission_problems() {
+        let path = Path::new("/srv/cache");
+
+        let denied = SweeperError::io(path, ErrorKind::PermissionDenied.into());
+        assert!(matches!(&denied, SweeperError::PermissionDenied { path: p } if p == path));
+        assert_eq!(denied.to_string(), "/srv/cache: permission denied");
+
+        let missing = SweeperError::io(
+            path,
+            std::io::Error::new(ErrorKind::NotFound, "no such file"),
+        );
+        assert_eq!(missing.to_string(), "/srv/cache: no such file");
+        assert_eq!(missing.source().unwrap().to_string(), "no such file");
+    }
+
+    #[test]
+    fn scans_of_unreadable_roots_fail_with_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("gone");
+        let scanner = crate::scanner::Scanner::with_scope(
+            crate::config::Config::default(),
+            vec![missing.clone()],
+        );
+        match scanner.scan() {
+            Err(SweeperError::Io { path, source }) => {
+                assert_eq!(path, missing);
+                assert_eq!(source.kind(), ErrorKind::NotFound);
+            }
+            other => panic!(
+                "expected an io error, got {:?}",
+                other.map(|items| items.len())
+            ),
+        }
+    }
+}
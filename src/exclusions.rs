@@ -0,0 +1,84 @@
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExclusionImport {
+    pub added: Vec<String>,
+    pub duplicates: Vec<String>,
+    pub unsupported: Vec<String>,
+}
+
+impl ExclusionImport {
+    pub fn plan(existing: &[String], contents: &str) -> Self {
+        let mut import = Self::default();
+        for pattern in parse(contents) {
+            if pattern.starts_with('!') {
+                import.unsupported.push(pattern);
+            } else if existing.contains(&pattern) || import.added.contains(&pattern) {
+                import.duplicates.push(pattern);
+            } else {
+                import.added.push(pattern);
+            }
+        }
+        import
+    }
+}
+
+pub fn parse(contents: &str) -> Vec<String> {
+    contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(|line| line.trim_end_matches('\r').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.strip_prefix("\\#") {
+            Some(rest) => format!("#{}", rest),
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+pub fn export(patterns: &[String]) -> String {
+    let mut contents = String::from("# Sweeper exclusions\n");
+    for pattern in patterns {
+        if pattern.starts_with('#') {
+            contents.push('\\');
+        }
+        contents.push_str(pattern);
+        contents.push('\n');
+    }
+    contents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_line_endings_and_a_bom_are_ignored() {
+        let contents = "\u{feff}# from Windows\r\n*.bak\r\n\r\n  node_modules  \r\n\\#literal\r\n";
+        assert_eq!(parse(contents), ["*.bak", "node_modules", "#literal"]);
+    }
+
+    #[test]
+    fn mixed_and_missing_trailing_newlines_parse_the_same() {
+        assert_eq!(parse("a\nb\r\nc"), ["a", "b", "c"]);
+        assert!(parse("").is_empty());
+        assert!(parse("\u{feff}").is_empty());
+    }
+
+    #[test]
+    fn export_round_trips_through_parse() {
+        let patterns = vec![
+            "*.iso".to_string(),
+            "#hash-prefixed".to_string(),
+            "/tmp/keep me".to_string(),
+        ];
+        assert_eq!(parse(&export(&patterns)), patterns);
+    }
+
+    #[test]
+    fn import_plan_splits_new_duplicate_and_negated_patterns() {
+        let existing = vec!["target".to_string()];
+        let plan = ExclusionImport::plan(&existing, "target\n*.log\n!keep.log\n*.log\n");
+        assert_eq!(plan.added, ["*.log"]);
+        assert_eq!(plan.duplicates, ["target", "*.log"]);
+        assert_eq!(plan.unsupported, ["!keep.log"]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Deterministic synthetic home folders for the integration tests and
+//! benchmarks. The same spec and seed always produce the same tree, so a
+//! failing test or a slow benchmark can be reproduced exactly.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const KB: u64 = 1024;
+const MB: u64 = 1024 * KB;
+
+/// How old generated files are made to look, so dev artifacts read as stale
+/// and old downloads as old.
+const AGE: Duration = Duration::from_secs(120 * 24 * 60 * 60);
+
+/// What to put in a generated tree.
+#[derive(Debug, Clone)]
+pub struct FixtureSpec {
+    pub seed: u64,
+    /// Projects with a marker file, a few sources and a dev artifact folder.
+    pub projects: usize,
+    /// Files written into each project's artifact folder.
+    pub artifact_files: usize,
+    /// Total size of each artifact folder. Anything under the dev artifact
+    /// minimum (1 MB) is walked but not reported.
+    pub artifact_bytes: u64,
+    /// Plain folders whose files match no category.
+    pub plain_dirs: usize,
+    pub files_per_dir: usize,
+    /// Pairs of identical files large enough for the duplicate pass.
+    pub duplicate_pairs: usize,
+    /// Small `.tmp` and `.bak` files.
+    pub temp_files: usize,
+}
+
+impl FixtureSpec {
+    /// A tree small enough to build in every test that needs one.
+    pub fn small(seed: u64) -> Self {
+        Self {
+            seed,
+            projects: 3,
+            artifact_files: 8,
+            artifact_bytes: MB + 256 * KB,
+            plain_dirs: 3,
+            files_per_dir: 5,
+            duplicate_pairs: 2,
+            temp_files: 4,
+        }
+    }
+
+    /// A wide tree of small files for timing the walk rather than the disk.
+    pub fn bench(seed: u64) -> Self {
+        Self {
+            seed,
+            projects: 60,
+            artifact_files: 80,
+            artifact_bytes: 320 * KB,
+            plain_dirs: 40,
+            files_per_dir: 50,
+            duplicate_pairs: 4,
+            temp_files: 40,
+        }
+    }
+}
+
+/// The paths a generated tree is expected to produce, so tests can compare
+/// what a scan reports against what was written.
+#[derive(Debug, Clone, Default)]
+pub struct Fixture {
+    pub root: PathBuf,
+    /// Dev artifact folders (`node_modules`, `target`, ...), one per project.
+    pub artifacts: Vec<PathBuf>,
+    /// Project files outside the artifact folders.
+    pub sources: Vec<PathBuf>,
+    /// Files in the plain folders.
+    pub plain: Vec<PathBuf>,
+    pub temp_files: Vec<PathBuf>,
+    /// Each pair holds the same bytes under two different folders.
+    pub duplicates: Vec<(PathBuf, PathBuf)>,
+    /// Every regular file written.
+    pub files: usize,
+}
+
+impl Fixture {
+    /// Every regular file under the root, sorted, with its contents. Taken
+    /// before and after an operation to prove what it touched.
+    pub fn snapshot(&self) -> io::Result<Vec<(PathBuf, Vec<u8>)>> {
+        snapshot(&self.root)
+    }
+}
+
+/// Writes the tree for `spec` under `root`, which should be empty.
+pub fn generate(root: &Path, spec: &FixtureSpec) -> io::Result<Fixture> {
+    let mut rng = Rng(spec.seed);
+    let mut fixture = Fixture {
+        root: root.to_path_buf(),
+        ..Fixture::default()
+    };
+    let old = SystemTime::now() - AGE;
+
+    for index in 0..spec.projects {
+        let (kind, marker, artifact) = PROJECT_KINDS[index % PROJECT_KINDS.len()];
+        let project = root.join("projects").join(format!("{}-{}", kind, index));
+        fs::create_dir_all(project.join("src"))?;
+        for (name, len) in [(marker, 200), ("README.md", 500), ("src/main.txt", 2 * KB)] {
+            let path = project.join(name);
+            write_file(&path, len, &mut rng, old)?;
+            fixture.sources.push(path);
+        }
+
+        let dir = project.join(artifact);
+        let per_file = (spec.artifact_bytes / spec.artifact_files.max(1) as u64).max(1);
+        for file in 0..spec.artifact_files {
+            let sub = dir.join(format!("pkg-{}", file % 4));
+            fs::create_dir_all(&sub)?;
+            write_file(
+                &sub.join(format!("part-{}.bin", file)),
+                per_file,
+                &mut rng,
+                old,
+            )?;
+        }
+        fixture.files += spec.artifact_files;
+        fixture.artifacts.push(dir);
+    }
+
+    for index in 0..spec.plain_dirs {
+        let dir = root.join("documents").join(format!("folder-{}", index));
+        fs::create_dir_all(&dir)?;
+        for file in 0..spec.files_per_dir {
+            let path = dir.join(format!("note-{}.txt", file));
+            let len = KB + rng.below(8 * KB);
+            write_file(&path, len, &mut rng, old)?;
+            fixture.plain.push(path);
+        }
+    }
+
+    if spec.temp_files > 0 {
+        let dir = root.join("scratch");
+        fs::create_dir_all(&dir)?;
+        for index in 0..spec.temp_files {
+            let ext = if index % 2 == 0 { "tmp" } else { "bak" };
+            let path = dir.join(format!("draft-{}.{}", index, ext));
+            write_file(&path, 512 + rng.below(4 * KB), &mut rng, old)?;
+            fixture.temp_files.push(path);
+        }
+    }
+
+    for index in 0..spec.duplicate_pairs {
+        let original = root.join("media").join(format!("clip-{}.bin", index));
+        let copy = root.join("backup").join(format!("clip-{} copy.bin", index));
+        let len = MB + rng.below(64 * KB);
+        write_file(&original, len, &mut rng, old)?;
+        fs::create_dir_all(copy.parent().unwrap_or(root))?;
+        fs::copy(&original, &copy)?;
+        set_modified(&copy, old)?;
+        fixture.duplicates.push((original, copy));
+    }
+
+    fixture.files += fixture.sources.len()
+        + fixture.plain.len()
+        + fixture.temp_files.len()
+        + fixture.duplicates.len() * 2;
+    Ok(fixture)
+}
+
+/// Two sparse files above the sampled-hash threshold that agree on every
+/// sampled chunk but differ by one byte between them, in `a/` and `b/`
+/// under `root`. Sparse, so they cost almost nothing on disk.
+pub fn sampled_lookalikes(root: &Path) -> io::Result<(PathBuf, PathBuf)> {
+    let len = crate::scanner::hashing::SAMPLED_HASH_THRESHOLD + 64 * MB;
+    let mut pair = Vec::new();
+    for (dir, byte) in [("a", 1u8), ("b", 2u8)] {
+        let path = root.join(dir).join("disk.img");
+        fs::create_dir_all(root.join(dir))?;
+        let file = File::create(&path)?;
+        file.set_len(len)?;
+        // Between the first and middle samples.
+        write_at(&file, 100 * MB, &[byte])?;
+        file.set_modified(SystemTime::now() - AGE)?;
+        pair.push(path);
+    }
+    let b = pair.pop().unwrap_or_default();
+    let a = pair.pop().unwrap_or_default();
+    Ok((a, b))
+}
+
+fn write_at(mut file: &File, offset: u64, bytes: &[u8]) -> io::Result<()> {
+    use std::io::Seek;
+    file.seek(io::SeekFrom::Start(offset))?;
+    file.write_all(bytes)
+}
+
+/// Every regular file under `root`, sorted, with its contents.
+pub fn snapshot(root: &Path) -> io::Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(root).sort_by_file_name() {
+        let entry = entry.map_err(io::Error::other)?;
+        if entry.file_type().is_file() {
+            files.push((entry.path().to_path_buf(), fs::read(entry.path())?));
+        }
+    }
+    Ok(files)
+}
+
+const PROJECT_KINDS: [(&str, &str, &str); 3] = [
+    ("web", "package.json", "node_modules"),
+    ("crate", "Cargo.toml", "target"),
+    ("service", "pyproject.toml", "__pycache__"),
+];
+
+fn write_file(path: &Path, len: u64, rng: &mut Rng, modified: SystemTime) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = File::create(path)?;
+    let mut buf = [0u8; 8 * 1024];
+    let mut left = len;
+    while left > 0 {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = rng.next().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        let n = left.min(buf.len() as u64) as usize;
+        file.write_all(&buf[..n])?;
+        left -= n as u64;
+    }
+    file.set_modified(modified)
+}
+
+fn set_modified(path: &Path, modified: SystemTime) -> io::Result<()> {
+    File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(modified)
+}
+
+/// SplitMix64: tiny, seedable and good enough to make file contents
+/// distinct, which is all the duplicate pass cares about.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound.max(1)
+    }
+}
@@ -0,0 +1,230 @@
+use std::ffi::OsStr;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+pub const TRACKED_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitOutput {
+    Success(Vec<u8>),
+    Failed { stderr: String },
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackedCheck {
+    Clean,
+    Tracked(usize),
+    NotInRepo,
+    TimedOut,
+    /// Git ran but failed for another reason, such as a corrupt index or a
+    /// repository owned by someone else, so tracked files couldn't be ruled
+    /// out.
+    Failed(String),
+    Unavailable(String),
+}
+
+/// How git reports running outside any repository. Git is run with
+/// `LC_ALL=C` so the message isn't translated.
+const NOT_A_REPOSITORY: &str = "not a git repository";
+
+pub trait GitRunner {
+    fn run(&self, cwd: &Path, args: &[&OsStr], timeout: Duration) -> std::io::Result<GitOutput>;
+}
+
+pub struct SystemGit;
+
+impl GitRunner for SystemGit {
+    fn run(&self, cwd: &Path, args: &[&OsStr], timeout: Duration) -> std::io::Result<GitOutput> {
+        let mut child = Command::new("git")
+            .current_dir(cwd)
+            .args(args)
+            .env("LC_ALL", "C")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let read_all = |pipe: Option<Box<dyn Read + Send>>| {
+            std::thread::spawn(move || {
+                let mut output = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_end(&mut output);
+                }
+                output
+            })
+        };
+        let reader = read_all(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+        let errors = read_all(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(GitOutput::TimedOut);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+
+        let output = reader.join().unwrap_or_default();
+        let stderr = errors.join().unwrap_or_default();
+        Ok(if status.success() {
+            GitOutput::Success(output)
+        } else {
+            GitOutput::Failed {
+                stderr: String::from_utf8_lossy(&stderr).trim().to_string(),
+            }
+        })
+    }
+}
+
+pub fn check_tracked(runner: &impl GitRunner, path: &Path, timeout: Duration) -> TrackedCheck {
+    let cwd = if path.is_dir() {
+        path
+    } else {
+        match path.parent() {
+            Some(parent) => parent,
+            None => return TrackedCheck::NotInRepo,
+        }
+    };
+
+    let args = [
+        OsStr::new("ls-files"),
+        OsStr::new("-z"),
+        OsStr::new("--"),
+        path.as_os_str(),
+    ];
+
+    match runner.run(cwd, &args, timeout) {
+        Ok(GitOutput::Success(output)) => {
+            let tracked = output
+                .split(|byte| *byte == 0)
+                .filter(|entry| !entry.is_empty())
+                .count();
+            if tracked == 0 {
+                TrackedCheck::Clean
+            } else {
+                TrackedCheck::Tracked(tracked)
+            }
+        }
+        Ok(GitOutput::Failed { stderr }) if stderr.contains(NOT_A_REPOSITORY) => {
+            TrackedCheck::NotInRepo
+        }
+        Ok(GitOutput::Failed { stderr }) if stderr.is_empty() => {
+            TrackedCheck::Failed("git failed".to_string())
+        }
+        Ok(GitOutput::Failed { stderr }) => TrackedCheck::Failed(stderr),
+        Ok(GitOutput::TimedOut) => TrackedCheck::TimedOut,
+        Err(e) => TrackedCheck::Unavailable(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeGit(GitOutput);
+
+    impl GitRunner for FakeGit {
+        fn run(
+            &self,
+            _cwd: &Path,
+            _args: &[&OsStr],
+            _timeout: Duration,
+        ) -> std::io::Result<GitOutput> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct MissingGit;
+
+    impl GitRunner for MissingGit {
+        fn run(
+            &self,
+            _cwd: &Path,
+            _args: &[&OsStr],
+            _timeout: Duration,
+        ) -> std::io::Result<GitOutput> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "git not found",
+            ))
+        }
+    }
+
+    fn failed(stderr: &str) -> GitOutput {
+        GitOutput::Failed {
+            stderr: stderr.to_string(),
+        }
+    }
+
+    #[test]
+    fn git_results_map_to_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let cases = [
+            (
+                GitOutput::Success(b"build/a\0build/b\0".to_vec()),
+                TrackedCheck::Tracked(2),
+            ),
+            (GitOutput::Success(Vec::new()), TrackedCheck::Clean),
+            (
+                failed("fatal: not a git repository (or any of the parent directories): .git"),
+                TrackedCheck::NotInRepo,
+            ),
+            (
+                failed("fatal: detected dubious ownership in repository at '/srv/repo'"),
+                TrackedCheck::Failed(
+                    "fatal: detected dubious ownership in repository at '/srv/repo'".to_string(),
+                ),
+            ),
+            (failed(""), TrackedCheck::Failed("git failed".to_string())),
+            (GitOutput::TimedOut, TrackedCheck::TimedOut),
+        ];
+        for (output, expected) in cases {
+            let check = check_tracked(&FakeGit(output.clone()), dir.path(), TRACKED_CHECK_TIMEOUT);
+            assert_eq!(check, expected, "{:?}", output);
+        }
+        assert_eq!(
+            check_tracked(&MissingGit, dir.path(), TRACKED_CHECK_TIMEOUT),
+            TrackedCheck::Unavailable("git not found".to_string())
+        );
+    }
+
+    #[test]
+    fn real_git_tells_tracked_untracked_and_outside_apart() {
+        let git = |cwd: &Path, args: &[&str]| {
+            Command::new("git")
+                .current_dir(cwd)
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::create_dir_all(repo.join("target")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        if !git(&repo, &["init", "-q"]) {
+            // No git on this machine; the fake runner covers the mapping.
+            return;
+        }
+        std::fs::write(repo.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(repo.join("target/out"), "x").unwrap();
+        assert!(git(&repo, &["add", "src/main.rs"]));
+
+        let check = |path: &Path| check_tracked(&SystemGit, path, TRACKED_CHECK_TIMEOUT);
+        assert_eq!(check(&repo.join("src")), TrackedCheck::Tracked(1));
+        assert_eq!(check(&repo.join("target")), TrackedCheck::Clean);
+        assert_eq!(check(&repo.join("target/out")), TrackedCheck::Clean);
+        assert_eq!(check(&outside), TrackedCheck::NotInRepo);
+    }
+}
@@ -0,0 +1,347 @@
+use crate::scanner;
+use crossbeam_channel::Receiver;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const SCHEME: &str = "sweeper";
+pub const SOCKET_NAME: &str = "instance.sock";
+#[cfg(unix)]
+const REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// A request from outside the app, such as a Finder service or a
+/// `sweeper://scan?path=…` link, to scan some folders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanRequest {
+    pub paths: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+impl ScanRequest {
+    /// Parses `sweeper://scan?path=<percent-encoded folder>&from=<app>`;
+    /// `path` may repeat.
+    pub fn from_url(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix(SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(|| format!("not a {}:// link: {}", SCHEME, url))?;
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if action.trim_end_matches('/') != "scan" {
+            return Err(format!("unknown action in link: {}", action));
+        }
+
+        let mut paths = Vec::new();
+        let mut from = None;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "path" => paths.push(PathBuf::from(percent_decode(value)?)),
+                "from" => from = Some(percent_decode(value)?),
+                _ => {}
+            }
+        }
+        Self::new(paths, from)
+    }
+
+    /// Checks that every path is an existing folder and resolves them to the
+    /// canonical paths the scan will use.
+    pub fn new(paths: Vec<PathBuf>, from: Option<String>) -> Result<Self, String> {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(|path| match path.strip_prefix("~") {
+                Ok(rest) => dirs::home_dir()
+                    .map(|home| home.join(rest))
+                    .ok_or_else(|| "no home folder to resolve ~ against".to_string()),
+                Err(_) => Ok(path),
+            })
+            .collect::<Result<_, _>>()?;
+        if paths.is_empty() {
+            return Err("no folder to scan".to_string());
+        }
+        for path in &paths {
+            if !path.is_absolute() {
+                return Err(format!("scan path must be absolute: {}", path.display()));
+            }
+            if !path.exists() {
+                return Err(format!("scan path does not exist: {}", path.display()));
+            }
+            if !path.is_dir() {
+                return Err(format!("scan path is not a directory: {}", path.display()));
+            }
+        }
+        let from = from
+            .map(|from| from.trim().to_string())
+            .filter(|from| !from.is_empty());
+        Ok(Self {
+            paths: scanner::normalize_scope(&paths),
+            from,
+        })
+    }
+
+    pub fn toast(&self) -> String {
+        let home = dirs::home_dir();
+        let shown: Vec<String> = self
+            .paths
+            .iter()
+            .map(|path| {
+                home.as_ref()
+                    .and_then(|home| path.strip_prefix(home).ok())
+                    .map(|rest| Path::new("~").join(rest))
+                    .unwrap_or_else(|| path.clone())
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        format!(
+            "Opened from {}: {}",
+            self.from.as_deref().unwrap_or("another app"),
+            shown.join(", ")
+        )
+    }
+}
+
+fn percent_decode(value: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = tail
+                    .get(..2)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("bad percent escape in link: {}", value))?;
+                bytes.push(hex);
+                rest = &tail[2..];
+            }
+            b'+' => {
+                bytes.push(b' ');
+                rest = tail;
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| format!("link is not valid UTF-8: {}", value))
+}
+
+/// Passes `request` to a Sweeper window that is already open. Fails when no
+/// instance is listening, in which case the caller opens a window itself.
+#[cfg(unix)]
+pub fn hand_off(dir: &Path, request: &ScanRequest) -> std::io::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(dir.join(SOCKET_NAME))?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let line = serde_json::to_string(request).map_err(std::io::Error::other)?;
+    writeln!(stream, "{}", line)?;
+    stream.flush()?;
+
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    match reply.trim() {
+        "ok" => Ok(()),
+        "" => Err(std::io::Error::other("the running instance did not answer")),
+        message => Err(std::io::Error::other(message.to_string())),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn hand_off(_dir: &Path, _request: &ScanRequest) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "handing off to a running instance is only available on Unix",
+    ))
+}
+
+/// The socket a running window listens on for scan requests from later
+/// launches. Only one instance holds it at a time.
+pub struct InstanceListener {
+    pub requests: Receiver<ScanRequest>,
+    socket_path: PathBuf,
+}
+
+impl InstanceListener {
+    #[cfg(unix)]
+    pub fn start(dir: &Path) -> std::io::Result<Self> {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::fs::PermissionsExt;
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        std::fs::create_dir_all(dir)?;
+        let socket_path = dir.join(SOCKET_NAME);
+        if socket_path.exists() {
+            if UnixStream::connect(&socket_path).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    "another Sweeper window is already listening",
+                ));
+            }
+            std::fs::remove_file(&socket_path)?;
+        }
+
+        let listener = UnixListener::bind(&socket_path)?;
+        std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))?;
+
+        let (sender, requests) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.set_read_timeout(Some(REPLY_TIMEOUT));
+                let mut line = String::new();
+                if BufReader::new(&stream).read_line(&mut line).is_err() {
+                    continue;
+                }
+                let reply = match serde_json::from_str::<ScanRequest>(&line)
+                    .map_err(|e| e.to_string())
+                    .and_then(|request| ScanRequest::new(request.paths, request.from))
+                {
+                    Ok(request) => match sender.send(request) {
+                        Ok(()) => "ok".to_string(),
+                        Err(_) => "Sweeper is shutting down".to_string(),
+                    },
+                    Err(message) => message,
+                };
+                let _ = writeln!(stream, "{}", reply);
+            }
+        });
+
+        Ok(Self {
+            requests,
+            socket_path,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_dir: &Path) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the instance socket is only available on Unix",
+        ))
+    }
+}
+
+impl Drop for InstanceListener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(path: &Path) -> String {
+        path.to_str()
+            .unwrap()
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (byte as char).to_string()
+                }
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn links_decode_percent_encoded_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let spaced = root.join("My Folder");
+        let accented = root.join("caf\u{e9} \u{2014} notes");
+        std::fs::create_dir(&spaced).unwrap();
+        std::fs::create_dir(&accented).unwrap();
+
+        let url = format!(
+            "sweeper://scan?path={}&path={}&from=Finder",
+            encode(&spaced),
+            encode(&accented)
+        );
+        let request = ScanRequest::from_url(&url).unwrap();
+        assert_eq!(request.paths, [spaced.clone(), accented]);
+        assert_eq!(request.from.as_deref(), Some("Finder"));
+
+        // `+` is a space, a trailing slash on the action is fine, repeats
+        // collapse and a blank `from` is dropped.
+        let plus = encode(&spaced).replace("%20", "+");
+        let url = format!(
+            "sweeper://scan/?path={}&path={}&from=+",
+            plus,
+            encode(&spaced)
+        );
+        let request = ScanRequest::from_url(&url).unwrap();
+        assert_eq!(request.paths, [spaced]);
+        assert_eq!(request.from, None);
+    }
+
+    #[test]
+    fn bad_links_are_rejected_with_a_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let file = root.join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+        let missing = root.join("missing");
+
+        let cases = [
+            (
+                "https://scan?path=%2Ftmp".to_string(),
+                "not a sweeper:// link: https://scan?path=%2Ftmp".to_string(),
+            ),
+            (
+                "sweeper:scan?path=%2Ftmp".to_string(),
+                "not a sweeper:// link: sweeper:scan?path=%2Ftmp".to_string(),
+            ),
+            (
+                "sweeper://delete?path=%2Ftmp".to_string(),
+                "unknown action in link: delete".to_string(),
+            ),
+            (
+                "sweeper://scan".to_string(),
+                "no folder to scan".to_string(),
+            ),
+            (
+                "sweeper://scan?path=".to_string(),
+                "no folder to scan".to_string(),
+            ),
+            (
+                "sweeper://scan?path=relative%2Fdir".to_string(),
+                "scan path must be absolute: relative/dir".to_string(),
+            ),
+            (
+                "sweeper://scan?path=%zz".to_string(),
+                "bad percent escape in link: %zz".to_string(),
+            ),
+            (
+                "sweeper://scan?path=%2".to_string(),
+                "bad percent escape in link: %2".to_string(),
+            ),
+            (
+                "sweeper://scan?path=%FF".to_string(),
+                "link is not valid UTF-8: %FF".to_string(),
+            ),
+            (
+                format!("sweeper://scan?path={}", encode(&missing)),
+                format!("scan path does not exist: {}", missing.display()),
+            ),
+            (
+                format!("sweeper://scan?path={}", encode(&file)),
+                format!("scan path is not a directory: {}", file.display()),
+            ),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(ScanRequest::from_url(&url), Err(expected), "{}", url);
+        }
+    }
+
+    #[test]
+    fn unknown_query_keys_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let url = format!("sweeper://scan?utm=x&path={}&flag", encode(&root));
+        assert_eq!(ScanRequest::from_url(&url).unwrap().paths, [root]);
+    }
+}
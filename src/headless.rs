@@ -0,0 +1,563 @@
+use crate::activity::{self, ActivityLog, ScheduledRun};
+use crate::audit;
+use crate::categories::FileCategory;
+use crate::ci::{EXIT_ERROR, EXIT_OK};
+use crate::cleaner::{CleanJob, CleanTarget};
+use crate::cli::{category_slug, CleanArgs, Command, ScanArgs};
+use crate::config::Config;
+use crate::duplicates;
+use crate::profile::Profile;
+use crate::scanner::persist::ItemRecordV1;
+use crate::scanner::{ScanStats, ScannedItem, Scanner};
+use crate::schedule::{self, AutoClean, ScheduleEntry};
+use bytesize::ByteSize;
+use chrono::{Local, Utc};
+use serde::Serialize;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+pub const EXIT_NOTHING_FOUND: i32 = 3;
+pub const EXIT_DELETE_FAILED: i32 = 4;
+
+#[derive(Serialize)]
+struct ScanOutput<'a> {
+    stats: &'a ScanStats,
+    warnings: &'a [String],
+    items: Vec<ItemRecordV1>,
+}
+
+#[derive(Serialize)]
+struct CleanOutput {
+    dry_run: bool,
+    use_trash: bool,
+    items: Vec<ItemRecordV1>,
+    items_deleted: usize,
+    freed_bytes: u64,
+    blocked: Vec<String>,
+    failed: Vec<String>,
+}
+
+pub fn run(command: &Command) -> i32 {
+    let config = match Config::load() {
+        Ok(config) => {
+            if config.audit_mode {
+                audit::enable();
+            }
+            config
+        }
+        Err(e) => {
+            eprintln!("failed to load config: {}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    match command {
+        Command::Scan(args) => scan(&config, args),
+        Command::Report(args) => report(&config, args),
+        Command::Clean(args) => clean(config, args),
+        Command::Scheduled => scheduled(&config),
+    }
+}
+
+fn scan(config: &Config, args: &ScanArgs) -> i32 {
+    let Some((items, warnings)) = collect(config, args) else {
+        return EXIT_ERROR;
+    };
+    if args.json {
+        let stats = ScanStats::from_items(&items);
+        if !print_json(&ScanOutput {
+            stats: &stats,
+            warnings: &warnings,
+            items: items.iter().map(ItemRecordV1::from).collect(),
+        }) {
+            return EXIT_ERROR;
+        }
+    } else {
+        print_table(&items);
+        print_warnings(&warnings);
+    }
+    found_code(&items)
+}
+
+fn report(config: &Config, args: &ScanArgs) -> i32 {
+    let Some((items, warnings)) = collect(config, args) else {
+        return EXIT_ERROR;
+    };
+    let stats = ScanStats::from_items(&items);
+    if args.json {
+        if !print_json(&ScanOutput {
+            stats: &stats,
+            warnings: &warnings,
+            items: items.iter().map(ItemRecordV1::from).collect(),
+        }) {
+            return EXIT_ERROR;
+        }
+        return found_code(&items);
+    }
+
+    for category in FileCategory::ALL {
+        let count = stats.items_by_category.get(&category).copied().unwrap_or(0);
+        if count == 0 {
+            continue;
+        }
+        let size = stats.size_by_category.get(&category).copied().unwrap_or(0);
+        println!(
+            "{:<16} {:>6} items {:>12}",
+            category.name(),
+            count,
+            ByteSize(size).to_string()
+        );
+    }
+    println!(
+        "{:<16} {:>6} items {:>12}",
+        "Total",
+        stats.total_items,
+        ByteSize(stats.total_size).to_string()
+    );
+    print_warnings(&warnings);
+    found_code(&items)
+}
+
+fn clean(config: Config, args: &CleanArgs) -> i32 {
+    let Some((items, mut warnings)) = collect(&config, &args.scan) else {
+        return EXIT_ERROR;
+    };
+    let keepers = {
+        let all: Vec<&ScannedItem> = items.iter().collect();
+        duplicates::keepers(&all, dirs::download_dir().as_deref())
+    };
+    let (unverified, items): (Vec<ScannedItem>, Vec<ScannedItem>) = items
+        .into_iter()
+        .filter(|item| !keepers.contains(&item.path))
+        .filter(|item| args.category.contains(&item.category))
+        .filter(|item| args.older_than.is_none_or(|days| item.age_days() >= days))
+        .partition(duplicates::is_unverified);
+    if !unverified.is_empty() {
+        warnings.push(format!(
+            "skipped {} duplicates that matched only on sampled content; verify them in the app",
+            unverified.len()
+        ));
+    }
+
+    let use_trash = args.use_trash(config.use_trash);
+    let dry_run = args.dry_run(config.dry_run);
+    if !args.scan.json {
+        print_table(&items);
+        print_warnings(&warnings);
+    }
+    if items.is_empty() {
+        if args.scan.json {
+            print_json(&CleanOutput {
+                dry_run,
+                use_trash,
+                items: Vec::new(),
+                items_deleted: 0,
+                freed_bytes: 0,
+                blocked: Vec::new(),
+                failed: Vec::new(),
+            });
+        }
+        return EXIT_NOTHING_FOUND;
+    }
+
+    if !dry_run && !args.yes {
+        let size: u64 = items.iter().map(|item| item.size).sum();
+        let action = if use_trash {
+            "Move to the trash"
+        } else {
+            "Permanently delete"
+        };
+        if !confirm(&format!(
+            "{} {} items ({})? [y/N] ",
+            action,
+            items.len(),
+            ByteSize(size)
+        )) {
+            eprintln!("Nothing was deleted.");
+            return EXIT_OK;
+        }
+    }
+
+    let job = match CleanJob::new(
+        items.iter().flat_map(CleanTarget::for_item).collect(),
+        use_trash,
+        dry_run,
+    ) {
+        Ok(job) => job
+            .with_quarantine(config.quarantine_dir.clone())
+            .with_manifest_dir(config.manifest_dir.clone()),
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_ERROR;
+        }
+    };
+    let report = job.run();
+    let failed: Vec<String> = report.failures().collect();
+
+    if args.scan.json {
+        if !print_json(&CleanOutput {
+            dry_run,
+            use_trash,
+            items: items.iter().map(ItemRecordV1::from).collect(),
+            items_deleted: report.deleted.len(),
+            freed_bytes: report.freed,
+            blocked: report.blocked.clone(),
+            failed: failed.clone(),
+        }) {
+            return EXIT_ERROR;
+        }
+    } else {
+        let verb = if dry_run { "Would free" } else { "Freed" };
+        println!(
+            "{} {} from {} items",
+            verb,
+            ByteSize(report.freed),
+            report.deleted.len()
+        );
+        for blocked in &report.blocked {
+            eprintln!("skipped: {}", blocked);
+        }
+        for failure in &failed {
+            eprintln!("failed: {}", failure);
+        }
+    }
+
+    if failed.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_DELETE_FAILED
+    }
+}
+
+fn scheduled(config: &Config) -> i32 {
+    let last_runs = ActivityLog::load().last_scheduled_runs();
+    let due = schedule::due(&config.schedule, &last_runs, &Local::now());
+    if due.is_empty() {
+        println!("No scheduled scans are due.");
+        return EXIT_OK;
+    }
+
+    let profiles = Profile::list_saved();
+    let mut code = EXIT_OK;
+    for entry in due {
+        let run = run_scheduled(config, entry, &profiles);
+        println!("{}", run.summary);
+        if run.refused.is_some() {
+            code = EXIT_ERROR;
+        }
+        let mut log = ActivityLog::load();
+        log.record_scheduled(run);
+        if let Err(e) = log.save() {
+            eprintln!("failed to save activity log: {}", e);
+            return EXIT_ERROR;
+        }
+    }
+    code
+}
+
+fn run_scheduled(
+    config: &Config,
+    entry: &ScheduleEntry,
+    profiles: &[(std::path::PathBuf, Profile)],
+) -> ScheduledRun {
+    let mut run = ScheduledRun {
+        schedule: entry.key(),
+        at: Utc::now(),
+        items_found: 0,
+        found_bytes: 0,
+        items_cleaned: 0,
+        freed_bytes: 0,
+        refused: None,
+        summary: String::new(),
+    };
+
+    let merged = match &entry.profile {
+        Some(name) => match profiles
+            .iter()
+            .find(|(_, profile)| profile.name.eq_ignore_ascii_case(name))
+        {
+            Some((_, profile)) => profile.apply(config),
+            None => {
+                return refuse(run, format!("profile \"{}\" is missing or invalid", name));
+            }
+        },
+        None => config.clone(),
+    };
+    if let Err(message) = merged.validate() {
+        return refuse(run, message);
+    }
+
+    let scanner = Scanner::new(merged.clone());
+    if let Err(e) = scanner.scan() {
+        return refuse(run, format!("scan failed: {}", e));
+    }
+    let store = scanner.snapshot();
+    let items: Vec<&ScannedItem> = store
+        .items()
+        .iter()
+        .filter(|item| merged.dismissed_key(&item.path).is_none())
+        .collect();
+    run.items_found = items.len();
+    run.found_bytes = items.iter().map(|item| item.size).sum();
+    run.summary = format!(
+        "{}: found {} items ({})",
+        run.schedule,
+        run.items_found,
+        ByteSize(run.found_bytes)
+    );
+
+    let Some(auto_clean) = &entry.auto_clean else {
+        return run;
+    };
+    let targets = auto_clean_targets(&items, auto_clean, dirs::download_dir().as_deref());
+    let job = match CleanJob::new(targets, true, merged.dry_run) {
+        Ok(job) => job.with_manifest_dir(merged.manifest_dir.clone()),
+        Err(e) => return refuse(run, e.to_string()),
+    };
+    let report = activity::run_logged(job);
+    run.items_cleaned = report.deleted.len();
+    run.freed_bytes = report.freed;
+    let verb = if merged.dry_run {
+        "would move"
+    } else {
+        "moved"
+    };
+    run.summary.push_str(&format!(
+        ", {} {} items ({}) to the trash",
+        verb,
+        run.items_cleaned,
+        ByteSize(run.freed_bytes)
+    ));
+    let failed = report.failures().count();
+    if failed > 0 {
+        run.summary.push_str(&format!(", {} failed", failed));
+    }
+    run
+}
+
+fn refuse(mut run: ScheduledRun, reason: String) -> ScheduledRun {
+    run.summary = format!("{}: not run, {}", run.schedule, reason);
+    run.refused = Some(reason);
+    run
+}
+
+/// What a scheduled run may clean: confident items in the allowed
+/// categories, never the copy a duplicate set keeps.
+fn auto_clean_targets(
+    items: &[&ScannedItem],
+    auto_clean: &AutoClean,
+    downloads: Option<&Path>,
+) -> Vec<CleanTarget> {
+    let keepers = duplicates::keepers(items, downloads);
+    items
+        .iter()
+        .filter(|item| !keepers.contains(&item.path) && !duplicates::is_unverified(item))
+        .filter(|item| item.confidence >= auto_clean.min_confidence)
+        .filter(|item| {
+            auto_clean
+                .categories
+                .as_ref()
+                .is_none_or(|categories| categories.contains(&item.category))
+        })
+        .flat_map(|item| CleanTarget::for_item(item))
+        .collect()
+}
+
+fn collect(config: &Config, args: &ScanArgs) -> Option<(Vec<ScannedItem>, Vec<String>)> {
+    let scope = match args.scope() {
+        Ok(scope) => scope,
+        Err(message) => {
+            eprintln!("{}", message);
+            return None;
+        }
+    };
+    let scanner = match scope {
+        Some(scope) => Scanner::with_scope(config.clone(), scope),
+        None => Scanner::new(config.clone()),
+    };
+    if let Err(e) = scanner.scan() {
+        eprintln!("scan failed: {}", e);
+        return None;
+    }
+
+    let store = scanner.snapshot();
+    let items = store
+        .items()
+        .iter()
+        .filter(|item| config.show_dismissed || config.dismissed_key(&item.path).is_none())
+        .cloned()
+        .collect();
+    Some((items, store.warnings().to_vec()))
+}
+
+fn print_table(items: &[ScannedItem]) {
+    if items.is_empty() {
+        println!("Nothing found.");
+        return;
+    }
+
+    for category in FileCategory::ALL {
+        let mut group: Vec<&ScannedItem> = items
+            .iter()
+            .filter(|item| item.category == category)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        group.sort_by_key(|item| std::cmp::Reverse(item.size));
+        let size: u64 = group.iter().map(|item| item.size).sum();
+        println!(
+            "{} [{}] - {} items, {}",
+            category.name(),
+            category_slug(category),
+            group.len(),
+            ByteSize(size)
+        );
+        for item in group {
+            println!(
+                "  {:>12}  {:>3}%  {}",
+                item.size_formatted(),
+                item.confidence_percent(),
+                item.path.display()
+            );
+        }
+    }
+
+    let total: u64 = items.iter().map(|item| item.size).sum();
+    println!("Total: {} items, {}", items.len(), ByteSize(total));
+}
+
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+}
+
+fn print_json(value: &impl Serialize) -> bool {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            println!("{}", json);
+            true
+        }
+        Err(e) => {
+            eprintln!("failed to serialize output: {}", e);
+            false
+        }
+    }
+}
+
+fn confirm(prompt: &str) -> bool {
+    eprint!("{}", prompt);
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn found_code(items: &[ScannedItem]) -> i32 {
+    if items.is_empty() {
+        EXIT_NOTHING_FOUND
+    } else {
+        EXIT_OK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{self, FixtureSpec};
+
+    #[test]
+    fn cleaning_duplicates_keeps_one_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let spec = FixtureSpec {
+            projects: 0,
+            plain_dirs: 0,
+            temp_files: 0,
+            duplicate_pairs: 1,
+            ..FixtureSpec::small(5)
+        };
+        let fixture = fixture::generate(&root, &spec).unwrap();
+        let (original, copy) = &fixture.duplicates[0];
+
+        let mut config = Config::default();
+        config.enabled_categories.insert(FileCategory::Duplicate);
+        config.dry_run = false;
+        let args = CleanArgs {
+            scan: ScanArgs {
+                paths: vec![root.clone()],
+                json: true,
+            },
+            category: vec![FileCategory::Duplicate],
+            older_than: None,
+            yes: true,
+            trash: false,
+            permanent: true,
+            dry_run: false,
+            no_dry_run: true,
+        };
+        assert_eq!(clean(config, &args), EXIT_OK);
+
+        let survivors = [original, copy]
+            .into_iter()
+            .filter(|path| path.exists())
+            .count();
+        assert_eq!(survivors, 1);
+    }
+
+    #[test]
+    fn cleaning_skips_duplicates_matched_on_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (a, b) = fixture::sampled_lookalikes(&root).unwrap();
+
+        let mut config = Config::default();
+        config.enabled_categories.insert(FileCategory::Duplicate);
+        let args = CleanArgs {
+            scan: ScanArgs {
+                paths: vec![root.clone()],
+                json: true,
+            },
+            category: vec![FileCategory::Duplicate],
+            older_than: None,
+            yes: true,
+            trash: false,
+            permanent: true,
+            dry_run: false,
+            no_dry_run: true,
+        };
+        assert_eq!(clean(config, &args), EXIT_NOTHING_FOUND);
+        assert!(a.exists() && b.exists());
+    }
+
+    #[test]
+    fn auto_clean_skips_duplicate_keepers() {
+        let copies: Vec<ScannedItem> = ["/home/u/a/clip.bin", "/home/u/b/clip.bin"]
+            .into_iter()
+            .map(|path| {
+                let mut item = ScannedItem::new(
+                    path.into(),
+                    2 * 1024 * 1024,
+                    FileCategory::Duplicate,
+                    Utc::now(),
+                );
+                item.hash = Some("same".to_string());
+                item.confidence = 1.0;
+                item
+            })
+            .collect();
+        let items: Vec<&ScannedItem> = copies.iter().collect();
+        let auto_clean = AutoClean {
+            categories: Some([FileCategory::Duplicate].into()),
+            min_confidence: 0.9,
+        };
+
+        let targets = auto_clean_targets(&items, &auto_clean, None);
+        assert_eq!(targets.len(), 1);
+        let keepers = duplicates::keepers(&items, None);
+        assert!(!keepers.contains(&targets[0].path));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::config::Config;
+use bytesize::ByteSize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const GB: u64 = 1024 * 1024 * 1024;
+pub const MILESTONES: [u64; 5] = [100 * GB, 250 * GB, 500 * GB, 1024 * GB, 5 * 1024 * GB];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    pub freed_bytes: u64,
+    pub cleanups: u64,
+    pub last_scan_paths: u64,
+    pub last_scan: Option<ScanSummary>,
+}
+
+/// Totals of the last completed full scan, kept to show progress on the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanSummary {
+    pub at: DateTime<Utc>,
+    pub total_size: u64,
+    pub total_items: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanDelta {
+    pub size: i128,
+    pub items: i128,
+    pub previous_at: DateTime<Utc>,
+}
+
+impl ScanDelta {
+    pub fn between(previous: Option<&ScanSummary>, current: &ScanSummary) -> Option<Self> {
+        let previous = previous?;
+        Some(Self {
+            size: i128::from(current.total_size) - i128::from(previous.total_size),
+            items: i128::from(current.total_items) - i128::from(previous.total_items),
+            previous_at: previous.at,
+        })
+    }
+
+    pub fn is_decrease(&self) -> bool {
+        self.size < 0 || (self.size == 0 && self.items < 0)
+    }
+
+    /// "↓ 39.0 GB from previous scan", or None when nothing changed.
+    pub fn size_label(&self) -> Option<String> {
+        if self.size == 0 {
+            return None;
+        }
+        let arrow = if self.size < 0 { "↓" } else { "↑" };
+        let bytes = u64::try_from(self.size.unsigned_abs()).unwrap_or(u64::MAX);
+        Some(format!("{} {} from previous scan", arrow, ByteSize(bytes)))
+    }
+
+    /// "↓ 12 items", or None when the count is unchanged.
+    pub fn items_label(&self) -> Option<String> {
+        match self.items {
+            0 => None,
+            n if n < 0 => Some(format!("↓ {} items", n.unsigned_abs())),
+            n => Some(format!("↑ {} items", n)),
+        }
+    }
+}
+
+impl History {
+    pub fn path() -> PathBuf {
+        Config::config_dir().join("history.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn record(&mut self, freed: u64) -> Option<u64> {
+        let previous = self.freed_bytes;
+        self.freed_bytes = self.freed_bytes.saturating_add(freed);
+        self.cleanups += 1;
+        crossed_milestone(previous, self.freed_bytes)
+    }
+
+    /// Replaces the last scan with `current` and returns the change since it.
+    pub fn record_scan(&mut self, current: ScanSummary) -> Option<ScanDelta> {
+        let delta = ScanDelta::between(self.last_scan.as_ref(), &current);
+        self.last_scan = Some(current);
+        delta
+    }
+}
+
+pub fn crossed_milestone(previous: u64, total: u64) -> Option<u64> {
+    MILESTONES
+        .iter()
+        .rev()
+        .copied()
+        .find(|milestone| previous < *milestone && total >= *milestone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn milestones_are_announced_once_when_crossed() {
+        let mut history = History::default();
+        assert_eq!(history.record(99 * GB), None);
+        assert_eq!(history.record(GB), Some(100 * GB));
+        assert_eq!(history.record(GB), None);
+        assert_eq!(history.cleanups, 3);
+        assert_eq!(history.freed_bytes, 101 * GB);
+    }
+
+    #[test]
+    fn a_big_cleanup_announces_the_highest_milestone_it_passed() {
+        assert_eq!(crossed_milestone(0, 600 * GB), Some(500 * GB));
+        assert_eq!(crossed_milestone(250 * GB - 1, 250 * GB), Some(250 * GB));
+        assert_eq!(crossed_milestone(250 * GB, 260 * GB), None);
+        assert_eq!(crossed_milestone(6 * 1024 * GB, 7 * 1024 * GB), None);
+    }
+
+    #[test]
+    fn the_running_total_saturates() {
+        let mut history = History {
+            freed_bytes: u64::MAX - 1,
+            ..History::default()
+        };
+        assert_eq!(history.record(10), None);
+        assert_eq!(history.freed_bytes, u64::MAX);
+    }
+
+    #[test]
+    fn older_history_files_still_load() {
+        let history: History = serde_json::from_str(r#"{"freed_bytes": 5}"#).unwrap();
+        assert_eq!(history.freed_bytes, 5);
+        assert_eq!(history.cleanups, 0);
+        assert_eq!(history.last_scan, None);
+    }
+
+    fn summary(days_ago: i64, total_size: u64, total_items: u64) -> ScanSummary {
+        ScanSummary {
+            at: Utc::now() - chrono::Duration::days(days_ago),
+            total_size,
+            total_items,
+        }
+    }
+
+    #[test]
+    fn the_first_scan_has_nothing_to_compare_against() {
+        let mut history = History::default();
+        let first = summary(0, 100, 3);
+        assert_eq!(history.record_scan(first), None);
+        assert_eq!(history.last_scan, Some(first));
+    }
+
+    #[test]
+    fn deltas_compare_against_the_previous_scan_only() {
+        let mut history = History::default();
+        let first = summary(2, 40_000_000_000, 10);
+        history.record_scan(first);
+
+        let delta = history.record_scan(summary(1, 1_000_000_000, 12)).unwrap();
+        assert_eq!(delta.size, -39_000_000_000);
+        assert_eq!(delta.items, 2);
+        assert_eq!(delta.previous_at, first.at);
+        assert!(delta.is_decrease());
+        assert_eq!(
+            delta.size_label().as_deref(),
+            Some("↓ 39.0 GB from previous scan")
+        );
+        assert_eq!(delta.items_label().as_deref(), Some("↑ 2 items"));
+
+        let delta = history.record_scan(summary(0, 3_000_000_000, 12)).unwrap();
+        assert_eq!(delta.size, 2_000_000_000);
+        assert!(!delta.is_decrease());
+        assert_eq!(delta.items_label(), None);
+    }
+
+    #[test]
+    fn unchanged_sizes_fall_back_to_the_item_count() {
+        let previous = summary(1, 500, 9);
+        let delta = ScanDelta::between(Some(&previous), &summary(0, 500, 4)).unwrap();
+        assert_eq!(delta.size_label(), None);
+        assert!(delta.is_decrease());
+        assert_eq!(delta.items_label().as_deref(), Some("↓ 5 items"));
+
+        let delta = ScanDelta::between(Some(&previous), &previous).unwrap();
+        assert!(!delta.is_decrease());
+        assert_eq!((delta.size_label(), delta.items_label()), (None, None));
+    }
+
+    #[test]
+    fn deltas_never_overflow() {
+        let delta = ScanDelta::between(Some(&summary(1, u64::MAX, 0)), &summary(0, 0, 0)).unwrap();
+        assert_eq!(delta.size, -i128::from(u64::MAX));
+        assert!(delta.size_label().is_some());
+    }
+}
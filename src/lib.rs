@@ -0,0 +1,32 @@
+pub mod activity;
+pub mod audit;
+pub mod bench;
+pub mod bundle;
+pub mod categories;
+pub mod ci;
+pub mod cleaner;
+pub mod cli;
+pub mod config;
+pub mod control;
+pub mod crash;
+pub mod duplicates;
+pub mod error;
+pub mod exclusions;
+#[doc(hidden)]
+pub mod fixture;
+pub mod git;
+pub mod handoff;
+pub mod headless;
+pub mod history;
+pub mod manifest;
+pub mod platform;
+pub mod profile;
+pub mod report;
+pub mod rules;
+pub mod scanner;
+pub mod schedule;
+pub mod shutdown;
+pub mod storage;
+pub mod suggest;
+pub mod update;
+pub mod volumes;
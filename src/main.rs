@@ -1,83 +1,170 @@
-mod categories;
-mod config;
-mod scanner;
 mod ui;
 
+use sweeper_rust::{
+    activity, audit, bench, bundle, categories, ci, cleaner, cli, config, control, crash,
+    duplicates, error, exclusions, handoff, headless, history, manifest, platform, profile, report,
+    rules, scanner, shutdown, storage, suggest, update, volumes,
+};
+
 use adabraka_ui::prelude::*;
+use clap::{CommandFactory, Parser};
+use cli::Cli;
+use config::Config;
 use gpui::*;
-use std::path::PathBuf;
-use ui::SweeperApp;
-
-struct Assets {
-    base: PathBuf,
-}
+use handoff::ScanRequest;
+use std::time::Instant;
+use ui::{RedoSelection, SweeperApp, ToggleReviewLater, UndoSelection};
 
-impl Assets {
-    fn new() -> Self {
-        let base = if cfg!(debug_assertions) {
-            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        } else {
-            std::env::current_exe()
-                .ok()
-                .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
-                .and_then(|bin_dir| bin_dir.parent().map(|p| p.join("Resources")))
-                .unwrap_or_else(|| PathBuf::from("."))
-        };
-        Self { base }
-    }
-}
+/// Icons and other assets, embedded in release builds so every load hands
+/// gpui a borrowed slice instead of a fresh copy. Debug builds read them from
+/// the source tree so edits show up without a rebuild.
+#[derive(rust_embed::RustEmbed)]
+#[folder = "assets"]
+#[prefix = "assets/"]
+struct Assets;
 
 impl gpui::AssetSource for Assets {
     fn load(&self, path: &str) -> Result<Option<std::borrow::Cow<'static, [u8]>>> {
-        std::fs::read(self.base.join(path))
-            .map(|data| Some(std::borrow::Cow::Owned(data)))
-            .map_err(|err| err.into())
+        Ok(Self::get(path).map(|file| file.data))
     }
 
     fn list(&self, path: &str) -> Result<Vec<SharedString>> {
-        std::fs::read_dir(self.base.join(path))
-            .map(|entries| {
-                entries
-                    .filter_map(|entry| {
-                        entry
-                            .ok()
-                            .and_then(|entry| entry.file_name().into_string().ok())
-                            .map(SharedString::from)
-                    })
-                    .collect()
+        let dir = format!("{}/", path.trim_end_matches('/'));
+        let mut names: Vec<SharedString> = Self::iter()
+            .filter_map(|file| {
+                let name = file.strip_prefix(dir.as_str())?.split('/').next()?;
+                Some(SharedString::from(name.to_string()))
             })
-            .map_err(|err| err.into())
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
     }
 }
 
 fn main() {
+    let launched = Instant::now();
     env_logger::init();
 
-    Application::new()
-        .with_assets(Assets::new())
-        .run(|cx| {
-            adabraka_ui::init(cx);
-            adabraka_ui::set_icon_base_path("assets/icons");
-            install_theme(cx, Theme::dark());
-
-            let window_result = cx.open_window(
-                WindowOptions {
-                    titlebar: Some(TitlebarOptions {
-                        title: Some("Sweeper - Disk Cleanup".into()),
-                        ..Default::default()
-                    }),
-                    window_bounds: Some(WindowBounds::Windowed(Bounds {
-                        origin: Point::default(),
-                        size: size(px(1000.0), px(700.0)),
-                    })),
+    let cli = Cli::parse();
+    if let Some(command) = &cli.command {
+        std::process::exit(headless::run(command));
+    }
+
+    if let Some(workspace) = &cli.ci {
+        std::process::exit(ci::run(
+            workspace,
+            cli.require_free.map(|size| size.as_u64()),
+            cli.dry_run,
+            cli.metrics_out.as_deref(),
+        ));
+    }
+
+    if let Some(dir) = &cli.bench_scan {
+        std::process::exit(bench::run(dir));
+    }
+
+    if cli.audit {
+        audit::enable();
+    }
+    crash::install();
+
+    let opened = match (&cli.open_url, &cli.from) {
+        (Some(url), _) => Some(ScanRequest::from_url(url)),
+        (None, Some(from)) => Some(ScanRequest::new(
+            cli.scan
+                .iter()
+                .map(|path| std::path::absolute(path).unwrap_or_else(|_| path.clone()))
+                .collect(),
+            Some(from.clone()),
+        )),
+        (None, None) => None,
+    }
+    .transpose()
+    .unwrap_or_else(|message| {
+        Cli::command()
+            .error(clap::error::ErrorKind::ValueValidation, message)
+            .exit()
+    });
+    if let Some(request) = &opened {
+        if handoff::hand_off(&Config::config_dir(), request).is_ok() {
+            return;
+        }
+    }
+
+    let scan_scope = match &opened {
+        Some(request) => Some(request.paths.clone()),
+        None => match cli.scan_paths() {
+            Ok(paths) => paths,
+            Err(message) => Cli::command()
+                .error(clap::error::ErrorKind::ValueValidation, message)
+                .exit(),
+        },
+    };
+
+    let application = Application::new().with_assets(Assets);
+    application.on_open_urls(|urls| {
+        for url in urls {
+            match ScanRequest::from_url(&url) {
+                Ok(request) => forward(request),
+                Err(message) => log::warn!("Ignoring {}: {}", url, message),
+            }
+        }
+    });
+    application.run(move |cx| {
+        adabraka_ui::init(cx);
+        adabraka_ui::set_icon_base_path("assets/icons");
+        install_theme(cx, Theme::dark());
+        cx.bind_keys([
+            KeyBinding::new("secondary-z", UndoSelection, Some("Sweeper")),
+            KeyBinding::new("secondary-shift-z", RedoSelection, Some("Sweeper")),
+            KeyBinding::new("r", ToggleReviewLater, Some("Sweeper && !Input")),
+        ]);
+
+        let window_result = cx.open_window(
+            WindowOptions {
+                titlebar: Some(TitlebarOptions {
+                    title: Some("Sweeper - Disk Cleanup".into()),
                     ..Default::default()
-                },
-                |_, cx| cx.new(SweeperApp::new),
-            );
+                }),
+                window_bounds: Some(WindowBounds::Windowed(Bounds {
+                    origin: Point::default(),
+                    size: size(px(1000.0), px(700.0)),
+                })),
+                ..Default::default()
+            },
+            |window, cx| {
+                let app = cx.new(|cx| SweeperApp::new(scan_scope, launched, cx));
+                if let Some(request) = &opened {
+                    app.update(cx, |app, _cx| app.show_opened(request));
+                }
+                window.focus(&app.focus_handle(cx));
+                let closing = app.clone();
+                window.on_window_should_close(cx, move |_window, cx| {
+                    closing.update(cx, |app, cx| app.request_close(cx))
+                });
+                app
+            },
+        );
+
+        if let Err(e) = window_result {
+            log::error!("Failed to create window: {:?}", e);
+            cx.quit();
+        }
+    });
+}
 
-            if let Err(e) = window_result {
-                log::error!("Failed to create window: {:?}", e);
-                cx.quit();
+/// Links opened through the OS reach the app before its window may be
+/// listening, so keep trying the instance socket for a few seconds.
+fn forward(request: ScanRequest) {
+    std::thread::spawn(move || {
+        let dir = Config::config_dir();
+        for _ in 0..20 {
+            if handoff::hand_off(&dir, &request).is_ok() {
+                return;
             }
-        });
+            std::thread::sleep(std::time::Duration::from_millis(250));
+        }
+        log::warn!("No Sweeper window took the request: {}", request.toast());
+    });
 }
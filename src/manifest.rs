@@ -0,0 +1,101 @@
+use crate::bundle::HostInfo;
+use crate::cleaner::{CleanJob, PlannedDeletion};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// What a cleanup is about to remove, written before it runs so the plan can
+/// be reviewed or archived.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletionManifest {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub hostname: String,
+    pub quarantine_dir: Option<PathBuf>,
+    pub total_bytes: u64,
+    pub entries: Vec<PlannedDeletion>,
+}
+
+impl DeletionManifest {
+    pub fn from_job(job: &CleanJob) -> Self {
+        let entries = job.plan();
+        Self {
+            version: MANIFEST_VERSION,
+            created_at: Utc::now(),
+            hostname: HostInfo::current().hostname,
+            quarantine_dir: job.quarantine.clone(),
+            total_bytes: entries.iter().map(|entry| entry.size).sum(),
+            entries,
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!(
+            "sweeper-manifest-{}.json",
+            self.created_at.format("%Y%m%d-%H%M%S")
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("path,size,mode,volume,modified\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{},{},{:?},{},{}\n",
+                csv_field(&entry.path.to_string_lossy()),
+                entry.size,
+                entry.mode,
+                csv_field(
+                    &entry
+                        .volume
+                        .as_deref()
+                        .map(|volume| volume.to_string_lossy())
+                        .unwrap_or_default()
+                ),
+                entry
+                    .modified
+                    .map(|modified| modified.to_rfc3339())
+                    .unwrap_or_default()
+            ));
+        }
+        csv
+    }
+
+    /// Writes CSV when `path` ends in `.csv` and JSON otherwise.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let is_csv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let contents = if is_csv {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(self).map_err(std::io::Error::other)?
+        };
+        std::fs::write(path, contents)
+    }
+
+    pub fn save_in(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let mut path = dir.join(self.file_name());
+        let mut suffix = 1;
+        while path.exists() {
+            path = dir.join(format!(
+                "sweeper-manifest-{}-{}.json",
+                self.created_at.format("%Y%m%d-%H%M%S"),
+                suffix
+            ));
+            suffix += 1;
+        }
+        self.save(&path)?;
+        Ok(path)
+    }
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
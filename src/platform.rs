@@ -0,0 +1,69 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Shows `path` in the system file manager. macOS and Windows select it in
+/// its folder; elsewhere the containing folder is opened.
+pub fn reveal(path: &Path) -> std::io::Result<()> {
+    spawn(reveal_command(path))
+}
+
+/// Opens `path` with its default application.
+pub fn open(path: &Path) -> std::io::Result<()> {
+    spawn(open_command(path))
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> Command {
+    let mut command = Command::new("open");
+    command.arg("-R").arg(path);
+    command
+}
+
+#[cfg(windows)]
+fn reveal_command(path: &Path) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    let mut command = Command::new("explorer");
+    command.raw_arg(format!("/select,\"{}\"", path.display()));
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn reveal_command(path: &Path) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(path.parent().unwrap_or(path));
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn open_command(path: &Path) -> Command {
+    let mut command = Command::new("open");
+    command.arg(path);
+    command
+}
+
+#[cfg(windows)]
+fn open_command(path: &Path) -> Command {
+    let mut command = Command::new("explorer");
+    command.arg(path);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn open_command(path: &Path) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(path);
+    command
+}
+
+fn spawn(mut command: Command) -> std::io::Result<()> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
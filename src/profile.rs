@@ -0,0 +1,359 @@
+use crate::categories::FileCategory;
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+    #[error("could not read profile: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("profile is not valid JSON: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("invalid profile: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_paths: Option<Vec<PathBuf>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_patterns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_categories: Option<HashSet<FileCategory>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_file_threshold: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_days_threshold: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_download_days: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub follow_symlinks: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_trash: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommend_quick_regen: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+impl Profile {
+    pub fn from_config(name: impl Into<String>, config: &Config) -> Self {
+        Self {
+            name: name.into(),
+            scan_paths: Some(config.scan_paths.clone()),
+            exclude_patterns: Some(config.exclude_patterns.clone()),
+            enabled_categories: Some(config.enabled_categories.clone()),
+            large_file_threshold: Some(config.large_file_threshold),
+            stale_days_threshold: Some(config.stale_days_threshold),
+            old_download_days: Some(config.old_download_days),
+            max_depth: Some(config.max_depth),
+            follow_symlinks: Some(config.follow_symlinks),
+            use_trash: Some(config.use_trash),
+            recommend_quick_regen: Some(config.recommend_quick_regen),
+        }
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, ProfileError> {
+        let profile: Self = serde_json::from_str(contents)?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ProfileError> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut profile = Self::from_json(&contents)?;
+        if profile.name.trim().is_empty() {
+            profile.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Unnamed profile".to_string());
+        }
+        Ok(profile)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProfileError> {
+        self.validate()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), ProfileError> {
+        if self
+            .scan_paths
+            .as_ref()
+            .is_some_and(|paths| paths.is_empty())
+        {
+            return Err(ProfileError::Invalid(
+                "scan_paths must list at least one path".to_string(),
+            ));
+        }
+        if self.max_depth == Some(0) {
+            return Err(ProfileError::Invalid(
+                "max_depth must be greater than zero".to_string(),
+            ));
+        }
+        if self.large_file_threshold == Some(0) {
+            return Err(ProfileError::Invalid(
+                "large_file_threshold must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn apply(&self, config: &Config) -> Config {
+        let mut merged = config.clone();
+        if let Some(scan_paths) = &self.scan_paths {
+            merged.scan_paths = scan_paths.clone();
+        }
+        if let Some(exclude_patterns) = &self.exclude_patterns {
+            merged.exclude_patterns = exclude_patterns.clone();
+        }
+        if let Some(enabled_categories) = &self.enabled_categories {
+            merged.enabled_categories = enabled_categories.clone();
+        }
+        if let Some(value) = self.large_file_threshold {
+            merged.large_file_threshold = value;
+        }
+        if let Some(value) = self.stale_days_threshold {
+            merged.stale_days_threshold = value;
+        }
+        if let Some(value) = self.old_download_days {
+            merged.old_download_days = value;
+        }
+        if let Some(value) = self.max_depth {
+            merged.max_depth = value;
+        }
+        if let Some(value) = self.follow_symlinks {
+            merged.follow_symlinks = value;
+        }
+        if let Some(value) = self.use_trash {
+            merged.use_trash = value;
+        }
+        if let Some(value) = self.recommend_quick_regen {
+            merged.recommend_quick_regen = value;
+        }
+        merged
+    }
+
+    pub fn diff(&self, config: &Config) -> Vec<ConfigChange> {
+        let merged = self.apply(config);
+        let mut changes = Vec::new();
+        let mut push = |field: &'static str, from: String, to: String| {
+            if from != to {
+                changes.push(ConfigChange { field, from, to });
+            }
+        };
+
+        push(
+            "scan_paths",
+            format_paths(&config.scan_paths),
+            format_paths(&merged.scan_paths),
+        );
+        push(
+            "exclude_patterns",
+            config.exclude_patterns.join(", "),
+            merged.exclude_patterns.join(", "),
+        );
+        push(
+            "enabled_categories",
+            format_categories(&config.enabled_categories),
+            format_categories(&merged.enabled_categories),
+        );
+        push(
+            "large_file_threshold",
+            bytesize::ByteSize(config.large_file_threshold).to_string(),
+            bytesize::ByteSize(merged.large_file_threshold).to_string(),
+        );
+        push(
+            "stale_days_threshold",
+            format!("{} days", config.stale_days_threshold),
+            format!("{} days", merged.stale_days_threshold),
+        );
+        push(
+            "old_download_days",
+            format!("{} days", config.old_download_days),
+            format!("{} days", merged.old_download_days),
+        );
+        push(
+            "max_depth",
+            config.max_depth.to_string(),
+            merged.max_depth.to_string(),
+        );
+        push(
+            "follow_symlinks",
+            config.follow_symlinks.to_string(),
+            merged.follow_symlinks.to_string(),
+        );
+        push(
+            "use_trash",
+            config.use_trash.to_string(),
+            merged.use_trash.to_string(),
+        );
+        push(
+            "recommend_quick_regen",
+            config.recommend_quick_regen.to_string(),
+            merged.recommend_quick_regen.to_string(),
+        );
+
+        changes
+    }
+
+    pub fn profiles_dir() -> PathBuf {
+        Config::config_dir().join("profiles")
+    }
+
+    pub fn list_saved() -> Vec<(PathBuf, Profile)> {
+        let Ok(entries) = std::fs::read_dir(Self::profiles_dir()) else {
+            return Vec::new();
+        };
+
+        let mut profiles: Vec<(PathBuf, Profile)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Self::load(&path).ok().map(|profile| (path, profile)))
+            .collect();
+        profiles.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        profiles
+    }
+
+    pub fn file_name(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let slug = slug.trim_matches('-');
+        if slug.is_empty() {
+            "profile.json".to_string()
+        } else {
+            format!("{}.json", slug)
+        }
+    }
+}
+
+fn format_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_categories(categories: &HashSet<FileCategory>) -> String {
+    let mut names: Vec<&str> = categories.iter().map(|c| c.name()).collect();
+    names.sort_unstable();
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_fields_keep_the_current_config() {
+        let config = Config {
+            max_depth: 7,
+            use_trash: false,
+            exclude_patterns: vec!["*.bak".to_string()],
+            ..Config::default()
+        };
+        let profile = Profile {
+            name: "partial".to_string(),
+            max_depth: Some(3),
+            ..Profile::default()
+        };
+
+        let merged = profile.apply(&config);
+        assert_eq!(merged.max_depth, 3);
+        assert!(!merged.use_trash);
+        assert_eq!(merged.exclude_patterns, config.exclude_patterns);
+        assert_eq!(merged.scan_paths, config.scan_paths);
+    }
+
+    #[test]
+    fn diff_lists_only_fields_that_change() {
+        let config = Config::default();
+        let profile = Profile {
+            name: "team".to_string(),
+            max_depth: Some(config.max_depth),
+            use_trash: Some(!config.use_trash),
+            exclude_patterns: Some(vec!["target".to_string(), "*.iso".to_string()]),
+            ..Profile::default()
+        };
+
+        let changes = profile.diff(&config);
+        let fields: Vec<&str> = changes.iter().map(|change| change.field).collect();
+        assert_eq!(fields, ["exclude_patterns", "use_trash"]);
+        assert_eq!(changes[0].to, "target, *.iso");
+        assert_eq!(changes[1].from, config.use_trash.to_string());
+
+        assert!(Profile::from_config("same", &config)
+            .diff(&config)
+            .is_empty());
+    }
+
+    #[test]
+    fn exported_profiles_round_trip() {
+        let config = Config {
+            large_file_threshold: 42 * 1024 * 1024,
+            ..Config::default()
+        };
+        let profile = Profile::from_config("Round trip", &config);
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(Profile::from_json(&json).unwrap(), profile);
+        assert_eq!(
+            profile.apply(&Config::default()).large_file_threshold,
+            config.large_file_threshold
+        );
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        for json in [
+            r#"{"name": "x", "scan_paths": []}"#,
+            r#"{"name": "x", "max_depth": 0}"#,
+            r#"{"name": "x", "large_file_threshold": 0}"#,
+        ] {
+            assert!(matches!(
+                Profile::from_json(json),
+                Err(ProfileError::Invalid(_))
+            ));
+        }
+        assert!(matches!(
+            Profile::from_json("{not json"),
+            Err(ProfileError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn file_names_are_slugs_of_the_profile_name() {
+        let named = |name: &str| Profile {
+            name: name.to_string(),
+            ..Profile::default()
+        };
+        assert_eq!(named("Team Defaults").file_name(), "team-defaults.json");
+        assert_eq!(named("--CI--").file_name(), "ci.json");
+        assert_eq!(named("???").file_name(), "profile.json");
+    }
+}
@@ -0,0 +1,421 @@
+use crate::bundle::HostInfo;
+use crate::categories::FileCategory;
+use crate::manifest::csv_field;
+use crate::scanner::{ScanStats, ScannedItem};
+use bytesize::ByteSize;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub const REPORT_VERSION: u32 = 1;
+
+/// A shareable summary of a scan: totals per category and one row per item.
+/// Rows are built one at a time as the file is written, so exporting a huge
+/// scan never holds more than one row beyond the items themselves.
+#[derive(Debug, Clone)]
+pub struct ScanReport<'a> {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub hostname: String,
+    pub summary: ReportSummary,
+    items: &'a [ScannedItem],
+    selected: &'a HashSet<PathBuf>,
+}
+
+/// Shared with the UI while a report is written: rows so far, and a flag
+/// that stops the export early.
+#[derive(Debug, Default)]
+pub struct ExportProgress {
+    written: AtomicUsize,
+    cancel: AtomicBool,
+}
+
+impl ExportProgress {
+    pub fn written(&self) -> usize {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::SeqCst);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSummary {
+    pub total_items: u64,
+    pub total_size: u64,
+    pub total_size_human: String,
+    pub duration_ms: u64,
+    pub categories: Vec<CategorySummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorySummary {
+    pub category: FileCategory,
+    pub name: &'static str,
+    pub items: u64,
+    pub size: u64,
+    pub size_human: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportRow {
+    pub path: String,
+    pub name: String,
+    pub category: FileCategory,
+    pub size: u64,
+    pub size_human: String,
+    pub modified: DateTime<Utc>,
+    pub confidence: f32,
+    pub is_stale: bool,
+    pub selected: bool,
+}
+
+impl ReportSummary {
+    fn from_stats(stats: &ScanStats) -> Self {
+        let categories = FileCategory::ALL
+            .into_iter()
+            .filter_map(|category| {
+                let items = stats.items_by_category.get(&category).copied()?;
+                let size = stats.size_by_category.get(&category).copied().unwrap_or(0);
+                Some(CategorySummary {
+                    category,
+                    name: category.name(),
+                    items,
+                    size,
+                    size_human: ByteSize(size).to_string(),
+                })
+            })
+            .collect();
+        Self {
+            total_items: stats.total_items,
+            total_size: stats.total_size,
+            total_size_human: ByteSize(stats.total_size).to_string(),
+            duration_ms: stats.duration_ms,
+            categories,
+        }
+    }
+}
+
+impl ReportRow {
+    fn new(item: &ScannedItem, selected: bool) -> Self {
+        Self {
+            path: item.path.to_string_lossy().into_owned(),
+            name: item.name.to_string(),
+            category: item.category,
+            size: item.size,
+            size_human: item.size_formatted(),
+            modified: item.modified,
+            confidence: item.confidence,
+            is_stale: item.is_stale,
+            selected,
+        }
+    }
+}
+
+pub fn default_file_name() -> String {
+    format!("sweeper-report-{}.json", Utc::now().format("%Y%m%d-%H%M%S"))
+}
+
+impl<'a> ScanReport<'a> {
+    pub fn capture(
+        items: &'a [ScannedItem],
+        stats: &ScanStats,
+        selected: &'a HashSet<PathBuf>,
+    ) -> Self {
+        Self {
+            version: REPORT_VERSION,
+            created_at: Utc::now(),
+            hostname: HostInfo::current().hostname,
+            summary: ReportSummary::from_stats(stats),
+            items,
+            selected,
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = ReportRow> + '_ {
+        self.items
+            .iter()
+            .map(|item| ReportRow::new(item, self.selected.contains(&item.path)))
+    }
+
+    /// Writes CSV when `path` ends in `.csv` and JSON otherwise. Returns
+    /// `Ok(None)` if `progress` was cancelled, in which case the partial
+    /// file is removed.
+    pub fn save(&self, path: &Path, progress: &ExportProgress) -> io::Result<Option<usize>> {
+        let is_csv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let mut out = BufWriter::new(File::create(path)?);
+        let written = if is_csv {
+            self.write_csv(&mut out, progress)
+        } else {
+            self.write_json(&mut out, progress)
+        }
+        .and_then(|written| out.flush().map(|()| written));
+        if !matches!(written, Ok(Some(_))) {
+            drop(out);
+            let _ = std::fs::remove_file(path);
+        }
+        written
+    }
+
+    /// The summary goes first as `#` comment lines, then one row per item.
+    fn write_csv(
+        &self,
+        out: &mut impl Write,
+        progress: &ExportProgress,
+    ) -> io::Result<Option<usize>> {
+        let summary = &self.summary;
+        writeln!(
+            out,
+            "# Sweeper scan report, {} on {}",
+            self.created_at.to_rfc3339(),
+            self.hostname
+        )?;
+        writeln!(
+            out,
+            "# Total,{},{},{}",
+            summary.total_items, summary.total_size, summary.total_size_human
+        )?;
+        for category in &summary.categories {
+            writeln!(
+                out,
+                "# {},{},{},{}",
+                category.name, category.items, category.size, category.size_human
+            )?;
+        }
+        writeln!(
+            out,
+            "path,name,category,size,size_human,modified,confidence,is_stale,selected"
+        )?;
+        self.write_rows(progress, |row, _| {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{:.2},{},{}",
+                csv_field(&row.path),
+                csv_field(&row.name),
+                row.category.name(),
+                row.size,
+                row.size_human,
+                row.modified.to_rfc3339(),
+                row.confidence,
+                row.is_stale,
+                row.selected
+            )
+        })
+    }
+
+    /// Writes the header fields, then streams `items` as an array with one
+    /// row per line instead of serializing the whole report at once.
+    fn write_json(
+        &self,
+        out: &mut impl Write,
+        progress: &ExportProgress,
+    ) -> io::Result<Option<usize>> {
+        writeln!(out, "{{")?;
+        write_json_field(out, "version", &self.version)?;
+        write_json_field(out, "created_at", &self.created_at)?;
+        write_json_field(out, "hostname", &self.hostname)?;
+        write_json_field(out, "summary", &self.summary)?;
+        write!(out, "  \"items\": [")?;
+        let written = self.write_rows(progress, |row, index| {
+            out.write_all(if index == 0 { b"\n    " } else { b",\n    " })?;
+            serde_json::to_writer(&mut *out, &row).map_err(io::Error::other)
+        })?;
+        let Some(count) = written else {
+            return Ok(None);
+        };
+        if count > 0 {
+            write!(out, "\n  ")?;
+        }
+        writeln!(out, "]\n}}")?;
+        Ok(written)
+    }
+
+    fn write_rows(
+        &self,
+        progress: &ExportProgress,
+        mut write_row: impl FnMut(ReportRow, usize) -> io::Result<()>,
+    ) -> io::Result<Option<usize>> {
+        for (index, row) in self.rows().enumerate() {
+            if progress.is_cancelled() {
+                return Ok(None);
+            }
+            write_row(row, index)?;
+            progress.written.store(index + 1, Ordering::Relaxed);
+        }
+        Ok(Some(self.items.len()))
+    }
+}
+
+fn write_json_field(out: &mut impl Write, key: &str, value: &impl Serialize) -> io::Result<()> {
+    write!(out, "  \"{}\": ", key)?;
+    serde_json::to_writer(&mut *out, value).map_err(io::Error::other)?;
+    writeln!(out, ",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn items() -> Vec<ScannedItem> {
+        let modified = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        vec![
+            ScannedItem::new(
+                PathBuf::from("/home/me/web/node_modules"),
+                3 * 1024 * 1024,
+                FileCategory::DevArtifact,
+                modified,
+            ),
+            ScannedItem::new(
+                PathBuf::from("/home/me/notes, \"old\".tmp"),
+                512,
+                FileCategory::TempFile,
+                modified,
+            ),
+        ]
+    }
+
+    /// Splits a CSV line on commas outside quotes, undoing `csv_field`.
+    fn csv_fields(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    fields.last_mut().unwrap().push('"');
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(String::new()),
+                c => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
+
+    /// Cancels the export once `after` rows have gone out.
+    struct CancelAfter<'a> {
+        progress: &'a ExportProgress,
+        after: usize,
+    }
+
+    impl Write for CancelAfter<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.progress.written() >= self.after {
+                self.progress.cancel();
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn json_report_parses_back() {
+        let items = items();
+        let selected = HashSet::from([items[1].path.clone()]);
+        let report = ScanReport::capture(&items, &ScanStats::from_items(&items), &selected);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+
+        let written = report.save(&path, &ExportProgress::default()).unwrap();
+        assert_eq!(written, Some(2));
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["version"], REPORT_VERSION);
+        assert_eq!(json["summary"]["total_items"], 2);
+        assert_eq!(json["summary"]["categories"].as_array().unwrap().len(), 2);
+        let rows = json["items"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["path"], "/home/me/web/node_modules");
+        assert_eq!(rows[0]["size"], 3 * 1024 * 1024);
+        assert_eq!(rows[0]["selected"], false);
+        assert_eq!(rows[1]["name"], "notes, \"old\".tmp");
+        assert_eq!(rows[1]["selected"], true);
+        assert_eq!(rows[1]["modified"], "2026-03-01T12:00:00Z");
+    }
+
+    #[test]
+    fn empty_json_report_parses_back() {
+        let selected = HashSet::new();
+        let report = ScanReport::capture(&[], &ScanStats::default(), &selected);
+        let mut out = Vec::new();
+        report
+            .write_json(&mut out, &ExportProgress::default())
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["items"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn csv_report_parses_back() {
+        let items = items();
+        let selected = HashSet::from([items[0].path.clone()]);
+        let report = ScanReport::capture(&items, &ScanStats::from_items(&items), &selected);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.CSV");
+
+        report.save(&path, &ExportProgress::default()).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let (summary, table): (Vec<&str>, Vec<&str>) =
+            csv.lines().partition(|line| line.starts_with('#'));
+        assert_eq!(summary.len(), 4);
+        assert!(summary[1].starts_with("# Total,2,"));
+
+        let header = csv_fields(table[0]);
+        let rows: Vec<Vec<String>> = table[1..].iter().map(|line| csv_fields(line)).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == header.len()));
+        let column = |row: &Vec<String>, name: &str| {
+            row[header.iter().position(|h| h == name).unwrap()].clone()
+        };
+        assert_eq!(column(&rows[0], "category"), "Dev Artifacts");
+        assert_eq!(column(&rows[0], "selected"), "true");
+        assert_eq!(column(&rows[1], "path"), "/home/me/notes, \"old\".tmp");
+        assert_eq!(column(&rows[1], "size"), "512");
+        assert_eq!(column(&rows[1], "modified"), "2026-03-01T12:00:00+00:00");
+    }
+
+    #[test]
+    fn cancelling_removes_the_partial_file() {
+        let items = items();
+        let selected = HashSet::new();
+        let report = ScanReport::capture(&items, &ScanStats::from_items(&items), &selected);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+
+        let progress = ExportProgress::default();
+        progress.cancel();
+        assert_eq!(report.save(&path, &progress).unwrap(), None);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn cancelling_stops_between_rows() {
+        let items: Vec<ScannedItem> = (0..10).flat_map(|_| items()).collect();
+        let selected = HashSet::new();
+        let report = ScanReport::capture(&items, &ScanStats::from_items(&items), &selected);
+        let progress = ExportProgress::default();
+        let mut out = CancelAfter {
+            progress: &progress,
+            after: 5,
+        };
+
+        assert_eq!(report.write_csv(&mut out, &progress).unwrap(), None);
+        assert_eq!(progress.written(), 6);
+    }
+}
@@ -0,0 +1,398 @@
+use crate::categories::FileCategory;
+use crate::config::{self, Config};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    label: String,
+    name: Option<String>,
+    path_prefix: Option<String>,
+    min_age_days: Option<u64>,
+    min_size: Option<String>,
+    is_dir: Option<bool>,
+    category: FileCategory,
+    confidence: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub label: String,
+    name: Option<Regex>,
+    path_prefix: Option<PathBuf>,
+    min_age_days: u64,
+    min_size: u64,
+    is_dir: Option<bool>,
+    pub category: FileCategory,
+    pub confidence: f32,
+}
+
+impl Rule {
+    fn compile(raw: RawRule, home: Option<&Path>) -> Result<Self, String> {
+        if raw.label.trim().is_empty() {
+            return Err("label must not be empty".to_string());
+        }
+        if raw.name.is_none() && raw.path_prefix.is_none() {
+            return Err("needs a name or path_prefix".to_string());
+        }
+        if !(0.0..=1.0).contains(&raw.confidence) {
+            return Err(format!(
+                "confidence {} is outside 0.0 to 1.0",
+                raw.confidence
+            ));
+        }
+        let name = raw.name.as_deref().map(glob_regex).transpose()?;
+        let path_prefix = raw
+            .path_prefix
+            .as_deref()
+            .map(|prefix| expand_home(prefix, home))
+            .transpose()?;
+        let min_size = raw
+            .min_size
+            .as_deref()
+            .map(config::parse_size)
+            .transpose()?
+            .unwrap_or(0);
+
+        Ok(Self {
+            label: raw.label,
+            name,
+            path_prefix,
+            min_age_days: raw.min_age_days.unwrap_or(0),
+            min_size,
+            is_dir: raw.is_dir,
+            category: raw.category,
+            confidence: raw.confidence,
+        })
+    }
+
+    pub fn matches(&self, path: &Path, is_dir: bool, age_days: u64) -> bool {
+        if self.is_dir.is_some_and(|want_dir| want_dir != is_dir) {
+            return false;
+        }
+        if age_days < self.min_age_days {
+            return false;
+        }
+        if let Some(prefix) = &self.path_prefix {
+            if !path.starts_with(prefix) {
+                return false;
+            }
+        }
+        match &self.name {
+            Some(name) => path
+                .file_name()
+                .is_some_and(|file_name| name.is_match(&file_name.to_string_lossy())),
+            None => true,
+        }
+    }
+
+    pub fn accepts_size(&self, size: u64) -> bool {
+        size >= self.min_size
+    }
+
+    pub fn reason(&self) -> String {
+        format!("Matched custom rule \"{}\"", self.label)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+    pub warnings: Vec<String>,
+}
+
+impl RuleSet {
+    pub fn path() -> PathBuf {
+        Config::config_dir().join("rules.toml")
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(Self::path()) {
+            Ok(contents) => Self::parse(&contents, dirs::home_dir().as_deref()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => Self {
+                rules: Vec::new(),
+                warnings: vec![format!("Could not read {}: {}", Self::path().display(), e)],
+            },
+        }
+    }
+
+    pub fn parse(contents: &str, home: Option<&Path>) -> Self {
+        let mut set = Self::default();
+        let table: toml::Table = match contents.parse() {
+            Ok(table) => table,
+            Err(e) => {
+                set.warnings
+                    .push(format!("rules.toml is not valid TOML: {}", e));
+                return set;
+            }
+        };
+
+        for (key, _) in table.iter().filter(|(key, _)| key.as_str() != "rule") {
+            set.warnings
+                .push(format!("Unknown top-level key \"{}\" ignored", key));
+        }
+        let rules = match table.get("rule") {
+            None => return set,
+            Some(toml::Value::Array(rules)) => rules.clone(),
+            Some(_) => {
+                set.warnings
+                    .push("\"rule\" must be an array of tables ([[rule]])".to_string());
+                return set;
+            }
+        };
+
+        for (index, value) in rules.into_iter().enumerate() {
+            let compiled = value
+                .try_into::<RawRule>()
+                .map_err(|e| e.message().to_string())
+                .and_then(|raw| Rule::compile(raw, home));
+            match compiled {
+                Ok(rule) => set.rules.push(rule),
+                Err(e) => set
+                    .warnings
+                    .push(format!("Rule {} skipped: {}", index + 1, e)),
+            }
+        }
+        set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn find(&self, path: &Path, is_dir: bool, age_days: u64) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(path, is_dir, age_days))
+    }
+}
+
+fn expand_home(prefix: &str, home: Option<&Path>) -> Result<PathBuf, String> {
+    let expanded = match prefix.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = home.ok_or("path_prefix uses ~ but there is no home directory")?;
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(prefix),
+    };
+    if expanded.is_relative() {
+        return Err(format!("path_prefix \"{}\" must be absolute", prefix));
+    }
+    Ok(expanded.canonicalize().unwrap_or(expanded))
+}
+
+pub fn glob_regex(glob: &str) -> Result<Regex, String> {
+    let mut pattern = String::from("^");
+    for ch in glob.chars() {
+        match ch {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '/' | '\\' => {
+                return Err(format!(
+                    "name \"{}\" must not contain a path separator",
+                    glob
+                ))
+            }
+            other => pattern.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOME: &str = "/home/me";
+
+    fn parse(contents: &str) -> RuleSet {
+        RuleSet::parse(contents, Some(Path::new(HOME)))
+    }
+
+    fn rule(body: &str) -> Result<Rule, String> {
+        let mut set = parse(&format!("[[rule]]\n{}", body));
+        match set.warnings.pop() {
+            Some(warning) => Err(warning),
+            None => Ok(set.rules.remove(0)),
+        }
+    }
+
+    #[test]
+    fn a_complete_rule_parses() {
+        let rule = rule(
+            r#"
+            label = "Old renders"
+            name = "*.exr"
+            path_prefix = "~/Renders"
+            min_age_days = 30
+            min_size = "10 MB"
+            is_dir = false
+            category = "LargeFile"
+            confidence = 0.8
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rule.label, "Old renders");
+        assert_eq!(rule.path_prefix, Some(PathBuf::from("/home/me/Renders")));
+        assert_eq!(rule.min_age_days, 30);
+        assert_eq!(rule.min_size, 10 << 20);
+        assert_eq!(rule.category, FileCategory::LargeFile);
+        assert_eq!(rule.confidence, 0.8);
+    }
+
+    #[test]
+    fn invalid_rules_are_skipped_with_a_reason() {
+        let valid = "label = \"x\"\nname = \"*.tmp\"\ncategory = \"TempFile\"\n";
+        let cases = [
+            (
+                format!("{}confidence = 1.5", valid),
+                "Rule 1 skipped: confidence 1.5 is outside 0.0 to 1.0",
+            ),
+            (
+                format!("{}confidence = -0.1", valid),
+                "Rule 1 skipped: confidence -0.1 is outside 0.0 to 1.0",
+            ),
+            (
+                format!("{}confidence = nan", valid),
+                "Rule 1 skipped: confidence NaN is outside 0.0 to 1.0",
+            ),
+            (
+                "label = \"x\"\ncategory = \"TempFile\"\nconfidence = 0.5".to_string(),
+                "Rule 1 skipped: needs a name or path_prefix",
+            ),
+            (
+                "label = \" \"\nname = \"a\"\ncategory = \"TempFile\"\nconfidence = 0.5"
+                    .to_string(),
+                "Rule 1 skipped: label must not be empty",
+            ),
+            (
+                "label = \"x\"\nname = \"logs/*.log\"\ncategory = \"TempFile\"\nconfidence = 0.5"
+                    .to_string(),
+                "Rule 1 skipped: name \"logs/*.log\" must not contain a path separator",
+            ),
+            (
+                "label = \"x\"\npath_prefix = \"relative/dir\"\ncategory = \"TempFile\"\nconfidence = 0.5"
+                    .to_string(),
+                "Rule 1 skipped: path_prefix \"relative/dir\" must be absolute",
+            ),
+            (
+                format!("{}confidence = 0.5\nmin_size = \"lots\"", valid),
+                "Rule 1 skipped: \"lots\" is not a size",
+            ),
+        ];
+        for (body, expected) in cases {
+            assert_eq!(rule(&body).unwrap_err(), expected, "{}", body);
+        }
+
+        let unknown = rule(&format!("{}confidence = 0.5\ncolour = \"red\"", valid));
+        assert!(unknown.unwrap_err().contains("unknown field `colour`"));
+    }
+
+    #[test]
+    fn file_level_problems_are_warnings_not_failures() {
+        let set = parse("rules = 1\n[[rule]]\nlabel = \"x\"\nname = \"*.tmp\"\ncategory = \"TempFile\"\nconfidence = 0.5\n");
+        assert_eq!(set.rules.len(), 1);
+        assert_eq!(set.warnings, ["Unknown top-level key \"rules\" ignored"]);
+
+        let set = parse("rule = \"everything\"");
+        assert!(set.is_empty());
+        assert_eq!(
+            set.warnings,
+            ["\"rule\" must be an array of tables ([[rule]])"]
+        );
+
+        let set = parse("[[rule]\n");
+        assert!(set.is_empty());
+        assert!(set.warnings[0].starts_with("rules.toml is not valid TOML"));
+
+        assert!(parse("").warnings.is_empty());
+    }
+
+    #[test]
+    fn globs_match_whole_names_only() {
+        let cases = [
+            ("*.log", "build.log", true),
+            ("*.log", "build.log.gz", false),
+            ("*.log", ".log", true),
+            ("cache-?", "cache-1", true),
+            ("cache-?", "cache-10", false),
+            ("a.b", "axb", false),
+            ("[x]+", "[x]+", true),
+            ("Thumbs.db", "thumbs.db", false),
+        ];
+        for (glob, name, expected) in cases {
+            assert_eq!(
+                glob_regex(glob).unwrap().is_match(name),
+                expected,
+                "{} vs {}",
+                glob,
+                name
+            );
+        }
+        assert!(glob_regex("a\\b").is_err());
+    }
+
+    #[test]
+    fn home_expands_only_as_a_leading_component() {
+        let home = Some(Path::new(HOME));
+        assert_eq!(expand_home("~", home), Ok(PathBuf::from(HOME)));
+        assert_eq!(
+            expand_home("~/Downloads", home),
+            Ok(PathBuf::from("/home/me/Downloads"))
+        );
+        assert_eq!(
+            expand_home("~other/x", home),
+            Err("path_prefix \"~other/x\" must be absolute".to_string())
+        );
+        assert_eq!(
+            expand_home("/srv/data", home),
+            Ok(PathBuf::from("/srv/data"))
+        );
+        assert!(expand_home("~/x", None).is_err());
+    }
+
+    #[test]
+    fn matches_checks_kind_age_prefix_and_name() {
+        let rule = rule(
+            "label = \"x\"\nname = \"*.bak\"\npath_prefix = \"/srv\"\nmin_age_days = 7\nis_dir = false\ncategory = \"TempFile\"\nconfidence = 0.5",
+        )
+        .unwrap();
+        let cases = [
+            ("/srv/db/dump.bak", false, 7, true),
+            ("/srv/db/dump.bak", false, 6, false),
+            ("/srv/db/dump.bak", true, 30, false),
+            ("/srv-old/dump.bak", false, 30, false),
+            ("/home/dump.bak", false, 30, false),
+            ("/srv/db/dump.bak.gz", false, 30, false),
+        ];
+        for (path, is_dir, age, expected) in cases {
+            assert_eq!(
+                rule.matches(Path::new(path), is_dir, age),
+                expected,
+                "{} dir={} age={}",
+                path,
+                is_dir,
+                age
+            );
+        }
+        assert!(rule.accepts_size(0));
+    }
+
+    #[test]
+    fn the_first_matching_rule_wins() {
+        let set = parse(
+            "[[rule]]\nlabel = \"first\"\nname = \"*.tmp\"\ncategory = \"TempFile\"\nconfidence = 0.5\n\
+             [[rule]]\nlabel = \"second\"\nname = \"*\"\ncategory = \"Unused\"\nconfidence = 0.2\n",
+        );
+        let find = |path: &str| {
+            set.find(Path::new(path), false, 0)
+                .map(|rule| rule.label.as_str())
+        };
+        assert_eq!(find("/a/b.tmp"), Some("first"));
+        assert_eq!(find("/a/b.txt"), Some("second"));
+    }
+}
@@ -0,0 +1,121 @@
+use super::names::{normalize_name, with_normalized, CaseSensitivity};
+use crate::rules;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct ExcludeSet {
+    names: HashSet<String>,
+    globs: Vec<Regex>,
+    prefixes: Vec<PathBuf>,
+    sensitivity: CaseSensitivity,
+}
+
+impl ExcludeSet {
+    pub fn new(patterns: &[String], home: Option<&Path>) -> Self {
+        let sensitivity = CaseSensitivity::platform_default();
+        let mut excludes = Self {
+            names: HashSet::new(),
+            globs: Vec::new(),
+            prefixes: Vec::new(),
+            sensitivity,
+        };
+        for pattern in patterns.iter().map(|pattern| pattern.trim()) {
+            if pattern.is_empty() {
+                continue;
+            }
+            if let Some(prefix) = prefix_of(pattern, home) {
+                excludes.prefixes.push(prefix);
+            } else if pattern.contains(['*', '?']) {
+                match rules::glob_regex(&normalize_name(pattern, sensitivity)) {
+                    Ok(glob) => excludes.globs.push(glob),
+                    Err(e) => log::warn!("Ignoring exclude pattern {}: {}", pattern, e),
+                }
+            } else {
+                excludes
+                    .names
+                    .insert(normalize_name(pattern, sensitivity).into_owned());
+            }
+        }
+        excludes
+    }
+
+    /// Adds paths the user chose to ignore; each one hides itself and
+    /// everything under it.
+    pub fn ignoring(mut self, paths: &[PathBuf]) -> Self {
+        self.prefixes.extend(paths.iter().cloned());
+        self
+    }
+
+    pub fn excludes(&self, path: &Path) -> bool {
+        if self.prefixes.iter().any(|prefix| path.starts_with(prefix)) {
+            return true;
+        }
+        let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+            return false;
+        };
+        with_normalized(&name, self.sensitivity, |name| {
+            self.names.contains(name) || self.globs.iter().any(|glob| glob.is_match(name))
+        })
+    }
+
+    pub fn covers(&self, path: &Path) -> bool {
+        path.ancestors().any(|ancestor| self.excludes(ancestor))
+    }
+}
+
+fn prefix_of(pattern: &str, home: Option<&Path>) -> Option<PathBuf> {
+    if let Some(rest) = pattern.strip_prefix('~') {
+        if rest.is_empty() || rest.starts_with(['/', '\\']) {
+            return home.map(|home| home.join(rest.trim_start_matches(['/', '\\'])));
+        }
+    }
+    let path = Path::new(pattern);
+    (path.is_absolute() || pattern.starts_with(['/', '\\'])).then(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(patterns: &[&str]) -> ExcludeSet {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        ExcludeSet::new(&patterns, Some(Path::new("/home/me")))
+    }
+
+    #[test]
+    fn bare_names_match_any_component() {
+        let excludes = set(&["Dropbox", "  ", ""]);
+        assert!(excludes.excludes(Path::new("/home/me/Dropbox")));
+        assert!(excludes.covers(Path::new("/home/me/Dropbox/work/report.pdf")));
+        assert!(!excludes.covers(Path::new("/home/me/Dropbox-old/report.pdf")));
+        assert!(!excludes.covers(Path::new("/home/me/Documents")));
+    }
+
+    #[test]
+    fn absolute_and_home_prefixes_match_everything_under_them() {
+        let excludes = set(&["/Volumes/Backup", "~/OneDrive", "~"]);
+        assert!(excludes.excludes(Path::new("/Volumes/Backup/2024/photos")));
+        assert!(!excludes.excludes(Path::new("/Volumes/BackupDrive")));
+        assert!(excludes.excludes(Path::new("/home/me/OneDrive/notes")));
+        assert!(excludes.excludes(Path::new("/home/me/anything")));
+        assert!(!excludes.excludes(Path::new("/srv/OneDrive")));
+    }
+
+    #[test]
+    fn globs_match_names() {
+        let excludes = set(&["*.photoslibrary", "backup-?"]);
+        assert!(excludes.covers(Path::new("/home/me/Pictures/Photos.photoslibrary/db")));
+        assert!(excludes.excludes(Path::new("/home/me/backup-1")));
+        assert!(!excludes.excludes(Path::new("/home/me/backup-12")));
+        assert!(!excludes.excludes(Path::new("/home/me/photoslibrary")));
+    }
+
+    #[test]
+    fn ignored_paths_hide_themselves_and_their_children() {
+        let excludes = set(&[]).ignoring(&[PathBuf::from("/home/me/keep")]);
+        assert!(excludes.excludes(Path::new("/home/me/keep/cache")));
+        assert!(!excludes.excludes(Path::new("/home/me/keeper")));
+    }
+}
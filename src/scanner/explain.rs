@@ -0,0 +1,161 @@
+use super::ScannedItem;
+use crate::categories::{CategoryPatterns, FileCategory};
+
+const SAFE_CONFIDENCE: f32 = 0.9;
+const USUALLY_SAFE_CONFIDENCE: f32 = 0.8;
+
+pub fn explain(item: &ScannedItem) -> String {
+    let mut sentences = vec![item.category.description().to_string()];
+
+    let rebuilder = if item.category == FileCategory::DevArtifact {
+        CategoryPatterns::rebuilder(&item.path)
+    } else {
+        None
+    };
+    if let Some(rebuilder) = rebuilder {
+        sentences.push(match rebuilder.command {
+            Some(command) => format!(
+                "{} doesn't restore it on its own; run `{}` in the project to get it back.",
+                rebuilder.tool, command
+            ),
+            None => format!("{} rebuilds this automatically.", rebuilder.tool),
+        });
+    }
+    let slower_next_time = rebuilder.is_some_and(|rebuilder| rebuilder.command.is_none());
+    if item.regenerates_quickly {
+        sentences.push(
+            "It fills back up quickly, so deleting it only frees the space for a short while."
+                .to_string(),
+        );
+    }
+
+    let known = CategoryPatterns::known_cache(&item.path);
+    if let Some(owner) = known.and_then(|known| known.owner) {
+        sentences.push(format!("It is kept by {}.", owner));
+    }
+    if let Some(caution) = known.and_then(|known| known.caution) {
+        sentences.push(format!("{}.", caution));
+    }
+
+    if let Some(instructions) = item.elevation_required() {
+        sentences.push(format!(
+            "Windows protects it, so Sweeper can't delete it. {}.",
+            instructions
+        ));
+    } else if let Some(store) = item.managed_store() {
+        sentences.push(format!(
+            "It belongs to the {} store, which breaks if files are removed by hand; run `{}` instead.",
+            store.tool, store.command
+        ));
+    } else if let Some(owner) = &item.managed_by {
+        sentences.push(format!(
+            "It is managed by {}, and deleting it can break that app or lose its data.",
+            owner
+        ));
+    }
+    if item.sampled_hash {
+        sentences.push(
+            "It was matched to its copy by checking parts of the file, not the whole file."
+                .to_string(),
+        );
+    }
+    if item.is_stale {
+        sentences.push(format!(
+            "It hasn't changed in {} days, so it probably isn't in use.",
+            item.age_days()
+        ));
+    }
+    if let Some(note) = &item.note {
+        sentences.push(format!("{}.", note.trim_end_matches('.')));
+    }
+
+    let managed = item.managed_store().is_some()
+        || item.managed_by.is_some()
+        || item.elevation_required().is_some();
+    sentences.push(
+        if managed || item.confidence < USUALLY_SAFE_CONFIDENCE {
+            "Look inside before deleting it."
+        } else if item.confidence >= SAFE_CONFIDENCE && slower_next_time {
+            "Deleting it is safe, but the next build will be slower."
+        } else if item.confidence >= SAFE_CONFIDENCE {
+            "Deleting it is safe."
+        } else {
+            "Deleting it is usually safe."
+        }
+        .to_string(),
+    );
+
+    sentences.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    fn item(path: &str, category: FileCategory, confidence: f32) -> ScannedItem {
+        let mut item = ScannedItem::new(PathBuf::from(path), 1, category, Utc::now());
+        item.confidence = confidence;
+        item
+    }
+
+    #[test]
+    fn build_output_explains_how_it_comes_back() {
+        let target = item("/code/app/target", FileCategory::DevArtifact, 0.95);
+        assert_eq!(
+            explain(&target),
+            format!(
+                "{} Cargo rebuilds this automatically. Deleting it is safe, but the next build will be slower.",
+                FileCategory::DevArtifact.description()
+            )
+        );
+
+        let modules = item("/code/web/node_modules", FileCategory::DevArtifact, 0.95);
+        let text = explain(&modules);
+        assert!(text.contains("run `npm install` in the project"));
+        assert!(text.ends_with("Deleting it is safe."));
+
+        // Only dev artifacts get rebuild advice.
+        let named_target = item("/home/me/target", FileCategory::LargeFile, 0.95);
+        assert!(!explain(&named_target).contains("Cargo"));
+    }
+
+    #[test]
+    fn the_verdict_follows_confidence() {
+        let verdict = |confidence| {
+            let text = explain(&item("/tmp/x.tmp", FileCategory::TempFile, confidence));
+            text.rsplit_once(". ").unwrap().1.to_string()
+        };
+        assert_eq!(verdict(0.95), "Deleting it is safe.");
+        assert_eq!(verdict(0.9), "Deleting it is safe.");
+        assert_eq!(verdict(0.85), "Deleting it is usually safe.");
+        assert_eq!(verdict(0.5), "Look inside before deleting it.");
+    }
+
+    #[test]
+    fn caches_name_their_owner_and_warn_about_refilling() {
+        let cache = item(
+            "/home/me/.config/Code/CachedData",
+            FileCategory::IdeCache,
+            0.9,
+        );
+        let text = explain(&cache);
+        assert!(text.contains("It fills back up quickly"));
+        assert!(text.contains("It is kept by VS Code."));
+    }
+
+    #[test]
+    fn managed_and_noted_items_ask_for_a_look() {
+        let mut container = item(
+            "/Users/me/Library/Containers/com.apple.mail",
+            FileCategory::AppContainer,
+            0.95,
+        );
+        container.managed_by = Some("com.apple.mail".to_string());
+        container.note = Some("Holds 3 vaults.".to_string());
+        let text = explain(&container);
+        assert!(text.contains("It is managed by com.apple.mail"));
+        assert!(text.contains("Holds 3 vaults. Look inside before deleting it."));
+    }
+}
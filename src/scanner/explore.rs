@@ -0,0 +1,209 @@
+use super::Scanner;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirNode {
+    pub path: PathBuf,
+    pub size: u64,
+    pub children: Vec<DirNode>,
+}
+
+impl DirNode {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+
+    pub fn direct_size(&self) -> u64 {
+        self.size
+            .saturating_sub(self.children.iter().map(|child| child.size).sum())
+    }
+
+    pub fn descend(&self, trail: &[usize]) -> Option<&DirNode> {
+        trail
+            .iter()
+            .try_fold(self, |node, &index| node.children.get(index))
+    }
+
+    pub fn remove(&mut self, path: &Path) -> Option<u64> {
+        if !path.starts_with(&self.path) || path == self.path {
+            return None;
+        }
+
+        if let Some(index) = self.children.iter().position(|child| child.path == path) {
+            let removed = self.children.remove(index).size;
+            self.size = self.size.saturating_sub(removed);
+            return Some(removed);
+        }
+
+        let removed = self
+            .children
+            .iter_mut()
+            .find(|child| path.starts_with(&child.path))?
+            .remove(path)?;
+        self.size = self.size.saturating_sub(removed);
+        Some(removed)
+    }
+
+    fn build(
+        path: PathBuf,
+        sizes: &HashMap<PathBuf, u64>,
+        children: &HashMap<PathBuf, Vec<PathBuf>>,
+    ) -> Self {
+        let mut nodes: Vec<DirNode> = children
+            .get(&path)
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|child| Self::build(child.clone(), sizes, children))
+                    .collect()
+            })
+            .unwrap_or_default();
+        nodes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+        Self {
+            size: sizes.get(&path).copied().unwrap_or(0),
+            path,
+            children: nodes,
+        }
+    }
+}
+
+impl Scanner {
+    pub fn explore(&self, root: &Path, depth: usize) -> DirNode {
+        self.should_stop.store(false, Ordering::SeqCst);
+        self.files_scanned.store(0, Ordering::SeqCst);
+
+        let mut sizes: HashMap<PathBuf, u64> = HashMap::new();
+        let mut children: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        sizes.insert(root.to_path_buf(), 0);
+        let site = self.sites.claim(root);
+
+        let walker = WalkDir::new(root)
+            .follow_links(self.config.follow_symlinks)
+            .same_file_system(self.config.same_filesystem_only)
+            .into_iter()
+            .filter_map(|e| e.ok());
+
+        for entry in walker {
+            if self.should_stop.load(Ordering::SeqCst) {
+                break;
+            }
+
+            self.files_scanned.fetch_add(1, Ordering::SeqCst);
+
+            if entry.file_type().is_dir() {
+                if entry.depth() > 0 && entry.depth() <= depth {
+                    site.update(entry.path());
+                    let path = entry.path().to_path_buf();
+                    if let Some(parent) = path.parent() {
+                        children
+                            .entry(parent.to_path_buf())
+                            .or_default()
+                            .push(path.clone());
+                    }
+                    sizes.insert(path, 0);
+                }
+                continue;
+            }
+
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            for ancestor in entry.path().ancestors().skip(1) {
+                if let Some(total) = sizes.get_mut(ancestor) {
+                    *total += size;
+                }
+                if ancestor == root {
+                    break;
+                }
+            }
+        }
+
+        DirNode::build(root.to_path_buf(), &sizes, &children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn write(path: &Path, size: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; size]).unwrap();
+    }
+
+    fn node(path: &str, size: u64, children: Vec<DirNode>) -> DirNode {
+        DirNode {
+            path: PathBuf::from(path),
+            size,
+            children,
+        }
+    }
+
+    #[test]
+    fn sizes_roll_up_and_deep_folders_fold_into_their_ancestor() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("top.bin"), 5);
+        write(&root.join("small/a.bin"), 10);
+        write(&root.join("big/b.bin"), 100);
+        write(&root.join("big/deep/er/c.bin"), 1000);
+
+        let scanner = Scanner::with_scope(Config::default(), vec![root.to_path_buf()]);
+        let tree = scanner.explore(root, 2);
+
+        assert_eq!(tree.size, 1115);
+        assert_eq!(tree.direct_size(), 5);
+        let names: Vec<String> = tree.children.iter().map(DirNode::name).collect();
+        assert_eq!(names, ["big", "small"]);
+
+        let big = tree.descend(&[0]).unwrap();
+        assert_eq!(big.size, 1100);
+        assert_eq!(big.direct_size(), 100);
+        let deep = tree.descend(&[0, 0]).unwrap();
+        assert_eq!(deep.name(), "deep");
+        // "er" is below the depth limit, so its file counts toward "deep".
+        assert_eq!(deep.size, 1000);
+        assert!(deep.children.is_empty());
+        assert_eq!(tree.descend(&[0, 0, 0]), None);
+        assert_eq!(tree.descend(&[5]), None);
+    }
+
+    #[test]
+    fn removing_a_folder_shrinks_every_ancestor() {
+        let mut tree = node(
+            "/r",
+            600,
+            vec![
+                node("/r/a", 500, vec![node("/r/a/x", 300, vec![])]),
+                node("/r/b", 50, vec![]),
+            ],
+        );
+
+        assert_eq!(tree.remove(Path::new("/r/a/x")), Some(300));
+        assert_eq!(tree.size, 300);
+        assert_eq!(tree.children[0].size, 200);
+        assert!(tree.children[0].children.is_empty());
+
+        assert_eq!(tree.remove(Path::new("/r/b")), Some(50));
+        assert_eq!(tree.size, 250);
+        assert_eq!(tree.remove(Path::new("/r")), None);
+        assert_eq!(tree.remove(Path::new("/elsewhere")), None);
+        assert_eq!(tree.remove(Path::new("/r/missing")), None);
+        assert_eq!(tree.size, 250);
+    }
+
+    #[test]
+    fn direct_size_never_underflows() {
+        let tree = node("/r", 10, vec![node("/r/a", 20, vec![])]);
+        assert_eq!(tree.direct_size(), 0);
+    }
+}
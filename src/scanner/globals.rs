@@ -0,0 +1,138 @@
+use crate::categories::{CategoryPatterns, GlobalPackageRoot};
+use std::path::{Component, Path, PathBuf};
+
+const MAX_LINK_HOPS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct BinLink {
+    pub path: PathBuf,
+    pub broken: bool,
+}
+
+pub fn packages(root: &GlobalPackageRoot) -> Vec<PathBuf> {
+    let bundled = CategoryPatterns::bundled_global_packages();
+    let mut packages = Vec::new();
+    for dir in child_dirs(&root.modules) {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if name.starts_with('.') || bundled.contains(&name.as_str()) {
+            continue;
+        }
+        if name.starts_with('@') {
+            packages.extend(child_dirs(&dir));
+        } else {
+            packages.push(dir);
+        }
+    }
+    packages
+}
+
+fn child_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+pub fn bin_links(bin: &Path, package: &Path) -> Vec<BinLink> {
+    let Ok(entries) = std::fs::read_dir(bin) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let target = resolve_link(&entry.path())?;
+            target.starts_with(package).then(|| BinLink {
+                path: entry.path(),
+                broken: !target.exists(),
+            })
+        })
+        .collect()
+}
+
+fn resolve_link(link: &Path) -> Option<PathBuf> {
+    let mut current = link.to_path_buf();
+    let mut hops = 0;
+    while hops < MAX_LINK_HOPS {
+        let Ok(target) = std::fs::read_link(&current) else {
+            break;
+        };
+        let base = current.parent().unwrap_or(Path::new(""));
+        current = normalize(&base.join(target));
+        hops += 1;
+    }
+    (hops > 0).then_some(current)
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+pub fn declares_bin(package: &Path) -> bool {
+    std::fs::read_to_string(package.join("package.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .is_some_and(|manifest| {
+            manifest
+                .get("bin")
+                .is_some_and(|bin| !bin.is_null() && bin != "")
+        })
+}
+
+pub fn orphan_reason(
+    root: &GlobalPackageRoot,
+    package: &Path,
+    links: &[BinLink],
+) -> Option<String> {
+    if !root.node_installed() {
+        return root.node_version.as_ref().map(|version| {
+            format!(
+                "Installed for Node {}, which is no longer installed",
+                version
+            )
+        });
+    }
+    if let Some(link) = links.iter().find(|link| link.broken) {
+        return Some(format!(
+            "Its command {} points to a missing file",
+            link.path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+    }
+    if links.is_empty() && root.bin.is_dir() && declares_bin(package) {
+        return Some(format!(
+            "Its command isn't linked in {}",
+            root.bin.display()
+        ));
+    }
+    None
+}
+
+pub fn dangling_links(roots: &[GlobalPackageRoot], package: &Path) -> Vec<PathBuf> {
+    let Some(root) = roots
+        .iter()
+        .find(|root| package.starts_with(&root.modules) && package != root.modules)
+    else {
+        return Vec::new();
+    };
+    bin_links(&root.bin, package)
+        .into_iter()
+        .filter(|link| link.broken)
+        .map(|link| link.path)
+        .collect()
+}
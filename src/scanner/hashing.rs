@@ -0,0 +1,77 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const SAMPLED_HASH_THRESHOLD: u64 = 512 * 1024 * 1024;
+pub const SAMPLE_CHUNK_SIZE: u64 = 1024 * 1024;
+
+const READ_BUFFER_SIZE: usize = 256 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentHash {
+    pub hex: String,
+    pub sampled: bool,
+}
+
+pub fn content_hash(
+    path: &Path,
+    size: u64,
+    should_stop: &AtomicBool,
+) -> io::Result<Option<ContentHash>> {
+    if size > SAMPLED_HASH_THRESHOLD {
+        return sampled_hash(path, size).map(|hex| Some(ContentHash { hex, sampled: true }));
+    }
+    full_hash(path, should_stop).map(|hex| {
+        hex.map(|hex| ContentHash {
+            hex,
+            sampled: false,
+        })
+    })
+}
+
+pub fn full_hash(path: &Path, should_stop: &AtomicBool) -> io::Result<Option<String>> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+
+    loop {
+        if should_stop.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(Some(hex::encode(hasher.finalize().as_bytes())))
+}
+
+fn sample_offsets(size: u64) -> Vec<u64> {
+    if size <= SAMPLE_CHUNK_SIZE {
+        return vec![0];
+    }
+    let last = size - SAMPLE_CHUNK_SIZE;
+    let mut offsets = vec![0, last / 2, last];
+    offsets.dedup();
+    offsets
+}
+
+pub fn sampled_hash(path: &Path, size: u64) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; SAMPLE_CHUNK_SIZE as usize];
+
+    hasher.update(&size.to_le_bytes());
+    for offset in sample_offsets(size) {
+        file.seek(SeekFrom::Start(offset))?;
+        let len = SAMPLE_CHUNK_SIZE.min(size - offset) as usize;
+        file.read_exact(&mut buffer[..len])?;
+        hasher.update(&offset.to_le_bytes());
+        hasher.update(&buffer[..len]);
+    }
+
+    Ok(hex::encode(hasher.finalize().as_bytes()))
+}
@@ -0,0 +1,234 @@
+use super::ItemStore;
+use crate::categories::FileCategory;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Prometheus,
+    Json,
+}
+
+impl MetricsFormat {
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Prometheus,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScanMetrics {
+    pub timestamp: i64,
+    pub duration_ms: u64,
+    pub total_bytes: u64,
+    pub total_items: u64,
+    pub errors: usize,
+    pub bytes_by_category: BTreeMap<String, u64>,
+    pub items_by_category: BTreeMap<String, u64>,
+}
+
+impl ScanMetrics {
+    pub fn from_store(store: &ItemStore, finished: DateTime<Utc>) -> Self {
+        let stats = store.stats();
+        let by_category = |counts: &std::collections::HashMap<FileCategory, u64>| {
+            FileCategory::ALL
+                .iter()
+                .map(|category| {
+                    (
+                        category_key(*category),
+                        counts.get(category).copied().unwrap_or(0),
+                    )
+                })
+                .collect()
+        };
+        Self {
+            timestamp: finished.timestamp(),
+            duration_ms: stats.duration_ms,
+            total_bytes: stats.total_size,
+            total_items: stats.total_items,
+            errors: store.warnings().len(),
+            bytes_by_category: by_category(&stats.size_by_category),
+            items_by_category: by_category(&stats.items_by_category),
+        }
+    }
+
+    pub fn render(&self, format: MetricsFormat) -> String {
+        match format {
+            MetricsFormat::Prometheus => self.prometheus(),
+            MetricsFormat::Json => {
+                let mut json = serde_json::to_string_pretty(self).unwrap_or_default();
+                json.push('\n');
+                json
+            }
+        }
+    }
+
+    pub fn prometheus(&self) -> String {
+        let mut out = String::new();
+        gauge(
+            &mut out,
+            "sweeper_scan_timestamp_seconds",
+            "Unix time the last scan finished.",
+            [(None, self.timestamp.to_string())],
+        );
+        gauge(
+            &mut out,
+            "sweeper_scan_duration_seconds",
+            "Wall time of the last scan.",
+            [(None, format!("{:.3}", self.duration_ms as f64 / 1000.0))],
+        );
+        gauge(
+            &mut out,
+            "sweeper_scan_errors",
+            "Warnings raised during the last scan.",
+            [(None, self.errors.to_string())],
+        );
+        gauge(
+            &mut out,
+            "sweeper_reclaimable_bytes",
+            "Reclaimable bytes found by the last scan, by category.",
+            self.bytes_by_category
+                .iter()
+                .map(|(category, bytes)| (Some(category.as_str()), bytes.to_string())),
+        );
+        gauge(
+            &mut out,
+            "sweeper_reclaimable_items",
+            "Reclaimable items found by the last scan, by category.",
+            self.items_by_category
+                .iter()
+                .map(|(category, items)| (Some(category.as_str()), items.to_string())),
+        );
+        out
+    }
+}
+
+fn gauge<'a>(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    samples: impl IntoIterator<Item = (Option<&'a str>, String)>,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (category, value) in samples {
+        match category {
+            Some(category) => {
+                let _ = writeln!(out, "{}{{category=\"{}\"}} {}", name, category, value);
+            }
+            None => {
+                let _ = writeln!(out, "{} {}", name, value);
+            }
+        }
+    }
+}
+
+fn category_key(category: FileCategory) -> String {
+    let mut key = String::new();
+    for (index, ch) in format!("{:?}", category).chars().enumerate() {
+        if ch.is_ascii_uppercase() && index > 0 {
+            key.push('_');
+        }
+        key.push(ch.to_ascii_lowercase());
+    }
+    key
+}
+
+pub fn write(path: &Path, store: &ItemStore) -> std::io::Result<()> {
+    let contents = ScanMetrics::from_store(store, Utc::now()).render(MetricsFormat::for_path(path));
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut temp = path.as_os_str().to_os_string();
+    temp.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ScannedItem;
+    use chrono::TimeZone;
+
+    fn metrics() -> ScanMetrics {
+        let modified = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let mut store = ItemStore::default();
+        for (path, size, category) in [
+            ("/p/target", 5_000_000, FileCategory::DevArtifact),
+            ("/p/node_modules", 1_200_000, FileCategory::DevArtifact),
+            ("/c/pip", 300_000, FileCategory::PackageCache),
+            ("/tmp/a.tmp", 42, FileCategory::TempFile),
+        ] {
+            store.push(ScannedItem::new(path.into(), size, category, modified));
+        }
+        store.push_warning("Permission denied: /root".to_string());
+        store.set_duration_ms(1234);
+        ScanMetrics::from_store(
+            &store,
+            Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap(),
+        )
+    }
+
+    // Scrapers and dashboards parse these, so any change to them is a
+    // change to the output format.
+    const GOLDEN_PROMETHEUS: &str = include_str!("../../tests/fixtures/metrics/scan.prom");
+    const GOLDEN_JSON: &str = include_str!("../../tests/fixtures/metrics/scan.json");
+
+    #[test]
+    fn prometheus_output_matches_the_golden_file() {
+        assert_eq!(
+            metrics().render(MetricsFormat::Prometheus),
+            GOLDEN_PROMETHEUS
+        );
+    }
+
+    #[test]
+    fn json_output_matches_the_golden_file() {
+        assert_eq!(metrics().render(MetricsFormat::Json), GOLDEN_JSON);
+    }
+
+    #[test]
+    fn format_follows_the_extension() {
+        let cases = [
+            ("metrics.json", MetricsFormat::Json),
+            ("METRICS.JSON", MetricsFormat::Json),
+            ("metrics.prom", MetricsFormat::Prometheus),
+            ("metrics", MetricsFormat::Prometheus),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                MetricsFormat::for_path(Path::new(path)),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn write_replaces_the_file_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/scan.prom");
+        write(&path, &ItemStore::default()).unwrap();
+        write(&path, &ItemStore::default()).unwrap();
+        let names: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["scan.prom"]);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("sweeper_reclaimable_bytes{category=\"dev_artifact\"} 0"));
+    }
+}
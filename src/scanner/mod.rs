@@ -1,14 +1,85 @@
-use crate::categories::{CategoryPatterns, FileCategory};
+use crate::categories::{self, CategoryPatterns, FileCategory, ManagedStore, PartialDownload};
 use crate::config::Config;
+use crate::duplicates;
+use crate::error::{self, SweeperError};
+use crate::rules::{Rule, RuleSet};
 use bytesize::ByteSize;
 use chrono::{DateTime, Duration, Utc};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use walkdir::WalkDir;
 
+pub mod exclude;
+pub mod explain;
+mod explore;
+pub mod globals;
+pub mod hashing;
+pub mod metrics;
+mod mounts;
+pub mod names;
+mod origin;
+mod owners;
+pub mod persist;
+mod progress;
+mod sites;
+pub mod sniff;
+mod store;
+
+use exclude::ExcludeSet;
+pub use explore::DirNode;
+use mounts::VisitedDirs;
+use names::{CaseSensitivity, NameSet};
+use owners::OwnerIndex;
+pub use progress::{ScanPhase, ScanProgress};
+use sites::ActiveSites;
+pub use store::{ItemCursor, ItemStore};
+
+/// Locks `mutex`, recovering the data if a panicking worker poisoned it.
+/// Every critical section here leaves its data consistent, so a poisoned
+/// lock only means a pass died, not that the store is corrupt.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScanSource {
+    #[default]
+    Unknown,
+    KnownCachePath,
+    SystemCacheChild,
+    ProjectWalk,
+    DownloadsPass,
+    LogDirectory,
+    DuplicatePass,
+    VersionedCache,
+    GlobalPackages,
+    TempFamily,
+    PartialDownloads,
+}
+
+impl ScanSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScanSource::Unknown => "Unknown",
+            ScanSource::KnownCachePath => "Known cache path",
+            ScanSource::SystemCacheChild => "System cache folder",
+            ScanSource::ProjectWalk => "Project walk",
+            ScanSource::DownloadsPass => "Downloads pass",
+            ScanSource::LogDirectory => "Log directory pass",
+            ScanSource::DuplicatePass => "Duplicate pass",
+            ScanSource::VersionedCache => "Versioned cache pass",
+            ScanSource::GlobalPackages => "Global package pass",
+            ScanSource::TempFamily => "Temp folder family",
+            ScanSource::PartialDownloads => "Partial download pass",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ScannedItem {
     pub path: PathBuf,
@@ -16,10 +87,64 @@ pub struct ScannedItem {
     pub size: u64,
     pub category: FileCategory,
     pub confidence: f32,
+    pub confidence_adjustment: f32,
+    pub confidence_overridden: bool,
     pub is_stale: bool,
+    /// A dev artifact whose project was changed within `protect_active_days`.
+    pub recently_active: bool,
+    pub modified: DateTime<Utc>,
+    pub accessed: DateTime<Utc>,
+    pub scanned_at: DateTime<Utc>,
+    pub hash: Option<String>,
+    pub sampled_hash: bool,
+    /// The copy of a duplicate set that stays behind. Never cleaned, so no
+    /// caller can delete the last copy by selecting every duplicate.
+    pub is_keeper: bool,
+    pub regenerates_quickly: bool,
+    pub source: ScanSource,
+    pub note: Option<String>,
+    pub origin: Option<String>,
+    pub managed_by: Option<String>,
+    pub members: Vec<(PathBuf, u64)>,
+    pub file_count: Option<u64>,
 }
 
 impl ScannedItem {
+    pub fn new(path: PathBuf, size: u64, category: FileCategory, modified: DateTime<Utc>) -> Self {
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into();
+        let regenerates_quickly =
+            CategoryPatterns::known_cache(&path).is_some_and(|known| known.regenerates_quickly);
+
+        Self {
+            path,
+            name,
+            size,
+            category,
+            confidence: category.base_confidence(),
+            confidence_adjustment: 0.0,
+            confidence_overridden: false,
+            is_stale: false,
+            recently_active: false,
+            modified,
+            accessed: modified,
+            scanned_at: Utc::now(),
+            hash: None,
+            sampled_hash: false,
+            is_keeper: false,
+            regenerates_quickly,
+            source: ScanSource::Unknown,
+            note: None,
+            origin: None,
+            managed_by: None,
+            members: Vec::new(),
+            file_count: None,
+        }
+    }
+
     pub fn size_formatted(&self) -> String {
         ByteSize(self.size).to_string()
     }
@@ -27,9 +152,252 @@ impl ScannedItem {
     pub fn confidence_percent(&self) -> u8 {
         (self.confidence * 100.0) as u8
     }
+
+    pub fn age_days(&self) -> u64 {
+        (self.scanned_at - self.accessed.max(self.modified))
+            .num_days()
+            .max(0) as u64
+    }
+
+    fn age_bonus(&self) -> f32 {
+        self.category
+            .age_curve()
+            .map(|curve| curve.bonus(self.age_days()))
+            .unwrap_or(0.0)
+    }
+
+    pub fn tracks_staleness(&self) -> bool {
+        matches!(
+            (self.category, self.source),
+            (FileCategory::DevArtifact, ScanSource::ProjectWalk)
+                | (FileCategory::OldDownload, ScanSource::DownloadsPass)
+                | (FileCategory::LogFile, ScanSource::LogDirectory)
+                | (FileCategory::Unused, ScanSource::ProjectWalk)
+        )
+    }
+
+    fn apply_age_curve(&mut self) {
+        if let Some(curve) = self.category.age_curve() {
+            self.confidence = curve.apply(self.confidence, self.age_days());
+        }
+    }
+
+    pub fn confidence_reasons(&self) -> Vec<String> {
+        let mut reasons = vec![format!(
+            "{} base {}%",
+            self.category.name(),
+            (self.category.base_confidence() * 100.0).round() as u8
+        )];
+        if self.confidence_overridden {
+            reasons.push("Custom confidence override for this category".to_string());
+        }
+        let age_bonus = (self.age_bonus() * 100.0).round() as u8;
+        if age_bonus > 0 {
+            reasons.push(format!(
+                "Not modified in {} days (+{}%)",
+                self.age_days(),
+                age_bonus
+            ));
+        }
+        if self.regenerates_quickly {
+            reasons.push("Known cache that regenerates quickly".to_string());
+        }
+        if self.sampled_hash {
+            reasons.push("Matched by sampled hash only".to_string());
+        }
+        if let Some(store) = self.managed_store() {
+            reasons.push(format!(
+                "Managed by the {} store; run `{}` instead of deleting files",
+                store.tool, store.command
+            ));
+        } else if let Some(owner) = &self.managed_by {
+            reasons.push(format!(
+                "Managed by {}; deleting inside it can break the app",
+                owner
+            ));
+        }
+        if let Some(note) = &self.note {
+            reasons.push(note.clone());
+        }
+        reasons
+    }
+
+    pub fn managed_store(&self) -> Option<ManagedStore> {
+        CategoryPatterns::managed_store(&self.path)
+    }
+
+    pub fn elevation_required(&self) -> Option<&'static str> {
+        CategoryPatterns::elevation_required(&self.path)
+    }
+
+    pub fn removed_by(&self, deleted: &HashSet<PathBuf>) -> bool {
+        if self.members.is_empty() {
+            deleted.contains(&self.path)
+        } else {
+            self.members.iter().all(|(path, _)| deleted.contains(path))
+        }
+    }
+
+    pub fn refreshed(&self) -> Option<Self> {
+        if !self.members.is_empty() {
+            let usages: Vec<(PathBuf, DirUsage)> = self
+                .members
+                .iter()
+                .filter_map(|(path, _)| {
+                    let usage = Scanner::dir_usage(path).ok()?;
+                    (usage.size > 0).then(|| (path.clone(), usage))
+                })
+                .collect();
+            let size = usages.iter().map(|(_, usage)| usage.size).sum::<u64>();
+            if size == 0 {
+                return None;
+            }
+            return Some(Self {
+                size,
+                file_count: Some(usages.iter().map(|(_, usage)| usage.files).sum()),
+                members: usages
+                    .into_iter()
+                    .map(|(path, usage)| (path, usage.size))
+                    .collect(),
+                scanned_at: Utc::now(),
+                ..self.clone()
+            });
+        }
+        let meta = std::fs::symlink_metadata(&self.path).ok()?;
+        let (size, file_count) = if meta.is_dir() {
+            let usage = Scanner::dir_usage(&self.path).ok()?;
+            (usage.size, Some(usage.files))
+        } else {
+            (meta.len(), None)
+        };
+        if size == 0 {
+            return None;
+        }
+
+        let modified = Scanner::modified_time(&meta);
+        Some(Self {
+            size,
+            file_count,
+            modified,
+            accessed: if self.category == FileCategory::Unused {
+                Scanner::accessed_time(&meta)
+            } else {
+                modified
+            },
+            scanned_at: Utc::now(),
+            ..self.clone()
+        })
+    }
+}
+
+pub fn allocated_size(path: &Path) -> Option<u64> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(meta.blocks() * 512)
+    }
+
+    #[cfg(not(unix))]
+    {
+        Some(meta.len())
+    }
+}
+
+pub fn is_outdated(
+    scanned_at: DateTime<Utc>,
+    parent_modified: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    max_age: Duration,
+) -> bool {
+    now - scanned_at > max_age || parent_modified.is_some_and(|modified| modified > scanned_at)
+}
+
+pub fn outdated_paths(items: &[ScannedItem], max_age: Duration) -> HashSet<PathBuf> {
+    let now = Utc::now();
+    items
+        .par_iter()
+        .filter(|item| {
+            let parent_modified = item
+                .path
+                .parent()
+                .and_then(|parent| std::fs::metadata(parent).ok())
+                .map(|meta| Scanner::modified_time(&meta));
+            is_outdated(item.scanned_at, parent_modified, now, max_age)
+        })
+        .map(|item| item.path.clone())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegenSummary {
+    pub items: usize,
+    pub size: u64,
+}
+
+impl RegenSummary {
+    pub fn of<'a>(items: impl IntoIterator<Item = &'a ScannedItem>) -> Self {
+        items
+            .into_iter()
+            .filter(|item| item.regenerates_quickly)
+            .fold(Self::default(), |summary, item| Self {
+                items: summary.items + 1,
+                size: summary.size + item.size,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirUsage {
+    pub size: u64,
+    pub files: u64,
+    /// Folders skipped because they were already counted under another path.
+    pub repeated: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeDrift {
+    Unchanged,
+    Grew,
+    Shrank,
+    ShrankSharply,
+    Gone,
+}
+
+impl SizeDrift {
+    const TOLERANCE: f64 = 0.01;
+    const SHARP_SHRINK: f64 = 0.20;
+
+    pub fn classify(reported: u64, measured: Option<u64>) -> Self {
+        let Some(measured) = measured.filter(|size| *size > 0) else {
+            return Self::Gone;
+        };
+        if reported == 0 {
+            return Self::Grew;
+        }
+        let change = (measured as f64 - reported as f64) / reported as f64;
+        if change.abs() <= Self::TOLERANCE {
+            Self::Unchanged
+        } else if change > 0.0 {
+            Self::Grew
+        } else if -change > Self::SHARP_SHRINK {
+            Self::ShrankSharply
+        } else {
+            Self::Shrank
+        }
+    }
+
+    pub fn flagged(&self) -> bool {
+        matches!(self, Self::ShrankSharply | Self::Gone)
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScanStats {
     pub total_items: u64,
     pub total_size: u64,
@@ -39,80 +407,325 @@ pub struct ScanStats {
 }
 
 impl ScanStats {
+    pub fn from_items(items: &[ScannedItem]) -> Self {
+        let mut stats = Self::default();
+        for item in items {
+            stats.record(item);
+        }
+        stats
+    }
+
     pub fn total_size_formatted(&self) -> String {
         ByteSize(self.total_size).to_string()
     }
+
+    fn record(&mut self, item: &ScannedItem) {
+        self.total_items += 1;
+        self.total_size += item.size;
+        *self.items_by_category.entry(item.category).or_insert(0) += 1;
+        *self.size_by_category.entry(item.category).or_insert(0) += item.size;
+    }
+
+    fn forget(&mut self, item: &ScannedItem) {
+        self.total_items = self.total_items.saturating_sub(1);
+        self.total_size = self.total_size.saturating_sub(item.size);
+        if let Some(count) = self.items_by_category.get_mut(&item.category) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.items_by_category.remove(&item.category);
+            }
+        }
+        if let Some(size) = self.size_by_category.get_mut(&item.category) {
+            *size = size.saturating_sub(item.size);
+            if !self.items_by_category.contains_key(&item.category) {
+                self.size_by_category.remove(&item.category);
+            }
+        }
+    }
+}
+
+const MIN_DUPLICATE_SIZE: u64 = 1024 * 1024;
+const REDOWNLOADABLE_BONUS: f32 = 0.10;
+const CACHEDIR_TAG_BONUS: f32 = 0.05;
+const BINARY_LOG_PENALTY: f32 = 0.4;
+const ORPHANED_PACKAGE_CONFIDENCE: f32 = 0.95;
+const TEMP_FAMILY_MIN_AGE_MINUTES: i64 = 60;
+const PARTIAL_DOWNLOAD_CONFIDENCE: f32 = 0.98;
+const ACTIVE_PROJECT_PENALTY: f32 = 0.5;
+const ORPHANED_CACHE_BONUS: f32 = 0.15;
+
+pub fn normalize_scope(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut scope: Vec<PathBuf> = Vec::with_capacity(paths.len());
+    for path in paths.iter().filter(|p| p.is_dir()) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if !scope.contains(&path) {
+            scope.push(path);
+        }
+    }
+    scope
+}
+
+type ScanPass = fn(&Scanner);
+
+pub fn reclassify(items: &mut [ScannedItem], config: &Config) -> usize {
+    let mut changed = 0;
+    for item in items {
+        let before = (item.confidence, item.is_stale);
+        let overridden = config.confidence_override(item.category);
+        item.confidence = (overridden.unwrap_or(item.category.base_confidence())
+            + item.confidence_adjustment)
+            .clamp(0.0, 1.0);
+        item.confidence_overridden = overridden.is_some();
+        if item.tracks_staleness() && !item.recently_active {
+            item.is_stale = item.age_days() >= config.stale_days_threshold;
+        }
+        if (item.confidence, item.is_stale) != before {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// `usage` of `path` minus the partial downloads inside it, which are
+/// already reported as their own items.
+fn without_partials(usage: DirUsage, partials: &[(PathBuf, DirUsage)], path: &Path) -> DirUsage {
+    partials
+        .iter()
+        .filter(|(partial, _)| partial.starts_with(path))
+        .fold(usage, |usage, (_, partial)| DirUsage {
+            size: usage.size.saturating_sub(partial.size),
+            files: usage.files.saturating_sub(partial.files),
+            ..usage
+        })
+}
+
+/// When the project holding `artifact` was last worked on: the newest of
+/// the files next to it (sources, manifests, lockfiles) and git's HEAD and
+/// index.
+fn project_last_active(artifact: &Path) -> Option<DateTime<Utc>> {
+    let project = artifact.parent()?;
+    let siblings = std::fs::read_dir(project)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path() != artifact)
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|meta| meta.is_file());
+    let git = [".git/HEAD", ".git/index"]
+        .iter()
+        .filter_map(|name| std::fs::metadata(project.join(name)).ok());
+    siblings
+        .chain(git)
+        .filter_map(|meta| meta.modified().ok())
+        .max()
+        .map(DateTime::<Utc>::from)
+}
+
+fn days_since(time: DateTime<Utc>) -> u64 {
+    (Utc::now() - time).num_days().max(0) as u64
+}
+
+fn rule_item(rule: &Rule, path: PathBuf, size: u64, modified: DateTime<Utc>) -> ScannedItem {
+    let mut item = ScannedItem::new(path, size, rule.category, modified);
+    item.confidence = rule.confidence;
+    item.note = Some(rule.reason());
+    item
+}
+
+/// Tracks how deep a depth-first walk may go: `shallow` levels by default,
+/// and `extended` more levels below every project root seen on the way down.
+struct DepthLimits {
+    shallow: usize,
+    extended: usize,
+    roots: Vec<(usize, usize)>,
+}
+
+impl DepthLimits {
+    fn new(shallow: usize, extended: usize) -> Self {
+        Self {
+            shallow,
+            extended,
+            roots: Vec::new(),
+        }
+    }
+
+    fn allows(&mut self, depth: usize) -> bool {
+        while self.roots.last().is_some_and(|(root, _)| *root >= depth) {
+            self.roots.pop();
+        }
+        depth <= self.limit()
+    }
+
+    fn extend_below(&mut self, depth: usize) {
+        let limit = self.limit().max(depth + self.extended);
+        self.roots.push((depth, limit));
+    }
+
+    fn limit(&self) -> usize {
+        self.roots.last().map_or(self.shallow, |(_, limit)| *limit)
+    }
+}
+
+struct ScanningGuard<'a>(&'a AtomicBool);
+
+impl Drop for ScanningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Counts a scan root as done once the project walk leaves it, however it
+/// leaves.
+struct RootDone<'a>(&'a Mutex<ScanProgress>);
+
+impl Drop for RootDone<'_> {
+    fn drop(&mut self) {
+        lock(self.0).roots_done += 1;
+    }
 }
 
 pub struct Scanner {
     config: Config,
-    items: Arc<Mutex<Vec<ScannedItem>>>,
-    stats: Arc<Mutex<ScanStats>>,
+    scope: Option<Vec<PathBuf>>,
+    store: Arc<Mutex<ItemStore>>,
     is_scanning: Arc<AtomicBool>,
     should_stop: Arc<AtomicBool>,
     files_scanned: Arc<AtomicU64>,
-    current_path: Arc<Mutex<String>>,
+    sites: Arc<ActiveSites>,
+    progress: Arc<Mutex<ScanProgress>>,
+    rules: Mutex<Arc<RuleSet>>,
+    excludes: ExcludeSet,
 }
 
 impl Scanner {
     pub fn new(config: Config) -> Self {
+        let excludes = ExcludeSet::new(&config.exclude_patterns, dirs::home_dir().as_deref())
+            .ignoring(&config.ignored_paths);
         Self {
             config,
-            items: Arc::new(Mutex::new(Vec::new())),
-            stats: Arc::new(Mutex::new(ScanStats::default())),
+            scope: None,
+            store: Arc::new(Mutex::new(ItemStore::default())),
             is_scanning: Arc::new(AtomicBool::new(false)),
             should_stop: Arc::new(AtomicBool::new(false)),
             files_scanned: Arc::new(AtomicU64::new(0)),
-            current_path: Arc::new(Mutex::new(String::new())),
+            sites: Arc::new(ActiveSites::default()),
+            progress: Arc::new(Mutex::new(ScanProgress::default())),
+            rules: Mutex::new(Arc::new(RuleSet::default())),
+            excludes,
         }
     }
 
+    pub fn with_scope(mut config: Config, scope: Vec<PathBuf>) -> Self {
+        config.scan_paths = scope.clone();
+        let mut scanner = Self::new(config);
+        scanner.scope = Some(scope);
+        scanner
+    }
+
     pub fn is_scanning(&self) -> bool {
         self.is_scanning.load(Ordering::SeqCst)
     }
 
+    pub fn stop(&self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+    }
+
     pub fn files_scanned(&self) -> u64 {
         self.files_scanned.load(Ordering::SeqCst)
     }
 
-    pub fn current_path(&self) -> String {
-        self.current_path
-            .lock()
-            .map(|guard| guard.clone())
-            .unwrap_or_default()
+    pub fn active_paths(&self) -> Vec<PathBuf> {
+        self.sites.paths()
+    }
+
+    pub fn progress(&self) -> ScanProgress {
+        let mut progress = lock(&self.progress).clone();
+        progress.files_scanned = self.files_scanned();
+        progress
     }
 
-    pub fn scan(&self) -> Vec<ScannedItem> {
+    pub fn scan(&self) -> error::Result<Vec<ScannedItem>> {
+        lock(&self.store).clear();
         self.is_scanning.store(true, Ordering::SeqCst);
+        let _scanning = ScanningGuard(&self.is_scanning);
         self.should_stop.store(false, Ordering::SeqCst);
         self.files_scanned.store(0, Ordering::SeqCst);
+        *lock(&self.progress) = ScanProgress::new(self.config.scan_paths.len());
 
-        if let Ok(mut items) = self.items.lock() {
-            items.clear();
+        for root in self.scope.iter().flatten() {
+            std::fs::read_dir(root).map_err(|e| SweeperError::io(root, e))?;
         }
-        if let Ok(mut stats) = self.stats.lock() {
-            *stats = ScanStats::default();
+        let rules = RuleSet::load();
+        for warning in &rules.warnings {
+            log::warn!("Custom rules: {}", warning);
         }
+        *lock(&self.rules) = Arc::new(rules);
 
         let start_time = std::time::Instant::now();
 
+        let passes: [(ScanPhase, ScanPass); 7] = [
+            (ScanPhase::Caches, Self::scan_known_cache_paths),
+            (ScanPhase::Projects, Self::scan_project_directories),
+            (ScanPhase::Downloads, Self::scan_downloads),
+            (ScanPhase::Duplicates, Self::scan_duplicates),
+            (ScanPhase::Logs, Self::scan_logs),
+            (ScanPhase::VersionedCaches, Self::scan_versioned_caches),
+            (ScanPhase::GlobalPackages, Self::scan_global_packages),
+        ];
         rayon::scope(|s| {
-            s.spawn(|_| self.scan_known_cache_paths());
-            s.spawn(|_| self.scan_project_directories());
-            s.spawn(|_| self.scan_downloads());
+            for (phase, pass) in passes {
+                s.spawn(move |_| self.run_pass(phase, pass));
+            }
         });
 
         let duration = start_time.elapsed();
-        if let Ok(mut stats) = self.stats.lock() {
-            stats.duration_ms = duration.as_millis() as u64;
+        {
+            let mut store = lock(&self.store);
+            let leftover = |item: &ScannedItem| {
+                matches!(
+                    item.category,
+                    FileCategory::LargeFile | FileCategory::Unused
+                )
+            };
+            let claimed: HashSet<PathBuf> = store
+                .items()
+                .iter()
+                .filter(|item| !leftover(item))
+                .map(|item| item.path.clone())
+                .collect();
+            store.retain(|item| {
+                !leftover(item) || !item.path.ancestors().any(|path| claimed.contains(path))
+            });
+            let partials: HashSet<PathBuf> = store
+                .items()
+                .iter()
+                .filter(|item| item.source == ScanSource::PartialDownloads)
+                .flat_map(|item| item.members.iter().map(|(path, _)| path.clone()))
+                .collect();
+            if !partials.is_empty() {
+                store.retain(|item| !partials.contains(&item.path));
+            }
+            store.set_duration_ms(duration.as_millis() as u64);
         }
 
-        self.is_scanning.store(false, Ordering::SeqCst);
+        Ok(self.get_items())
+    }
 
-        self.items
-            .lock()
-            .map(|guard| guard.clone())
-            .unwrap_or_default()
+    fn run_pass(&self, phase: ScanPhase, pass: ScanPass) {
+        let name = phase.pass_name();
+        let started = std::time::Instant::now();
+        lock(&self.progress).phase_mut(phase).started = true;
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| pass(self)));
+        lock(&self.store).record_pass(name, started.elapsed().as_millis() as u64);
+        lock(&self.progress).phase_mut(phase).done = true;
+        if let Err(payload) = result {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown error".to_string());
+            log::error!("{} panicked: {}", name, message);
+            self.add_warning(format!("{} failed: {}", name, message));
+        }
     }
 
     fn scan_known_cache_paths(&self) {
@@ -130,10 +743,20 @@ impl Scanner {
                 CategoryPatterns::browser_cache_paths(),
                 FileCategory::BrowserCache,
             ),
+            (
+                CategoryPatterns::windows_system_paths(),
+                FileCategory::SystemCache,
+            ),
+            (
+                CategoryPatterns::windows_report_paths(),
+                FileCategory::LogFile,
+            ),
         ];
+        let versioned = Self::versioned_roots();
+        let partials = self.scan_partial_downloads();
 
         cache_configs.into_par_iter().for_each(|(paths, category)| {
-            if self.should_stop.load(Ordering::SeqCst) {
+            if self.should_stop.load(Ordering::SeqCst) || !self.category_enabled(category) {
                 return;
             }
 
@@ -143,40 +766,238 @@ impl Scanner {
                 }
 
                 let path = PathBuf::from(cache_path);
-                if path.exists() && path.is_dir() {
-                    self.update_current_path(&path);
-
-                    if let Ok(size) = Self::dir_size_parallel(&path) {
-                        if size > 0 {
-                            let item = ScannedItem {
-                                path: path.clone(),
-                                name: path
-                                    .file_name()
-                                    .unwrap_or_default()
-                                    .to_string_lossy()
-                                    .into(),
-                                size,
-                                category,
-                                confidence: category.base_confidence(),
-                                is_stale: false,
-                            };
-
-                            self.add_item(item);
+                if path.exists()
+                    && path.is_dir()
+                    && self.in_scope(&path)
+                    && !versioned.contains(&path)
+                {
+                    let _site = self.sites.claim(&path);
+
+                    if let Ok(usage) = self.measure(&path) {
+                        let usage = without_partials(usage, &partials, &path);
+                        if usage.size > 0 {
+                            let modified = std::fs::metadata(&path)
+                                .map(|meta| Self::modified_time(&meta))
+                                .unwrap_or_else(|_| Utc::now());
+                            let mut item =
+                                ScannedItem::new(path.clone(), usage.size, category, modified);
+                            item.file_count = Some(usage.files);
+
+                            self.add_item(item, ScanSource::KnownCachePath);
                         }
                     }
                 }
             });
         });
 
-        self.scan_system_caches();
+        self.scan_system_caches(&partials);
     }
 
-    fn scan_system_caches(&self) {
-        if self.should_stop.load(Ordering::SeqCst) {
+    /// Reports unfinished package downloads hidden inside caches as their own
+    /// temp items, one per tool. Returns what was reported so the caches
+    /// holding them can leave it out of their size.
+    fn scan_partial_downloads(&self) -> Vec<(PathBuf, DirUsage)> {
+        if !self.category_enabled(FileCategory::TempFile) {
+            return Vec::new();
+        }
+        self.add_partial_downloads(CategoryPatterns::partial_downloads())
+    }
+
+    fn add_partial_downloads(&self, roots: Vec<PartialDownload>) -> Vec<(PathBuf, DirUsage)> {
+        let mut reported = Vec::new();
+        let cutoff = Utc::now() - Duration::minutes(TEMP_FAMILY_MIN_AGE_MINUTES);
+
+        for partial in roots {
+            if self.should_stop.load(Ordering::SeqCst) {
+                break;
+            }
+            if !partial.root.is_dir()
+                || !self.in_scope(&partial.root)
+                || self.excludes.covers(&partial.root)
+            {
+                continue;
+            }
+
+            let mut candidates = Vec::new();
+            let mut walker = WalkDir::new(&partial.root)
+                .max_depth(partial.depth)
+                .same_file_system(self.config.same_filesystem_only)
+                .into_iter();
+            while let Some(entry) = walker.next() {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                let name = entry.file_name().to_string_lossy();
+                if entry.file_type().is_dir() && entry.depth() > 0 && partial.holds_partials(&name)
+                {
+                    walker.skip_current_dir();
+                    if let Ok(children) = std::fs::read_dir(entry.path()) {
+                        candidates
+                            .extend(children.filter_map(|child| child.ok()).map(|c| c.path()));
+                    }
+                } else if entry.file_type().is_file() && partial.matches_file(&name) {
+                    candidates.push(entry.into_path());
+                }
+            }
+
+            let members: Vec<(PathBuf, DirUsage, DateTime<Utc>)> = candidates
+                .into_par_iter()
+                .filter_map(|path| {
+                    let modified = Self::modified_time(&std::fs::symlink_metadata(&path).ok()?);
+                    if modified > cutoff || self.excludes.excludes(&path) {
+                        return None;
+                    }
+                    let usage = self.measure(&path).ok()?;
+                    (usage.size > 0).then_some((path, usage, modified))
+                })
+                .collect();
+            let Some(newest) = members.iter().map(|(.., modified)| *modified).max() else {
+                continue;
+            };
+
+            let mut item = ScannedItem::new(
+                partial.root.join(partial.pattern),
+                members.iter().map(|(_, usage, _)| usage.size).sum(),
+                FileCategory::TempFile,
+                newest,
+            );
+            item.name = format!("{} partial {} downloads", members.len(), partial.tool).into();
+            item.confidence = PARTIAL_DOWNLOAD_CONFIDENCE;
+            item.note = Some(format!(
+                "Left behind by interrupted {} downloads",
+                partial.tool
+            ));
+            item.members = members
+                .iter()
+                .map(|(path, usage, _)| (path.clone(), usage.size))
+                .collect();
+            item.file_count = Some(members.iter().map(|(_, usage, _)| usage.files).sum());
+            if self.add_item(item, ScanSource::PartialDownloads) {
+                reported.extend(members.into_iter().map(|(path, usage, _)| (path, usage)));
+            }
+        }
+        reported
+    }
+
+    fn scan_versioned_caches(&self) {
+        for cache in CategoryPatterns::versioned_caches() {
+            if self.should_stop.load(Ordering::SeqCst) {
+                return;
+            }
+            if !self.category_enabled(cache.category) {
+                continue;
+            }
+
+            let root = PathBuf::from(&cache.root);
+            if !root.is_dir() || !self.in_scope(&root) {
+                continue;
+            }
+            let _site = self.sites.claim(&root);
+
+            let Ok(entries) = std::fs::read_dir(&root) else {
+                continue;
+            };
+            let names: Vec<String> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect();
+
+            let keep = self.config.keep_newest(&cache);
+            categories::prunable_versions(&names, keep)
+                .par_iter()
+                .for_each(|version| {
+                    let path = root.join(&version.name);
+                    let Ok(usage) = self.measure(&path) else {
+                        return;
+                    };
+                    if usage.size == 0 {
+                        return;
+                    }
+                    let modified = std::fs::metadata(&path)
+                        .map(|meta| Self::modified_time(&meta))
+                        .unwrap_or_else(|_| Utc::now());
+
+                    let mut item = ScannedItem::new(path, usage.size, cache.category, modified);
+                    item.file_count = Some(usage.files);
+                    item.note = Some(format!(
+                        "Older version; keeping {}",
+                        version.kept.join(", ")
+                    ));
+                    self.add_item(item, ScanSource::VersionedCache);
+                });
+        }
+    }
+
+    fn versioned_roots() -> HashSet<PathBuf> {
+        CategoryPatterns::versioned_caches()
+            .into_iter()
+            .map(|cache| PathBuf::from(cache.root))
+            .collect()
+    }
+
+    fn scan_global_packages(&self) {
+        if !self.category_enabled(FileCategory::GlobalPackage) {
+            return;
+        }
+
+        for root in CategoryPatterns::global_package_roots() {
+            if self.should_stop.load(Ordering::SeqCst) {
+                return;
+            }
+            if !root.modules.is_dir() || !self.in_scope(&root.modules) {
+                continue;
+            }
+            let _site = self.sites.claim(&root.modules);
+
+            globals::packages(&root).par_iter().for_each(|package| {
+                if self.should_stop.load(Ordering::SeqCst) {
+                    return;
+                }
+                let Ok(usage) = self.measure(package) else {
+                    return;
+                };
+                if usage.size == 0 {
+                    return;
+                }
+                let modified = std::fs::metadata(package)
+                    .map(|meta| Self::modified_time(&meta))
+                    .unwrap_or_else(|_| Utc::now());
+
+                let mut item = ScannedItem::new(
+                    package.clone(),
+                    usage.size,
+                    FileCategory::GlobalPackage,
+                    modified,
+                );
+                item.file_count = Some(usage.files);
+                let links = globals::bin_links(&root.bin, package);
+                if let Some(reason) = globals::orphan_reason(&root, package, &links) {
+                    item.confidence = ORPHANED_PACKAGE_CONFIDENCE;
+                    item.note = Some(reason);
+                }
+                self.add_item(item, ScanSource::GlobalPackages);
+            });
+        }
+    }
+
+    fn global_module_roots() -> HashSet<PathBuf> {
+        CategoryPatterns::global_package_roots()
+            .into_iter()
+            .map(|root| root.modules)
+            .collect()
+    }
+
+    fn scan_system_caches(&self, partials: &[(PathBuf, DirUsage)]) {
+        if self.should_stop.load(Ordering::SeqCst)
+            || !self.category_enabled(FileCategory::SystemCache)
+        {
             return;
         }
 
         let system_paths = CategoryPatterns::system_cache_paths();
+        let versioned = Self::versioned_roots();
+        let owners = OwnerIndex::load();
 
         system_paths.par_iter().for_each(|cache_path| {
             if self.should_stop.load(Ordering::SeqCst) {
@@ -184,75 +1005,227 @@ impl Scanner {
             }
 
             let path = PathBuf::from(cache_path);
-            if !path.exists() || !path.is_dir() {
+            if !path.exists()
+                || !path.is_dir()
+                || !self.in_scope(&path)
+                || self.excludes.covers(&path)
+            {
                 return;
             }
 
-            self.update_current_path(&path);
+            let _site = self.sites.claim(&path);
 
             let entries: Vec<_> = WalkDir::new(&path)
                 .max_depth(1)
                 .into_iter()
                 .filter_map(|e| e.ok())
                 .filter(|e| e.depth() == 1 && e.file_type().is_dir())
+                .filter(|e| !versioned.contains(e.path()) && !self.excludes.excludes(e.path()))
                 .collect();
+            let (family_members, entries): (Vec<_>, Vec<_>) =
+                entries.into_iter().partition(|entry| {
+                    CategoryPatterns::temp_family(&entry.file_name().to_string_lossy()).is_some()
+                });
+            self.add_temp_families(&path, &family_members);
 
             entries.par_iter().for_each(|entry| {
                 if self.should_stop.load(Ordering::SeqCst) {
                     return;
                 }
 
-                if let Ok(size) = Self::dir_size_parallel(entry.path()) {
-                    if size > 1024 * 1024 {
-                        let item = ScannedItem {
-                            path: entry.path().to_path_buf(),
-                            name: entry.file_name().to_string_lossy().into(),
-                            size,
-                            category: FileCategory::SystemCache,
-                            confidence: FileCategory::SystemCache.base_confidence(),
-                            is_stale: false,
-                        };
-
-                        self.add_item(item);
+                if let Ok(usage) = self.measure(entry.path()) {
+                    let usage = without_partials(usage, partials, entry.path());
+                    if usage.size > 1024 * 1024 {
+                        let modified = entry
+                            .metadata()
+                            .map(|meta| Self::modified_time(&meta))
+                            .unwrap_or_else(|_| Utc::now());
+                        let mut item = ScannedItem::new(
+                            entry.path().to_path_buf(),
+                            usage.size,
+                            FileCategory::SystemCache,
+                            modified,
+                        );
+                        item.file_count = Some(usage.files);
+                        Self::name_owner(&mut item, &owners, &entry.file_name().to_string_lossy());
+
+                        self.add_item(item, ScanSource::SystemCacheChild);
                     }
                 }
             });
         });
     }
 
+    /// Names a cache folder like `com.spotify.client` after its app, and
+    /// raises confidence when that app is no longer installed.
+    fn name_owner(item: &mut ScannedItem, owners: &OwnerIndex, folder: &str) {
+        let Some(owner) = owners.owner(folder) else {
+            return;
+        };
+        if owner.installed {
+            item.name = format!("{} cache", owner.name).into();
+            item.note = Some(format!("Cache of {} ({})", owner.name, folder));
+        } else {
+            item.name = format!("Orphaned {} cache", owner.name).into();
+            item.confidence = (item.confidence + ORPHANED_CACHE_BONUS).min(0.95);
+            item.note = Some(format!(
+                "Orphaned cache: {} ({}) is no longer installed",
+                owner.name, folder
+            ));
+        }
+    }
+
+    fn add_temp_families(&self, root: &Path, entries: &[walkdir::DirEntry]) {
+        let cutoff = Utc::now() - Duration::minutes(TEMP_FAMILY_MIN_AGE_MINUTES);
+        let members: Vec<(
+            &'static categories::TempFamily,
+            PathBuf,
+            DirUsage,
+            DateTime<Utc>,
+        )> = entries
+            .par_iter()
+            .filter_map(|entry| {
+                let family = CategoryPatterns::temp_family(&entry.file_name().to_string_lossy())?;
+                let modified = Self::modified_time(&entry.metadata().ok()?);
+                if modified > cutoff || self.should_stop.load(Ordering::SeqCst) {
+                    return None;
+                }
+                let usage = self.measure(entry.path()).ok()?;
+                Some((family, entry.path().to_path_buf(), usage, modified))
+            })
+            .collect();
+
+        for family in CategoryPatterns::temp_families() {
+            let group: Vec<_> = members
+                .iter()
+                .filter(|(member_family, ..)| member_family.pattern == family.pattern)
+                .collect();
+            let size = group.iter().map(|(_, _, usage, _)| usage.size).sum::<u64>();
+            let Some(newest) = group.iter().map(|(.., modified)| *modified).max() else {
+                continue;
+            };
+            if size <= 1024 * 1024 {
+                continue;
+            }
+            let mut item = ScannedItem::new(
+                root.join(family.pattern),
+                size,
+                FileCategory::TempFile,
+                newest,
+            );
+            item.name = format!("{} leftover {}", group.len(), family.label).into();
+            item.members = group
+                .iter()
+                .map(|(_, path, usage, _)| (path.clone(), usage.size))
+                .collect();
+            item.file_count = Some(group.iter().map(|(_, _, usage, _)| usage.files).sum());
+            self.add_item(item, ScanSource::TempFamily);
+        }
+    }
+
     fn scan_project_directories(&self) {
         if self.should_stop.load(Ordering::SeqCst) {
             return;
         }
 
-        let dev_dirs: HashSet<&str> = CategoryPatterns::dev_artifact_dirs()
-            .iter()
-            .copied()
-            .collect();
         let temp_exts = CategoryPatterns::temp_extensions();
         let log_exts = CategoryPatterns::log_extensions();
         let found_artifacts: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
+        let rules = Arc::clone(&lock(&self.rules));
+        let global_modules = Self::global_module_roots();
+        let large_files = self.category_enabled(FileCategory::LargeFile);
+        let unused_files = self.category_enabled(FileCategory::Unused);
+        let mut visited = VisitedDirs::default();
 
         for base_path in &self.config.scan_paths {
+            let _root = RootDone(&self.progress);
             if !base_path.exists() || self.should_stop.load(Ordering::SeqCst) {
                 continue;
             }
 
-            self.update_current_path(base_path);
+            let site = self.sites.claim(base_path);
+
+            let dev_dirs = NameSet::new(
+                CategoryPatterns::dev_artifact_dirs(),
+                CaseSensitivity::detect(base_path),
+            );
 
             let walker = WalkDir::new(base_path)
-                .max_depth(self.config.max_depth as usize)
                 .follow_links(self.config.follow_symlinks)
+                .same_file_system(self.config.same_filesystem_only)
                 .into_iter();
 
-            let mut pending_artifacts: Vec<(PathBuf, String, std::fs::Metadata)> = Vec::new();
+            let mut pending_artifacts: Vec<(PathBuf, std::fs::Metadata)> = Vec::new();
+            let mut containers: Vec<(PathBuf, String)> = Vec::new();
+            let mut tagged_caches: Vec<PathBuf> = Vec::new();
+            let mut rule_dirs: Vec<(PathBuf, std::fs::Metadata, &Rule)> = Vec::new();
+            let mut depth_limits = DepthLimits::new(
+                self.config.shallow_depth as usize,
+                self.config.max_depth as usize,
+            );
 
             for entry in walker.filter_entry(|e| {
-                let dominated = found_artifacts
-                    .lock()
-                    .map(|guard| guard.iter().any(|artifact| e.path().starts_with(artifact)))
-                    .unwrap_or(false);
-                !dominated
+                if !depth_limits.allows(e.depth()) || self.excludes.excludes(e.path()) {
+                    return false;
+                }
+                if e.file_type().is_dir()
+                    && !e
+                        .metadata()
+                        .ok()
+                        .is_none_or(|meta| visited.first_visit(&meta))
+                {
+                    let overlapping_root = self
+                        .config
+                        .scan_paths
+                        .iter()
+                        .any(|root| root != base_path && e.path().starts_with(root));
+                    if !overlapping_root {
+                        self.add_warning(mounts::repeat_warning(e.path()));
+                    }
+                    return false;
+                }
+                if e.file_type().is_dir() && CategoryPatterns::is_project_root(e.path()) {
+                    depth_limits.extend_below(e.depth());
+                }
+                let dominated = lock(&found_artifacts)
+                    .iter()
+                    .any(|artifact| e.path().starts_with(artifact));
+                if dominated {
+                    return false;
+                }
+                if e.file_type().is_dir() {
+                    if CategoryPatterns::has_keep_marker(e.path())
+                        || global_modules.contains(e.path())
+                    {
+                        return false;
+                    }
+                    if e.depth() > 0
+                        && !dev_dirs.contains(&e.file_name().to_string_lossy())
+                        && CategoryPatterns::has_cachedir_tag(e.path())
+                    {
+                        tagged_caches.push(e.path().to_path_buf());
+                        return false;
+                    }
+                    if let Some(owner) =
+                        CategoryPatterns::container_owner(e.path(), &self.config.container_markers)
+                    {
+                        containers.push((e.path().to_path_buf(), owner));
+                        return false;
+                    }
+                    if !rules.is_empty()
+                        && e.depth() > 0
+                        && !dev_dirs.contains(&e.file_name().to_string_lossy())
+                    {
+                        if let Ok(meta) = e.metadata() {
+                            let modified = Self::modified_time(&meta);
+                            if let Some(rule) = rules.find(e.path(), true, days_since(modified)) {
+                                rule_dirs.push((e.path().to_path_buf(), meta, rule));
+                                return false;
+                            }
+                        }
+                    }
+                }
+                true
             }) {
                 if self.should_stop.load(Ordering::SeqCst) {
                     break;
@@ -266,26 +1239,26 @@ impl Scanner {
                 self.files_scanned.fetch_add(1, Ordering::SeqCst);
 
                 if entry.file_type().is_dir() {
+                    site.update(entry.path());
                     let name = entry.file_name().to_string_lossy();
-                    if dev_dirs.contains(name.as_ref()) {
-                        if let Ok(mut guard) = found_artifacts.lock() {
-                            guard.insert(entry.path().to_path_buf());
-                        }
+                    if dev_dirs.contains(&name) {
+                        lock(&found_artifacts).insert(entry.path().to_path_buf());
 
+                        if !self.category_enabled(FileCategory::DevArtifact) {
+                            continue;
+                        }
                         if let Ok(meta) = entry.metadata() {
-                            pending_artifacts.push((
-                                entry.path().to_path_buf(),
-                                name.to_string(),
-                                meta,
-                            ));
+                            pending_artifacts.push((entry.path().to_path_buf(), meta));
                         }
                     }
                 } else if entry.file_type().is_file() {
-                    let name = entry.file_name().to_string_lossy();
-                    let name_lower = name.to_lowercase();
-
-                    let is_temp = temp_exts.iter().any(|ext| name_lower.ends_with(ext));
-                    let is_log = log_exts.iter().any(|ext| name_lower.ends_with(ext));
+                    let name = entry.file_name();
+                    let is_temp = temp_exts
+                        .iter()
+                        .any(|ext| names::ends_with_ignore_case(name, ext));
+                    let is_log = log_exts
+                        .iter()
+                        .any(|ext| names::ends_with_ignore_case(name, ext));
 
                     if is_temp || is_log {
                         if let Ok(meta) = entry.metadata() {
@@ -297,69 +1270,154 @@ impl Scanner {
                                     FileCategory::LogFile
                                 };
 
-                                let item = ScannedItem {
-                                    path: entry.path().to_path_buf(),
-                                    name: name.to_string().into_boxed_str(),
+                                let item = ScannedItem::new(
+                                    entry.path().to_path_buf(),
                                     size,
                                     category,
-                                    confidence: category.base_confidence(),
-                                    is_stale: false,
-                                };
+                                    Self::modified_time(&meta),
+                                );
 
-                                self.add_item(item);
+                                self.add_item(item, ScanSource::ProjectWalk);
+                            }
+                        }
+                    } else if !rules.is_empty() || large_files || unused_files {
+                        if let Ok(meta) = entry.metadata() {
+                            let modified = Self::modified_time(&meta);
+                            if let Some(rule) = rules
+                                .find(entry.path(), false, days_since(modified))
+                                .filter(|rule| rule.accepts_size(meta.len()))
+                            {
+                                self.add_item(
+                                    rule_item(
+                                        rule,
+                                        entry.path().to_path_buf(),
+                                        meta.len(),
+                                        modified,
+                                    ),
+                                    ScanSource::ProjectWalk,
+                                );
+                            } else if large_files && meta.len() >= self.config.large_file_threshold
+                            {
+                                let item = ScannedItem::new(
+                                    entry.path().to_path_buf(),
+                                    meta.len(),
+                                    FileCategory::LargeFile,
+                                    modified,
+                                );
+                                self.add_item(item, ScanSource::ProjectWalk);
+                            } else if unused_files {
+                                let accessed = Self::accessed_time(&meta);
+                                if days_since(accessed) >= self.config.stale_days_threshold {
+                                    let mut item = ScannedItem::new(
+                                        entry.path().to_path_buf(),
+                                        meta.len(),
+                                        FileCategory::Unused,
+                                        modified,
+                                    );
+                                    item.accessed = accessed;
+                                    item.is_stale = true;
+                                    self.add_item(item, ScanSource::ProjectWalk);
+                                }
                             }
                         }
                     }
                 }
             }
 
-            pending_artifacts.par_iter().for_each(|(path, name, meta)| {
+            rule_dirs.par_iter().for_each(|(path, meta, rule)| {
                 if self.should_stop.load(Ordering::SeqCst) {
                     return;
                 }
+                if let Ok(usage) = self.measure(path) {
+                    if rule.accepts_size(usage.size) {
+                        let mut item =
+                            rule_item(rule, path.clone(), usage.size, Self::modified_time(meta));
+                        item.file_count = Some(usage.files);
+                        self.add_item(item, ScanSource::ProjectWalk);
+                    }
+                }
+            });
 
-                if let Ok(size) = Self::dir_size_parallel(path) {
-                    let modified = meta
-                        .modified()
-                        .ok()
-                        .map(DateTime::<Utc>::from)
-                        .unwrap_or_else(Utc::now);
+            pending_artifacts.par_iter().for_each(|(path, meta)| {
+                if self.should_stop.load(Ordering::SeqCst) {
+                    return;
+                }
 
-                    let age_days = (Utc::now() - modified).num_days() as u64;
-                    let is_stale = age_days >= CategoryPatterns::stale_threshold_days();
+                if let Ok(usage) = self.measure(path) {
+                    let modified = Self::modified_time(meta);
 
-                    let mut confidence = FileCategory::DevArtifact.base_confidence();
-                    if is_stale {
-                        confidence += 0.10;
-                    }
-                    confidence = confidence.min(0.98);
-
-                    let item = ScannedItem {
-                        path: path.clone(),
-                        name: name.clone().into_boxed_str(),
-                        size,
-                        category: FileCategory::DevArtifact,
-                        confidence,
-                        is_stale,
-                    };
+                    let mut item = ScannedItem::new(
+                        path.clone(),
+                        usage.size,
+                        FileCategory::DevArtifact,
+                        modified,
+                    );
+                    item.file_count = Some(usage.files);
+                    item.is_stale = item.age_days() >= self.config.stale_days_threshold;
+                    self.protect_active_project(&mut item);
+
+                    self.add_item(item, ScanSource::ProjectWalk);
+                }
+            });
+
+            tagged_caches.par_iter().for_each(|path| {
+                if self.should_stop.load(Ordering::SeqCst) {
+                    return;
+                }
 
-                    self.add_item(item);
+                if let Ok(usage) = self.measure(path) {
+                    let modified = std::fs::metadata(path)
+                        .map(|meta| Self::modified_time(&meta))
+                        .unwrap_or_else(|_| Utc::now());
+                    let mut item = ScannedItem::new(
+                        path.clone(),
+                        usage.size,
+                        FileCategory::SystemCache,
+                        modified,
+                    );
+                    item.file_count = Some(usage.files);
+
+                    self.add_item(item, ScanSource::ProjectWalk);
+                }
+            });
+
+            containers.par_iter().for_each(|(path, owner)| {
+                if self.should_stop.load(Ordering::SeqCst) {
+                    return;
+                }
+
+                if let Ok(usage) = self.measure(path) {
+                    let modified = std::fs::metadata(path)
+                        .map(|meta| Self::modified_time(&meta))
+                        .unwrap_or_else(|_| Utc::now());
+                    let mut item = ScannedItem::new(
+                        path.clone(),
+                        usage.size,
+                        FileCategory::AppContainer,
+                        modified,
+                    );
+                    item.file_count = Some(usage.files);
+                    item.managed_by = Some(owner.clone());
+
+                    self.add_item(item, ScanSource::ProjectWalk);
                 }
             });
         }
     }
 
     fn scan_downloads(&self) {
-        if self.should_stop.load(Ordering::SeqCst) {
+        if self.should_stop.load(Ordering::SeqCst)
+            || !self.category_enabled(FileCategory::OldDownload)
+        {
             return;
         }
 
         let downloads = match dirs::download_dir() {
-            Some(d) if d.exists() => d,
+            Some(d) if d.exists() && self.in_scope(&d) && !self.excludes.covers(&d) => d,
             _ => return,
         };
 
-        self.update_current_path(&downloads);
+        let _site = self.sites.claim(&downloads);
 
         let threshold = Utc::now() - Duration::days(CategoryPatterns::old_download_days() as i64);
 
@@ -368,6 +1426,7 @@ impl Scanner {
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.depth() == 1 && e.file_type().is_file())
+            .filter(|e| !self.excludes.excludes(e.path()))
             .collect();
 
         entries.par_iter().for_each(|entry| {
@@ -376,87 +1435,1102 @@ impl Scanner {
             }
 
             if let Ok(meta) = entry.metadata() {
-                let modified = meta
-                    .modified()
-                    .ok()
-                    .map(DateTime::<Utc>::from)
-                    .unwrap_or_else(Utc::now);
+                let modified = Self::modified_time(&meta);
 
                 if modified < threshold {
-                    let size = meta.len();
-                    let age_days = (Utc::now() - modified).num_days() as u64;
-                    let is_stale = age_days >= CategoryPatterns::stale_threshold_days();
+                    let mut item = ScannedItem::new(
+                        entry.path().to_path_buf(),
+                        meta.len(),
+                        FileCategory::OldDownload,
+                        modified,
+                    );
+                    item.is_stale = item.age_days() >= self.config.stale_days_threshold;
+                    self.apply_download_origin(&mut item);
+
+                    self.add_item(item, ScanSource::DownloadsPass);
+                }
+            }
+        });
+    }
+
+    fn scan_logs(&self) {
+        if self.should_stop.load(Ordering::SeqCst) || !self.category_enabled(FileCategory::LogFile)
+        {
+            return;
+        }
 
-                    let mut confidence = FileCategory::OldDownload.base_confidence();
-                    if is_stale {
-                        confidence += 0.10;
+        let mut log_dirs: Vec<PathBuf> = Vec::new();
+
+        for root in CategoryPatterns::log_root_paths() {
+            let root = PathBuf::from(root);
+            if !root.is_dir() || !self.in_scope(&root) {
+                continue;
+            }
+            match std::fs::read_dir(&root) {
+                Ok(entries) => log_dirs.extend(
+                    entries
+                        .filter_map(|e| e.ok())
+                        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                        .map(|e| e.path()),
+                ),
+                Err(e) => self.add_warning(format!("Skipped {}: {}", root.display(), e)),
+            }
+        }
+
+        for parent in CategoryPatterns::app_log_parents() {
+            let parent = PathBuf::from(parent);
+            if !parent.is_dir() || !self.in_scope(&parent) {
+                continue;
+            }
+            let Ok(entries) = std::fs::read_dir(&parent) else {
+                continue;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                for name in ["logs", "Logs"] {
+                    let candidate = entry.path().join(name);
+                    if candidate.is_dir() && !log_dirs.contains(&candidate) {
+                        log_dirs.push(candidate);
                     }
-                    confidence = confidence.min(0.95);
-
-                    let item = ScannedItem {
-                        path: entry.path().to_path_buf(),
-                        name: entry.file_name().to_string_lossy().into(),
-                        size,
-                        category: FileCategory::OldDownload,
-                        confidence,
-                        is_stale,
-                    };
+                }
+            }
+        }
+
+        let stale_days = self.config.stale_days_threshold;
+
+        log_dirs.par_iter().for_each(|path| {
+            if self.should_stop.load(Ordering::SeqCst) {
+                return;
+            }
+            if let Err(e) = std::fs::read_dir(path) {
+                self.add_warning(format!("Skipped {}: {}", path.display(), e));
+                return;
+            }
 
-                    self.add_item(item);
+            let _site = self.sites.claim(path);
+
+            if let Ok(usage) = self.measure(path) {
+                if usage.size > 1024 * 1024 {
+                    let modified = std::fs::metadata(path)
+                        .map(|meta| Self::modified_time(&meta))
+                        .unwrap_or_else(|_| Utc::now());
+                    let mut item =
+                        ScannedItem::new(path.clone(), usage.size, FileCategory::LogFile, modified);
+                    item.file_count = Some(usage.files);
+                    item.is_stale = item.age_days() >= stale_days;
+
+                    self.add_item(item, ScanSource::LogDirectory);
                 }
             }
         });
     }
 
-    fn add_item(&self, item: ScannedItem) {
-        let Ok(mut items) = self.items.lock() else {
+    /// Reports every copy in each duplicate set, the kept one included, so
+    /// callers can show which copies belong together. Exactly one copy per
+    /// set has `is_keeper`; anything that cleans or totals reclaimable space
+    /// must leave it out.
+    fn scan_duplicates(&self) {
+        if self.should_stop.load(Ordering::SeqCst)
+            || !self.category_enabled(FileCategory::Duplicate)
+        {
+            return;
+        }
+
+        let dev_dirs: HashSet<&str> = CategoryPatterns::dev_artifact_dirs()
+            .iter()
+            .copied()
+            .collect();
+        let downloads = dirs::download_dir();
+        let mut by_size: HashMap<u64, Vec<(PathBuf, DateTime<Utc>)>> = HashMap::new();
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        let mut visited = VisitedDirs::default();
+
+        for base_path in &self.config.scan_paths {
+            if !base_path.exists() || self.should_stop.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            // A file seen again through a bind mount is the same file, not a
+            // duplicate; deleting "one copy" would delete both.
+            let walker = WalkDir::new(base_path)
+                .max_depth(self.config.max_depth as usize)
+                .follow_links(self.config.follow_symlinks)
+                .same_file_system(self.config.same_filesystem_only)
+                .into_iter()
+                .filter_entry(|e| {
+                    if !e.file_type().is_dir() {
+                        return !self.excludes.excludes(e.path());
+                    }
+                    let dev_dir = dev_dirs.contains(e.file_name().to_string_lossy().as_ref());
+                    !dev_dir
+                        && !self.excludes.excludes(e.path())
+                        && e.metadata()
+                            .ok()
+                            .is_none_or(|meta| visited.first_visit(&meta))
+                });
+
+            for entry in walker.filter_map(|e| e.ok()) {
+                if self.should_stop.load(Ordering::SeqCst) {
+                    return;
+                }
+                if !entry.file_type().is_file() || !seen.insert(entry.path().to_path_buf()) {
+                    continue;
+                }
+                if let Ok(meta) = entry.metadata() {
+                    if meta.len() >= MIN_DUPLICATE_SIZE {
+                        by_size
+                            .entry(meta.len())
+                            .or_default()
+                            .push((entry.path().to_path_buf(), Self::modified_time(&meta)));
+                    }
+                }
+            }
+        }
+
+        by_size
+            .into_par_iter()
+            .filter(|(_, candidates)| candidates.len() > 1)
+            .for_each(|(size, candidates)| {
+                let mut by_hash: HashMap<hashing::ContentHash, Vec<(PathBuf, DateTime<Utc>)>> =
+                    HashMap::new();
+                for (path, modified) in candidates {
+                    if self.should_stop.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    let _site = self.sites.claim(&path);
+                    if let Ok(Some(hash)) = hashing::content_hash(&path, size, &self.should_stop) {
+                        by_hash.entry(hash).or_default().push((path, modified));
+                    }
+                }
+
+                for (hash, copies) in by_hash.into_iter().filter(|(_, c)| c.len() > 1) {
+                    let mut items: Vec<ScannedItem> = copies
+                        .into_iter()
+                        .map(|(path, modified)| {
+                            let mut item =
+                                ScannedItem::new(path, size, FileCategory::Duplicate, modified);
+                            item.hash = Some(hash.hex.clone());
+                            item.sampled_hash = hash.sampled;
+                            item
+                        })
+                        .collect();
+                    let members: Vec<&ScannedItem> = items.iter().collect();
+                    let keeper = duplicates::pick_keeper(&members, downloads.as_deref());
+                    items[keeper].is_keeper = true;
+                    for item in items {
+                        self.add_item(item, ScanSource::DuplicatePass);
+                    }
+                }
+            });
+    }
+
+    fn apply_download_origin(&self, item: &mut ScannedItem) {
+        let Some(url) = origin::download_origin(&item.path) else {
+            return;
+        };
+        if let Some(domain) = origin::origin_domain(&url)
+            .filter(|domain| origin::is_redownloadable(domain, &self.config.redownloadable_domains))
+        {
+            item.confidence = (item.confidence + REDOWNLOADABLE_BONUS).min(0.95);
+            item.note = Some(format!(
+                "Downloaded from {}, can be downloaded again",
+                domain
+            ));
+        }
+        item.origin = Some(url);
+    }
+
+    /// Returns whether the item made it into the results.
+    fn add_item(&self, mut item: ScannedItem, source: ScanSource) -> bool {
+        if !self.category_enabled(item.category)
+            || CategoryPatterns::has_keep_marker(&item.path)
+            || self.excludes.covers(&item.path)
+        {
+            return false;
+        }
+        if CategoryPatterns::has_cachedir_tag(&item.path) {
+            item.confidence = (item.confidence + CACHEDIR_TAG_BONUS).min(0.98);
+            item.note
+                .get_or_insert_with(|| "Marked as a cache by CACHEDIR.TAG".to_string());
+        }
+        if let Some(store) = item.managed_store() {
+            item.managed_by = Some(store.tool.to_string());
+        }
+        item.source = source;
+        item.apply_age_curve();
+        if self.config.sniff_logs {
+            Self::sniff_log(&mut item);
+        }
+        let below_threshold = item.size < self.config.min_size(item.category);
+        self.apply_confidence_override(&mut item);
+        if !below_threshold {
+            if let Some(phase) = ScanPhase::of(source) {
+                let mut progress = lock(&self.progress);
+                let found = progress.phase_mut(phase);
+                found.items += 1;
+                found.bytes += item.size;
+            }
+        }
+        let mut store = lock(&self.store);
+        if below_threshold {
+            store.record_suppressed();
+        } else {
+            store.push(item);
+        }
+        !below_threshold
+    }
+
+    /// Sharply lowers the confidence of a dev artifact whose project was
+    /// changed within `protect_active_days`, since it is likely to be
+    /// rebuilt right away.
+    fn protect_active_project(&self, item: &mut ScannedItem) {
+        let protect_days = self.config.protect_active_days;
+        if protect_days == 0 {
+            return;
+        }
+        let Some(active) = project_last_active(&item.path) else {
             return;
         };
-        let Ok(mut stats) = self.stats.lock() else {
-            items.push(item);
+        let days = days_since(active);
+        if days >= protect_days {
+            return;
+        }
+        item.recently_active = true;
+        item.is_stale = false;
+        item.confidence = (item.confidence - ACTIVE_PROJECT_PENALTY).max(0.0);
+        item.note = Some(match days {
+            0 => "Its project was changed today".to_string(),
+            1 => "Its project was changed yesterday".to_string(),
+            days => format!("Its project was changed {} days ago", days),
+        });
+    }
+
+    fn sniff_log(item: &mut ScannedItem) {
+        if item.category != FileCategory::LogFile || item.size < sniff::MIN_SIZE {
+            return;
+        }
+        let Ok(sample) = sniff::sample(&item.path) else {
             return;
         };
+        if sniff::classify(&sample) == sniff::Content::Binary {
+            item.confidence = (item.confidence - BINARY_LOG_PENALTY).max(0.0);
+            item.note = Some("Not a text log: the file looks like binary data".to_string());
+        }
+    }
+
+    fn apply_confidence_override(&self, item: &mut ScannedItem) {
+        item.confidence_adjustment = item.confidence - item.category.base_confidence();
+        if let Some(base) = self.config.confidence_override(item.category) {
+            item.confidence = (base + item.confidence_adjustment).clamp(0.0, 1.0);
+            item.confidence_overridden = true;
+        }
+    }
+
+    fn add_warning(&self, warning: String) {
+        lock(&self.store).push_warning(warning);
+    }
 
-        stats.total_items += 1;
-        stats.total_size += item.size;
-        *stats.items_by_category.entry(item.category).or_insert(0) += 1;
-        *stats.size_by_category.entry(item.category).or_insert(0) += item.size;
+    fn category_enabled(&self, category: FileCategory) -> bool {
+        self.config.enabled_categories.contains(&category)
+    }
+
+    fn in_scope(&self, path: &Path) -> bool {
+        match &self.scope {
+            Some(scope) => scope.iter().any(|root| path.starts_with(root)),
+            None => true,
+        }
+    }
+
+    fn modified_time(meta: &std::fs::Metadata) -> DateTime<Utc> {
+        meta.modified()
+            .ok()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(Utc::now)
+    }
+
+    fn accessed_time(meta: &std::fs::Metadata) -> DateTime<Utc> {
+        let modified = Self::modified_time(meta);
+        meta.accessed()
+            .ok()
+            .map(DateTime::<Utc>::from)
+            .filter(|accessed| *accessed > modified)
+            .unwrap_or(modified)
+    }
 
-        items.push(item);
+    pub fn dir_size_parallel(path: &Path) -> error::Result<u64> {
+        Ok(Self::dir_usage(path)?.size)
     }
 
-    fn update_current_path(&self, path: &Path) {
-        if let Ok(mut current) = self.current_path.lock() {
-            *current = path.to_string_lossy().to_string();
+    pub fn dir_usage(path: &Path) -> error::Result<DirUsage> {
+        Self::dir_usage_until(path, &AtomicBool::new(false), false)
+    }
+
+    fn measure(&self, path: &Path) -> error::Result<DirUsage> {
+        let usage =
+            Self::dir_usage_until(path, &self.should_stop, self.config.same_filesystem_only)?;
+        if usage.repeated > 0 {
+            self.add_warning(format!(
+                "{}: {} folders inside appear more than once (bind mounts or mount loops); counted once",
+                path.display(),
+                usage.repeated
+            ));
         }
+        Ok(usage)
     }
 
-    fn dir_size_parallel(path: &Path) -> Result<u64, std::io::Error> {
+    fn dir_usage_until(
+        path: &Path,
+        stop: &AtomicBool,
+        same_filesystem: bool,
+    ) -> error::Result<DirUsage> {
+        std::fs::symlink_metadata(path).map_err(|e| SweeperError::io(path, e))?;
+        let mut visited = VisitedDirs::default();
+        let mut repeated = 0;
         let entries: Vec<_> = WalkDir::new(path)
+            .same_file_system(same_filesystem)
             .into_iter()
+            .filter_entry(|e| {
+                if !e.file_type().is_dir() {
+                    return true;
+                }
+                let first = e
+                    .metadata()
+                    .ok()
+                    .is_none_or(|meta| visited.first_visit(&meta));
+                if !first {
+                    repeated += 1;
+                }
+                first
+            })
+            .take_while(|_| !stop.load(Ordering::Relaxed))
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
             .collect();
+        if stop.load(Ordering::Relaxed) {
+            return Err(SweeperError::Cancelled);
+        }
 
         let size: u64 = entries
             .par_iter()
             .map(|e| e.metadata().map(|m| m.len()).unwrap_or(0))
             .sum();
 
-        Ok(size)
+        Ok(DirUsage {
+            size,
+            files: entries.len() as u64,
+            repeated,
+        })
     }
 
-    pub fn get_stats(&self) -> ScanStats {
-        self.stats
-            .lock()
-            .map(|guard| guard.clone())
-            .unwrap_or_default()
+    pub fn snapshot(&self) -> ItemStore {
+        lock(&self.store).clone()
+    }
+
+    pub fn items_after(&self, cursor: &mut ItemCursor) -> Vec<ScannedItem> {
+        lock(&self.store).items_after(cursor).to_vec()
     }
 
     pub fn get_items(&self) -> Vec<ScannedItem> {
-        self.items
-            .lock()
-            .map(|guard| guard.clone())
-            .unwrap_or_default()
+        lock(&self.store).items().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{self, FixtureSpec};
+
+    fn scan(root: &Path, config: Config) -> Vec<ScannedItem> {
+        Scanner::with_scope(config, vec![root.to_path_buf()])
+            .scan()
+            .unwrap()
+    }
+
+    #[test]
+    fn excluded_paths_are_not_scanned() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let fixture = fixture::generate(&root, &FixtureSpec::small(11)).unwrap();
+        let prefix = fixture.artifacts[0].parent().unwrap().to_path_buf();
+        let config = Config {
+            exclude_patterns: vec![
+                prefix.to_string_lossy().into_owned(),
+                "crate-1".to_string(),
+                "*.bak".to_string(),
+            ],
+            ..Config::default()
+        };
+
+        let excluded = |path: &Path| {
+            path.starts_with(&prefix)
+                || path.components().any(|part| part.as_os_str() == "crate-1")
+                || path.extension().is_some_and(|ext| ext == "bak")
+        };
+        let unfiltered = scan(&root, Config::default());
+        assert_eq!(
+            unfiltered
+                .iter()
+                .filter(|item| excluded(&item.path))
+                .count(),
+            2 + fixture.temp_files.len() / 2
+        );
+
+        let items = scan(&root, config);
+        assert!(items.iter().all(|item| !excluded(&item.path)));
+        // Everything not excluded is still found.
+        assert!(items.iter().any(|item| item.path == fixture.artifacts[2]));
+        assert!(items
+            .iter()
+            .any(|item| item.path.extension().is_some_and(|ext| ext == "tmp")));
+    }
+
+    #[test]
+    fn disabled_categories_are_not_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let fixture = fixture::generate(&root, &FixtureSpec::small(12)).unwrap();
+        let config = Config {
+            enabled_categories: HashSet::from([FileCategory::TempFile]),
+            ..Config::default()
+        };
+
+        let items = scan(&root, config);
+        assert!(items
+            .iter()
+            .all(|item| item.category == FileCategory::TempFile));
+        let found: HashSet<&Path> = items.iter().map(|item| item.path.as_path()).collect();
+        assert!(fixture
+            .temp_files
+            .iter()
+            .all(|temp| found.contains(temp.as_path())));
+
+        let mut config = Config::default();
+        config.enabled_categories.remove(&FileCategory::TempFile);
+        config.enabled_categories.insert(FileCategory::Duplicate);
+        let items = scan(&root, config);
+        assert!(items
+            .iter()
+            .any(|item| item.category == FileCategory::DevArtifact));
+        assert!(items
+            .iter()
+            .any(|item| item.category == FileCategory::Duplicate));
+        assert!(items
+            .iter()
+            .all(|item| item.category != FileCategory::TempFile));
+    }
+
+    #[test]
+    fn size_drift_boundaries() {
+        let cases = [
+            (1000, Some(1000), SizeDrift::Unchanged),
+            // Within 1% either way.
+            (1000, Some(1010), SizeDrift::Unchanged),
+            (1000, Some(990), SizeDrift::Unchanged),
+            (1000, Some(1011), SizeDrift::Grew),
+            (1000, Some(989), SizeDrift::Shrank),
+            // Up to 20% smaller is an ordinary shrink.
+            (1000, Some(800), SizeDrift::Shrank),
+            (1000, Some(799), SizeDrift::ShrankSharply),
+            (1000, Some(1), SizeDrift::ShrankSharply),
+            (1000, Some(0), SizeDrift::Gone),
+            (1000, None, SizeDrift::Gone),
+            (0, Some(1), SizeDrift::Grew),
+            (0, None, SizeDrift::Gone),
+            (u64::MAX, Some(u64::MAX), SizeDrift::Unchanged),
+        ];
+        for (reported, measured, expected) in cases {
+            let drift = SizeDrift::classify(reported, measured);
+            assert_eq!(drift, expected, "{} -> {:?}", reported, measured);
+            assert_eq!(
+                drift.flagged(),
+                matches!(expected, SizeDrift::ShrankSharply | SizeDrift::Gone)
+            );
+        }
+    }
+
+    #[test]
+    fn system_caches_are_named_after_their_app() {
+        let cache = |folder: &str| {
+            let path = PathBuf::from("/home/u/Library/Caches").join(folder);
+            let mut item = ScannedItem::new(path, 10, FileCategory::SystemCache, Utc::now());
+            Scanner::name_owner(&mut item, &OwnerIndex::default(), folder);
+            item
+        };
+
+        let spotify = cache("com.spotify.client");
+        assert_eq!(&*spotify.name, "Spotify cache");
+        assert_eq!(
+            spotify.note.as_deref(),
+            Some("Cache of Spotify (com.spotify.client)")
+        );
+        assert_eq!(
+            spotify.confidence,
+            FileCategory::SystemCache.base_confidence()
+        );
+        assert_eq!(&*cache("net.example.Widget").name, "net.example.Widget");
+    }
+
+    #[test]
+    fn regen_summary_counts_only_quickly_regenerating_caches() {
+        let now = Utc::now();
+        let item = |path: &str, size| {
+            ScannedItem::new(PathBuf::from(path), size, FileCategory::SystemCache, now)
+        };
+        let items = [
+            item("/home/u/.config/Code/CachedData", 300),
+            item("/home/u/.cache/mesa_shader_cache", 50),
+            item("/home/u/.cache/pip", 1000),
+            item("/home/u/.cache/something-else", 7),
+        ];
+        assert!(items[0].regenerates_quickly);
+        assert!(items[1].regenerates_quickly);
+        assert!(!items[2].regenerates_quickly);
+        assert!(!items[3].regenerates_quickly);
+
+        assert_eq!(
+            RegenSummary::of(&items),
+            RegenSummary {
+                items: 2,
+                size: 350
+            }
+        );
+        assert_eq!(RegenSummary::of(&items[2..]), RegenSummary::default());
+        assert_eq!(RegenSummary::of(&[]), RegenSummary::default());
+    }
+
+    #[test]
+    fn results_go_out_of_date_with_age_or_a_newer_parent() {
+        let scanned = Utc::now() - Duration::hours(2);
+        let max_age = Duration::hours(24);
+
+        assert!(!is_outdated(scanned, None, Utc::now(), max_age));
+        assert!(!is_outdated(
+            scanned,
+            Some(scanned - Duration::minutes(5)),
+            Utc::now(),
+            max_age
+        ));
+        assert!(is_outdated(
+            scanned,
+            Some(scanned + Duration::seconds(1)),
+            Utc::now(),
+            max_age
+        ));
+        assert!(!is_outdated(scanned, None, scanned + max_age, max_age));
+        assert!(is_outdated(
+            scanned,
+            None,
+            scanned + max_age + Duration::seconds(1),
+            max_age
+        ));
+    }
+
+    #[test]
+    fn outdated_paths_checks_the_parent_folder_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cache.bin");
+        std::fs::write(&file, b"x").unwrap();
+        let mut fresh = ScannedItem::new(file.clone(), 1, FileCategory::SystemCache, Utc::now());
+        fresh.scanned_at = Utc::now() + Duration::minutes(1);
+        let mut old = fresh.clone();
+        old.path = dir.path().join("old.bin");
+        old.scanned_at = Utc::now() - Duration::days(3);
+        let mut before_change = fresh.clone();
+        before_change.path = dir.path().join("changed.bin");
+        before_change.scanned_at = Utc::now() - Duration::hours(1);
+
+        let outdated = outdated_paths(&[fresh, old, before_change], Duration::days(1));
+        assert_eq!(
+            outdated,
+            HashSet::from([dir.path().join("old.bin"), dir.path().join("changed.bin")])
+        );
+    }
+
+    #[test]
+    fn every_item_records_the_pass_that_found_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fixture::generate(&root, &FixtureSpec::small(5)).unwrap();
+        let mut config = Config::default();
+        config.enabled_categories.insert(FileCategory::Duplicate);
+
+        let items = scan(&root, config);
+        assert!(items
+            .iter()
+            .any(|item| item.category == FileCategory::Duplicate));
+        for item in &items {
+            let expected = match item.category {
+                FileCategory::DevArtifact | FileCategory::TempFile => ScanSource::ProjectWalk,
+                FileCategory::Duplicate => ScanSource::DuplicatePass,
+                _ => continue,
+            };
+            assert_eq!(item.source, expected, "{}", item.path.display());
+        }
+        assert!(items.iter().all(|item| item.source != ScanSource::Unknown));
+    }
+
+    #[test]
+    fn scan_sources_have_distinct_names() {
+        let sources = [
+            ScanSource::Unknown,
+            ScanSource::KnownCachePath,
+            ScanSource::SystemCacheChild,
+            ScanSource::ProjectWalk,
+            ScanSource::DownloadsPass,
+            ScanSource::LogDirectory,
+            ScanSource::DuplicatePass,
+            ScanSource::VersionedCache,
+            ScanSource::GlobalPackages,
+            ScanSource::TempFamily,
+            ScanSource::PartialDownloads,
+        ];
+        let names: HashSet<&str> = sources.iter().map(ScanSource::name).collect();
+        assert_eq!(names.len(), sources.len());
+    }
+
+    #[test]
+    fn a_panicking_pass_becomes_a_warning_and_the_others_still_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fixture::generate(&root, &FixtureSpec::small(3)).unwrap();
+        let scanner = Scanner::with_scope(Config::default(), vec![root]);
+
+        scanner.run_pass(ScanPhase::Logs, |_| panic!("boom"));
+        scanner.run_pass(ScanPhase::Downloads, |_| panic!("{} exploded", "walker"));
+        scanner.run_pass(ScanPhase::Projects, Scanner::scan_project_directories);
+
+        let store = scanner.snapshot();
+        assert_eq!(
+            store.warnings(),
+            [
+                "Log scan failed: boom",
+                "Downloads scan failed: walker exploded"
+            ]
+        );
+        assert_eq!(store.pass_timings().len(), 3);
+        assert!(store
+            .items()
+            .iter()
+            .any(|item| item.category == FileCategory::DevArtifact));
+        let progress = scanner.progress();
+        for phase in [ScanPhase::Logs, ScanPhase::Downloads, ScanPhase::Projects] {
+            assert!(progress.phase(phase).done, "{:?}", phase);
+        }
+    }
+
+    #[test]
+    fn the_scanning_flag_clears_even_when_a_scan_panics() {
+        let scanning = AtomicBool::new(true);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _scanning = ScanningGuard(&scanning);
+            panic!("scan blew up");
+        }));
+        assert!(result.is_err());
+        assert!(!scanning.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn keep_markers_skip_folders_and_cachedir_tags_mark_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let fixture = fixture::generate(&root, &FixtureSpec::small(8)).unwrap();
+        let kept_artifact = &fixture.artifacts[0];
+        let kept_project = fixture.artifacts[1].parent().unwrap();
+        std::fs::write(kept_artifact.join(CategoryPatterns::KEEP_MARKER), b"").unwrap();
+        std::fs::write(kept_project.join(CategoryPatterns::KEEP_MARKER), b"").unwrap();
+        let tagged = root.join("renders/cache");
+        std::fs::create_dir_all(&tagged).unwrap();
+        std::fs::write(
+            tagged.join(CategoryPatterns::CACHEDIR_TAG),
+            b"Signature: 8a477f597d28d172789f06886806bc55\n",
+        )
+        .unwrap();
+        std::fs::write(tagged.join("frame.bin"), [0u8; 4096]).unwrap();
+        let config = Config {
+            min_sizes: HashMap::new(),
+            ..Config::default()
+        };
+
+        let items = scan(&root, config);
+        assert!(items
+            .iter()
+            .all(|item| !item.path.starts_with(kept_artifact)
+                && !item.path.starts_with(kept_project)));
+        assert!(items.iter().any(|item| item.path == fixture.artifacts[2]));
+        let cache = items.iter().find(|item| item.path == tagged).unwrap();
+        assert_eq!(cache.category, FileCategory::SystemCache);
+        assert_eq!(
+            cache.note.as_deref(),
+            Some("Marked as a cache by CACHEDIR.TAG")
+        );
+    }
+
+    #[test]
+    fn depth_limits_extend_below_project_roots_only() {
+        let mut limits = DepthLimits::new(3, 4);
+        assert!(limits.allows(3));
+        assert!(!limits.allows(4));
+
+        assert!(limits.allows(2));
+        limits.extend_below(2);
+        assert!(limits.allows(6));
+        assert!(!limits.allows(7));
+        // A nested root never shortens the limit set by an outer one.
+        limits.extend_below(3);
+        assert!(limits.allows(7));
+        assert!(!limits.allows(8));
+
+        // Leaving the project's subtree restores the shallow limit.
+        assert!(limits.allows(2));
+        assert!(!limits.allows(4));
+    }
+
+    #[test]
+    fn deep_artifacts_are_found_only_inside_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let deep = |top: &str| {
+            let repo = root.join(top).join("a/b/c/repo");
+            let artifact = repo.join("d/e/f/g/h/node_modules");
+            std::fs::create_dir_all(artifact.join("pkg")).unwrap();
+            std::fs::write(artifact.join("pkg/index.js"), [0u8; 64]).unwrap();
+            (repo, artifact)
+        };
+        let (repo, in_project) = deep("with-repo");
+        std::fs::create_dir(repo.join(".git")).unwrap();
+        let (_, outside) = deep("without-repo");
+        let config = Config {
+            shallow_depth: 6,
+            max_depth: 10,
+            min_sizes: HashMap::new(),
+            ..Config::default()
+        };
+
+        let items = scan(&root, config);
+        assert!(items.iter().any(|item| item.path == in_project));
+        assert!(items.iter().all(|item| item.path != outside));
+    }
+
+    #[test]
+    fn artifacts_of_recently_changed_projects_are_protected() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let long_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(60 * 86_400);
+        let set_modified = |path: &Path| {
+            std::fs::File::open(path)
+                .unwrap()
+                .set_modified(long_ago)
+                .unwrap();
+        };
+        let project = |name: &str, touched_recently: bool| {
+            let project = root.join(name);
+            let artifact = project.join("node_modules");
+            std::fs::create_dir_all(artifact.join("pkg")).unwrap();
+            std::fs::write(artifact.join("pkg/index.js"), [0u8; 64]).unwrap();
+            std::fs::write(project.join("package.json"), "{}").unwrap();
+            std::fs::create_dir(project.join(".git")).unwrap();
+            std::fs::write(project.join(".git/HEAD"), "ref: refs/heads/main").unwrap();
+            set_modified(&project.join(".git/HEAD"));
+            if !touched_recently {
+                set_modified(&project.join("package.json"));
+            }
+            artifact
+        };
+        let active = project("active", true);
+        let dormant = project("dormant", false);
+        // Reading the folders refreshes their access time, so every artifact
+        // counts as stale here unless its project is protected.
+        let config = Config {
+            stale_days_threshold: 0,
+            min_sizes: HashMap::new(),
+            ..Config::default()
+        };
+
+        let items = scan(&root, config.clone());
+        let find = |path: &Path| items.iter().find(|item| item.path == path).unwrap().clone();
+        let mut active_item = find(&active);
+        let dormant_item = find(&dormant);
+        assert!(active_item.recently_active);
+        assert!(!active_item.is_stale);
+        assert_eq!(
+            active_item.note.as_deref(),
+            Some("Its project was changed today")
+        );
+        assert!(!dormant_item.recently_active);
+        assert!(dormant_item.is_stale);
+        assert!(
+            (dormant_item.confidence - active_item.confidence - ACTIVE_PROJECT_PENALTY).abs()
+                < 1e-6
+        );
+
+        reclassify(std::slice::from_mut(&mut active_item), &config);
+        assert!(!active_item.is_stale);
+
+        let unprotected = scan(
+            &root,
+            Config {
+                protect_active_days: 0,
+                ..config
+            },
+        );
+        assert!(unprotected
+            .iter()
+            .filter(|item| item.path == active || item.path == dormant)
+            .all(|item| !item.recently_active && item.is_stale));
+    }
+
+    #[test]
+    fn large_files_are_reported_unless_another_item_covers_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let fixture = fixture::generate(&root, &FixtureSpec::small(4)).unwrap();
+        let big = root.join("videos/raw.mov");
+        let small = root.join("videos/clip.mov");
+        let inside_artifact = fixture.artifacts[0].join("huge.bin");
+        std::fs::create_dir_all(big.parent().unwrap()).unwrap();
+        std::fs::write(&big, vec![0u8; 64 * 1024]).unwrap();
+        std::fs::write(&small, vec![0u8; 1024]).unwrap();
+        std::fs::write(&inside_artifact, vec![0u8; 64 * 1024]).unwrap();
+        let mut config = Config {
+            large_file_threshold: 32 * 1024,
+            min_sizes: HashMap::new(),
+            ..Config::default()
+        };
+        config.enabled_categories.insert(FileCategory::LargeFile);
+
+        let items = scan(&root, config.clone());
+        let large: Vec<&Path> = items
+            .iter()
+            .filter(|item| item.category == FileCategory::LargeFile)
+            .map(|item| item.path.as_path())
+            .collect();
+        assert!(large.contains(&big.as_path()));
+        assert!(!large.contains(&small.as_path()));
+        assert!(!large.contains(&inside_artifact.as_path()));
+        assert!(items.iter().any(|item| item.path == fixture.artifacts[0]));
+
+        config.enabled_categories.remove(&FileCategory::LargeFile);
+        let items = scan(&root, config);
+        assert!(items.iter().all(|item| item.path != big));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn old_tool_temp_folders_are_grouped_into_one_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(2 * 60 * 60);
+        let folder = |name: &str, size: usize, modified: Option<std::time::SystemTime>| {
+            let path = root.join(name);
+            std::fs::create_dir(&path).unwrap();
+            std::fs::write(path.join("blob"), vec![1u8; size]).unwrap();
+            if let Some(modified) = modified {
+                std::fs::File::open(&path)
+                    .unwrap()
+                    .set_modified(modified)
+                    .unwrap();
+            }
+            path
+        };
+        let first = folder("pip-install-a1", 700 * 1024, Some(old));
+        let second = folder("pip-build-b2", 700 * 1024, Some(old));
+        folder("pip-install-fresh", 700 * 1024, None);
+        folder("npm-123-small", 10 * 1024, Some(old));
+        let entries: Vec<walkdir::DirEntry> = WalkDir::new(&root)
+            .min_depth(1)
+            .max_depth(1)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        let scanner = Scanner::with_scope(Config::default(), vec![root.clone()]);
+
+        scanner.add_temp_families(&root, &entries);
+        let items = scanner.get_items();
+        assert_eq!(items.len(), 1);
+        let group = &items[0];
+        assert_eq!(group.path, root.join("pip-*"));
+        assert_eq!(&*group.name, "2 leftover pip build dirs");
+        assert_eq!(group.source, ScanSource::TempFamily);
+        let mut members: Vec<&Path> = group
+            .members
+            .iter()
+            .map(|(path, _)| path.as_path())
+            .collect();
+        members.sort();
+        assert_eq!(members, [second.as_path(), first.as_path()]);
+        assert_eq!(
+            group.size,
+            group.members.iter().map(|(_, size)| size).sum::<u64>()
+        );
+
+        assert!(!group.removed_by(&HashSet::from([first.clone()])));
+        assert!(group.removed_by(&HashSet::from([first.clone(), second.clone()])));
+
+        std::fs::remove_dir_all(&first).unwrap();
+        let refreshed = group.refreshed().unwrap();
+        assert_eq!(refreshed.members.len(), 1);
+        assert_eq!(refreshed.members[0].0, second);
+        std::fs::remove_dir_all(&second).unwrap();
+        assert!(group.refreshed().is_none());
+    }
+
+    #[test]
+    fn old_partial_downloads_are_reported_per_tool() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().canonicalize().unwrap();
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(2 * 60 * 60);
+        let file = |path: PathBuf, modified: Option<std::time::SystemTime>| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, vec![1u8; 4096]).unwrap();
+            if let Some(modified) = modified {
+                std::fs::File::options()
+                    .write(true)
+                    .open(&path)
+                    .unwrap()
+                    .set_modified(modified)
+                    .unwrap();
+            }
+            path
+        };
+        let registry = home.join(".cargo/registry/cache/index");
+        let stale = file(registry.join("serde-1.0.0.crate.tmp"), Some(old));
+        file(registry.join("rand-0.8.5.crate.tmp"), None);
+        file(registry.join("libc-0.2.0.crate"), Some(old));
+        let npm_tmp = file(home.join(".npm/_cacache/tmp/1a2b3c"), Some(old));
+        file(home.join(".npm/_cacache/content-v2/sha512/blob"), Some(old));
+        let scanner = Scanner::with_scope(Config::default(), vec![home.clone()]);
+
+        let reported = scanner.add_partial_downloads(CategoryPatterns::partial_downloads_in(&home));
+        let mut reported: Vec<&Path> = reported.iter().map(|(path, _)| path.as_path()).collect();
+        reported.sort();
+        assert_eq!(reported, [stale.as_path(), npm_tmp.as_path()]);
+
+        let items = scanner.get_items();
+        assert_eq!(items.len(), 2);
+        let cargo = items
+            .iter()
+            .find(|item| item.path == home.join(".cargo/registry/*.tmp"))
+            .unwrap();
+        assert_eq!(&*cargo.name, "1 partial Cargo downloads");
+        assert_eq!(cargo.category, FileCategory::TempFile);
+        assert_eq!(cargo.source, ScanSource::PartialDownloads);
+        assert_eq!(cargo.members, [(stale, 4096)]);
+        let npm = items
+            .iter()
+            .find(|item| item.path == home.join(".npm/_cacache/tmp"))
+            .unwrap();
+        assert_eq!(npm.members, [(npm_tmp, 4096)]);
+    }
+
+    #[test]
+    fn age_counts_from_the_last_access_or_change() {
+        let now = Utc::now();
+        let mut item = ScannedItem::new(
+            PathBuf::from("/home/me/notes.txt"),
+            1,
+            FileCategory::Unused,
+            now - Duration::days(400),
+        );
+        item.scanned_at = now;
+        assert_eq!(item.age_days(), 400);
+        item.accessed = now - Duration::days(10);
+        assert_eq!(item.age_days(), 10);
+        // A bogus access time before the last change doesn't make it older.
+        item.accessed = now - Duration::days(900);
+        assert_eq!(item.age_days(), 400);
+        item.accessed = now + Duration::days(1);
+        assert_eq!(item.age_days(), 0);
+    }
+
+    #[test]
+    fn files_not_opened_within_the_threshold_are_unused() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let fixture = fixture::generate(&root, &FixtureSpec::small(6)).unwrap();
+        let now = std::time::SystemTime::now();
+        let days = |n: u64| now - std::time::Duration::from_secs(n * 24 * 60 * 60);
+        let touch = |path: &Path, accessed, modified| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"old notes").unwrap();
+            let times = std::fs::FileTimes::new()
+                .set_accessed(accessed)
+                .set_modified(modified);
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_times(times)
+                .unwrap();
+        };
+        let forgotten = root.join("archive/2019-taxes.pdf");
+        let recently_read = root.join("archive/manual.pdf");
+        let inside_artifact = fixture.artifacts[0].join("old.txt");
+        touch(&forgotten, days(200), days(300));
+        touch(&recently_read, days(2), days(300));
+        touch(&inside_artifact, days(200), days(300));
+        let mut config = Config {
+            stale_days_threshold: 90,
+            min_sizes: HashMap::new(),
+            ..Config::default()
+        };
+        config.enabled_categories.insert(FileCategory::Unused);
+
+        let items = scan(&root, config);
+        let unused: Vec<&ScannedItem> = items
+            .iter()
+            .filter(|item| item.category == FileCategory::Unused)
+            .collect();
+        let forgotten_item = unused
+            .iter()
+            .find(|item| item.path == forgotten)
+            .expect("file unopened for 200 days is listed");
+        assert!(forgotten_item.is_stale);
+        assert!((199..=201).contains(&forgotten_item.age_days()));
+        assert!(unused.iter().all(|item| item.path != recently_read));
+        assert!(unused.iter().all(|item| item.path != inside_artifact));
+    }
+
+    #[test]
+    fn folder_items_count_the_files_inside() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let fixture = fixture::generate(&root, &FixtureSpec::small(9)).unwrap();
+
+        let items = scan(&root, Config::default());
+        for artifact in &fixture.artifacts {
+            let item = items.iter().find(|item| &item.path == artifact).unwrap();
+            let files = WalkDir::new(artifact)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .count() as u64;
+            assert_eq!(item.file_count, Some(files), "{}", artifact.display());
+        }
+    }
+
+    #[test]
+    fn measuring_a_folder_counts_files_and_stops_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::write(dir.path().join("a/one"), [0u8; 10]).unwrap();
+        std::fs::write(dir.path().join("a/b/two"), [0u8; 20]).unwrap();
+
+        let running = AtomicBool::new(false);
+        assert_eq!(
+            Scanner::dir_usage_until(dir.path(), &running, false).unwrap(),
+            DirUsage {
+                size: 30,
+                files: 2,
+                repeated: 0
+            }
+        );
+        let stopped = AtomicBool::new(true);
+        assert!(matches!(
+            Scanner::dir_usage_until(dir.path(), &stopped, false),
+            Err(SweeperError::Cancelled)
+        ));
+        assert!(matches!(
+            Scanner::dir_usage_until(&dir.path().join("missing"), &running, false),
+            Err(SweeperError::Io { .. })
+        ));
     }
 }
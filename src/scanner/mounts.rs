@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+use std::fs::Metadata;
+
+/// A directory's identity on disk: device and inode.
+pub type DirId = (u64, u64);
+
+#[cfg(unix)]
+pub fn dir_id(meta: &Metadata) -> Option<DirId> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn dir_id(_meta: &Metadata) -> Option<DirId> {
+    None
+}
+
+/// Directories already walked in one pass. A bind mount shows the same
+/// directory under a second path, and a loop mount of a parent nests it
+/// inside itself; walking either again double counts or never ends.
+#[derive(Debug, Default)]
+pub struct VisitedDirs {
+    seen: HashSet<DirId>,
+}
+
+impl VisitedDirs {
+    /// False when the directory was already walked under another path.
+    /// Platforms without inode numbers never report a repeat.
+    pub fn first_visit(&mut self, meta: &Metadata) -> bool {
+        dir_id(meta).is_none_or(|id| self.insert(id))
+    }
+
+    pub fn insert(&mut self, id: DirId) -> bool {
+        self.seen.insert(id)
+    }
+}
+
+pub fn repeat_warning(path: &std::path::Path) -> String {
+    format!(
+        "{} is a folder that was already scanned under another path (bind mount or mount loop); skipped",
+        path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn a_directory_seen_under_a_second_path_is_a_repeat() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("data");
+        let other = dir.path().join("other");
+        let alias = dir.path().join("alias");
+        std::fs::create_dir(&real).unwrap();
+        std::fs::create_dir(&other).unwrap();
+        std::os::unix::fs::symlink(&real, &alias).unwrap();
+
+        let mut visited = VisitedDirs::default();
+        assert!(visited.first_visit(&std::fs::metadata(&real).unwrap()));
+        assert!(visited.first_visit(&std::fs::metadata(&other).unwrap()));
+        assert!(!visited.first_visit(&std::fs::metadata(&alias).unwrap()));
+        assert!(!visited.first_visit(&std::fs::metadata(&real).unwrap()));
+    }
+
+    #[test]
+    fn ids_are_compared_by_device_and_inode() {
+        let mut visited = VisitedDirs::default();
+        assert!(visited.insert((1, 42)));
+        assert!(visited.insert((2, 42)));
+        assert!(!visited.insert((1, 42)));
+    }
+
+    #[test]
+    fn repeat_warnings_name_the_skipped_path() {
+        let warning = repeat_warning(std::path::Path::new("/mnt/loop/home"));
+        assert!(warning.starts_with("/mnt/loop/home is a folder that was already scanned"));
+    }
+}
@@ -0,0 +1,215 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::Path;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    pub fn platform_default() -> Self {
+        if cfg!(any(target_os = "macos", target_os = "windows")) {
+            Self::Insensitive
+        } else {
+            Self::Sensitive
+        }
+    }
+
+    pub fn detect(path: &Path) -> Self {
+        for candidate in path.ancestors() {
+            let Some(name) = candidate.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let swapped: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_lowercase() {
+                        c.to_uppercase().next().unwrap_or(c)
+                    } else {
+                        c.to_lowercase().next().unwrap_or(c)
+                    }
+                })
+                .collect();
+            if swapped == name {
+                continue;
+            }
+
+            return if same_file(candidate, &candidate.with_file_name(swapped)) {
+                Self::Insensitive
+            } else {
+                Self::Sensitive
+            };
+        }
+        Self::platform_default()
+    }
+}
+
+/// Whether both paths name one file, by device and file index rather than
+/// by name, so a folder that merely shares a name in another case doesn't
+/// count.
+fn same_file(a: &Path, b: &Path) -> bool {
+    same_file::is_same_file(a, b).unwrap_or(false)
+}
+
+pub fn normalize_name(name: &str, sensitivity: CaseSensitivity) -> Cow<'_, str> {
+    let name: Cow<str> = if is_nfc_quick(name.chars()) == IsNormalized::Yes {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(name.nfc().collect())
+    };
+
+    match sensitivity {
+        CaseSensitivity::Sensitive => name,
+        CaseSensitivity::Insensitive if name.chars().any(char::is_uppercase) => {
+            Cow::Owned(name.to_lowercase())
+        }
+        CaseSensitivity::Insensitive => name,
+    }
+}
+
+/// Longest name folded on the stack by `with_normalized`; longer ones fall
+/// back to `normalize_name`.
+const INLINE_NAME_LEN: usize = 128;
+
+/// Runs `f` on `name` normalized like `normalize_name`. Walks call this for
+/// every entry, so short ASCII names are lowercased in a stack buffer
+/// instead of a new String.
+pub fn with_normalized<R>(
+    name: &str,
+    sensitivity: CaseSensitivity,
+    f: impl FnOnce(&str) -> R,
+) -> R {
+    if sensitivity == CaseSensitivity::Insensitive
+        && name.len() <= INLINE_NAME_LEN
+        && name.is_ascii()
+    {
+        let mut buf = [0u8; INLINE_NAME_LEN];
+        let folded = &mut buf[..name.len()];
+        folded.copy_from_slice(name.as_bytes());
+        folded.make_ascii_lowercase();
+        if let Ok(folded) = std::str::from_utf8(folded) {
+            return f(folded);
+        }
+    }
+    f(&normalize_name(name, sensitivity))
+}
+
+/// ASCII case-insensitive suffix test straight on the OS bytes.
+pub fn ends_with_ignore_case(name: &OsStr, suffix: &str) -> bool {
+    let name = name.as_encoded_bytes();
+    name.len() >= suffix.len()
+        && name[name.len() - suffix.len()..].eq_ignore_ascii_case(suffix.as_bytes())
+}
+
+#[derive(Debug, Clone)]
+pub struct NameSet {
+    names: HashSet<String>,
+    sensitivity: CaseSensitivity,
+}
+
+impl NameSet {
+    pub fn new(names: &[&str], sensitivity: CaseSensitivity) -> Self {
+        Self {
+            names: names
+                .iter()
+                .map(|name| normalize_name(name, sensitivity).into_owned())
+                .collect(),
+            sensitivity,
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        with_normalized(name, self.sensitivity, |name| self.names.contains(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitive_names_match_only_exactly() {
+        let set = NameSet::new(&["target", "node_modules"], CaseSensitivity::Sensitive);
+        assert!(set.contains("target"));
+        assert!(!set.contains("Target"));
+        assert!(!set.contains("NODE_MODULES"));
+        assert_eq!(
+            normalize_name("Target", CaseSensitivity::Sensitive),
+            "Target"
+        );
+    }
+
+    #[test]
+    fn insensitive_names_match_in_any_case() {
+        let set = NameSet::new(&["Target", "node_modules"], CaseSensitivity::Insensitive);
+        for name in ["target", "Target", "TARGET", "Node_Modules"] {
+            assert!(set.contains(name), "{}", name);
+        }
+        assert!(!set.contains("targets"));
+        assert_eq!(
+            normalize_name("Ünïcode", CaseSensitivity::Insensitive),
+            "ünïcode"
+        );
+    }
+
+    #[test]
+    fn decomposed_names_match_composed_ones_in_both_modes() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        for sensitivity in [CaseSensitivity::Sensitive, CaseSensitivity::Insensitive] {
+            let set = NameSet::new(&[composed], sensitivity);
+            assert!(set.contains(decomposed), "{:?}", sensitivity);
+        }
+    }
+
+    #[test]
+    fn long_and_non_ascii_names_fold_like_short_ones() {
+        let long = "A".repeat(INLINE_NAME_LEN + 1);
+        for name in [long.as_str(), "Ärger", "MiXeD"] {
+            let folded = with_normalized(name, CaseSensitivity::Insensitive, str::to_string);
+            assert_eq!(folded, normalize_name(name, CaseSensitivity::Insensitive));
+            let kept = with_normalized(name, CaseSensitivity::Sensitive, str::to_string);
+            assert_eq!(kept, name);
+        }
+    }
+
+    #[test]
+    fn same_file_compares_identity_not_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, "x").unwrap();
+        std::fs::write(&b, "x").unwrap();
+        assert!(same_file(&a, &a));
+        assert!(!same_file(&a, &b));
+        assert!(!same_file(&a, &dir.path().join("missing")));
+
+        let link = dir.path().join("link");
+        std::fs::hard_link(&a, &link).unwrap();
+        assert!(same_file(&a, &link));
+    }
+
+    #[test]
+    fn detect_checks_the_folder_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("Cache");
+        std::fs::create_dir(&folder).unwrap();
+        let expected = if folder.with_file_name("cACHE").exists() {
+            CaseSensitivity::Insensitive
+        } else {
+            CaseSensitivity::Sensitive
+        };
+        assert_eq!(CaseSensitivity::detect(&folder), expected);
+
+        // A sibling that only differs in case is a different folder, not
+        // proof of an insensitive file system.
+        if expected == CaseSensitivity::Sensitive {
+            std::fs::create_dir(folder.with_file_name("cACHE")).unwrap();
+            assert_eq!(CaseSensitivity::detect(&folder), CaseSensitivity::Sensitive);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::path::Path;
+
+#[cfg(target_os = "macos")]
+const WHERE_FROMS_ATTR: &str = "com.apple.metadata:kMDItemWhereFroms";
+#[cfg(all(unix, not(target_os = "macos")))]
+const ORIGIN_URL_ATTR: &str = "user.xdg.origin.url";
+
+#[cfg(target_os = "macos")]
+pub fn download_origin(path: &Path) -> Option<String> {
+    let value = xattr::get(path, WHERE_FROMS_ATTR).ok()??;
+    parse_where_froms(&value).into_iter().next()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn download_origin(path: &Path) -> Option<String> {
+    let value = xattr::get(path, ORIGIN_URL_ATTR).ok()??;
+    let url = String::from_utf8(value).ok()?;
+    let url = url.trim_end_matches('\0').trim();
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+#[cfg(windows)]
+pub fn download_origin(path: &Path) -> Option<String> {
+    let mut stream = path.as_os_str().to_os_string();
+    stream.push(":Zone.Identifier");
+    let contents = std::fs::read_to_string(stream).ok()?;
+    parse_zone_identifier(&contents)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn download_origin(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(windows)]
+fn parse_zone_identifier(contents: &str) -> Option<String> {
+    let mut referrer = None;
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("about:internet") {
+            continue;
+        }
+        match key.trim() {
+            "HostUrl" => return Some(value.to_string()),
+            "ReferrerUrl" => referrer = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    referrer
+}
+
+#[cfg(target_os = "macos")]
+fn parse_where_froms(data: &[u8]) -> Vec<String> {
+    BinaryPlist::parse(data)
+        .map(|plist| plist.strings())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+struct BinaryPlist<'a> {
+    data: &'a [u8],
+    offsets: Vec<usize>,
+    ref_size: usize,
+    top: usize,
+}
+
+#[cfg(target_os = "macos")]
+impl<'a> BinaryPlist<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.len() < 40 || !data.starts_with(b"bplist00") {
+            return None;
+        }
+        let trailer = &data[data.len() - 32..];
+        let offset_size = trailer[6] as usize;
+        let ref_size = trailer[7] as usize;
+        let count = read_uint(&trailer[8..16])?;
+        let top = read_uint(&trailer[16..24])?;
+        let table = read_uint(&trailer[24..32])?;
+        if offset_size == 0 || ref_size == 0 || count > data.len() {
+            return None;
+        }
+
+        let offsets = (0..count)
+            .map(|index| {
+                let start = table.checked_add(index.checked_mul(offset_size)?)?;
+                read_uint(data.get(start..start.checked_add(offset_size)?)?)
+            })
+            .collect::<Option<Vec<usize>>>()?;
+
+        Some(Self {
+            data,
+            offsets,
+            ref_size,
+            top,
+        })
+    }
+
+    fn strings(&self) -> Vec<String> {
+        let Some(offset) = self.offsets.get(self.top).copied() else {
+            return Vec::new();
+        };
+        let Some(marker) = self.data.get(offset) else {
+            return Vec::new();
+        };
+
+        if marker >> 4 != 0xA {
+            return self.string_at(self.top).into_iter().collect();
+        }
+        let Some((len, start)) = self.length(offset) else {
+            return Vec::new();
+        };
+        (0..len)
+            .filter_map(|index| {
+                let at = start.checked_add(index.checked_mul(self.ref_size)?)?;
+                let object = read_uint(self.data.get(at..at.checked_add(self.ref_size)?)?)?;
+                self.string_at(object)
+            })
+            .collect()
+    }
+
+    fn string_at(&self, object: usize) -> Option<String> {
+        let offset = *self.offsets.get(object)?;
+        let marker = *self.data.get(offset)?;
+        let (len, start) = self.length(offset)?;
+        match marker >> 4 {
+            0x5 => {
+                let bytes = self.data.get(start..start.checked_add(len)?)?;
+                Some(String::from_utf8_lossy(bytes).to_string())
+            }
+            0x6 => {
+                let bytes = self
+                    .data
+                    .get(start..start.checked_add(len.checked_mul(2)?)?)?;
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                Some(String::from_utf16_lossy(&units))
+            }
+            _ => None,
+        }
+    }
+
+    fn length(&self, offset: usize) -> Option<(usize, usize)> {
+        let marker = *self.data.get(offset)?;
+        if marker & 0x0F != 0x0F {
+            return Some(((marker & 0x0F) as usize, offset + 1));
+        }
+        let int_marker = *self.data.get(offset + 1)?;
+        if int_marker >> 4 != 0x1 {
+            return None;
+        }
+        let width = 1usize << (int_marker & 0x0F);
+        let start = offset + 2;
+        let len = read_uint(self.data.get(start..start.checked_add(width)?)?)?;
+        Some((len, start + width))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_uint(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() > 8 {
+        return None;
+    }
+    let value = bytes
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64);
+    usize::try_from(value).ok()
+}
+
+pub fn origin_domain(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, rest)| rest)?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next()?.trim_end_matches('.');
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+pub fn is_redownloadable(domain: &str, known: &[String]) -> bool {
+    known.iter().any(|known| {
+        let known = known.trim().trim_start_matches('.').to_ascii_lowercase();
+        !known.is_empty()
+            && (domain == known
+                || domain
+                    .strip_suffix(&known)
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(domains: &[&str]) -> Vec<String> {
+        domains.iter().map(|domain| domain.to_string()).collect()
+    }
+
+    #[test]
+    fn domains_are_taken_from_the_host_only() {
+        let cases = [
+            ("https://dl.Example.com/file.zip", Some("dl.example.com")),
+            (
+                "https://user:pw@example.com:8443/x?y#z",
+                Some("example.com"),
+            ),
+            ("http://example.com.", Some("example.com")),
+            ("https://example.com?download=1", Some("example.com")),
+            ("ftp://mirror.org", Some("mirror.org")),
+            ("example.com/file.zip", None),
+            ("https:///file.zip", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(origin_domain(url).as_deref(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn subdomains_of_known_hosts_are_redownloadable() {
+        let known = known(&["github.com", " .Python.org ", ""]);
+        assert!(is_redownloadable("github.com", &known));
+        assert!(is_redownloadable("objects.github.com", &known));
+        assert!(is_redownloadable("www.python.org", &known));
+        assert!(!is_redownloadable("notgithub.com", &known));
+        assert!(!is_redownloadable("github.com.evil.io", &known));
+        assert!(!is_redownloadable("anything", &[]));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn the_origin_url_is_read_from_the_xdg_attribute() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("setup.tar.gz");
+        std::fs::write(&file, b"x").unwrap();
+        assert_eq!(download_origin(&file), None);
+
+        // Not every filesystem allows user attributes.
+        if xattr::set(
+            &file,
+            ORIGIN_URL_ATTR,
+            b"https://example.com/setup.tar.gz\0",
+        )
+        .is_err()
+        {
+            return;
+        }
+        assert_eq!(
+            download_origin(&file).as_deref(),
+            Some("https://example.com/setup.tar.gz")
+        );
+        xattr::set(&file, ORIGIN_URL_ATTR, b"  ").unwrap();
+        assert_eq!(download_origin(&file), None);
+    }
+
+    #[cfg(target_os = "macos")]
+    fn where_froms(urls: &[&str]) -> Vec<u8> {
+        let mut data = b"bplist00".to_vec();
+        let mut offsets = vec![data.len()];
+        data.push(0xA0 | urls.len() as u8);
+        data.extend((1..=urls.len()).map(|index| index as u8));
+        for url in urls {
+            offsets.push(data.len());
+            data.extend([0x5F, 0x10, url.len() as u8]);
+            data.extend(url.as_bytes());
+        }
+        let table = data.len();
+        data.extend(offsets.iter().map(|offset| *offset as u8));
+        data.extend([0; 6]);
+        data.extend([1, 1]);
+        data.extend((offsets.len() as u64).to_be_bytes());
+        data.extend(0u64.to_be_bytes());
+        data.extend((table as u64).to_be_bytes());
+        data
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn where_froms_lists_the_download_url_first() {
+        let data = where_froms(&["https://example.com/app.dmg", "https://example.com/"]);
+        assert_eq!(
+            parse_where_froms(&data),
+            ["https://example.com/app.dmg", "https://example.com/"]
+        );
+        assert!(parse_where_froms(&data[..data.len() - 1]).is_empty());
+        assert!(parse_where_froms(b"not a plist at all, just some bytes...").is_empty());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn zone_identifiers_prefer_the_host_url() {
+        let both = "[ZoneTransfer]\r\nZoneId=3\r\nReferrerUrl=https://example.com/\r\nHostUrl=https://cdn.example.com/a.zip\r\n";
+        assert_eq!(
+            parse_zone_identifier(both).as_deref(),
+            Some("https://cdn.example.com/a.zip")
+        );
+        let referrer_only = "[ZoneTransfer]\r\nZoneId=3\r\nReferrerUrl=https://example.com/\r\nHostUrl=about:internet\r\n";
+        assert_eq!(
+            parse_zone_identifier(referrer_only).as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(
+            parse_zone_identifier("[ZoneTransfer]\r\nZoneId=3\r\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,416 @@
+#[cfg(target_os = "macos")]
+use rayon::prelude::*;
+use std::collections::HashMap;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::path::{Path, PathBuf};
+
+/// Names for common cache folders whose app may not be in a standard
+/// Applications folder.
+const KNOWN_OWNERS: &[(&str, &str)] = &[
+    ("com.spotify.client", "Spotify"),
+    ("org.mozilla.firefox", "Firefox"),
+    ("org.mozilla.thunderbird", "Thunderbird"),
+    ("com.google.Chrome", "Google Chrome"),
+    ("com.brave.Browser", "Brave"),
+    ("com.microsoft.VSCode", "Visual Studio Code"),
+    ("com.microsoft.edgemac", "Microsoft Edge"),
+    ("com.microsoft.teams2", "Microsoft Teams"),
+    ("com.tinyspeck.slackmacgap", "Slack"),
+    ("com.hnc.Discord", "Discord"),
+    ("us.zoom.xos", "Zoom"),
+    ("com.docker.docker", "Docker Desktop"),
+    ("com.figma.Desktop", "Figma"),
+    ("com.apple.Safari", "Safari"),
+];
+
+/// Bundle id prefixes of system components that have no app bundle, so a
+/// missing app never makes their caches orphaned.
+const SYSTEM_PREFIXES: &[&str] = &["com.apple."];
+
+pub struct Owner {
+    pub name: String,
+    pub installed: bool,
+}
+
+/// Maps opaque cache folder names, such as reverse-DNS bundle ids under
+/// `~/Library/Caches` or app names under `~/.cache`, to the app that owns
+/// them. Built once per scan.
+#[derive(Debug, Default)]
+pub struct OwnerIndex {
+    installed: HashMap<String, String>,
+}
+
+impl OwnerIndex {
+    #[cfg(target_os = "macos")]
+    pub fn load() -> Self {
+        let mut roots = vec![
+            PathBuf::from("/Applications"),
+            PathBuf::from("/Applications/Utilities"),
+            PathBuf::from("/System/Applications"),
+        ];
+        roots.extend(dirs::home_dir().map(|home| home.join("Applications")));
+        let installed = app_bundles(&roots)
+            .par_iter()
+            .filter_map(|bundle| read_info_plist(&bundle.join("Contents/Info.plist")))
+            .map(|(id, name)| (id.to_lowercase(), name))
+            .collect();
+        Self { installed }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn load() -> Self {
+        let mut roots = vec![
+            PathBuf::from("/usr/share/applications"),
+            PathBuf::from("/usr/local/share/applications"),
+            PathBuf::from("/var/lib/flatpak/exports/share/applications"),
+            PathBuf::from("/var/lib/snapd/desktop/applications"),
+        ];
+        if let Some(data) = dirs::data_dir() {
+            roots.push(data.join("applications"));
+            roots.push(data.join("flatpak/exports/share/applications"));
+        }
+        let mut index = Self::default();
+        for root in roots {
+            let Ok(entries) = std::fs::read_dir(&root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "desktop") {
+                    continue;
+                }
+                let Ok(contents) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let Some(desktop) = parse_desktop_entry(&contents) else {
+                    continue;
+                };
+                let stem = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                for key in desktop.keys(&stem) {
+                    index
+                        .installed
+                        .entry(key)
+                        .or_insert_with(|| desktop.name.clone());
+                }
+            }
+        }
+        index
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    pub fn load() -> Self {
+        Self::default()
+    }
+
+    /// The app that owns the cache folder `name`, if it can be told. Only
+    /// reverse-DNS ids can be reported as no longer installed; plain names
+    /// are too often shared by command-line tools.
+    pub fn owner(&self, name: &str) -> Option<Owner> {
+        let key = name.to_lowercase();
+        if let Some(app) = self.installed.get(&key) {
+            return Some(Owner {
+                name: app.clone(),
+                installed: true,
+            });
+        }
+        if !is_bundle_id(name) {
+            return None;
+        }
+        let known = KNOWN_OWNERS
+            .iter()
+            .find(|(id, _)| id.eq_ignore_ascii_case(name))
+            .map(|(_, app)| app.to_string());
+        // Helpers and updaters often use their own id, so any installed app
+        // from the same vendor (`com.google.`) keeps a cache from counting
+        // as orphaned.
+        let vendor: String = key.split_inclusive('.').take(2).collect();
+        let installed = self.installed.is_empty()
+            || SYSTEM_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+            || self.installed.keys().any(|id| id.starts_with(&vendor));
+        let name = known.or_else(|| {
+            (!installed).then(|| name.rsplit('.').next().unwrap_or(name).to_string())
+        })?;
+        Some(Owner { name, installed })
+    }
+}
+
+fn is_bundle_id(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('.').collect();
+    parts.len() >= 3
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn app_bundles(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut bundles = Vec::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "app") {
+                bundles.push(path);
+            } else if path.is_dir() {
+                // Vendor folders such as /Applications/Microsoft Office/.
+                let Ok(nested) = std::fs::read_dir(&path) else {
+                    continue;
+                };
+                bundles.extend(
+                    nested
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| path.extension().is_some_and(|ext| ext == "app")),
+                );
+            }
+        }
+    }
+    bundles
+}
+
+/// Reads the bundle id and display name from an `Info.plist`. Binary plists
+/// are converted with `plutil` first.
+#[cfg(target_os = "macos")]
+fn read_info_plist(path: &Path) -> Option<(String, String)> {
+    let bytes = std::fs::read(path).ok()?;
+    let xml = if bytes.starts_with(b"bplist") {
+        let output = std::process::Command::new("plutil")
+            .args(["-convert", "xml1", "-o", "-"])
+            .arg(path)
+            .output()
+            .ok()?;
+        String::from_utf8(output.stdout).ok()?
+    } else {
+        String::from_utf8(bytes).ok()?
+    };
+    let fallback = path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_stem)
+        .map(|stem| stem.to_string_lossy().into_owned());
+    parse_plist(&xml, fallback)
+}
+
+/// Pulls `CFBundleIdentifier` and the best available name out of an XML
+/// plist without a full parser; both are plain top-level strings.
+#[cfg(target_os = "macos")]
+fn parse_plist(xml: &str, fallback_name: Option<String>) -> Option<(String, String)> {
+    let id = plist_string(xml, "CFBundleIdentifier")?;
+    let name = plist_string(xml, "CFBundleDisplayName")
+        .or_else(|| plist_string(xml, "CFBundleName"))
+        .or(fallback_name)?;
+    Some((id, name))
+}
+
+#[cfg(target_os = "macos")]
+fn plist_string(xml: &str, key: &str) -> Option<String> {
+    let tag = format!("<key>{}</key>", key);
+    let rest = &xml[xml.find(&tag)? + tag.len()..];
+    let rest = rest.trim_start().strip_prefix("<string>")?;
+    let value = &rest[..rest.find("</string>")?];
+    let value = value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(target_os = "linux")]
+struct DesktopEntry {
+    name: String,
+    exec: Option<String>,
+    wm_class: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+impl DesktopEntry {
+    /// Lowercased names a cache folder could use for this app: the desktop
+    /// file name, its last dotted part, the executable and the window class.
+    fn keys(&self, stem: &str) -> Vec<String> {
+        let mut keys = vec![stem.to_lowercase()];
+        keys.extend(stem.rsplit('.').next().map(str::to_lowercase));
+        keys.extend(self.exec.as_deref().map(str::to_lowercase));
+        keys.extend(self.wm_class.as_deref().map(str::to_lowercase));
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn parse_desktop_entry(contents: &str) -> Option<DesktopEntry> {
+    let mut in_entry = false;
+    let mut name = None;
+    let mut exec = None;
+    let mut wm_class = None;
+    let mut hidden = false;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Exec" => {
+                exec = value
+                    .split_whitespace()
+                    .find(|part| *part != "env" && !part.contains('='))
+                    .and_then(|program| Path::new(program).file_name())
+                    .map(|program| program.to_string_lossy().into_owned());
+            }
+            "StartupWMClass" => wm_class = Some(value.trim().to_string()),
+            "Hidden" => hidden = value.trim() == "true",
+            _ => {}
+        }
+    }
+    let name = name.filter(|name| !name.is_empty())?;
+    (!hidden).then_some(DesktopEntry {
+        name,
+        exec,
+        wm_class,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(installed: &[(&str, &str)]) -> OwnerIndex {
+        OwnerIndex {
+            installed: installed
+                .iter()
+                .map(|(id, name)| (id.to_lowercase(), name.to_string()))
+                .collect(),
+        }
+    }
+
+    fn owner(index: &OwnerIndex, name: &str) -> Option<(String, bool)> {
+        index.owner(name).map(|owner| (owner.name, owner.installed))
+    }
+
+    #[test]
+    fn bundle_ids_need_three_plain_parts() {
+        assert!(is_bundle_id("com.spotify.client"));
+        assert!(is_bundle_id("org.mozilla.firefox-esr"));
+        assert!(!is_bundle_id("com.spotify"));
+        assert!(!is_bundle_id("com..client"));
+        assert!(!is_bundle_id("pip"));
+        assert!(!is_bundle_id("node-gyp.cache.d/x"));
+    }
+
+    #[test]
+    fn installed_apps_name_their_caches() {
+        let index = index(&[
+            ("com.example.Notes", "Notes"),
+            ("thunderbird", "Thunderbird"),
+        ]);
+        assert_eq!(
+            owner(&index, "com.example.notes"),
+            Some(("Notes".to_string(), true))
+        );
+        assert_eq!(
+            owner(&index, "Thunderbird"),
+            Some(("Thunderbird".to_string(), true))
+        );
+        // Plain names are never reported as orphaned.
+        assert_eq!(owner(&index, "pip"), None);
+    }
+
+    #[test]
+    fn bundle_ids_without_an_installed_app_are_orphaned() {
+        let index = index(&[("com.google.Chrome", "Google Chrome")]);
+        assert_eq!(
+            owner(&index, "com.spotify.client"),
+            Some(("Spotify".to_string(), false))
+        );
+        assert_eq!(
+            owner(&index, "net.example.WidgetMaker"),
+            Some(("WidgetMaker".to_string(), false))
+        );
+        // Another app from the same vendor, or a system component, keeps a
+        // cache from counting as orphaned.
+        assert_eq!(owner(&index, "com.google.SoftwareUpdate"), None);
+        assert_eq!(
+            owner(&index, "com.apple.Safari"),
+            Some(("Safari".to_string(), true))
+        );
+        assert_eq!(owner(&index, "com.apple.bird"), None);
+    }
+
+    #[test]
+    fn an_empty_index_never_reports_orphans() {
+        let index = OwnerIndex::default();
+        assert_eq!(
+            owner(&index, "com.spotify.client"),
+            Some(("Spotify".to_string(), true))
+        );
+        assert_eq!(owner(&index, "net.example.WidgetMaker"), None);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn plists_give_the_bundle_id_and_best_name() {
+        let plist = |body: &str| format!("<plist><dict>{}</dict></plist>", body);
+        assert_eq!(
+            parse_plist(
+                &plist(
+                    "<key>CFBundleIdentifier</key>\n  <string>com.example.App</string>\
+                     <key>CFBundleName</key><string>App</string>\
+                     <key>CFBundleDisplayName</key><string>Tom &amp; Jerry</string>"
+                ),
+                None
+            ),
+            Some(("com.example.App".to_string(), "Tom & Jerry".to_string()))
+        );
+        assert_eq!(
+            parse_plist(
+                &plist("<key>CFBundleIdentifier</key><string>com.example.App</string>"),
+                Some("App".to_string())
+            ),
+            Some(("com.example.App".to_string(), "App".to_string()))
+        );
+        assert_eq!(
+            parse_plist(&plist("<key>CFBundleName</key><string>App</string>"), None),
+            None
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn desktop_entries_give_every_name_a_cache_may_use() {
+        let entry = parse_desktop_entry(
+            "[Desktop Entry]\n\
+             Name=Visual Studio Code\n\
+             Exec=env GDK_BACKEND=x11 /usr/share/code/code --unity-launch %F\n\
+             StartupWMClass=Code\n\
+             [Desktop Action new-empty-window]\n\
+             Name=New Empty Window\n",
+        )
+        .unwrap();
+        assert_eq!(entry.name, "Visual Studio Code");
+        assert_eq!(
+            entry.keys("com.visualstudio.code"),
+            ["code", "com.visualstudio.code"]
+        );
+
+        assert!(parse_desktop_entry("[Desktop Entry]\nName=Gone\nHidden=true\n").is_none());
+        assert!(parse_desktop_entry("[Desktop Entry]\nExec=tool\n").is_none());
+    }
+}
@@ -0,0 +1,271 @@
+use super::{ScanSource, ScannedItem};
+use crate::categories::FileCategory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+pub const CURRENT_SCHEMA: SchemaVersion = SchemaVersion(1);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SchemaVersion(pub u32);
+
+impl std::fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PersistError {
+    #[error("could not read items: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("items are not valid JSON: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("written by a newer version of Sweeper (item format {found}); this version reads up to {CURRENT_SCHEMA}")]
+    Newer { found: SchemaVersion },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemRecordV1 {
+    pub path: PathBuf,
+    pub size: u64,
+    pub category: FileCategory,
+    #[serde(default)]
+    pub confidence: f32,
+    #[serde(default)]
+    pub confidence_adjustment: Option<f32>,
+    #[serde(default)]
+    pub confidence_overridden: bool,
+    #[serde(default)]
+    pub is_stale: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub recently_active: bool,
+    pub modified: DateTime<Utc>,
+    #[serde(default)]
+    pub accessed: Option<DateTime<Utc>>,
+    #[serde(default = "Utc::now")]
+    pub scanned_at: DateTime<Utc>,
+    #[serde(default)]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub sampled_hash: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_keeper: bool,
+    #[serde(default)]
+    pub source: ScanSource,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub origin: Option<String>,
+    #[serde(default)]
+    pub managed_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<(PathBuf, u64)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_count: Option<u64>,
+}
+
+impl From<&ScannedItem> for ItemRecordV1 {
+    fn from(item: &ScannedItem) -> Self {
+        Self {
+            path: item.path.clone(),
+            size: item.size,
+            category: item.category,
+            confidence: item.confidence,
+            confidence_adjustment: Some(item.confidence_adjustment),
+            confidence_overridden: item.confidence_overridden,
+            is_stale: item.is_stale,
+            recently_active: item.recently_active,
+            modified: item.modified,
+            accessed: Some(item.accessed),
+            scanned_at: item.scanned_at,
+            hash: item.hash.clone(),
+            sampled_hash: item.sampled_hash,
+            is_keeper: item.is_keeper,
+            source: item.source,
+            note: item.note.clone(),
+            origin: item.origin.clone(),
+            managed_by: item.managed_by.clone(),
+            members: item.members.clone(),
+            name: (!item.members.is_empty()).then(|| item.name.to_string()),
+            file_count: item.file_count,
+        }
+    }
+}
+
+impl From<ItemRecordV1> for ScannedItem {
+    fn from(record: ItemRecordV1) -> Self {
+        let mut item = ScannedItem::new(record.path, record.size, record.category, record.modified);
+        item.confidence = record.confidence.clamp(0.0, 1.0);
+        item.confidence_adjustment = record
+            .confidence_adjustment
+            .unwrap_or(item.confidence - item.category.base_confidence());
+        item.confidence_overridden = record.confidence_overridden;
+        item.is_stale = record.is_stale;
+        item.recently_active = record.recently_active;
+        item.accessed = record.accessed.unwrap_or(record.modified);
+        item.scanned_at = record.scanned_at;
+        item.hash = record.hash;
+        item.sampled_hash = record.sampled_hash;
+        item.is_keeper = record.is_keeper;
+        item.source = record.source;
+        item.note = record.note;
+        item.origin = record.origin;
+        item.managed_by = record.managed_by;
+        item.members = record.members;
+        item.file_count = record.file_count;
+        if let Some(name) = record.name {
+            item.name = name.into();
+        }
+        item
+    }
+}
+
+#[derive(Serialize)]
+struct ItemFile<'a, H: Serialize> {
+    #[serde(flatten)]
+    header: &'a H,
+    schema: SchemaVersion,
+    items: Vec<ItemRecordV1>,
+}
+
+#[derive(Deserialize)]
+struct RawItemFile {
+    #[serde(default)]
+    schema: SchemaVersion,
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+}
+
+pub fn write_items<H: Serialize>(
+    writer: impl Write,
+    header: &H,
+    items: &[ScannedItem],
+) -> Result<(), PersistError> {
+    let file = ItemFile {
+        header,
+        schema: CURRENT_SCHEMA,
+        items: items.iter().map(ItemRecordV1::from).collect(),
+    };
+    serde_json::to_writer_pretty(writer, &file)?;
+    Ok(())
+}
+
+/// Items read back from a file written by [`write_items`].
+#[derive(Debug)]
+pub struct LoadedItems {
+    pub items: Vec<ScannedItem>,
+    pub schema: SchemaVersion,
+    /// Items that could not be read and were left out.
+    pub skipped: usize,
+}
+
+pub fn read_items(reader: impl Read) -> Result<LoadedItems, PersistError> {
+    let raw: RawItemFile = serde_json::from_reader(reader)?;
+    let schema = if raw.schema == SchemaVersion::default() {
+        CURRENT_SCHEMA
+    } else {
+        raw.schema
+    };
+    if schema > CURRENT_SCHEMA {
+        return Err(PersistError::Newer { found: schema });
+    }
+
+    let total = raw.items.len();
+    let items: Vec<ScannedItem> = raw
+        .items
+        .into_iter()
+        .filter_map(|value| serde_json::from_value::<ItemRecordV1>(value).ok())
+        .map(ScannedItem::from)
+        .collect();
+    let skipped = total - items.len();
+    if skipped > 0 {
+        log::warn!("Skipped {} unreadable items", skipped);
+    }
+    Ok(LoadedItems {
+        items,
+        schema,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::ScanBundle;
+    use serde_json::json;
+
+    const BUNDLE_V1: &str = include_str!("../../tests/fixtures/bundle-v1.json");
+
+    #[test]
+    fn v1_fixture_loads() {
+        let loaded = read_items(BUNDLE_V1.as_bytes()).unwrap();
+        assert_eq!(loaded.schema, SchemaVersion(1));
+        assert_eq!(loaded.items.len(), 3);
+        assert_eq!(loaded.skipped, 2);
+
+        let artifact = &loaded.items[0];
+        assert_eq!(artifact.category, FileCategory::DevArtifact);
+        assert_eq!(artifact.file_count, Some(48211));
+        assert!(artifact.is_stale);
+        assert!((artifact.confidence - 0.9).abs() < f32::EPSILON);
+
+        // Fields an early writer left out fall back to their defaults.
+        let cache = &loaded.items[1];
+        assert_eq!(cache.accessed, cache.modified);
+        assert_eq!(cache.source, ScanSource::Unknown);
+        assert!(!cache.is_keeper);
+
+        let duplicate = &loaded.items[2];
+        assert_eq!(duplicate.hash.as_deref().map(str::len), Some(64));
+        assert_eq!(
+            duplicate.origin.as_deref(),
+            Some("https://example.com/clip.mov")
+        );
+    }
+
+    #[test]
+    fn v1_fixture_loads_as_a_bundle() {
+        let bundle = ScanBundle::from_json(BUNDLE_V1).unwrap();
+        assert_eq!(bundle.host.hostname, "build-07");
+        assert_eq!(bundle.store.len(), 3);
+        assert_eq!(bundle.skipped, 2);
+        assert_eq!(bundle.store.stats().duration_ms, 4210);
+        assert!(!bundle.is_newer());
+    }
+
+    #[test]
+    fn newer_schema_is_refused() {
+        let mut file: serde_json::Value = serde_json::from_str(BUNDLE_V1).unwrap();
+        file["schema"] = json!(CURRENT_SCHEMA.0 + 1);
+        let err = read_items(file.to_string().as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            PersistError::Newer { found } if found == SchemaVersion(CURRENT_SCHEMA.0 + 1)
+        ));
+    }
+
+    #[test]
+    fn missing_schema_reads_as_current() {
+        let loaded = read_items(r#"{"items": []}"#.as_bytes()).unwrap();
+        assert_eq!(loaded.schema, CURRENT_SCHEMA);
+        assert_eq!(loaded.skipped, 0);
+    }
+
+    #[test]
+    fn written_items_read_back_unchanged() {
+        let loaded = read_items(BUNDLE_V1.as_bytes()).unwrap();
+        let mut buffer = Vec::new();
+        write_items(&mut buffer, &json!({ "version": 1 }), &loaded.items).unwrap();
+        let again = read_items(buffer.as_slice()).unwrap();
+        assert_eq!(again.skipped, 0);
+        let records = |items: &[ScannedItem]| -> Vec<ItemRecordV1> {
+            items.iter().map(ItemRecordV1::from).collect()
+        };
+        assert_eq!(records(&again.items), records(&loaded.items));
+    }
+}
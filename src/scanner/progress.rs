@@ -0,0 +1,201 @@
+use super::ScanSource;
+use bytesize::ByteSize;
+
+/// The passes a scan runs in parallel, in the order they are listed to the
+/// user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanPhase {
+    Caches,
+    Projects,
+    Downloads,
+    Duplicates,
+    Logs,
+    VersionedCaches,
+    GlobalPackages,
+}
+
+impl ScanPhase {
+    pub const ALL: [ScanPhase; 7] = [
+        ScanPhase::Caches,
+        ScanPhase::Projects,
+        ScanPhase::Downloads,
+        ScanPhase::Duplicates,
+        ScanPhase::Logs,
+        ScanPhase::VersionedCaches,
+        ScanPhase::GlobalPackages,
+    ];
+
+    /// The name recorded in the pass timings and warnings.
+    pub fn pass_name(&self) -> &'static str {
+        match self {
+            ScanPhase::Caches => "Cache scan",
+            ScanPhase::Projects => "Project scan",
+            ScanPhase::Downloads => "Downloads scan",
+            ScanPhase::Duplicates => "Duplicate scan",
+            ScanPhase::Logs => "Log scan",
+            ScanPhase::VersionedCaches => "Versioned cache scan",
+            ScanPhase::GlobalPackages => "Global package scan",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanPhase::Caches => "caches",
+            ScanPhase::Projects => "project directories",
+            ScanPhase::Downloads => "downloads",
+            ScanPhase::Duplicates => "duplicates",
+            ScanPhase::Logs => "logs",
+            ScanPhase::VersionedCaches => "versioned caches",
+            ScanPhase::GlobalPackages => "global packages",
+        }
+    }
+
+    /// The phase whose pass reports items found by `source`.
+    pub fn of(source: ScanSource) -> Option<ScanPhase> {
+        match source {
+            ScanSource::Unknown => None,
+            ScanSource::KnownCachePath
+            | ScanSource::SystemCacheChild
+            | ScanSource::TempFamily
+            | ScanSource::PartialDownloads => Some(ScanPhase::Caches),
+            ScanSource::ProjectWalk => Some(ScanPhase::Projects),
+            ScanSource::DownloadsPass => Some(ScanPhase::Downloads),
+            ScanSource::DuplicatePass => Some(ScanPhase::Duplicates),
+            ScanSource::LogDirectory => Some(ScanPhase::Logs),
+            ScanSource::VersionedCache => Some(ScanPhase::VersionedCaches),
+            ScanSource::GlobalPackages => Some(ScanPhase::GlobalPackages),
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseProgress {
+    pub started: bool,
+    pub done: bool,
+    pub items: usize,
+    pub bytes: u64,
+}
+
+/// A snapshot of how far a running scan has got, cheap enough to copy on
+/// every UI poll.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanProgress {
+    phases: [PhaseProgress; 7],
+    pub roots_done: usize,
+    pub roots_total: usize,
+    pub files_scanned: u64,
+}
+
+impl ScanProgress {
+    pub(super) fn new(roots_total: usize) -> Self {
+        Self {
+            roots_total,
+            ..Self::default()
+        }
+    }
+
+    pub fn phase(&self, phase: ScanPhase) -> &PhaseProgress {
+        &self.phases[phase.index()]
+    }
+
+    pub(super) fn phase_mut(&mut self, phase: ScanPhase) -> &mut PhaseProgress {
+        &mut self.phases[phase.index()]
+    }
+
+    pub fn phases_done(&self) -> usize {
+        self.phases.iter().filter(|phase| phase.done).count()
+    }
+
+    /// Estimated share of the scan that is finished. Every phase counts the
+    /// same, and the project walk earns partial credit per scan root since
+    /// it is usually the longest. Held below 1.0 until every phase is done.
+    pub fn fraction(&self) -> f32 {
+        let partial: f32 = ScanPhase::ALL
+            .iter()
+            .map(|&phase| {
+                let progress = self.phase(phase);
+                if progress.done {
+                    1.0
+                } else if phase == ScanPhase::Projects && self.roots_total > 0 {
+                    self.roots_done.min(self.roots_total) as f32 / self.roots_total as f32
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+        let fraction = partial / ScanPhase::ALL.len() as f32;
+        if self.phases_done() == ScanPhase::ALL.len() {
+            1.0
+        } else {
+            fraction.min(0.99)
+        }
+    }
+
+    /// A line such as "Scanning project directories (2/5 roots)…" naming a
+    /// phase that is still running.
+    pub fn label(&self) -> String {
+        let running: Vec<ScanPhase> = ScanPhase::ALL
+            .into_iter()
+            .filter(|&phase| {
+                let progress = self.phase(phase);
+                progress.started && !progress.done
+            })
+            .collect();
+        let Some(&first) = running.first() else {
+            return if self.phases_done() == ScanPhase::ALL.len() {
+                "Finishing up…".to_string()
+            } else {
+                "Starting scan…".to_string()
+            };
+        };
+        // The project walk is the one worth watching whenever it is running.
+        let phase = if running.contains(&ScanPhase::Projects) {
+            ScanPhase::Projects
+        } else {
+            first
+        };
+        if phase == ScanPhase::Projects && self.roots_total > 0 {
+            format!(
+                "Scanning {} ({}/{} roots)…",
+                phase.label(),
+                self.roots_done.min(self.roots_total),
+                self.roots_total
+            )
+        } else {
+            format!(
+                "Scanning {} ({}/{} steps done)…",
+                phase.label(),
+                self.phases_done(),
+                ScanPhase::ALL.len()
+            )
+        }
+    }
+
+    /// One line per phase with what it has found so far.
+    pub fn breakdown(&self) -> Vec<String> {
+        ScanPhase::ALL
+            .into_iter()
+            .map(|phase| {
+                let progress = self.phase(phase);
+                let state = if progress.done {
+                    "done"
+                } else if progress.started {
+                    "running"
+                } else {
+                    "waiting"
+                };
+                format!(
+                    "{}: {}, {} items ({})",
+                    phase.pass_name(),
+                    state,
+                    progress.items,
+                    ByteSize(progress.bytes)
+                )
+            })
+            .collect()
+    }
+}
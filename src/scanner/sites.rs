@@ -0,0 +1,110 @@
+use super::lock;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+pub struct ActiveSites {
+    next: AtomicU64,
+    slots: Mutex<BTreeMap<u64, PathBuf>>,
+}
+
+impl ActiveSites {
+    pub fn claim(&self, path: &Path) -> SiteSlot<'_> {
+        let id = self.next.fetch_add(1, Ordering::Relaxed);
+        lock(&self.slots).insert(id, path.to_path_buf());
+        SiteSlot { sites: self, id }
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        lock(&self.slots).values().cloned().collect()
+    }
+
+    fn update(&self, id: u64, path: &Path) {
+        if let Some(slot) = lock(&self.slots).get_mut(&id) {
+            slot.clear();
+            slot.push(path);
+        }
+    }
+
+    fn release(&self, id: u64) {
+        lock(&self.slots).remove(&id);
+    }
+}
+
+#[derive(Debug)]
+pub struct SiteSlot<'a> {
+    sites: &'a ActiveSites,
+    id: u64,
+}
+
+impl SiteSlot<'_> {
+    pub fn update(&self, path: &Path) {
+        self.sites.update(self.id, path);
+    }
+}
+
+impl Drop for SiteSlot<'_> {
+    fn drop(&mut self) {
+        self.sites.release(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sites_are_listed_in_claim_order_until_released() {
+        let sites = ActiveSites::default();
+        let caches = sites.claim(Path::new("/home/me/.cache"));
+        let projects = sites.claim(Path::new("/home/me/code"));
+        assert_eq!(
+            sites.paths(),
+            [
+                PathBuf::from("/home/me/.cache"),
+                PathBuf::from("/home/me/code")
+            ]
+        );
+
+        projects.update(Path::new("/home/me/code/app/src"));
+        drop(caches);
+        assert_eq!(sites.paths(), [PathBuf::from("/home/me/code/app/src")]);
+
+        let downloads = sites.claim(Path::new("/home/me/Downloads"));
+        assert_eq!(
+            sites.paths().last().unwrap(),
+            Path::new("/home/me/Downloads")
+        );
+        drop(projects);
+        drop(downloads);
+        assert!(sites.paths().is_empty());
+    }
+
+    #[test]
+    fn a_released_slot_is_not_brought_back_by_a_late_update() {
+        let sites = ActiveSites::default();
+        let id = {
+            let slot = sites.claim(Path::new("/a"));
+            slot.id
+        };
+        sites.update(id, Path::new("/a/b"));
+        assert!(sites.paths().is_empty());
+    }
+
+    #[test]
+    fn sites_can_be_claimed_from_many_threads() {
+        let sites = ActiveSites::default();
+        std::thread::scope(|scope| {
+            for index in 0..8 {
+                let sites = &sites;
+                scope.spawn(move || {
+                    let slot = sites.claim(Path::new("/root"));
+                    slot.update(&PathBuf::from(format!("/root/{}", index)));
+                });
+            }
+        });
+        assert!(sites.paths().is_empty());
+    }
+}
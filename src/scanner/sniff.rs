@@ -0,0 +1,191 @@
+use std::io::Read;
+use std::path::Path;
+
+pub const SAMPLE_BYTES: usize = 8 * 1024;
+pub const MAX_SAMPLE_BYTES: usize = 64 * 1024;
+pub const MIN_SIZE: u64 = 1024 * 1024;
+
+const MIN_VALID_UTF8: f32 = 0.95;
+const MIN_PRINTABLE: f32 = 0.9;
+
+const COMPRESSED_MAGIC: &[&[u8]] = &[
+    &[0x1f, 0x8b],
+    b"BZh",
+    &[0xfd, b'7', b'z', b'X', b'Z', 0x00],
+    &[0x28, 0xb5, 0x2f, 0xfd],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Content {
+    Text,
+    CompressedText,
+    Binary,
+}
+
+pub fn sample(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut sample = Vec::with_capacity(SAMPLE_BYTES);
+    std::fs::File::open(path)?
+        .take(SAMPLE_BYTES.min(MAX_SAMPLE_BYTES) as u64)
+        .read_to_end(&mut sample)?;
+    Ok(sample)
+}
+
+pub fn classify(sample: &[u8]) -> Content {
+    if sample.is_empty() {
+        return Content::Text;
+    }
+    if COMPRESSED_MAGIC
+        .iter()
+        .any(|magic| sample.starts_with(magic))
+    {
+        return Content::CompressedText;
+    }
+
+    let text = match utf16_order(sample) {
+        Some(little_endian) => {
+            let units = sample
+                .strip_prefix(&[0xff, 0xfe])
+                .or_else(|| sample.strip_prefix(&[0xfe, 0xff]))
+                .unwrap_or(sample)
+                .chunks_exact(2)
+                .map(|pair| {
+                    if little_endian {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    }
+                });
+            char::decode_utf16(units)
+                .map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect::<String>()
+        }
+        None => {
+            let mut valid = 0;
+            let mut text = String::new();
+            let mut chunks = sample.utf8_chunks().peekable();
+            while let Some(chunk) = chunks.next() {
+                valid += chunk.valid().len();
+                text.push_str(chunk.valid());
+                if chunks.peek().is_none() && chunk.invalid().len() < 4 {
+                    valid += chunk.invalid().len();
+                }
+            }
+            if (valid as f32) / (sample.len() as f32) < MIN_VALID_UTF8 {
+                return Content::Binary;
+            }
+            text
+        }
+    };
+
+    if printable_ratio(&text) < MIN_PRINTABLE {
+        Content::Binary
+    } else {
+        Content::Text
+    }
+}
+
+fn utf16_order(sample: &[u8]) -> Option<bool> {
+    match sample {
+        [0xff, 0xfe, ..] => return Some(true),
+        [0xfe, 0xff, ..] => return Some(false),
+        _ => {}
+    }
+
+    let pairs = sample.len() / 2;
+    if pairs == 0 {
+        return None;
+    }
+    let zeros = |offset: usize| {
+        sample
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count() as f32
+            / pairs as f32
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+    if odd > 0.4 && even < 0.05 {
+        Some(true)
+    } else if even > 0.4 && odd < 0.05 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn printable_ratio(text: &str) -> f32 {
+    let mut total = 0usize;
+    let mut printable = 0usize;
+    for c in text.chars() {
+        total += 1;
+        if !c.is_control() || matches!(c, '\n' | '\r' | '\t' | '\x1b') {
+            printable += 1;
+        }
+    }
+    if total == 0 {
+        return 1.0;
+    }
+    printable as f32 / total as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/sniff")
+            .join(name)
+    }
+
+    #[test]
+    fn real_files_are_classified_by_their_bytes() {
+        let cases = [
+            ("app.log", Content::Text),
+            ("windows-utf16le.log", Content::Text),
+            ("no-bom-utf16be.log", Content::Text),
+            ("app.log.gz", Content::CompressedText),
+            ("app.log.bz2", Content::CompressedText),
+            ("app.log.xz", Content::CompressedText),
+            ("app.log.zst", Content::CompressedText),
+            ("image.png", Content::Binary),
+            ("elf-binary.bin", Content::Binary),
+        ];
+        for (name, expected) in cases {
+            let sample = sample(&fixture(name)).unwrap();
+            assert_eq!(classify(&sample), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn a_sample_cut_inside_a_character_is_still_text() {
+        let log = std::fs::read(fixture("app.log")).unwrap();
+        let dash = log
+            .windows(3)
+            .position(|bytes| bytes == "—".as_bytes())
+            .unwrap();
+        assert_eq!(classify(&log[..dash + 1]), Content::Text);
+        assert_eq!(classify(&log[..dash + 2]), Content::Text);
+    }
+
+    #[test]
+    fn control_heavy_or_empty_samples() {
+        assert_eq!(classify(b""), Content::Text);
+        assert_eq!(classify(&[0u8; 512]), Content::Binary);
+        assert_eq!(classify(b"\x1b[32mok\x1b[0m\r\n\tdone\n"), Content::Text);
+        let controls: Vec<u8> = (0..200)
+            .map(|i| if i % 4 == 0 { b'a' } else { 1 })
+            .collect();
+        assert_eq!(classify(&controls), Content::Binary);
+    }
+
+    #[test]
+    fn samples_stop_at_the_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.log");
+        std::fs::write(&path, vec![b'a'; SAMPLE_BYTES * 3]).unwrap();
+        assert_eq!(sample(&path).unwrap().len(), SAMPLE_BYTES);
+    }
+}
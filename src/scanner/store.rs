@@ -0,0 +1,260 @@
+use super::{ScanStats, ScannedItem};
+use crate::config::Config;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static REVISIONS: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct ItemStore {
+    items: Vec<ScannedItem>,
+    stats: ScanStats,
+    warnings: Vec<String>,
+    suppressed: usize,
+    pass_timings: Vec<(String, u64)>,
+    generation: u64,
+    revision: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemCursor {
+    generation: u64,
+    seen: usize,
+}
+
+impl ItemStore {
+    pub fn items(&self) -> &[ScannedItem] {
+        &self.items
+    }
+
+    pub fn stats(&self) -> &ScanStats {
+        &self.stats
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn pass_timings(&self) -> &[(String, u64)] {
+        &self.pass_timings
+    }
+
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Changes whenever the items do and is never shared by two different
+    /// sets of items, even across stores, so callers can cache what they
+    /// derive from them.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn items_after(&self, cursor: &mut ItemCursor) -> &[ScannedItem] {
+        if cursor.generation != self.generation || cursor.seen > self.items.len() {
+            *cursor = ItemCursor {
+                generation: self.generation,
+                seen: 0,
+            };
+        }
+        let fresh = &self.items[cursor.seen..];
+        cursor.seen = self.items.len();
+        fresh
+    }
+
+    pub fn push(&mut self, item: ScannedItem) {
+        self.stats.record(&item);
+        self.items.push(item);
+        self.touch();
+    }
+
+    pub fn push_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    pub fn record_pass(&mut self, name: &str, duration_ms: u64) {
+        self.pass_timings.push((name.to_string(), duration_ms));
+    }
+
+    pub fn record_suppressed(&mut self) {
+        self.suppressed += 1;
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&ScannedItem) -> bool) -> Vec<ScannedItem> {
+        let mut removed = Vec::new();
+        let mut kept = Vec::with_capacity(self.items.len());
+        for item in self.items.drain(..) {
+            if keep(&item) {
+                kept.push(item);
+            } else {
+                self.stats.forget(&item);
+                removed.push(item);
+            }
+        }
+        self.items = kept;
+        if !removed.is_empty() {
+            self.touch();
+        }
+        self.debug_check();
+        removed
+    }
+
+    pub fn reclassify(&mut self, config: &Config) -> usize {
+        let changed = super::reclassify(&mut self.items, config);
+        if changed > 0 {
+            self.touch();
+        }
+        self.debug_check();
+        changed
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.stats = ScanStats::default();
+        self.warnings.clear();
+        self.suppressed = 0;
+        self.pass_timings.clear();
+        self.generation += 1;
+        self.touch();
+    }
+
+    fn touch(&mut self) {
+        self.revision = REVISIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_duration_ms(&mut self, duration_ms: u64) {
+        self.stats.duration_ms = duration_ms;
+        self.debug_check();
+    }
+
+    fn debug_check(&self) {
+        if cfg!(debug_assertions) {
+            let mut expected = ScanStats::from_items(&self.items);
+            expected.duration_ms = self.stats.duration_ms;
+            debug_assert_eq!(self.stats, expected, "ScanStats drifted from items");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::FileCategory;
+    use chrono::Utc;
+
+    fn item(path: &str, size: u64, category: FileCategory) -> ScannedItem {
+        ScannedItem::new(path.into(), size, category, Utc::now())
+    }
+
+    fn filled() -> ItemStore {
+        let mut store = ItemStore::default();
+        store.push(item("/p/node_modules", 300, FileCategory::DevArtifact));
+        store.push(item("/p/target", 500, FileCategory::DevArtifact));
+        store.push(item("/tmp/a.tmp", 7, FileCategory::TempFile));
+        store.push(item("/logs/big.log", 0, FileCategory::LogFile));
+        store
+    }
+
+    fn assert_consistent(store: &ItemStore) {
+        let mut expected = ScanStats::from_items(store.items());
+        expected.duration_ms = store.stats().duration_ms;
+        assert_eq!(store.stats(), &expected);
+    }
+
+    #[test]
+    fn push_keeps_stats_in_step() {
+        let store = filled();
+        assert_consistent(&store);
+        assert_eq!(store.stats().total_items, 4);
+        assert_eq!(store.stats().total_size, 807);
+        assert_eq!(
+            store.stats().size_by_category[&FileCategory::DevArtifact],
+            800
+        );
+    }
+
+    #[test]
+    fn retain_returns_what_it_removed_and_keeps_stats_in_step() {
+        let mut store = filled();
+        let removed = store.retain(|item| item.category != FileCategory::DevArtifact);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(store.len(), 2);
+        assert_consistent(&store);
+        assert!(!store
+            .stats()
+            .items_by_category
+            .contains_key(&FileCategory::DevArtifact));
+        assert!(!store
+            .stats()
+            .size_by_category
+            .contains_key(&FileCategory::DevArtifact));
+
+        // Removing a zero-sized item must drop its category too.
+        store.retain(|item| item.category != FileCategory::LogFile);
+        assert_consistent(&store);
+
+        store.retain(|_| false);
+        assert_eq!(store.stats(), &ScanStats::default());
+    }
+
+    #[test]
+    fn reclassify_changes_confidence_but_not_totals() {
+        let mut store = filled();
+        store.set_duration_ms(42);
+        let mut config = Config::default();
+        config
+            .confidence_overrides
+            .insert(FileCategory::DevArtifact, 0.5);
+
+        assert_eq!(store.reclassify(&config), 2);
+        assert_consistent(&store);
+        assert_eq!(store.stats().duration_ms, 42);
+        for item in store.items() {
+            if item.category == FileCategory::DevArtifact {
+                assert!(item.confidence_overridden);
+                assert_eq!(item.confidence, 0.5);
+            }
+        }
+        assert_eq!(store.reclassify(&config), 0);
+    }
+
+    #[test]
+    fn cursor_restarts_after_clear() {
+        let mut store = filled();
+        let mut cursor = ItemCursor::default();
+        assert_eq!(store.items_after(&mut cursor).len(), 4);
+        assert!(store.items_after(&mut cursor).is_empty());
+
+        store.clear();
+        assert_consistent(&store);
+        store.push(item("/tmp/b.tmp", 1, FileCategory::TempFile));
+        assert_eq!(store.items_after(&mut cursor).len(), 1);
+    }
+
+    #[test]
+    fn revision_follows_the_items_across_stores() {
+        let mut store = filled();
+        let filled_at = store.revision();
+        assert_eq!(store.clone().revision(), filled_at);
+
+        store.retain(|_| true);
+        assert_eq!(store.revision(), filled_at);
+        store.retain(|item| item.category != FileCategory::TempFile);
+        let retained_at = store.revision();
+        assert_ne!(retained_at, filled_at);
+
+        // Another store with the same number of pushes holds other items,
+        // so it must not look the same.
+        assert_ne!(filled().revision(), filled_at);
+
+        store.clear();
+        assert_ne!(store.revision(), retained_at);
+    }
+}
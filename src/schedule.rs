@@ -0,0 +1,229 @@
+use crate::categories::FileCategory;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub const AUTO_CLEAN_MIN_CONFIDENCE: f32 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "every", rename_all = "lowercase")]
+pub enum Cadence {
+    Daily { hour: u32 },
+    Weekly { weekday: Weekday, hour: u32 },
+}
+
+impl Cadence {
+    pub fn hour(&self) -> u32 {
+        match self {
+            Self::Daily { hour } | Self::Weekly { hour, .. } => *hour,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Daily { hour } => format!("daily at {:02}:00", hour),
+            Self::Weekly { weekday, hour } => format!("every {} at {:02}:00", weekday, hour),
+        }
+    }
+
+    fn runs_on(&self, weekday: Weekday) -> bool {
+        match self {
+            Self::Daily { .. } => true,
+            Self::Weekly { weekday: day, .. } => *day == weekday,
+        }
+    }
+
+    /// The most recent time this cadence fired at or before `now`, in the
+    /// local time of `now`'s zone. An hour skipped by a DST change fires at
+    /// the first valid time after it; a repeated hour fires once, the first
+    /// time round.
+    pub fn last_occurrence<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Option<DateTime<Utc>> {
+        let zone = now.timezone();
+        let today = now.date_naive();
+        (0..=7)
+            .filter_map(|back| today.checked_sub_signed(Duration::days(back)))
+            .filter(|date| self.runs_on(date.weekday()))
+            .filter_map(|date| date.and_hms_opt(self.hour(), 0, 0))
+            .filter_map(|local| resolve(&zone, local))
+            .find(|at| *at <= now.with_timezone(&Utc))
+    }
+}
+
+fn resolve<Tz: TimeZone>(zone: &Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    (0..=3)
+        .map(|shift| local + Duration::minutes(30 * shift))
+        .find_map(|shifted| zone.from_local_datetime(&shifted).earliest())
+        .map(|at| at.with_timezone(&Utc))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoClean {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categories: Option<HashSet<FileCategory>>,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+}
+
+fn default_min_confidence() -> f32 {
+    AUTO_CLEAN_MIN_CONFIDENCE
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    pub cadence: Cadence,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_clean: Option<AutoClean>,
+}
+
+impl ScheduleEntry {
+    pub fn key(&self) -> String {
+        format!(
+            "{} / {}",
+            self.cadence.label(),
+            self.profile.as_deref().unwrap_or("current settings")
+        )
+    }
+}
+
+pub fn validate(entries: &[ScheduleEntry]) -> Result<(), String> {
+    for entry in entries {
+        if entry.cadence.hour() > 23 {
+            return Err(format!("{}: hour must be between 0 and 23", entry.key()));
+        }
+        if entry
+            .profile
+            .as_ref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err("scheduled profile names cannot be empty".to_string());
+        }
+        if let Some(auto_clean) = &entry.auto_clean {
+            if !(AUTO_CLEAN_MIN_CONFIDENCE..=1.0).contains(&auto_clean.min_confidence) {
+                return Err(format!(
+                    "{}: auto_clean min_confidence must be between {} and 1.0",
+                    entry.key(),
+                    AUTO_CLEAN_MIN_CONFIDENCE
+                ));
+            }
+            if auto_clean
+                .categories
+                .as_ref()
+                .is_some_and(|categories| categories.is_empty())
+            {
+                return Err(format!(
+                    "{}: auto_clean categories must list at least one category",
+                    entry.key()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Entries whose latest occurrence is newer than their last run. Runs missed
+/// while the machine was off collapse into one run.
+pub fn due<'a, Tz: TimeZone>(
+    entries: &'a [ScheduleEntry],
+    last_runs: &HashMap<String, DateTime<Utc>>,
+    now: &DateTime<Tz>,
+) -> Vec<&'a ScheduleEntry> {
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.cadence.last_occurrence(now).is_some_and(|fired| {
+                last_runs
+                    .get(&entry.key())
+                    .is_none_or(|last_run| *last_run < fired)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    fn daily(hour: u32) -> ScheduleEntry {
+        ScheduleEntry {
+            cadence: Cadence::Daily { hour },
+            profile: None,
+            auto_clean: None,
+        }
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn before_the_hour_the_last_occurrence_is_yesterday() {
+        let now = New_York.with_ymd_and_hms(2026, 6, 10, 1, 0, 0).unwrap();
+        let fired = Cadence::Daily { hour: 3 }.last_occurrence(&now);
+        // 03:00 EDT on the 9th.
+        assert_eq!(fired, Some(utc(2026, 6, 9, 7, 0)));
+    }
+
+    #[test]
+    fn skipped_hour_fires_at_the_first_valid_time_after_it() {
+        // 2026-03-08 02:00 does not exist in New York; clocks jump to 03:00.
+        let now = New_York.with_ymd_and_hms(2026, 3, 8, 4, 0, 0).unwrap();
+        let fired = Cadence::Daily { hour: 2 }.last_occurrence(&now);
+        assert_eq!(fired, Some(utc(2026, 3, 8, 7, 0)));
+    }
+
+    #[test]
+    fn repeated_hour_fires_once() {
+        // 2026-11-01 01:00 happens twice in New York: 05:00 and 06:00 UTC.
+        let entries = [daily(1)];
+        let first = utc(2026, 11, 1, 5, 0);
+        let second_pass = utc(2026, 11, 1, 6, 30).with_timezone(&New_York);
+        assert_eq!(
+            entries[0].cadence.last_occurrence(&second_pass),
+            Some(first)
+        );
+
+        let last_runs = HashMap::from([(entries[0].key(), first)]);
+        assert!(due(&entries, &last_runs, &second_pass).is_empty());
+    }
+
+    #[test]
+    fn missed_runs_collapse_into_one() {
+        let weekly = ScheduleEntry {
+            cadence: Cadence::Weekly {
+                weekday: Weekday::Mon,
+                hour: 9,
+            },
+            ..daily(0)
+        };
+        let entries = [weekly, daily(9)];
+        let now = New_York.with_ymd_and_hms(2026, 6, 17, 12, 0, 0).unwrap();
+        let three_weeks_ago = utc(2026, 5, 27, 13, 0);
+        let last_runs: HashMap<String, DateTime<Utc>> = entries
+            .iter()
+            .map(|entry| (entry.key(), three_weeks_ago))
+            .collect();
+
+        let due_now = due(&entries, &last_runs, &now);
+        assert_eq!(due_now.len(), 2);
+
+        // Once run, neither is due again until its next occurrence.
+        let ran: HashMap<String, DateTime<Utc>> = entries
+            .iter()
+            .map(|entry| (entry.key(), now.with_timezone(&Utc)))
+            .collect();
+        assert!(due(&entries, &ran, &now).is_empty());
+        let tomorrow = New_York.with_ymd_and_hms(2026, 6, 18, 10, 0, 0).unwrap();
+        let due_tomorrow = due(&entries, &ran, &tomorrow);
+        assert_eq!(due_tomorrow, vec![&entries[1]]);
+    }
+
+    #[test]
+    fn never_run_entries_are_due() {
+        let entries = [daily(9)];
+        let now = New_York.with_ymd_and_hms(2026, 6, 17, 12, 0, 0).unwrap();
+        assert_eq!(due(&entries, &HashMap::new(), &now).len(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::scanner::Scanner;
+use std::time::{Duration, Instant};
+
+pub const TIMEOUT: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+pub trait Job {
+    fn name(&self) -> &'static str;
+    fn cancel(&self);
+    fn is_running(&self) -> bool;
+}
+
+impl Job for Scanner {
+    fn name(&self) -> &'static str {
+        "scan"
+    }
+
+    fn cancel(&self) {
+        self.stop();
+    }
+
+    fn is_running(&self) -> bool {
+        self.is_scanning()
+    }
+}
+
+/// Cancels every job and waits up to `timeout` for them to stop, returning
+/// the names of any still running. Blocks while it waits, so the app calls
+/// it from a background task.
+pub fn run(jobs: &[&dyn Job], timeout: Duration) -> Vec<&'static str> {
+    for job in jobs {
+        job.cancel();
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let running: Vec<&'static str> = jobs
+            .iter()
+            .filter(|job| job.is_running())
+            .map(|job| job.name())
+            .collect();
+        if running.is_empty() || Instant::now() >= deadline {
+            return running;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Keeps running for `stops_after` once cancelled.
+    struct FakeJob {
+        name: &'static str,
+        stops_after: Duration,
+        cancelled: Mutex<Option<Instant>>,
+    }
+
+    impl FakeJob {
+        fn new(name: &'static str, stops_after: Duration) -> Self {
+            Self {
+                name,
+                stops_after,
+                cancelled: Mutex::new(None),
+            }
+        }
+    }
+
+    impl Job for FakeJob {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn cancel(&self) {
+            self.cancelled
+                .lock()
+                .unwrap()
+                .get_or_insert_with(Instant::now);
+        }
+
+        fn is_running(&self) -> bool {
+            self.cancelled
+                .lock()
+                .unwrap()
+                .is_none_or(|at| at.elapsed() < self.stops_after)
+        }
+    }
+
+    #[test]
+    fn returns_as_soon_as_every_job_stops() {
+        let scan = FakeJob::new("scan", Duration::from_millis(50));
+        let export = FakeJob::new("export", Duration::ZERO);
+        let started = Instant::now();
+
+        let unfinished = run(&[&scan, &export], Duration::from_secs(5));
+
+        assert!(unfinished.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(scan.cancelled.lock().unwrap().is_some());
+        assert!(export.cancelled.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn gives_up_at_the_timeout_and_names_what_is_still_running() {
+        let scan = FakeJob::new("scan", Duration::from_secs(60));
+        let export = FakeJob::new("export", Duration::ZERO);
+        let timeout = Duration::from_millis(100);
+        let started = Instant::now();
+
+        let unfinished = run(&[&scan, &export], timeout);
+
+        assert_eq!(unfinished, ["scan"]);
+        assert!(started.elapsed() >= timeout);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn nothing_to_wait_for_returns_immediately() {
+        let started = Instant::now();
+        assert!(run(&[], TIMEOUT).is_empty());
+        assert!(started.elapsed() < POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::audit;
+use crate::config::Config;
+use crate::history::History;
+use crate::profile::Profile;
+use crate::scanner::Scanner;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataStore {
+    Config,
+    Profiles,
+    History,
+    Quarantine,
+}
+
+impl DataStore {
+    pub const ALL: [DataStore; 4] = [
+        Self::Config,
+        Self::Profiles,
+        Self::History,
+        Self::Quarantine,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Config => "Config",
+            Self::Profiles => "Profiles",
+            Self::History => "History",
+            Self::Quarantine => "Quarantine",
+        }
+    }
+
+    pub fn location(&self, config: &Config) -> Option<PathBuf> {
+        match self {
+            Self::Config => Some(Config::config_path()),
+            Self::Profiles => Some(Profile::profiles_dir()),
+            Self::History => Some(History::path()),
+            Self::Quarantine => config.quarantine_dir.clone(),
+        }
+    }
+
+    pub fn clearable(&self) -> bool {
+        *self != Self::Config
+    }
+
+    pub fn size(&self, config: &Config) -> u64 {
+        let Some(path) = self.location(config) else {
+            return 0;
+        };
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => Scanner::dir_size_parallel(&path).unwrap_or(0),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        }
+    }
+
+    pub fn clear(&self, config: &Config) -> std::io::Result<()> {
+        if !self.clearable() {
+            return Ok(());
+        }
+        audit::ensure_writable().map_err(std::io::Error::other)?;
+        let Some(dir) = self.location(config) else {
+            return Ok(());
+        };
+        if dir.parent().is_none() || dirs::home_dir().is_some_and(|home| home.starts_with(&dir)) {
+            return Err(std::io::Error::other(format!(
+                "refusing to clear {}",
+                dir.display()
+            )));
+        }
+        if std::fs::symlink_metadata(&dir).is_ok_and(|meta| meta.is_file()) {
+            return std::fs::remove_file(&dir);
+        }
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if std::fs::symlink_metadata(&path)?.is_dir() {
+                std::fs::remove_dir_all(&path)?;
+            } else {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clearing_empties_the_store_but_keeps_its_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = dir.path().join("quarantine");
+        std::fs::create_dir_all(quarantine.join("batch/nested")).unwrap();
+        std::fs::write(quarantine.join("batch/nested/a.bin"), [0u8; 300]).unwrap();
+        std::fs::write(quarantine.join("b.bin"), [0u8; 20]).unwrap();
+        let config = Config {
+            quarantine_dir: Some(quarantine.clone()),
+            ..Config::default()
+        };
+
+        assert!(DataStore::Quarantine.size(&config) >= 320);
+        DataStore::Quarantine.clear(&config).unwrap();
+        assert!(quarantine.is_dir());
+        assert_eq!(std::fs::read_dir(&quarantine).unwrap().count(), 0);
+        assert_eq!(DataStore::Quarantine.size(&config), 0);
+    }
+
+    #[test]
+    fn missing_or_unset_stores_are_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = Config {
+            quarantine_dir: Some(dir.path().join("never-created")),
+            ..Config::default()
+        };
+        assert_eq!(DataStore::Quarantine.size(&missing), 0);
+        DataStore::Quarantine.clear(&missing).unwrap();
+
+        let unset = Config {
+            quarantine_dir: None,
+            ..Config::default()
+        };
+        assert_eq!(DataStore::Quarantine.location(&unset), None);
+        assert_eq!(DataStore::Quarantine.size(&unset), 0);
+        DataStore::Quarantine.clear(&unset).unwrap();
+    }
+
+    #[test]
+    fn only_config_is_kept_when_clearing() {
+        assert!(!DataStore::Config.clearable());
+        assert!(DataStore::ALL
+            .iter()
+            .filter(|store| **store != DataStore::Config)
+            .all(DataStore::clearable));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::scanner::ScannedItem;
+
+const STALE_AFTER_DAYS: f32 = 365.0;
+
+/// Ranks an item by how much space it frees per unit of risk.
+///
+/// `score = size × confidence × staleness`, each normalized to 0..=1:
+/// - size is `ln(1 + size) / ln(1 + largest)`, so one huge item doesn't flatten the rest
+/// - confidence is the item's confidence score
+/// - staleness is `0.5 + 0.5 × min(age / 365 days, 1)`, so fresh items are halved, not dropped
+pub fn space_per_risk(item: &ScannedItem, largest: u64) -> f32 {
+    size_weight(item.size, largest) * item.confidence * staleness_weight(item.age_days())
+}
+
+fn size_weight(size: u64, largest: u64) -> f32 {
+    if largest == 0 {
+        return 0.0;
+    }
+    ((size as f64).ln_1p() / (largest as f64).ln_1p()).clamp(0.0, 1.0) as f32
+}
+
+fn staleness_weight(age_days: u64) -> f32 {
+    0.5 + 0.5 * (age_days as f32 / STALE_AFTER_DAYS).min(1.0)
+}
+
+pub fn rank(items: &[&ScannedItem]) -> Vec<usize> {
+    let largest = items.iter().map(|item| item.size).max().unwrap_or(0);
+    let scores: Vec<f32> = items
+        .iter()
+        .map(|item| space_per_risk(item, largest))
+        .collect();
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| {
+        scores[b]
+            .total_cmp(&scores[a])
+            .then_with(|| items[b].size.cmp(&items[a].size))
+            .then_with(|| items[a].path.cmp(&items[b].path))
+    });
+    order
+}
+
+pub fn select_until(items: &[&ScannedItem], ranked: &[usize], target: u64) -> Vec<usize> {
+    let mut total = 0u64;
+    ranked
+        .iter()
+        .copied()
+        .take_while(|&index| {
+            let take = total < target;
+            total += items[index].size;
+            take
+        })
+        .collect()
+}
+
+pub fn describe(item: &ScannedItem, largest: u64, position: usize) -> String {
+    let size = match size_weight(item.size, largest) {
+        weight if weight >= 0.85 => "large",
+        weight if weight >= 0.6 => "medium",
+        _ => "small",
+    };
+    let confidence = match item.confidence {
+        confidence if confidence >= 0.9 => "high confidence",
+        confidence if confidence >= 0.8 => "good confidence",
+        _ => "lower confidence",
+    };
+    let age_days = item.age_days();
+    let age = match age_days {
+        0..7 => "modified recently".to_string(),
+        7..60 => format!("untouched {} days", age_days),
+        60..730 => format!("untouched {} months", age_days / 30),
+        _ => format!("untouched {} years", age_days / 365),
+    };
+    format!(
+        "Ranked #{}: {}, {}, {}",
+        position + 1,
+        size,
+        confidence,
+        age
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::FileCategory;
+    use chrono::{Duration, Utc};
+
+    fn item(path: &str, size: u64, confidence: f32, age_days: i64) -> ScannedItem {
+        let modified = Utc::now() - Duration::days(age_days);
+        let mut item = ScannedItem::new(path.into(), size, FileCategory::DevArtifact, modified);
+        item.accessed = modified;
+        item.confidence = confidence;
+        item
+    }
+
+    fn refs(items: &[ScannedItem]) -> Vec<&ScannedItem> {
+        items.iter().collect()
+    }
+
+    #[test]
+    fn selection_stops_once_the_target_is_reached() {
+        let items = [
+            item("/a", 400, 0.9, 400),
+            item("/b", 300, 0.9, 400),
+            item("/c", 200, 0.9, 400),
+            item("/d", 100, 0.9, 400),
+        ];
+        let items = refs(&items);
+        let ranked = [0, 1, 2, 3];
+        let cases: [(u64, &[usize]); 6] = [
+            (0, &[]),
+            (1, &[0]),
+            (400, &[0]),
+            (700, &[0, 1]),
+            // Overshoots by taking the whole next item rather than stopping short.
+            (701, &[0, 1, 2]),
+            (10_000, &[0, 1, 2, 3]),
+        ];
+        for (target, expected) in cases {
+            assert_eq!(
+                select_until(&items, &ranked, target),
+                expected,
+                "{}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn selection_follows_the_ranking_and_handles_nothing_to_pick() {
+        let items = [item("/a", 100, 0.9, 400), item("/b", 900, 0.9, 400)];
+        let items = refs(&items);
+        assert_eq!(select_until(&items, &[1, 0], 500), [1]);
+        assert!(select_until(&items, &[], 500).is_empty());
+        assert!(select_until(&[], &[], 500).is_empty());
+    }
+
+    #[test]
+    fn ranking_prefers_big_confident_stale_items() {
+        let items = [
+            item("/small", 1 << 10, 0.9, 400),
+            item("/risky", 1 << 30, 0.3, 400),
+            item("/fresh", 1 << 30, 0.9, 0),
+            item("/best", 1 << 30, 0.9, 400),
+        ];
+        let items = refs(&items);
+        let order: Vec<&str> = rank(&items)
+            .into_iter()
+            .map(|index| items[index].path.to_str().unwrap())
+            .collect();
+        assert_eq!(order, ["/best", "/fresh", "/small", "/risky"]);
+    }
+}
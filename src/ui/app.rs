@@ -1,20 +1,69 @@
-use crate::categories::FileCategory;
-use crate::config::Config;
-use crate::scanner::{ScanStats, ScannedItem, Scanner};
+mod clean;
+mod control;
+mod explorer;
+mod results;
+mod scan;
+mod select;
+mod settings;
+mod sharing;
+mod trash;
+mod updates;
+
+use super::compact::{self, CompactStatus, CompactView};
+use super::components::{self, InfoTooltip};
+use super::mode::ModeGuard;
+use super::poll::{PollCadence, IDLE_POLL_INTERVAL};
+use super::selection::{SelectionHistory, SelectionPreset};
+use crate::activity::{self, ActivityLog, RestoreError, TrashStatus, TrashedEntry};
+use crate::audit;
+use crate::bundle::{HostInfo, LoadedBundle, ScanBundle};
+use crate::categories::{self, FileCategory};
+use crate::cleaner::{self, CleanJob, CleanReport, CleanTarget, PendingClean, COALESCE_MIN_FILES};
+use crate::config::{self, Config, ListDensity, ReloadDecision};
+use crate::control::{ControlRequest, ControlServer};
+use crate::crash;
+use crate::duplicates::{self, DuplicateGroup};
+use crate::error;
+use crate::exclusions::{self, ExclusionImport};
+use crate::handoff::{InstanceListener, ScanRequest};
+use crate::history::{History, ScanDelta, ScanSummary};
+use crate::manifest::DeletionManifest;
+use crate::platform;
+use crate::profile::{ConfigChange, Profile};
+use crate::report::{self, ExportProgress, ScanReport};
+use crate::rules::RuleSet;
+use crate::scanner::{
+    self, explain, hashing, metrics, DirNode, ItemCursor, ItemStore, RegenSummary, ScanProgress,
+    ScanSource, ScannedItem, Scanner, SizeDrift,
+};
+use crate::shutdown;
+use crate::storage::DataStore;
+use crate::suggest;
+use crate::update::{self, HttpManifestSource, ReleaseManifest};
+use crate::volumes::{Capacity, VolumePressure, VolumeTable};
 use adabraka_ui::components::button::{Button, ButtonSize, ButtonVariant};
 use adabraka_ui::components::checkbox::Checkbox;
 use adabraka_ui::components::icon::Icon;
+use adabraka_ui::components::icon_button::IconButton;
+use adabraka_ui::components::input::Input;
+use adabraka_ui::components::input_state::InputState;
 use adabraka_ui::components::scrollable::scrollable_vertical;
 use adabraka_ui::components::sparkline::Sparkline;
 use adabraka_ui::components::spinner::Spinner;
 use adabraka_ui::display::badge::{Badge, BadgeVariant};
 use adabraka_ui::display::card::Card;
+use adabraka_ui::overlays::popover::{Popover, PopoverContent};
 use adabraka_ui::prelude::*;
+use chrono::{DateTime, Utc};
+use gpui::prelude::FluentBuilder;
 use gpui::*;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
+
+actions!(sweeper, [UndoSelection, RedoSelection, ToggleReviewLater]);
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum FilterTab {
@@ -24,6 +73,9 @@ pub enum FilterTab {
     Caches,
     TempLogs,
     Downloads,
+    Duplicates,
+    LargeFiles,
+    Review,
 }
 
 impl FilterTab {
@@ -35,6 +87,9 @@ impl FilterTab {
             Self::Caches => "Caches",
             Self::TempLogs => "Temp & Logs",
             Self::Downloads => "Downloads",
+            Self::Duplicates => "Duplicates",
+            Self::LargeFiles => "Large Files",
+            Self::Review => "Review Later",
         }
     }
 
@@ -46,6 +101,60 @@ impl FilterTab {
             Self::Caches => "database",
             Self::TempLogs => "file-text",
             Self::Downloads => "download",
+            Self::Duplicates => "copy",
+            Self::LargeFiles => "file-archive",
+            Self::Review => "clock",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Size,
+    Name,
+    Path,
+    Modified,
+    Confidence,
+    Suggested,
+}
+
+impl SortOrder {
+    const COLUMNS: [Self; 5] = [
+        Self::Size,
+        Self::Name,
+        Self::Path,
+        Self::Modified,
+        Self::Confidence,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Size => "Size",
+            Self::Name => "Name",
+            Self::Path => "Path",
+            Self::Modified => "Modified",
+            Self::Confidence => "Confidence",
+            Self::Suggested => "Suggested",
+        }
+    }
+
+    /// Direction a column starts in when picked: biggest, oldest and most
+    /// confident first, names and paths alphabetical.
+    fn default_descending(&self) -> bool {
+        matches!(self, Self::Size | Self::Confidence | Self::Suggested)
+    }
+
+    fn compare(&self, a: &ScannedItem, b: &ScannedItem) -> std::cmp::Ordering {
+        match self {
+            Self::Size | Self::Suggested => a.size.cmp(&b.size),
+            Self::Name => a
+                .name
+                .chars()
+                .flat_map(char::to_lowercase)
+                .cmp(b.name.chars().flat_map(char::to_lowercase)),
+            Self::Path => a.path.cmp(&b.path),
+            Self::Modified => a.modified.cmp(&b.modified),
+            Self::Confidence => a.confidence.total_cmp(&b.confidence),
         }
     }
 }
@@ -63,248 +172,637 @@ fn category_icon(category: FileCategory) -> &'static str {
         FileCategory::LargeFile => "file-archive",
         FileCategory::Duplicate => "copy",
         FileCategory::Unused => "clock",
+        FileCategory::AppContainer => "lock",
+        FileCategory::GlobalPackage => "package",
     }
 }
 
 pub struct SweeperApp {
     config: Config,
     scanner: Arc<Scanner>,
-    items: Vec<ScannedItem>,
+    scan_scope: Option<Vec<PathBuf>>,
+    scope_history: Vec<Vec<PathBuf>>,
+    store: ItemStore,
     selected: HashSet<PathBuf>,
-    stats: ScanStats,
     active_tab: FilterTab,
+    sort_order: SortOrder,
+    sort_descending: bool,
+    target_input: Entity<InputState>,
+    search_input: Entity<InputState>,
+    search_query: String,
+    list_limit: usize,
     is_scanning: bool,
-    scan_progress: String,
+    scan_progress: ScanProgress,
     show_delete_dialog: bool,
     is_deleting: bool,
+    notice: Option<String>,
+    profiles: Vec<(PathBuf, Profile)>,
+    active_profile: Option<String>,
+    show_settings: bool,
+    pending_profile: Option<(Profile, Vec<ConfigChange>)>,
+    outdated: HashSet<PathBuf>,
+    pending_exclusions: Option<ExclusionImport>,
+    verifying: HashSet<String>,
+    confirm_sampled: bool,
+    available_update: Option<ReleaseManifest>,
+    pinned: Vec<PathBuf>,
+    /// Items set aside as "not sure"; they only show in the Review tab and
+    /// stay flagged across rescans.
+    review_later: HashSet<PathBuf>,
+    hovered_item: Option<PathBuf>,
+    show_explorer: bool,
+    is_exploring: bool,
+    explorer: Option<DirNode>,
+    explorer_trail: Vec<usize>,
+    explorer_delete: Option<(PathBuf, u64)>,
+    confirm_input: Entity<InputState>,
+    threshold_category: Option<FileCategory>,
+    threshold_input: Entity<InputState>,
+    scan_path_input: Entity<InputState>,
+    max_depth_input: Entity<InputState>,
+    large_file_input: Entity<InputState>,
+    download_days_input: Entity<InputState>,
+    settings_error: Option<String>,
+    dismissed_items: Vec<ScannedItem>,
+    pending_clean: Option<PendingClean>,
+    viewing_bundle: Option<(HostInfo, DateTime<Utc>)>,
+    trash_groups: Vec<(PathBuf, usize)>,
+    coalesce_trash: bool,
+    oversized: Vec<(PathBuf, u64, u64)>,
+    unchecked_oversized: usize,
+    compact: Option<WindowHandle<CompactView>>,
+    main_window: Option<AnyWindowHandle>,
+    scan_started: Option<Instant>,
+    trash_fallback: Vec<CleanTarget>,
+    data_usage: Vec<(DataStore, u64)>,
+    confirm_clear_quarantine: bool,
+    ruleset_notes: Option<String>,
+    trash_log: Option<ActivityLog>,
+    trash_selected: HashSet<(u64, PathBuf)>,
+    trash_busy: bool,
+    custom_rules: RuleSet,
+    scan_sites: Vec<PathBuf>,
+    scan_cursor: ItemCursor,
+    scan_cancelled: bool,
+    scan_delta: Option<ScanDelta>,
+    last_deleted: Option<UndoDelete>,
+    size_check: Option<SizeCheck>,
+    report_export: Option<ReportExport>,
+    crash_report: Option<PathBuf>,
+    volumes: VolumeTable,
+    volume_filter: Option<PathBuf>,
+    confirm_quit: bool,
+    mode_guard: ModeGuard,
+    quit_when_idle: bool,
+    quitting: bool,
+    control: Option<ControlServer>,
+    instance: Option<InstanceListener>,
+    local_store: Option<ItemStore>,
+    synced_config: Config,
+    config_modified: Option<SystemTime>,
+    config_conflict: Option<Config>,
+    pending_disk_config: Option<Config>,
+    config_loaded: bool,
+    launched: Option<Instant>,
+    focus_handle: FocusHandle,
+    selection_history: SelectionHistory,
+    keepers_cache: Option<(u64, Arc<HashSet<PathBuf>>)>,
+    history: History,
+    polling: bool,
+}
+
+/// The last cleanup that went to the trash, kept for `UNDO_WINDOW` so it can
+/// be put back.
+#[derive(Debug, Clone)]
+struct UndoDelete {
+    entries: Vec<TrashedEntry>,
+    items: Vec<ScannedItem>,
+    expires: Instant,
+}
+
+impl UndoDelete {
+    fn size(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+}
+
+/// A report being written in the background.
+#[derive(Debug, Clone)]
+struct ReportExport {
+    total: usize,
+    progress: Arc<ExportProgress>,
+}
+
+#[derive(Debug, Clone)]
+struct SizeCheck {
+    total: usize,
+    results: Vec<(PathBuf, u64, Option<u64>)>,
+    cancel: Arc<AtomicBool>,
+    running: bool,
+}
+
+impl SizeCheck {
+    fn flagged(&self) -> Vec<&PathBuf> {
+        self.results
+            .iter()
+            .filter(|(_, reported, measured)| SizeDrift::classify(*reported, *measured).flagged())
+            .map(|(path, _, _)| path)
+            .collect()
+    }
 }
 
+const SCOPE_HISTORY_LIMIT: usize = 5;
+const MAX_SCAN_SITES: usize = 3;
+const SCAN_SITE_CHARS: usize = 48;
+const RECOMMENDED_CONFIDENCE: f32 = 0.85;
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DELETION_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const UNDO_WINDOW: Duration = Duration::from_secs(30);
+const EXPLORER_TOP_N: usize = 25;
+const LIST_PAGE_SIZE: usize = 200;
+const EXPLORER_MAX_DEPTH: usize = 8;
+const CONFIDENCE_OVERRIDE_STEP: f32 = 0.05;
+const OUTDATED_AFTER_MINUTES: i64 = 30;
+const FRESHNESS_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
 impl SweeperApp {
-    pub fn new(cx: &mut Context<Self>) -> Self {
-        let config = Config::load();
-        let scanner = Arc::new(Scanner::new(config.clone()));
+    pub fn new(
+        scan_scope: Option<Vec<PathBuf>>,
+        launched: Instant,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let config = Config::default();
+        let scanner = Arc::new(Self::build_scanner(&config, scan_scope.as_ref()));
+
+        cx.spawn(async move |this, cx| {
+            let (config, profiles, modified, history) = cx
+                .background_executor()
+                .spawn(async {
+                    (
+                        Config::load(),
+                        Profile::list_saved(),
+                        Config::disk_modified(),
+                        History::load(),
+                    )
+                })
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                app.history = history;
+                let config = config.unwrap_or_else(|e| {
+                    app.notice = Some(format!("Could not load settings, using defaults: {}", e));
+                    Config::default()
+                });
+                app.finish_loading(config, profiles, modified, cx);
+            });
+        })
+        .detach();
 
         cx.spawn(async move |this, cx| loop {
             cx.background_executor()
-                .timer(Duration::from_millis(100))
+                .timer(FRESHNESS_CHECK_INTERVAL)
                 .await;
 
-            let should_continue = this
-                .update(cx, |app, cx| {
-                    if app.scanner.is_scanning() {
-                        app.is_scanning = true;
-                        let files_scanned = app.scanner.files_scanned();
-                        app.scan_progress = format!(
-                            "{} paths checked • {}",
-                            files_scanned,
-                            app.scanner.current_path()
-                        );
-                        cx.notify();
-                    } else if app.is_scanning {
-                        app.is_scanning = false;
-                        app.items = app.scanner.get_items();
-                        app.stats = app.scanner.get_stats();
-                        app.scan_progress = String::new();
-                        cx.notify();
-                    }
+            let Ok(items) = this.update(cx, |app, _| {
+                (!app.is_scanning && app.viewing_bundle.is_none())
+                    .then(|| app.store.items().to_vec())
+            }) else {
+                break;
+            };
+            let Some(items) = items else {
+                continue;
+            };
+
+            let outdated = cx
+                .background_executor()
+                .spawn(async move {
+                    scanner::outdated_paths(
+                        &items,
+                        chrono::Duration::minutes(OUTDATED_AFTER_MINUTES),
+                    )
                 })
-                .is_ok();
+                .await;
+
+            let updated = this.update(cx, |app, cx| {
+                if !app.is_scanning && app.viewing_bundle.is_none() && app.outdated != outdated {
+                    app.outdated = outdated;
+                    cx.notify();
+                }
+            });
+            if updated.is_err() {
+                break;
+            }
+        })
+        .detach();
+
+        cx.on_app_quit(|app, _cx| {
+            app.shutdown();
+            async {}
+        })
+        .detach();
+
+        let confirm_input = cx.new(InputState::new);
+        cx.observe(&confirm_input, |_, _, cx| cx.notify()).detach();
+        let target_input = cx.new(InputState::new);
+        cx.observe(&target_input, |_, _, cx| cx.notify()).detach();
+        let search_input = cx.new(InputState::new);
+        cx.observe(&search_input, |app: &mut Self, input, cx| {
+            let query = input.read(cx).content().trim().to_lowercase();
+            if query != app.search_query {
+                app.search_query = query;
+                app.list_limit = LIST_PAGE_SIZE;
+            }
+            cx.notify();
+        })
+        .detach();
+
+        cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(CONFIG_POLL_INTERVAL).await;
+
+            let modified = Config::disk_modified();
+            let Ok(changed) = this.update(cx, |app, _| {
+                app.config_loaded && modified != app.config_modified
+            }) else {
+                break;
+            };
+            if !changed {
+                continue;
+            }
 
-            if !should_continue {
+            let disk = cx
+                .background_executor()
+                .spawn(async { Config::read_from_disk() })
+                .await;
+            let updated = this.update(cx, |app, cx| {
+                app.config_changed_on_disk(modified, disk, cx);
+            });
+            if updated.is_err() {
                 break;
             }
         })
         .detach();
 
-        Self {
-            config,
+        let mut app = Self {
+            config: config.clone(),
             scanner,
-            items: Vec::new(),
+            scope_history: scan_scope.iter().cloned().collect(),
+            scan_scope,
+            store: ItemStore::default(),
             selected: HashSet::new(),
-            stats: ScanStats::default(),
             active_tab: FilterTab::All,
+            sort_order: SortOrder::Size,
+            sort_descending: true,
+            target_input,
+            search_input,
+            search_query: String::new(),
+            list_limit: LIST_PAGE_SIZE,
             is_scanning: false,
-            scan_progress: String::new(),
+            scan_progress: ScanProgress::default(),
             show_delete_dialog: false,
             is_deleting: false,
-        }
+            notice: None,
+            profiles: Vec::new(),
+            active_profile: None,
+            show_settings: false,
+            pending_profile: None,
+            outdated: HashSet::new(),
+            pending_exclusions: None,
+            verifying: HashSet::new(),
+            confirm_sampled: false,
+            available_update: None,
+            pinned: Vec::new(),
+            review_later: HashSet::new(),
+            hovered_item: None,
+            show_explorer: false,
+            is_exploring: false,
+            explorer: None,
+            explorer_trail: Vec::new(),
+            explorer_delete: None,
+            confirm_input,
+            threshold_category: None,
+            threshold_input: cx.new(InputState::new),
+            scan_path_input: cx.new(InputState::new),
+            max_depth_input: cx.new(InputState::new),
+            large_file_input: cx.new(InputState::new),
+            download_days_input: cx.new(InputState::new),
+            settings_error: None,
+            dismissed_items: Vec::new(),
+            pending_clean: None,
+            viewing_bundle: None,
+            trash_groups: Vec::new(),
+            coalesce_trash: false,
+            oversized: Vec::new(),
+            unchecked_oversized: 0,
+            compact: None,
+            main_window: None,
+            scan_started: None,
+            trash_fallback: Vec::new(),
+            data_usage: Vec::new(),
+            confirm_clear_quarantine: false,
+            ruleset_notes: None,
+            trash_log: None,
+            trash_selected: HashSet::new(),
+            trash_busy: false,
+            custom_rules: RuleSet::default(),
+            scan_sites: Vec::new(),
+            scan_cursor: ItemCursor::default(),
+            scan_cancelled: false,
+            scan_delta: None,
+            last_deleted: None,
+            size_check: None,
+            report_export: None,
+            crash_report: crash::pending_report(),
+            volumes: VolumeTable::default(),
+            volume_filter: None,
+            confirm_quit: false,
+            mode_guard: ModeGuard::default(),
+            quit_when_idle: false,
+            quitting: false,
+            control: None,
+            instance: InstanceListener::start(&Config::config_dir())
+                .map_err(|e| log::info!("Not listening for scan requests: {}", e))
+                .ok(),
+            synced_config: config,
+            config_modified: None,
+            config_conflict: None,
+            pending_disk_config: None,
+            local_store: None,
+            config_loaded: false,
+            launched: Some(launched),
+            focus_handle: cx.focus_handle(),
+            selection_history: SelectionHistory::default(),
+            keepers_cache: None,
+            history: History::default(),
+            polling: false,
+        };
+        app.resume_polling(cx);
+        app.listen_for_scan_requests(cx);
+        app
     }
 
-    fn start_scan(&mut self, cx: &mut Context<Self>) {
-        self.is_scanning = true;
-        self.items.clear();
-        self.selected.clear();
-        self.scan_progress = "Starting scan...".to_string();
+    fn finish_loading(
+        &mut self,
+        config: Config,
+        profiles: Vec<(PathBuf, Profile)>,
+        modified: Option<SystemTime>,
+        cx: &mut Context<Self>,
+    ) {
+        if config.audit_mode {
+            audit::enable();
+        }
+        self.scanner = Arc::new(Self::build_scanner(&config, self.scan_scope.as_ref()));
+        self.synced_config = config.clone();
+        self.config = config;
+        self.config_modified = modified;
+        self.profiles = profiles;
+        self.config_loaded = true;
+        self.sync_delete_mode(cx);
+        self.ruleset_notes = categories::summarize_ruleset_changes(
+            &categories::ruleset_changes_since(self.config.seen_ruleset_version),
+        );
+        if self.ruleset_notes.is_none() {
+            self.acknowledge_ruleset();
+        }
+
+        if self.config.check_for_updates {
+            self.check_for_updates(cx);
+        }
+
+        if self.config.control_server {
+            self.start_control_server(cx);
+        }
+
+        if self.scan_scope.is_some() {
+            self.start_scan(cx);
+        }
+
         cx.notify();
+    }
+
+    pub fn request_close(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.is_deleting {
+            self.confirm_quit = true;
+            cx.notify();
+            return false;
+        }
+        self.quit(cx);
+        false
+    }
 
+    /// Stops running jobs and quits once they finish or `shutdown::TIMEOUT`
+    /// passes. The wait runs in the background so the window keeps drawing.
+    pub(super) fn quit(&mut self, cx: &mut Context<Self>) {
+        if self.quitting {
+            return;
+        }
+        self.quitting = true;
         let scanner = self.scanner.clone();
-        cx.spawn(async move |this, cx| {
-            let (items, stats) = cx
+        cx.spawn(async move |_, cx| {
+            let unfinished = cx
                 .background_executor()
-                .spawn(async move {
-                    let items = scanner.scan();
-                    let stats = scanner.get_stats();
-                    (items, stats)
-                })
+                .spawn(async move { shutdown::run(&[scanner.as_ref()], shutdown::TIMEOUT) })
                 .await;
-
-            let _ = this.update(cx, |app, cx| {
-                app.items = items;
-                app.stats = stats;
-                app.is_scanning = false;
-                app.scan_progress = String::new();
-                cx.notify();
-            });
+            if !unfinished.is_empty() {
+                log::warn!("Quitting while still running: {}", unfinished.join(", "));
+            }
+            let _ = cx.update(|cx| cx.quit());
         })
         .detach();
     }
 
-    fn filtered_items(&self) -> Vec<&ScannedItem> {
-        let mut items: Vec<&ScannedItem> = match self.active_tab {
-            FilterTab::All => self.items.iter().collect(),
-            FilterTab::Largest => {
-                let mut sorted: Vec<_> = self.items.iter().collect();
-                sorted.sort_by(|a, b| b.size.cmp(&a.size));
-                sorted
-            }
-            FilterTab::DevArtifacts => self
-                .items
-                .iter()
-                .filter(|i| i.category == FileCategory::DevArtifact)
-                .collect(),
-            FilterTab::Caches => self
-                .items
-                .iter()
-                .filter(|i| {
-                    matches!(
-                        i.category,
-                        FileCategory::PackageCache
-                            | FileCategory::IdeCache
-                            | FileCategory::BrowserCache
-                            | FileCategory::SystemCache
-                    )
-                })
-                .collect(),
-            FilterTab::TempLogs => self
-                .items
-                .iter()
-                .filter(|i| matches!(i.category, FileCategory::TempFile | FileCategory::LogFile))
-                .collect(),
-            FilterTab::Downloads => self
-                .items
-                .iter()
-                .filter(|i| i.category == FileCategory::OldDownload)
-                .collect(),
-        };
+    fn shutdown(&mut self) {
+        self.pending_clean = None;
+        if let Some(check) = &self.size_check {
+            check.cancel.store(true, Ordering::SeqCst);
+        }
+        if let Some(export) = &self.report_export {
+            export.progress.cancel();
+        }
+        self.scanner.stop();
+        if self.is_deleting {
+            log::warn!("Quitting during a cleanup; its items may be missing from the Trash log");
+        }
+        if let Err(e) = self.history.save() {
+            log::warn!("Failed to save history: {}", e);
+        }
+    }
 
-        if self.active_tab != FilterTab::Largest {
-            items.sort_by(|a, b| b.size.cmp(&a.size));
+    fn dismiss_crash_report(&mut self) -> Option<PathBuf> {
+        let report = self.crash_report.take()?;
+        match crash::dismiss_report() {
+            Ok(archived) => Some(archived),
+            Err(e) => {
+                log::warn!("Failed to archive crash report: {}", e);
+                Some(report)
+            }
         }
+    }
 
-        items
+    fn acknowledge_ruleset(&mut self) {
+        self.ruleset_notes = None;
+        if self.config.seen_ruleset_version == categories::RULESET_VERSION {
+            return;
+        }
+        self.config.seen_ruleset_version = categories::RULESET_VERSION;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
     }
 
-    fn toggle_selection(&mut self, path: PathBuf, cx: &mut Context<Self>) {
-        if self.selected.contains(&path) {
-            self.selected.remove(&path);
-        } else {
-            self.selected.insert(path);
+    fn build_scanner(config: &Config, scan_scope: Option<&Vec<PathBuf>>) -> Scanner {
+        match scan_scope {
+            Some(paths) => Scanner::with_scope(config.clone(), paths.clone()),
+            None => Scanner::new(config.clone()),
         }
-        cx.notify();
     }
 
-    fn select_all(&mut self, cx: &mut Context<Self>) {
-        let paths: Vec<PathBuf> = self
-            .filtered_items()
-            .iter()
-            .map(|i| i.path.clone())
-            .collect();
-        for path in paths {
-            self.selected.insert(path);
+    fn is_busy(&self) -> bool {
+        self.is_scanning || self.is_deleting || self.pending_clean.is_some()
+    }
+
+    fn enter_compact(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.compact.is_some() || !self.is_busy() {
+            return;
+        }
+        let app = cx.entity();
+        let options = compact::window_options(cx);
+        match cx.open_window(options, |window, cx| {
+            cx.new(|cx| CompactView::new(app, window, cx))
+        }) {
+            Ok(handle) => {
+                self.compact = Some(handle);
+                self.main_window = Some(window.window_handle());
+                window.minimize_window();
+            }
+            Err(e) => {
+                self.notice = Some(format!("Could not open the compact window: {}", e));
+                cx.notify();
+            }
         }
-        cx.notify();
     }
 
-    fn deselect_all(&mut self, cx: &mut Context<Self>) {
-        self.selected.clear();
-        cx.notify();
+    pub fn exit_compact(&mut self, cx: &mut Context<Self>) {
+        let Some(handle) = self.compact.take() else {
+            return;
+        };
+        let main = self.main_window.take();
+        cx.defer(move |cx| {
+            let _ = handle.update(cx, |_, window, _| window.remove_window());
+            if let Some(main) = main {
+                let _ = main.update(cx, |_, window, _| window.activate_window());
+            }
+        });
     }
 
-    fn selected_size(&self) -> u64 {
-        self.items
-            .iter()
-            .filter(|i| self.selected.contains(&i.path))
-            .map(|i| i.size)
-            .sum()
+    pub fn compact_closed(&mut self, cx: &mut Context<Self>) {
+        self.compact = None;
+        if let Some(main) = self.main_window.take() {
+            cx.defer(move |cx| {
+                let _ = main.update(cx, |_, window, _| window.activate_window());
+            });
+        }
     }
 
-    fn delete_selected(&mut self, cx: &mut Context<Self>) {
-        self.is_deleting = true;
-        self.show_delete_dialog = false;
-        cx.notify();
+    pub fn compact_status(&self) -> CompactStatus {
+        if self.is_scanning {
+            let (fraction, eta) = match (&self.scan_scope, self.scan_started) {
+                (None, Some(started)) => compact::estimate(
+                    self.scanner.files_scanned(),
+                    self.history.last_scan_paths,
+                    started.elapsed(),
+                ),
+                _ => (None, None),
+            };
+            return CompactStatus {
+                title: "Scanning…".to_string(),
+                detail: format!(
+                    "{} · {} paths checked",
+                    self.scan_progress.label(),
+                    self.scan_progress.files_scanned
+                ),
+                fraction: fraction.or(Some(self.scan_progress.fraction())),
+                eta,
+                can_cancel: true,
+            };
+        }
+        if let Some(pending) = &self.pending_clean {
+            return CompactStatus {
+                title: format!("Deleting {} items", pending.len()),
+                detail: format!(
+                    "Starting in {}s",
+                    pending.remaining(Instant::now()).as_secs() + 1
+                ),
+                fraction: None,
+                eta: None,
+                can_cancel: true,
+            };
+        }
+        if self.is_deleting {
+            return CompactStatus {
+                title: format!("Deleting {} items", self.selected.len()),
+                detail: bytesize::ByteSize(self.selected_size()).to_string(),
+                fraction: None,
+                eta: None,
+                can_cancel: false,
+            };
+        }
+        CompactStatus {
+            title: "Done".to_string(),
+            detail: self.notice.clone().unwrap_or_default(),
+            fraction: Some(1.0),
+            eta: None,
+            can_cancel: false,
+        }
+    }
 
-        let paths_to_delete: Vec<PathBuf> = self
-            .items
-            .iter()
-            .filter(|i| self.selected.contains(&i.path))
-            .map(|i| i.path.clone())
-            .collect();
+    pub fn cancel_operation(&mut self, cx: &mut Context<Self>) {
+        if self.pending_clean.is_some() {
+            self.cancel_pending_clean(cx);
+        } else if self.is_scanning {
+            self.scan_started = None;
+            self.scan_cancelled = true;
+            self.scanner.stop();
+            self.notice = Some("Scan stopped, showing what was found so far".to_string());
+            cx.notify();
+        }
+    }
+
+    fn reveal_item(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.launch(path, "reveal", platform::reveal, cx);
+    }
 
-        let use_trash = self.config.use_trash;
-        let dry_run = self.config.dry_run;
+    fn open_item(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.launch(path, "open", platform::open, cx);
+    }
 
+    fn launch(
+        &mut self,
+        path: PathBuf,
+        verb: &'static str,
+        action: fn(&std::path::Path) -> std::io::Result<()>,
+        cx: &mut Context<Self>,
+    ) {
         cx.spawn(async move |this, cx| {
-            let deleted_paths: HashSet<PathBuf> = cx
+            let target = path.clone();
+            let launched = cx
                 .background_executor()
-                .spawn(async move {
-                    let mut deleted = HashSet::new();
-                    for path in paths_to_delete {
-                        let success = if use_trash {
-                            trash::delete(&path).is_ok()
-                        } else if !dry_run {
-                            if path.is_dir() {
-                                std::fs::remove_dir_all(&path).is_ok()
-                            } else {
-                                std::fs::remove_file(&path).is_ok()
-                            }
-                        } else {
-                            true
-                        };
-                        if success {
-                            deleted.insert(path);
-                        }
-                    }
-                    deleted
-                })
+                .spawn(async move { action(&target) })
                 .await;
-
-            let _ = this.update(cx, |app, cx| {
-                app.items.retain(|i| !deleted_paths.contains(&i.path));
-                app.selected.clear();
-                app.is_deleting = false;
-
-                // Recalculate stats based on remaining items
-                app.stats.total_items = app.items.len() as u64;
-                app.stats.total_size = app.items.iter().map(|i| i.size).sum();
-                app.stats.items_by_category.clear();
-                app.stats.size_by_category.clear();
-                for item in &app.items {
-                    *app.stats.items_by_category.entry(item.category).or_insert(0) += 1;
-                    *app.stats.size_by_category.entry(item.category).or_insert(0) += item.size;
-                }
-
-                cx.notify();
-            });
+            if let Err(e) = launched {
+                let _ = this.update(cx, |app, cx| {
+                    app.notice = Some(format!("Could not {} {}: {}", verb, path.display(), e));
+                    cx.notify();
+                });
+            }
         })
         .detach();
     }
 
     fn get_size_distribution(&self) -> Vec<f64> {
-        if self.items.is_empty() {
+        if self.store.is_empty() {
             return vec![0.0; 10];
         }
 
-        let mut sorted_sizes: Vec<u64> = self.items.iter().map(|i| i.size).collect();
+        let mut sorted_sizes: Vec<u64> = self.store.items().iter().map(|i| i.size).collect();
         sorted_sizes.sort();
 
         let chunk_size = (sorted_sizes.len() / 10).max(1);
@@ -324,7 +822,9 @@ impl SweeperApp {
     fn render_header(&self, cx: &Context<Self>) -> impl IntoElement {
         let theme = use_theme();
         let is_scanning = self.is_scanning;
-        let scan_progress = self.scan_progress.clone();
+        let scan_label = self.scan_progress.label();
+        let scan_fraction = self.scan_progress.fraction();
+        let scan_breakdown = self.scan_progress.breakdown();
 
         div()
             .flex()
@@ -371,14 +871,69 @@ impl SweeperApp {
                                             .text_color(theme.tokens.foreground)
                                             .child("Sweeper"),
                                     )
-                                    .child(Badge::new(format!("v{}", env!("CARGO_PKG_VERSION"))).variant(BadgeVariant::Secondary)),
+                                    .child(
+                                        Badge::new(format!("v{}", env!("CARGO_PKG_VERSION")))
+                                            .variant(BadgeVariant::Secondary),
+                                    )
+                                    .when(audit::is_enabled(), |title| {
+                                        title.child(
+                                            Badge::new("Audit mode").variant(BadgeVariant::Warning),
+                                        )
+                                    })
+                                    .when_some(
+                                        self.mode_guard.badge(),
+                                        |title, (label, variant)| {
+                                            title.child(Badge::new(label).variant(variant))
+                                        },
+                                    )
+                                    .when(self.history.freed_bytes > 0, |title| {
+                                        title.child(
+                                            Badge::new(format!(
+                                                "You've freed {} with Sweeper",
+                                                bytesize::ByteSize(self.history.freed_bytes)
+                                            ))
+                                            .variant(BadgeVariant::Outline),
+                                        )
+                                    }),
                             )
-                            .child(
-                                div()
+                            .child(match &self.scan_scope {
+                                Some(scope) => div()
+                                    .flex()
+                                    .items_center()
+                                    .gap(px(8.0))
+                                    .child(
+                                        div()
+                                            .text_size(px(14.0))
+                                            .text_color(theme.tokens.muted_foreground)
+                                            .max_w(px(420.0))
+                                            .overflow_hidden()
+                                            .text_ellipsis()
+                                            .child(format!(
+                                                "Scope: {}",
+                                                scope
+                                                    .iter()
+                                                    .map(|p| p.to_string_lossy())
+                                                    .collect::<Vec<_>>()
+                                                    .join(", ")
+                                            )),
+                                    )
+                                    .child(
+                                        Button::new("clear_scope", "Full scan")
+                                            .variant(ButtonVariant::Ghost)
+                                            .size(ButtonSize::Sm)
+                                            .icon("x")
+                                            .disabled(is_scanning)
+                                            .on_click(cx.listener(|this, _, _window, cx| {
+                                                this.set_scan_scope(None, cx);
+                                            })),
+                                    )
+                                    .into_any_element(),
+                                None => div()
                                     .text_size(px(14.0))
                                     .text_color(theme.tokens.muted_foreground)
-                                    .child("Clean up your disk space with confidence"),
-                            ),
+                                    .child("Clean up your disk space with confidence")
+                                    .into_any_element(),
+                            }),
                     ),
             )
             .child(if is_scanning {
@@ -399,86 +954,401 @@ impl SweeperApp {
                                     .font_weight(FontWeight::MEDIUM)
                                     .text_color(theme.tokens.foreground)
                                     .child("Scanning..."),
+                            )
+                            .child(
+                                Button::new("cancel_scan", "Cancel")
+                                    .variant(ButtonVariant::Outline)
+                                    .size(ButtonSize::Sm)
+                                    .icon("x")
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.cancel_operation(cx);
+                                    })),
+                            )
+                            .child(self.render_compact_button("compact_scan", cx)),
+                    )
+                    .child(
+                        div()
+                            .h(px(4.0))
+                            .w(px(350.0))
+                            .rounded_full()
+                            .bg(theme.tokens.muted)
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(relative(scan_fraction))
+                                    .rounded_full()
+                                    .bg(theme.tokens.primary),
                             ),
                     )
                     .child(
                         div()
+                            .id("scan_phase")
                             .text_size(px(12.0))
                             .text_color(theme.tokens.muted_foreground)
                             .max_w(px(350.0))
                             .overflow_hidden()
                             .text_ellipsis()
-                            .child(scan_progress),
+                            .child(scan_label)
+                            .tooltip(move |_window, cx| {
+                                InfoTooltip::view(scan_breakdown.clone(), cx)
+                            }),
                     )
+                    .children(self.scan_sites.iter().take(MAX_SCAN_SITES).map(|path| {
+                        div()
+                            .text_size(px(11.0))
+                            .text_color(theme.tokens.muted_foreground)
+                            .max_w(px(350.0))
+                            .overflow_hidden()
+                            .child(components::elide_path_left(path, SCAN_SITE_CHARS))
+                    }))
                     .into_any_element()
             } else {
-                Button::new("scan", "Rescan Disk")
-                    .icon("refresh-cw")
-                    .variant(ButtonVariant::Default)
-                    .on_click(cx.listener(|this, _, _window, cx| {
-                        this.start_scan(cx);
-                    }))
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(
+                        Button::new("explorer", "Space Explorer")
+                            .icon("hard-drive")
+                            .variant(if self.show_explorer {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.show_explorer = !this.show_explorer;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new("trash_log", "Trash")
+                            .icon("trash-2")
+                            .variant(if self.trash_log.is_some() {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.open_trash_log(cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("settings", "Settings")
+                            .icon("sliders-horizontal")
+                            .variant(if self.show_settings {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.show_settings = !this.show_settings;
+                                if this.show_settings {
+                                    this.profiles = Profile::list_saved();
+                                    this.custom_rules = RuleSet::load();
+                                    this.reset_scan_settings(window, cx);
+                                    this.refresh_data_usage(cx);
+                                }
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new("scan", "Rescan Disk")
+                            .icon("refresh-cw")
+                            .variant(ButtonVariant::Default)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.start_scan(cx);
+                            })),
+                    )
                     .into_any_element()
             })
     }
 
-    fn render_stats(&self) -> impl IntoElement {
+    fn render_notice(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
         let theme = use_theme();
-        let size_distribution = self.get_size_distribution();
-        let selection_percent = if self.items.is_empty() {
-            0.0
-        } else {
-            (self.selected.len() as f32 / self.items.len() as f32) * 100.0
-        };
+        let notice = self.notice.clone()?;
 
-        div()
-            .flex()
-            .gap(px(16.0))
-            .px(px(24.0))
-            .py(px(16.0))
-            .child(
-                Card::new()
-                    .content(
-                        div()
-                            .flex()
-                            .flex_col()
-                            .gap(px(8.0))
-                            .child(
-                                div()
-                                    .flex()
-                                    .items_center()
-                                    .justify_between()
-                                    .child(
-                                        div()
-                                            .text_size(px(13.0))
-                                            .font_weight(FontWeight::MEDIUM)
-                                            .text_color(theme.tokens.muted_foreground)
-                                            .child("Total Found"),
-                                    )
-                                    .child(
-                                        Icon::new("hard-drive")
-                                            .size(px(18.0))
-                                            .color(theme.tokens.primary),
-                                    ),
-                            )
-                            .child(
-                                div()
-                                    .text_size(px(32.0))
-                                    .font_weight(FontWeight::BOLD)
-                                    .text_color(theme.tokens.foreground)
-                                    .child(self.stats.total_size_formatted()),
-                            )
-                            .child(
-                                div()
-                                    .flex()
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.muted.opacity(0.4))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Icon::new("triangle-alert")
+                                .size(px(14.0))
+                                .color(theme.tokens.muted_foreground),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.foreground)
+                                .child(notice),
+                        ),
+                )
+                .child(
+                    IconButton::new("x")
+                        .variant(ButtonVariant::Ghost)
+                        .size(px(24.0))
+                        .icon_size(px(14.0))
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.notice = None;
+                            cx.notify();
+                        })),
+                ),
+        )
+    }
+
+    fn render_volume_banner(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let pressure = self.volume_pressure();
+        if pressure.is_empty() && self.volume_filter.is_none() {
+            return None;
+        }
+
+        let rows = pressure.into_iter().enumerate().map(|(index, full)| {
+            let mount = full.volume.mount_point.clone();
+            let filtered = self.volume_filter.as_ref() == Some(&mount);
+            let select_mount = mount.clone();
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .gap(px(12.0))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Icon::new("hard-drive")
+                                .size(px(14.0))
+                                .color(theme.tokens.destructive),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(full.summary()),
+                        ),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .gap(px(8.0))
+                        .child(
+                            Button::new(
+                                ("volume_filter", index),
+                                if filtered {
+                                    "Show All"
+                                } else {
+                                    "Show Only These"
+                                },
+                            )
+                            .variant(ButtonVariant::Outline)
+                            .size(ButtonSize::Sm)
+                            .icon("filter")
+                            .on_click(cx.listener(
+                                move |this, _, _window, cx| {
+                                    let mount = (!filtered).then(|| mount.clone());
+                                    this.set_volume_filter(mount, cx);
+                                },
+                            )),
+                        )
+                        .child(
+                            Button::new(("volume_recommended", index), "Select Recommended")
+                                .variant(ButtonVariant::Default)
+                                .size(ButtonSize::Sm)
+                                .icon("sparkles")
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.select_recommended_on(select_mount.clone(), cx);
+                                })),
+                        ),
+                )
+        });
+        let rows: Vec<_> = rows.collect();
+        let orphan_filter = rows
+            .is_empty()
+            .then(|| self.volume_filter.clone())
+            .flatten();
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(6.0))
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.destructive.opacity(0.1))
+                .border_b_1()
+                .border_color(theme.tokens.destructive.opacity(0.4))
+                .children(rows)
+                .children(orphan_filter.map(|mount| {
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.foreground)
+                                .child(format!("Showing only items on {}", mount.display())),
+                        )
+                        .child(
+                            Button::new("volume_filter_clear", "Show All")
+                                .variant(ButtonVariant::Outline)
+                                .size(ButtonSize::Sm)
+                                .icon("x")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.set_volume_filter(None, cx);
+                                })),
+                        )
+                })),
+        )
+    }
+
+    fn render_audit_watermark(&self) -> impl IntoElement {
+        let theme = use_theme();
+
+        div()
+            .absolute()
+            .bottom(px(16.0))
+            .right(px(24.0))
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .child(
+                Icon::new("eye")
+                    .size(px(20.0))
+                    .color(theme.tokens.muted_foreground.opacity(0.4)),
+            )
+            .child(
+                div()
+                    .text_size(px(20.0))
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.tokens.muted_foreground.opacity(0.4))
+                    .child("Audit mode"),
+            )
+    }
+
+    fn render_stats(&self) -> impl IntoElement {
+        let theme = use_theme();
+        let size_distribution = self.get_size_distribution();
+        let (selected_size, overlapping) = self.selected_totals();
+        let selection_percent = if self.store.is_empty() {
+            0.0
+        } else {
+            (self.selected.len() as f32 / self.store.len() as f32) * 100.0
+        };
+
+        div()
+            .flex()
+            .gap(px(16.0))
+            .px(px(24.0))
+            .py(px(16.0))
+            .child(
+                Card::new()
+                    .content(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(8.0))
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .justify_between()
+                                    .child(
+                                        div()
+                                            .text_size(px(13.0))
+                                            .font_weight(FontWeight::MEDIUM)
+                                            .text_color(theme.tokens.muted_foreground)
+                                            .child("Total Found"),
+                                    )
+                                    .child(
+                                        Icon::new("hard-drive")
+                                            .size(px(18.0))
+                                            .color(theme.tokens.primary),
+                                    ),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_baseline()
+                                    .gap(px(8.0))
+                                    .child(
+                                        div()
+                                            .text_size(px(32.0))
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(theme.tokens.foreground)
+                                            .child(self.store.stats().total_size_formatted()),
+                                    )
+                                    .when_some(self.scan_delta, |total, delta| {
+                                        let label = [delta.size_label(), delta.items_label()]
+                                            .into_iter()
+                                            .flatten()
+                                            .collect::<Vec<_>>()
+                                            .join(", ");
+                                        let color = if delta.is_decrease() {
+                                            theme.tokens.primary
+                                        } else {
+                                            theme.tokens.destructive
+                                        };
+                                        let previous = delta
+                                            .previous_at
+                                            .with_timezone(&chrono::Local)
+                                            .format("%b %-d, %Y %H:%M")
+                                            .to_string();
+                                        total.when(!label.is_empty(), |total| {
+                                            total.child(
+                                                div()
+                                                    .id("scan_delta")
+                                                    .text_size(px(12.0))
+                                                    .font_weight(FontWeight::MEDIUM)
+                                                    .text_color(color)
+                                                    .tooltip(move |_window, cx| {
+                                                        InfoTooltip::view(
+                                                            vec![format!(
+                                                                "Previous scan: {}",
+                                                                previous
+                                                            )],
+                                                            cx,
+                                                        )
+                                                    })
+                                                    .child(label),
+                                            )
+                                        })
+                                    }),
+                            )
+                            .child(
+                                div()
+                                    .flex()
                                     .items_center()
                                     .gap(px(8.0))
                                     .child(
                                         div()
                                             .text_size(px(13.0))
                                             .text_color(theme.tokens.muted_foreground)
-                                            .child(format!("{} items", self.stats.total_items)),
+                                            .child(format!(
+                                                "{} items",
+                                                self.store.stats().total_items
+                                            )),
                                     )
+                                    .when(self.scan_cancelled, |row| {
+                                        row.child(
+                                            Badge::new("Scan cancelled — partial results")
+                                                .variant(BadgeVariant::Warning),
+                                        )
+                                    })
                                     .child(Sparkline::area(size_distribution.clone()).size(
                                         adabraka_ui::components::sparkline::SparklineSize::Sm,
                                     )),
@@ -521,8 +1391,19 @@ impl SweeperApp {
                                     } else {
                                         theme.tokens.primary
                                     })
-                                    .child(bytesize::ByteSize(self.selected_size()).to_string()),
+                                    .child(bytesize::ByteSize(selected_size).to_string()),
                             )
+                            .when(overlapping > 0, |card| {
+                                card.child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child(format!(
+                                            "{} overlapping selections merged",
+                                            overlapping
+                                        )),
+                                )
+                            })
                             .child(
                                 div()
                                     .flex()
@@ -579,7 +1460,7 @@ impl SweeperApp {
                                     .text_color(theme.tokens.foreground)
                                     .child(format!(
                                         "{:.1}s",
-                                        self.stats.duration_ms as f64 / 1000.0
+                                        self.store.stats().duration_ms as f64 / 1000.0
                                     )),
                             )
                             .child(
@@ -587,9 +1468,13 @@ impl SweeperApp {
                                     .text_size(px(13.0))
                                     .text_color(theme.tokens.muted_foreground)
                                     .child(
-                                        if self.stats.duration_ms > 0 && self.stats.total_items > 0
+                                        if self.store.stats().duration_ms > 0
+                                            && self.store.stats().total_items > 0
                                         {
-                                            format!("{} items found", self.stats.total_items)
+                                            format!(
+                                                "{} items found",
+                                                self.store.stats().total_items
+                                            )
                                         } else {
                                             "Ready to scan".to_string()
                                         },
@@ -601,69 +1486,6 @@ impl SweeperApp {
             )
     }
 
-    fn render_tabs(&self, cx: &Context<Self>) -> impl IntoElement {
-        let theme = use_theme();
-        let tabs = [
-            FilterTab::All,
-            FilterTab::Largest,
-            FilterTab::DevArtifacts,
-            FilterTab::Caches,
-            FilterTab::TempLogs,
-            FilterTab::Downloads,
-        ];
-
-        div()
-            .flex()
-            .items_center()
-            .gap(px(4.0))
-            .px(px(24.0))
-            .py(px(12.0))
-            .children(tabs.into_iter().map(|tab| {
-                let is_active = self.active_tab == tab;
-                let bg = if is_active {
-                    theme.tokens.primary
-                } else {
-                    gpui::transparent_black()
-                };
-                let fg = if is_active {
-                    theme.tokens.primary_foreground
-                } else {
-                    theme.tokens.muted_foreground
-                };
-
-                div()
-                    .id(SharedString::from(tab.label()))
-                    .flex()
-                    .items_center()
-                    .gap(px(6.0))
-                    .px(px(14.0))
-                    .py(px(8.0))
-                    .bg(bg)
-                    .text_color(fg)
-                    .text_size(px(13.0))
-                    .font_weight(if is_active {
-                        FontWeight::SEMIBOLD
-                    } else {
-                        FontWeight::NORMAL
-                    })
-                    .rounded(px(8.0))
-                    .cursor_pointer()
-                    .hover(|s| {
-                        s.bg(if is_active {
-                            theme.tokens.primary
-                        } else {
-                            theme.tokens.muted.opacity(0.5)
-                        })
-                    })
-                    .on_click(cx.listener(move |this, _, _window, cx| {
-                        this.active_tab = tab;
-                        cx.notify();
-                    }))
-                    .child(Icon::new(tab.icon()).size(px(14.0)).color(fg))
-                    .child(tab.label())
-            }))
-    }
-
     fn render_actions(&self, cx: &Context<Self>) -> impl IntoElement {
         let theme = use_theme();
         let has_selection = !self.selected.is_empty();
@@ -690,7 +1512,11 @@ impl SweeperApp {
                             .text_size(px(14.0))
                             .font_weight(FontWeight::MEDIUM)
                             .text_color(theme.tokens.foreground)
-                            .child(format!("{} files in view", filtered_count)),
+                            .child(if self.search_query.is_empty() {
+                                format!("{} files in view", filtered_count)
+                            } else {
+                                format!("{} matching files in view", filtered_count)
+                            }),
                     )
                     .child(
                         Button::new("select_all", "Select All")
@@ -702,6 +1528,72 @@ impl SweeperApp {
                                 this.select_all(cx);
                             })),
                     )
+                    .child(self.render_selection_presets(is_deleting || filtered_count == 0, cx))
+                    .when(self.active_tab == FilterTab::Review, |actions| {
+                        actions
+                            .child(
+                                Button::new("promote_review", "Select for cleanup")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .icon("square-check")
+                                    .disabled(is_deleting || filtered_count == 0)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.promote_review(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("dismiss_review", "Dismiss all")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .icon("eye-off")
+                                    .disabled(filtered_count == 0 || self.viewing_bundle.is_some())
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.dismiss_review(cx);
+                                    })),
+                            )
+                    })
+                    .child(
+                        Button::new(
+                            "select_recommended",
+                            match self.selection_target(cx) {
+                                Some(target) => {
+                                    format!("Select ~{} of the safest", bytesize::ByteSize(target))
+                                }
+                                None => "Select Recommended".to_string(),
+                            },
+                        )
+                        .variant(ButtonVariant::Ghost)
+                        .size(ButtonSize::Sm)
+                        .icon("gauge")
+                        .disabled(is_deleting || filtered_count == 0)
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.select_recommended(cx);
+                        })),
+                    )
+                    .child(
+                        div().w(px(110.0)).child(
+                            Input::new(&self.target_input).placeholder("Target, e.g. 20 GB"),
+                        ),
+                    )
+                    .child(
+                        Button::new("suggested_order", "Suggested order")
+                            .variant(if self.sort_order == SortOrder::Suggested {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .size(ButtonSize::Sm)
+                            .icon("arrow-down-wide-narrow")
+                            .disabled(self.active_tab == FilterTab::Largest)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                let order = if this.sort_order == SortOrder::Suggested {
+                                    SortOrder::Size
+                                } else {
+                                    SortOrder::Suggested
+                                };
+                                this.set_sort(order, cx);
+                            })),
+                    )
                     .child(
                         Button::new("deselect", "Clear")
                             .variant(ButtonVariant::Ghost)
@@ -711,7 +1603,82 @@ impl SweeperApp {
                             .on_click(cx.listener(|this, _, _window, cx| {
                                 this.deselect_all(cx);
                             })),
-                    ),
+                    )
+                    .child(match &self.report_export {
+                        Some(export) => Button::new(
+                            "stop_export",
+                            format!(
+                                "Exporting {}/{}… Stop",
+                                export.progress.written(),
+                                export.total
+                            ),
+                        )
+                        .variant(ButtonVariant::Ghost)
+                        .size(ButtonSize::Sm)
+                        .icon("x")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            if let Some(export) = &this.report_export {
+                                export.progress.cancel();
+                            }
+                            cx.notify();
+                        })),
+                        None => Button::new("export_report", "Export report…")
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .icon("download")
+                            .disabled(self.store.is_empty() || self.is_scanning)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.export_report(cx);
+                            })),
+                    })
+                    .when(!self.store.warnings().is_empty(), |actions| {
+                        let warnings = self.store.warnings().join("\n");
+                        actions.child(
+                            Button::new(
+                                "scan_warnings",
+                                format!("{} locations skipped", self.store.warnings().len()),
+                            )
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .icon("shield-alert")
+                            .tooltip(warnings.clone())
+                            .on_click(cx.listener(
+                                move |this, _, _window, cx| {
+                                    this.notice = Some(warnings.replace('\n', "; "));
+                                    cx.notify();
+                                },
+                            )),
+                        )
+                    })
+                    .when(self.store.suppressed() > 0, |actions| {
+                        actions.child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!(
+                                    "{} below size thresholds",
+                                    self.store.suppressed()
+                                )),
+                        )
+                    })
+                    .when(!self.outdated.is_empty(), |actions| {
+                        actions.child(
+                            Button::new(
+                                "refresh_outdated",
+                                format!("Refresh {} outdated", self.outdated.len()),
+                            )
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .icon("refresh-cw")
+                            .disabled(is_deleting || self.is_scanning)
+                            .on_click(cx.listener(
+                                |this, _, _window, cx| {
+                                    let paths = this.outdated.iter().cloned().collect();
+                                    this.rescan_paths(paths, cx);
+                                },
+                            )),
+                        )
+                    }),
             )
             .child(if is_deleting {
                 div()
@@ -730,341 +1697,271 @@ impl SweeperApp {
                             .text_color(theme.tokens.destructive_foreground)
                             .child("Deleting..."),
                     )
+                    .child(self.render_compact_button("compact_delete", cx))
+                    .into_any_element()
+            } else if audit::is_enabled() {
+                Button::new("export_findings", "Export findings")
+                    .variant(ButtonVariant::Default)
+                    .icon("download")
+                    .disabled(self.store.is_empty())
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.export_bundle(cx);
+                    }))
                     .into_any_element()
             } else {
                 Button::new("delete", format!("Delete {} items", selected_count))
                     .variant(ButtonVariant::Destructive)
                     .icon("trash-2")
-                    .disabled(!has_selection)
-                    .on_click(cx.listener(|this, _, _window, cx| {
-                        this.show_delete_dialog = true;
-                        cx.notify();
+                    .disabled(!has_selection || self.viewing_bundle.is_some())
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.open_delete_dialog(window, cx);
                     }))
                     .into_any_element()
             })
     }
 
-    fn render_list(&self, cx: &Context<Self>) -> impl IntoElement {
+    fn render_compact_button(&self, id: &'static str, cx: &Context<Self>) -> impl IntoElement {
+        Button::new(id, "Compact")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::Sm)
+            .icon("minimize-2")
+            .disabled(self.compact.is_some())
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.enter_compact(window, cx);
+            }))
+    }
+
+    fn render_crash_report_dialog(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let path = self.crash_report.clone()?;
         let theme = use_theme();
-        let items = self.filtered_items();
 
-        if items.is_empty() && !self.is_scanning {
-            return div()
-                .flex_1()
+        Some(
+            div()
+                .absolute()
+                .inset_0()
                 .flex()
                 .items_center()
                 .justify_center()
+                .bg(gpui::black().opacity(0.6))
                 .child(
                     div()
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
                         .flex()
                         .flex_col()
-                        .items_center()
-                        .gap(px(20.0))
-                        .child(
-                            div()
-                                .size(px(80.0))
-                                .rounded_full()
-                                .bg(theme.tokens.muted.opacity(0.5))
-                                .flex()
-                                .items_center()
-                                .justify_center()
-                                .child(
-                                    Icon::new("inbox")
-                                        .size(px(40.0))
-                                        .color(theme.tokens.muted_foreground),
-                                ),
-                        )
+                        .gap(px(16.0))
                         .child(
                             div()
                                 .text_size(px(20.0))
-                                .font_weight(FontWeight::SEMIBOLD)
+                                .font_weight(FontWeight::BOLD)
                                 .text_color(theme.tokens.foreground)
-                                .child("No items found"),
+                                .child("Sweeper quit unexpectedly"),
                         )
                         .child(
                             div()
-                                .text_size(px(14.0))
+                                .text_size(px(13.0))
                                 .text_color(theme.tokens.muted_foreground)
-                                .child("Click 'Rescan Disk' to search for cleanup candidates"),
+                                .child(format!(
+                                    "A crash report was saved to {}. Attaching it to a bug report helps us fix the problem. File paths are left out unless you turned them on in Settings.",
+                                    path.display()
+                                )),
                         )
                         .child(
-                            Button::new("scan_empty", "Start Scanning")
-                                .variant(ButtonVariant::Default)
-                                .icon("search")
-                                .on_click(cx.listener(|this, _, _window, cx| {
-                                    this.start_scan(cx);
-                                })),
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("dismiss_crash_report", "Dismiss")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            let _ = this.dismiss_crash_report();
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("show_crash_report", "Show Report")
+                                        .variant(ButtonVariant::Default)
+                                        .icon("external-link")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            if let Some(report) = this.dismiss_crash_report() {
+                                                cx.reveal_path(&report);
+                                            }
+                                            cx.notify();
+                                        })),
+                                ),
                         ),
-                )
-                .into_any_element();
+                ),
+        )
+    }
+
+    fn render_quit_dialog(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.confirm_quit {
+            return None;
         }
+        let theme = use_theme();
 
-        scrollable_vertical(
+        Some(
             div()
+                .absolute()
+                .inset_0()
                 .flex()
-                .flex_col()
-                .gap(px(4.0))
-                .px(px(24.0))
-                .py(px(16.0))
-                .children(items.into_iter().take(200).map(|item| {
-                    let path = item.path.clone();
-                    let is_selected = self.selected.contains(&path);
-                    let name: String = item.name.clone().into();
-                    let category = item.category;
-                    let category_name = category.name();
-                    let path_str = item.path.to_string_lossy().to_string();
-                    let size_str = item.size_formatted();
-                    let confidence = item.confidence_percent();
-                    let is_stale = item.is_stale;
-
-                    let bg = if is_selected {
-                        theme.tokens.primary.opacity(0.1)
-                    } else {
-                        theme.tokens.card
-                    };
-
-                    let border_color = if is_selected {
-                        theme.tokens.primary.opacity(0.5)
-                    } else {
-                        theme.tokens.border
-                    };
-
-                    let header = if is_stale {
-                        div()
-                            .flex()
-                            .items_center()
-                            .gap(px(10.0))
-                            .child(
-                                div()
-                                    .text_size(px(14.0))
-                                    .font_weight(FontWeight::SEMIBOLD)
-                                    .text_color(theme.tokens.foreground)
-                                    .child(name),
-                            )
-                            .child(Badge::new(category_name).variant(BadgeVariant::Secondary))
-                            .child(Badge::new("Stale").variant(BadgeVariant::Destructive))
-                    } else {
-                        div()
-                            .flex()
-                            .items_center()
-                            .gap(px(10.0))
-                            .child(
-                                div()
-                                    .text_size(px(14.0))
-                                    .font_weight(FontWeight::SEMIBOLD)
-                                    .text_color(theme.tokens.foreground)
-                                    .child(name),
-                            )
-                            .child(Badge::new(category_name).variant(BadgeVariant::Secondary))
-                    };
-
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
                     div()
-                        .id(SharedString::from(path_str.clone()))
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
                         .flex()
-                        .items_center()
+                        .flex_col()
                         .gap(px(16.0))
-                        .px(px(16.0))
-                        .py(px(14.0))
-                        .bg(bg)
-                        .border_1()
-                        .border_color(border_color)
-                        .rounded(px(8.0))
-                        .hover(|s| s.bg(theme.tokens.muted.opacity(0.5)).shadow_md())
-                        .cursor_pointer()
-                        .on_click(cx.listener(move |this, _, _window, cx| {
-                            this.toggle_selection(path.clone(), cx);
-                        }))
-                        .child(
-                            Checkbox::new(SharedString::from(format!("check-{}", path_str)))
-                                .checked(is_selected),
-                        )
                         .child(
                             div()
-                                .size(px(40.0))
-                                .rounded(px(8.0))
-                                .bg(theme.tokens.muted.opacity(0.5))
-                                .flex()
-                                .items_center()
-                                .justify_center()
-                                .child(
-                                    Icon::new(category_icon(category))
-                                        .size(px(20.0))
-                                        .color(theme.tokens.primary),
-                                ),
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child("Cleanup still running"),
                         )
                         .child(
                             div()
-                                .flex_1()
-                                .flex()
-                                .flex_col()
-                                .gap(px(6.0))
-                                .overflow_hidden()
-                                .child(header)
-                                .child(
-                                    div()
-                                        .text_size(px(12.0))
-                                        .text_color(theme.tokens.muted_foreground)
-                                        .overflow_hidden()
-                                        .text_ellipsis()
-                                        .child(path_str),
-                                ),
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Quitting now could leave items half removed and missing from the Trash log. Sweeper can quit as soon as the cleanup finishes."),
                         )
                         .child(
                             div()
                                 .flex()
-                                .flex_col()
-                                .items_end()
-                                .gap(px(6.0))
+                                .justify_end()
+                                .gap(px(12.0))
                                 .child(
-                                    div()
-                                        .text_size(px(16.0))
-                                        .font_weight(FontWeight::BOLD)
-                                        .text_color(theme.tokens.primary)
-                                        .child(size_str),
+                                    Button::new("keep_open", "Keep Open")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_quit = false;
+                                            cx.notify();
+                                        })),
                                 )
                                 .child(
-                                    div()
-                                        .flex()
-                                        .items_center()
-                                        .gap(px(4.0))
-                                        .child(
-                                            Icon::new("gauge")
-                                                .size(px(12.0))
-                                                .color(theme.tokens.muted_foreground),
-                                        )
-                                        .child(
-                                            div()
-                                                .text_size(px(12.0))
-                                                .text_color(theme.tokens.muted_foreground)
-                                                .child(format!("{}%", confidence)),
-                                        ),
+                                    Button::new("quit_when_done", "Quit When Done")
+                                        .variant(ButtonVariant::Default)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_quit = false;
+                                            this.quit_when_idle = true;
+                                            this.resume_polling(cx);
+                                            this.notice = Some(
+                                                "Sweeper will quit when the cleanup finishes"
+                                                    .to_string(),
+                                            );
+                                            cx.notify();
+                                        })),
                                 ),
-                        )
-                })),
+                        ),
+                ),
         )
-        .into_any_element()
     }
 
-    fn render_delete_dialog(&self, cx: &Context<Self>) -> impl IntoElement {
+    fn render_ruleset_dialog(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let notes = self.ruleset_notes.clone()?;
         let theme = use_theme();
-        let selected_count = self.selected.len();
-        let selected_size = bytesize::ByteSize(self.selected_size()).to_string();
 
-        div()
-            .absolute()
-            .inset_0()
-            .flex()
-            .items_center()
-            .justify_center()
-            .bg(gpui::black().opacity(0.6))
-            .child(
-                div()
-                    .w(px(440.0))
-                    .p(px(28.0))
-                    .bg(theme.tokens.card)
-                    .border_1()
-                    .border_color(theme.tokens.border)
-                    .rounded(px(16.0))
-                    .shadow_xl()
-                    .flex()
-                    .flex_col()
-                    .gap(px(20.0))
-                    .child(
-                        div()
-                            .flex()
-                            .items_center()
-                            .gap(px(16.0))
-                            .child(
-                                div()
-                                    .size(px(48.0))
-                                    .rounded_full()
-                                    .bg(theme.tokens.destructive.opacity(0.1))
-                                    .flex()
-                                    .items_center()
-                                    .justify_center()
-                                    .child(
-                                        Icon::new("triangle-alert")
-                                            .size(px(24.0))
-                                            .color(theme.tokens.destructive),
-                                    ),
-                            )
-                            .child(
-                                div()
-                                    .flex()
-                                    .flex_col()
-                                    .gap(px(4.0))
-                                    .child(
-                                        div()
-                                            .text_size(px(20.0))
-                                            .font_weight(FontWeight::BOLD)
-                                            .text_color(theme.tokens.foreground)
-                                            .child("Confirm Deletion"),
-                                    )
-                                    .child(
-                                        div()
-                                            .text_size(px(14.0))
-                                            .text_color(theme.tokens.muted_foreground)
-                                            .child("This action will move files to trash"),
-                                    ),
-                            ),
-                    )
-                    .child(
-                        div()
-                            .p(px(16.0))
-                            .bg(theme.tokens.muted.opacity(0.3))
-                            .rounded(px(8.0))
-                            .flex()
-                            .items_center()
-                            .justify_between()
-                            .child(
-                                div()
-                                    .flex()
-                                    .items_center()
-                                    .gap(px(12.0))
-                                    .child(
-                                        Icon::new("files")
-                                            .size(px(20.0))
-                                            .color(theme.tokens.muted_foreground),
-                                    )
-                                    .child(
-                                        div()
-                                            .text_size(px(14.0))
-                                            .text_color(theme.tokens.foreground)
-                                            .child(format!("{} items selected", selected_count)),
-                                    ),
-                            )
-                            .child(Badge::new(selected_size).variant(BadgeVariant::Outline)),
-                    )
-                    .child(
-                        div()
-                            .flex()
-                            .justify_end()
-                            .gap(px(12.0))
-                            .child(
-                                Button::new("cancel", "Cancel")
-                                    .variant(ButtonVariant::Ghost)
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child("Detection rules updated"),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(
+                                    "This version of Sweeper flags some things it didn't before. Nothing is deleted without your review.",
+                                ),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.foreground)
+                                .child(notes),
+                        )
+                        .child(
+                            div().flex().justify_end().child(
+                                Button::new("acknowledge_ruleset", "Got It")
+                                    .variant(ButtonVariant::Default)
                                     .on_click(cx.listener(|this, _, _window, cx| {
-                                        this.show_delete_dialog = false;
+                                        this.acknowledge_ruleset();
                                         cx.notify();
                                     })),
-                            )
-                            .child(
-                                Button::new("confirm_delete", "Delete Files")
-                                    .variant(ButtonVariant::Destructive)
-                                    .icon("trash-2")
-                                    .on_click(cx.listener(|this, _, _window, cx| {
-                                        this.delete_selected(cx);
-                                    })),
                             ),
-                    ),
-            )
+                        ),
+                ),
+        )
+    }
+}
+
+impl Focusable for SweeperApp {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
     }
 }
 
 impl Render for SweeperApp {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let theme = use_theme();
+        if let Some(launched) = self.launched.take() {
+            log::info!("First frame after {:?}", launched.elapsed());
+        }
+        if !self.config_loaded {
+            return div()
+                .size_full()
+                .bg(theme.tokens.background)
+                .flex()
+                .flex_col()
+                .items_center()
+                .justify_center()
+                .gap(px(12.0))
+                .child(Spinner::new())
+                .child(
+                    div()
+                        .text_size(px(14.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Loading settings…"),
+                );
+        }
         let show_dialog = self.show_delete_dialog;
+        let is_dragging = cx.has_active_drag();
 
         let mut container = div()
             .size_full()
@@ -1072,16 +1969,103 @@ impl Render for SweeperApp {
             .flex()
             .flex_col()
             .relative()
+            .key_context("Sweeper")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::undo_selection))
+            .on_action(cx.listener(Self::redo_selection))
+            .on_action(cx.listener(Self::review_hovered))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, _window, cx| {
+                this.handle_dropped_paths(paths.paths(), cx);
+            }))
             .child(self.render_header(cx))
-            .child(self.render_stats())
-            .child(self.render_tabs(cx))
-            .child(self.render_actions(cx))
-            .child(div().flex_1().overflow_hidden().child(self.render_list(cx)));
+            .children(self.render_profiles(cx))
+            .children(self.render_exclusions(cx))
+            .children(self.render_preferences(cx))
+            .children(self.render_scan_settings(cx))
+            .children(self.render_confidence_overrides(cx))
+            .children(self.render_size_thresholds(cx))
+            .children(self.render_dismissed(cx))
+            .children(self.render_ignored(cx))
+            .children(self.render_bundles(cx))
+            .children(self.render_custom_rules(cx))
+            .children(self.render_data_usage(cx))
+            .children(self.render_update_banner(cx))
+            .children(self.render_notice(cx))
+            .children(self.render_config_conflict(cx))
+            .children(self.render_pending_clean(cx))
+            .children(self.render_undo_bar(cx))
+            .children(self.render_bundle_banner(cx))
+            .children(self.render_volume_banner(cx))
+            .children(self.render_scope_history(cx))
+            .when(self.show_explorer, |container| {
+                container.child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .child(self.render_explorer(cx)),
+                )
+            })
+            .when(!self.show_explorer, |container| {
+                container
+                    .child(self.render_stats())
+                    .child(self.render_tabs(cx))
+                    .child(self.render_search(cx))
+                    .child(self.render_actions(cx))
+                    .child(div().flex_1().overflow_hidden().child(self.render_list(cx)))
+            });
 
         if show_dialog {
             container = container.child(self.render_delete_dialog(cx));
         }
 
+        if let Some(dialog) = self.render_profile_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_exclusions_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_explorer_delete_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_trash_fallback_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_clear_quarantine_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_ruleset_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_trash_log_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_crash_report_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_quit_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if let Some(dialog) = self.render_permanent_mode_dialog(cx) {
+            container = container.child(dialog);
+        }
+
+        if audit::is_enabled() {
+            container = container.child(self.render_audit_watermark());
+        }
+
+        if is_dragging {
+            container = container.child(self.render_drop_overlay());
+        }
+
         container
     }
 }
@@ -0,0 +1,958 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn delete_selected(&mut self, cx: &mut Context<Self>) {
+        if self.viewing_bundle.is_some() {
+            return;
+        }
+        self.is_deleting = true;
+        self.resume_polling(cx);
+        self.show_delete_dialog = false;
+        self.cancel_size_check();
+        cx.notify();
+
+        let job = match self.confirmed_clean_job() {
+            Ok(job) => job,
+            Err(e) => {
+                self.is_deleting = false;
+                self.notice = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        self.oversized.clear();
+
+        if self.config.deletion_grace_period {
+            self.pending_clean = Some(PendingClean::new(job, DELETION_GRACE_PERIOD));
+        } else {
+            self.run_clean_job(job, cx);
+        }
+    }
+
+    pub(super) fn selected_clean_job(&self) -> error::Result<CleanJob> {
+        let job = CleanJob::new(
+            self.selected_items()
+                .flat_map(CleanTarget::for_item)
+                .collect(),
+            self.config.use_trash,
+            self.config.dry_run,
+        )?;
+        Ok(job
+            .with_quarantine(self.config.quarantine_dir.clone())
+            .with_manifest_dir(self.config.manifest_dir.clone())
+            .with_permanent(
+                self.oversized
+                    .iter()
+                    .map(|(path, _, _)| path.clone())
+                    .collect(),
+            ))
+    }
+
+    /// The job the confirm dialog would run, with the folder coalescing the
+    /// user picked already applied.
+    pub(super) fn confirmed_clean_job(&self) -> error::Result<CleanJob> {
+        let mut job = self.selected_clean_job()?;
+        if self.coalesce_trash && job.use_trash {
+            job.coalesce(COALESCE_MIN_FILES);
+        }
+        Ok(job)
+    }
+
+    pub(super) fn export_manifest(&mut self, cx: &mut Context<Self>) {
+        let job = match self.confirmed_clean_job() {
+            Ok(job) => job,
+            Err(e) => {
+                self.notice = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        let manifest = DeletionManifest::from_job(&job);
+        let directory = self
+            .config
+            .manifest_dir
+            .clone()
+            .or_else(dirs::document_dir)
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let path = cx.prompt_for_new_path(&directory, Some(&manifest.file_name()));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+
+            let saved = manifest.save(&path);
+            let _ = this.update(cx, |app, cx| {
+                app.notice = Some(match saved {
+                    Ok(()) => format!(
+                        "Exported the deletion manifest for {} items to {}",
+                        manifest.entries.len(),
+                        path.display()
+                    ),
+                    Err(e) => format!("Could not export the deletion manifest: {}", e),
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn open_delete_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.mode_guard.needs_acknowledgment() {
+            cx.notify();
+            return;
+        }
+        self.check_trash_capacity();
+        self.confirm_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.trash_groups = if self.config.use_trash {
+            self.selected_clean_job()
+                .map(|job| job.coalescible(COALESCE_MIN_FILES))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        self.coalesce_trash = !self.trash_groups.is_empty();
+        self.show_delete_dialog = true;
+        self.cancel_size_check();
+        self.confirm_sampled = false;
+        cx.notify();
+    }
+
+    pub(super) fn check_trash_capacity(&mut self) {
+        self.oversized.clear();
+        self.unchecked_oversized = 0;
+        if !self.config.use_trash || self.config.dry_run {
+            return;
+        }
+        let limit = self.config.large_item_limit;
+        let large: Vec<(PathBuf, u64)> = self
+            .selected_items()
+            .filter(|item| item.size > limit)
+            .map(|item| (item.path.clone(), item.size))
+            .collect();
+        if large.is_empty() {
+            return;
+        }
+
+        self.volumes = VolumeTable::current();
+        let trash = activity::trash_dir().ok();
+        for (path, size) in large {
+            match self.volumes.capacity_for(&path, size, trash.as_deref()) {
+                Capacity::Fits => {}
+                Capacity::TooSmall { available } => self.oversized.push((path, size, available)),
+                Capacity::Unknown => self.unchecked_oversized += 1,
+            }
+        }
+    }
+
+    pub(super) fn cancel_pending_clean(&mut self, cx: &mut Context<Self>) {
+        if self.pending_clean.take().is_some() {
+            self.is_deleting = false;
+            self.notice = Some("Deletion cancelled, nothing was removed".to_string());
+        }
+        cx.notify();
+    }
+
+    pub(super) fn run_clean_job(&mut self, job: CleanJob, cx: &mut Context<Self>) {
+        self.last_deleted = None;
+        cx.spawn(async move |this, cx| {
+            let (mut report, trashed) = cx
+                .background_executor()
+                .spawn(async move {
+                    let report = activity::run_logged(job);
+                    let trashed = report
+                        .batch
+                        .filter(|_| activity::UNDO_RESTORE)
+                        .map(|batch| ActivityLog::load().batch(batch))
+                        .unwrap_or_default();
+                    (report, trashed)
+                })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                let removed = app.store.retain(|i| {
+                    !i.removed_by(&report.deleted)
+                        && !i.path.parent().is_some_and(|p| report.deleted.contains(p))
+                        && !report.skipped.contains(&i.path)
+                });
+                if !trashed.is_empty() {
+                    app.last_deleted = Some(UndoDelete {
+                        entries: trashed,
+                        items: removed,
+                        expires: Instant::now() + UNDO_WINDOW,
+                    });
+                }
+                if let Some(tree) = app.explorer.as_mut() {
+                    for path in &report.deleted {
+                        tree.remove(path);
+                    }
+                }
+                app.selected.clear();
+                app.is_deleting = false;
+                app.trash_fallback = std::mem::take(&mut report.untrashable);
+                if !report.blocked.is_empty() {
+                    app.notice = Some(format!(
+                        "Kept {} protected items: {}",
+                        report.blocked.len(),
+                        report.blocked.join("; ")
+                    ));
+                } else if !report.unchecked.is_empty() {
+                    app.notice = Some(format!(
+                        "Could not check git status for: {}",
+                        report.unchecked.join("; ")
+                    ));
+                } else if !report.failed.is_empty() {
+                    app.notice = Some(format!(
+                        "Could not delete {} items: {}",
+                        report.failed.len(),
+                        report
+                            .failed
+                            .iter()
+                            .map(|e| e.to_string())
+                            .collect::<Vec<_>>()
+                            .join("; ")
+                    ));
+                } else if !report.deleted.is_empty() {
+                    app.notice = Some(format!(
+                        "Removed {} in {:.1}s",
+                        bytesize::ByteSize(report.freed),
+                        report.elapsed.as_secs_f64()
+                    ));
+                }
+                app.record_freed(&report);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn undo_last_delete(&mut self, cx: &mut Context<Self>) {
+        let Some(undo) = self.last_deleted.take() else {
+            return;
+        };
+        self.trash_busy = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let (items, restored, failed) = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut log = ActivityLog::load();
+                    let mut restored = 0;
+                    let mut failed = Vec::new();
+                    for entry in &undo.entries {
+                        match activity::restore(entry, None) {
+                            Ok(_) => {
+                                log.set_status(entry.batch, &entry.path, TrashStatus::Restored);
+                                restored += entry.size;
+                            }
+                            Err(e) => failed.push(format!("{}: {}", entry.path.display(), e)),
+                        }
+                    }
+                    if let Err(e) = log.save() {
+                        log::warn!("Failed to save activity log: {}", e);
+                    }
+                    let exists = |path: &std::path::Path| std::fs::symlink_metadata(path).is_ok();
+                    let items: Vec<ScannedItem> = undo
+                        .items
+                        .into_iter()
+                        .filter(|item| {
+                            if item.members.is_empty() {
+                                exists(&item.path)
+                            } else {
+                                item.members.iter().all(|(path, _)| exists(path))
+                            }
+                        })
+                        .collect();
+                    (items, restored, failed)
+                })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                app.trash_busy = false;
+                let count = items.len();
+                for item in items {
+                    app.store.push(item);
+                }
+                app.history.freed_bytes = app.history.freed_bytes.saturating_sub(restored);
+                if let Err(e) = app.history.save() {
+                    log::warn!("Failed to save history: {}", e);
+                }
+                app.notice = Some(if failed.is_empty() {
+                    format!(
+                        "Restored {} items ({})",
+                        count,
+                        bytesize::ByteSize(restored)
+                    )
+                } else {
+                    format!(
+                        "Restored {} items; could not recover {}: {}",
+                        count,
+                        failed.len(),
+                        failed.join("; ")
+                    )
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn delete_untrashable(&mut self, cx: &mut Context<Self>) {
+        let targets = std::mem::take(&mut self.trash_fallback);
+        if targets.is_empty() {
+            return;
+        }
+        self.is_deleting = true;
+        self.resume_polling(cx);
+        cx.notify();
+
+        match CleanJob::new(targets, false, self.config.dry_run) {
+            Ok(job) => {
+                self.run_clean_job(job.with_manifest_dir(self.config.manifest_dir.clone()), cx)
+            }
+            Err(e) => {
+                self.is_deleting = false;
+                self.notice = Some(e.to_string());
+                cx.notify();
+            }
+        }
+    }
+
+    pub(super) fn record_freed(&mut self, report: &CleanReport) {
+        self.volumes = VolumeTable::current();
+        if self.config.dry_run || report.freed == 0 {
+            return;
+        }
+        let milestone = self.history.record(report.freed);
+        if let Err(e) = self.history.save() {
+            log::warn!("Failed to save history: {}", e);
+        }
+        if let Some(milestone) = milestone {
+            self.notice = Some(format!(
+                "Milestone: you've freed {} with Sweeper",
+                bytesize::ByteSize(milestone)
+            ));
+        }
+    }
+
+    pub(super) fn render_delete_dialog(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let selected_count = self.selected.len();
+        let selected_size = bytesize::ByteSize(self.selected_size()).to_string();
+        let regen = RegenSummary::of(self.selected_items());
+        let sampled_count = self
+            .selected_items()
+            .filter(|item| item.sampled_hash)
+            .count();
+        let oversized_size =
+            bytesize::ByteSize(self.oversized.iter().map(|(_, size, _)| size).sum::<u64>())
+                .to_string();
+        let needs_confirmation = (sampled_count > 0 && !self.confirm_sampled)
+            || (!self.oversized.is_empty()
+                && self.confirm_input.read(cx).content().trim() != oversized_size);
+        let last_copies = self.fully_selected_duplicates();
+        let selected_files: u64 = self
+            .selected_items()
+            .map(|item| item.file_count.unwrap_or(1))
+            .sum();
+        let duration_hint = cleaner::duration_hint(cleaner::estimated_duration(selected_files));
+
+        div()
+            .absolute()
+            .inset_0()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(gpui::black().opacity(0.6))
+            .child(
+                div()
+                    .w(px(440.0))
+                    .p(px(28.0))
+                    .bg(theme.tokens.card)
+                    .border_1()
+                    .border_color(theme.tokens.border)
+                    .rounded(px(16.0))
+                    .shadow_xl()
+                    .flex()
+                    .flex_col()
+                    .gap(px(20.0))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(16.0))
+                            .child(
+                                div()
+                                    .size(px(48.0))
+                                    .rounded_full()
+                                    .bg(theme.tokens.destructive.opacity(0.1))
+                                    .flex()
+                                    .items_center()
+                                    .justify_center()
+                                    .child(
+                                        Icon::new("triangle-alert")
+                                            .size(px(24.0))
+                                            .color(theme.tokens.destructive),
+                                    ),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap(px(4.0))
+                                    .child(
+                                        div()
+                                            .text_size(px(20.0))
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(theme.tokens.foreground)
+                                            .child("Confirm Deletion"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_size(px(14.0))
+                                            .text_color(theme.tokens.muted_foreground)
+                                            .child("This action will move files to trash"),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .p(px(16.0))
+                            .bg(theme.tokens.muted.opacity(0.3))
+                            .rounded(px(8.0))
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap(px(12.0))
+                                    .child(
+                                        Icon::new("files")
+                                            .size(px(20.0))
+                                            .color(theme.tokens.muted_foreground),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_size(px(14.0))
+                                            .text_color(theme.tokens.foreground)
+                                            .child(format!("{} items selected", selected_count)),
+                                    ),
+                            )
+                            .child(Badge::new(selected_size).variant(BadgeVariant::Outline)),
+                    )
+                    .when_some(duration_hint, |dialog, hint| {
+                        dialog.child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .child(
+                                    Icon::new("clock")
+                                        .size(px(14.0))
+                                        .color(theme.tokens.muted_foreground),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child(format!(
+                                            "{} ({} to remove)",
+                                            hint,
+                                            components::file_count_label(selected_files)
+                                        )),
+                                ),
+                        )
+                    })
+                    .when(regen.items > 0, |dialog| {
+                        dialog.child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .child(
+                                    Icon::new("refresh-cw")
+                                        .size(px(14.0))
+                                        .color(theme.tokens.muted_foreground),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child(format!(
+                                            "{} of this ({} items) will likely regenerate within a day",
+                                            bytesize::ByteSize(regen.size),
+                                            regen.items
+                                        )),
+                                ),
+                        )
+                    })
+                    .when(sampled_count > 0, |dialog| {
+                        dialog.child(
+                            div()
+                                .id("confirm_sampled")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.confirm_sampled = !this.confirm_sampled;
+                                    cx.notify();
+                                }))
+                                .child(
+                                    Checkbox::new("confirm_sampled_check")
+                                        .checked(self.confirm_sampled),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.foreground)
+                                        .child(format!(
+                                            "{} items are probable duplicates matched by sampling, not a full comparison. Delete them anyway",
+                                            sampled_count
+                                        )),
+                                ),
+                        )
+                    })
+                    .when(!last_copies.is_empty(), |dialog| {
+                        dialog.child(
+                            div()
+                                .p(px(12.0))
+                                .bg(theme.tokens.destructive.opacity(0.08))
+                                .rounded(px(8.0))
+                                .flex()
+                                .flex_col()
+                                .gap(px(6.0))
+                                .children(last_copies.iter().take(5).map(|(name, count, _)| {
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.foreground)
+                                        .overflow_hidden()
+                                        .text_ellipsis()
+                                        .child(format!(
+                                            "You're deleting all {} copies of {}",
+                                            count, name
+                                        ))
+                                }))
+                                .when(last_copies.len() > 5, |list| {
+                                    list.child(
+                                        div()
+                                            .text_size(px(12.0))
+                                            .text_color(theme.tokens.muted_foreground)
+                                            .child(format!(
+                                                "and {} more duplicate groups",
+                                                last_copies.len() - 5
+                                            )),
+                                    )
+                                })
+                                .child(
+                                    div().flex().justify_end().child(
+                                        Button::new("keep_newest_copies", "Keep Newest Copy")
+                                            .variant(ButtonVariant::Secondary)
+                                            .size(ButtonSize::Sm)
+                                            .icon("shield-check")
+                                            .on_click(cx.listener(|this, _, _window, cx| {
+                                                this.keep_newest_duplicates(cx);
+                                            })),
+                                    ),
+                                ),
+                        )
+                    })
+                    .children(self.render_size_check(cx))
+                    .when(!self.oversized.is_empty(), |dialog| {
+                        dialog.child(
+                            div()
+                                .p(px(12.0))
+                                .bg(theme.tokens.destructive.opacity(0.08))
+                                .rounded(px(8.0))
+                                .flex()
+                                .flex_col()
+                                .gap(px(8.0))
+                                .children(self.oversized.iter().take(3).map(
+                                    |(path, size, available)| {
+                                        div()
+                                            .text_size(px(13.0))
+                                            .text_color(theme.tokens.foreground)
+                                            .child(format!(
+                                                "{} is {}, but the Trash only has {} free, so it will be deleted permanently.",
+                                                path.display(),
+                                                bytesize::ByteSize(*size),
+                                                bytesize::ByteSize(*available)
+                                            ))
+                                    },
+                                ))
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child(format!(
+                                            "Type {} to confirm you want to permanently delete it.",
+                                            oversized_size
+                                        )),
+                                )
+                                .child(
+                                    Input::new(&self.confirm_input)
+                                        .placeholder(oversized_size.clone()),
+                                ),
+                        )
+                    })
+                    .when(self.unchecked_oversized > 0, |dialog| {
+                        dialog.child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!(
+                                    "Couldn't check whether the Trash has room for {} items larger than {}.",
+                                    self.unchecked_oversized,
+                                    bytesize::ByteSize(self.config.large_item_limit)
+                                )),
+                        )
+                    })
+                    .when(!self.trash_groups.is_empty(), |dialog| {
+                        let files: usize = self.trash_groups.iter().map(|(_, count)| count).sum();
+                        dialog.child(
+                            div()
+                                .id("coalesce_trash")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.coalesce_trash = !this.coalesce_trash;
+                                    cx.notify();
+                                }))
+                                .child(
+                                    Checkbox::new("coalesce_trash_check")
+                                        .checked(self.coalesce_trash),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.foreground)
+                                        .child(format!(
+                                            "Trash {} whole folders instead of {} separate files",
+                                            self.trash_groups.len(),
+                                            files
+                                        )),
+                                ),
+                        )
+                    })
+                    .child(
+                        div()
+                            .flex()
+                            .justify_end()
+                            .gap(px(12.0))
+                            .child(
+                                Button::new("cancel", "Cancel")
+                                    .variant(ButtonVariant::Ghost)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.show_delete_dialog = false;
+                                        this.cancel_size_check();
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("export_manifest", "Export manifest…")
+                                    .variant(ButtonVariant::Ghost)
+                                    .icon("file-text")
+                                    .disabled(selected_count == 0)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.export_manifest(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("verify_sizes", "Verify Sizes")
+                                    .variant(ButtonVariant::Ghost)
+                                    .icon("gauge")
+                                    .disabled(
+                                        selected_count == 0
+                                            || self
+                                                .size_check
+                                                .as_ref()
+                                                .is_some_and(|check| check.running),
+                                    )
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.verify_selected_sizes(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("confirm_delete", "Delete Files")
+                                    .variant(ButtonVariant::Destructive)
+                                    .icon("trash-2")
+                                    .disabled(needs_confirmation)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.delete_selected(cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+
+    pub(super) fn render_pending_clean(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let pending = self.pending_clean.as_ref()?;
+        let seconds = pending.remaining(Instant::now()).as_secs_f32().ceil() as u64;
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.destructive.opacity(0.15))
+                .border_b_1()
+                .border_color(theme.tokens.destructive.opacity(0.4))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Icon::new("trash-2")
+                                .size(px(14.0))
+                                .color(theme.tokens.destructive),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!(
+                                    "Deleting {} items in {}…",
+                                    pending.len(),
+                                    seconds.max(1)
+                                )),
+                        ),
+                )
+                .child(
+                    Button::new("cancel_pending_clean", "Cancel")
+                        .variant(ButtonVariant::Outline)
+                        .size(ButtonSize::Sm)
+                        .icon("x")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.cancel_pending_clean(cx);
+                        })),
+                ),
+        )
+    }
+
+    pub(super) fn render_undo_bar(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let undo = self.last_deleted.as_ref()?;
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.primary.opacity(0.1))
+                .border_b_1()
+                .border_color(theme.tokens.primary.opacity(0.3))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Icon::new("trash-2")
+                                .size(px(14.0))
+                                .color(theme.tokens.primary),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!(
+                                    "Deleted {} items ({})",
+                                    undo.items.len(),
+                                    bytesize::ByteSize(undo.size())
+                                )),
+                        ),
+                )
+                .child(
+                    Button::new("undo_delete", "Undo")
+                        .variant(ButtonVariant::Outline)
+                        .size(ButtonSize::Sm)
+                        .icon("rotate-ccw")
+                        .disabled(self.trash_busy || self.is_deleting)
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.undo_last_delete(cx);
+                        })),
+                ),
+        )
+    }
+
+    pub(super) fn render_trash_fallback_dialog(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        if self.trash_fallback.is_empty() {
+            return None;
+        }
+        let size: u64 = self.trash_fallback.iter().map(|target| target.size).sum();
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!(
+                                    "{} items can't be moved to the Trash",
+                                    self.trash_fallback.len()
+                                )),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!(
+                                    "Their drive has no trash or doesn't support it. Delete them permanently ({})? The rest of this cleanup already went to the Trash.",
+                                    bytesize::ByteSize(size)
+                                )),
+                        )
+                        .children(self.trash_fallback.iter().take(5).map(|target| {
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .child(target.path.display().to_string())
+                        }))
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("keep_untrashable", "Keep Them")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.notice = Some(format!(
+                                                "Kept {} items that couldn't be moved to the Trash",
+                                                this.trash_fallback.len()
+                                            ));
+                                            this.trash_fallback.clear();
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("delete_untrashable", "Delete Permanently")
+                                        .variant(ButtonVariant::Destructive)
+                                        .icon("trash-2")
+                                        .disabled(self.is_deleting)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.delete_untrashable(cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_permanent_mode_dialog(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<impl IntoElement> {
+        if !self.mode_guard.needs_acknowledgment() {
+            return None;
+        }
+        let theme = use_theme();
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(10.0))
+                                .child(
+                                    Icon::new("triangle-alert")
+                                        .size(px(20.0))
+                                        .color(theme.tokens.destructive),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(20.0))
+                                        .font_weight(FontWeight::BOLD)
+                                        .text_color(theme.tokens.foreground)
+                                        .child("Permanent delete mode"),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("config.json has use_trash and dry_run turned off, so deleted items skip the Trash and can't be restored. Switch back to the Trash, or confirm that you want to delete permanently this session."),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("switch_to_trash", "Use Trash")
+                                        .variant(ButtonVariant::Default)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.switch_to_trash(cx);
+                                        })),
+                                )
+                                .child(
+                                    Button::new("acknowledge_permanent", "Delete Permanently")
+                                        .variant(ButtonVariant::Destructive)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.mode_guard.acknowledge();
+                                            cx.notify();
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+use crate::control::{self, Call, CleanMode};
+
+impl SweeperApp {
+    pub(super) fn start_control_server(&mut self, cx: &mut Context<Self>) {
+        match ControlServer::start(&Config::config_dir()) {
+            Ok(server) => {
+                self.control = Some(server);
+                self.resume_polling(cx);
+            }
+            Err(e) => {
+                self.notice = Some(format!("Could not start the control socket: {}", e));
+            }
+        }
+    }
+
+    pub(super) fn set_control_server(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.config.control_server = enabled;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        if enabled {
+            self.start_control_server(cx);
+            if let Some(control) = &self.control {
+                self.notice = Some(format!(
+                    "Control socket listening at {}",
+                    control.socket_path().display()
+                ));
+            }
+        } else {
+            self.control = None;
+        }
+        cx.notify();
+    }
+
+    /// Checks the instance socket once a second on its own timer, so an idle
+    /// window can stop the main poll and still pick up scan requests.
+    pub(super) fn listen_for_scan_requests(&mut self, cx: &mut Context<Self>) {
+        if self.instance.is_none() {
+            return;
+        }
+        cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(IDLE_POLL_INTERVAL).await;
+            let Ok(listening) = this.update(cx, |app, cx| {
+                let Some(instance) = &app.instance else {
+                    return false;
+                };
+                let requests: Vec<ScanRequest> = instance.requests.try_iter().collect();
+                for request in requests {
+                    app.open_scan_request(request, cx);
+                }
+                true
+            }) else {
+                break;
+            };
+            if !listening {
+                break;
+            }
+        })
+        .detach();
+    }
+
+    pub fn show_opened(&mut self, request: &ScanRequest) {
+        self.notice = Some(request.toast());
+    }
+
+    /// A later launch asked to scan a folder: bring the window forward and
+    /// scan it here instead of opening a second window.
+    pub(super) fn open_scan_request(&mut self, request: ScanRequest, cx: &mut Context<Self>) {
+        cx.activate(true);
+        if self.is_scanning {
+            self.notice = Some(format!(
+                "{}, but a scan is already running",
+                request.toast()
+            ));
+            cx.notify();
+            return;
+        }
+        self.scan_folders(request.paths.clone(), cx);
+        self.show_opened(&request);
+        cx.notify();
+    }
+
+    pub(super) fn handle_control(&mut self, request: ControlRequest, cx: &mut Context<Self>) {
+        let ControlRequest { call, reply } = request;
+        let result = match call {
+            Call::Status => Ok(serde_json::json!({
+                "scanning": self.is_scanning,
+                "files_scanned": self.scanner.files_scanned(),
+                "current_path": self.scanner.active_paths().first(),
+                "active_paths": self.scanner.active_paths(),
+                "items": self.store.len(),
+                "total_size": self.store.stats().total_size,
+                "size_by_category": self.store.stats().size_by_category,
+                "viewing_bundle": self.viewing_bundle.is_some(),
+            })),
+            Call::Scan { .. } if self.is_scanning => Err("a scan is already running".to_string()),
+            Call::Scan { scope } => {
+                match scope {
+                    Some(scope) => self.scan_folders(scope, cx),
+                    None => {
+                        self.set_scan_scope(None, cx);
+                        self.start_scan(cx);
+                    }
+                }
+                Ok(serde_json::json!({ "started": true }))
+            }
+            Call::Items(filter) => Ok(control::list_items(self.store.items(), &filter)),
+            Call::Clean { .. } if !self.config.allow_remote_clean => {
+                Err("remote cleaning is disabled; set allow_remote_clean in the config".to_string())
+            }
+            Call::Clean { .. }
+                if self.is_scanning || self.is_deleting || self.viewing_bundle.is_some() =>
+            {
+                Err("Sweeper is busy".to_string())
+            }
+            Call::Clean {
+                paths,
+                filter,
+                mode,
+            } => {
+                let downloads = dirs::download_dir();
+                let job = match CleanJob::new(
+                    control::clean_targets(
+                        self.store.items(),
+                        &paths,
+                        filter.as_ref(),
+                        downloads.as_deref(),
+                    ),
+                    mode == CleanMode::Trash,
+                    self.config.dry_run,
+                ) {
+                    Ok(job) => job
+                        .with_quarantine(self.config.quarantine_dir.clone())
+                        .with_manifest_dir(self.config.manifest_dir.clone()),
+                    Err(e) => {
+                        let _ = reply.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                self.is_deleting = true;
+                self.resume_polling(cx);
+                cx.notify();
+
+                cx.spawn(async move |this, cx| {
+                    let report = cx
+                        .background_executor()
+                        .spawn(async move { activity::run_logged(job) })
+                        .await;
+                    let result = serde_json::json!({
+                        "deleted": report.deleted,
+                        "freed": report.freed,
+                        "blocked": report.blocked,
+                        "unchecked": report.unchecked,
+                        "failed": report.failures().collect::<Vec<_>>(),
+                    });
+
+                    let _ = this.update(cx, |app, cx| {
+                        app.store.retain(|item| !item.removed_by(&report.deleted));
+                        app.selected.retain(|path| !report.deleted.contains(path));
+                        app.is_deleting = false;
+                        app.record_freed(&report);
+                        cx.notify();
+                    });
+                    let _ = reply.send(Ok(result));
+                })
+                .detach();
+                return;
+            }
+        };
+        let _ = reply.send(result);
+    }
+}
@@ -0,0 +1,474 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn choose_explorer_root(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Explore".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            if let Some(root) = paths.into_iter().next() {
+                let _ = this.update(cx, |app, cx| app.explore(root, cx));
+            }
+        })
+        .detach();
+    }
+
+    pub(super) fn explore(&mut self, root: PathBuf, cx: &mut Context<Self>) {
+        if self.is_scanning || self.is_exploring {
+            return;
+        }
+        self.is_exploring = true;
+        self.explorer_trail.clear();
+        cx.notify();
+
+        let scanner = self.scanner.clone();
+        let depth = self.config.explorer_depth;
+        cx.spawn(async move |this, cx| {
+            let tree = cx
+                .background_executor()
+                .spawn(async move { scanner.explore(&root, depth) })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                app.explorer = Some(tree);
+                app.is_exploring = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn set_explorer_depth(&mut self, depth: usize, cx: &mut Context<Self>) {
+        self.config.explorer_depth = depth.clamp(1, EXPLORER_MAX_DEPTH);
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        if let Some(root) = self.explorer.as_ref().map(|tree| tree.path.clone()) {
+            self.explore(root, cx);
+        }
+        cx.notify();
+    }
+
+    pub(super) fn request_explorer_delete(
+        &mut self,
+        path: PathBuf,
+        size: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.explorer_delete = Some((path, size));
+        self.confirm_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        cx.notify();
+    }
+
+    pub(super) fn delete_explored(&mut self, cx: &mut Context<Self>) {
+        let Some((path, size)) = self.explorer_delete.take() else {
+            return;
+        };
+        let job = match CleanJob::new(
+            vec![CleanTarget {
+                path: path.clone(),
+                size,
+                check_git: true,
+            }],
+            self.config.use_trash,
+            self.config.dry_run,
+        ) {
+            Ok(job) => job
+                .with_quarantine(self.config.quarantine_dir.clone())
+                .with_manifest_dir(self.config.manifest_dir.clone()),
+            Err(e) => {
+                self.notice = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        self.is_deleting = true;
+        self.resume_polling(cx);
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let report = cx
+                .background_executor()
+                .spawn(async move { activity::run_logged(job) })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                app.is_deleting = false;
+                app.record_freed(&report);
+                if report.deleted.contains(&path) {
+                    if let Some(tree) = app.explorer.as_mut() {
+                        tree.remove(&path);
+                        app.explorer_trail = Vec::new();
+                    }
+                    app.store.retain(|item| !item.path.starts_with(&path));
+                    app.notice = Some(format!(
+                        "Deleted {} ({})",
+                        path.display(),
+                        bytesize::ByteSize(report.freed)
+                    ));
+                } else if !report.untrashable.is_empty() {
+                    app.trash_fallback = report.untrashable;
+                } else {
+                    app.notice = Some(
+                        report
+                            .blocked
+                            .into_iter()
+                            .chain(report.failed.iter().map(|e| e.to_string()))
+                            .next()
+                            .unwrap_or_else(|| format!("Could not delete {}", path.display())),
+                    );
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn render_explorer(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let depth = self.config.explorer_depth;
+        let busy = self.is_exploring || self.is_scanning;
+        let current = self
+            .explorer
+            .as_ref()
+            .and_then(|tree| tree.descend(&self.explorer_trail));
+
+        let toolbar = div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .px(px(24.0))
+            .py(px(12.0))
+            .bg(theme.tokens.muted.opacity(0.3))
+            .border_b_1()
+            .border_color(theme.tokens.border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(12.0))
+                    .child(
+                        Button::new("explore_root", "Choose Folder…")
+                            .variant(ButtonVariant::Default)
+                            .size(ButtonSize::Sm)
+                            .icon("search")
+                            .disabled(busy)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.choose_explorer_root(cx);
+                            })),
+                    )
+                    .child(Badge::new("Not cleanup candidates").variant(BadgeVariant::Outline))
+                    .when(self.is_exploring, |toolbar| {
+                        toolbar.child(Spinner::new()).child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .max_w(px(320.0))
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .child(format!(
+                                    "{} entries • {}",
+                                    self.scanner.files_scanned(),
+                                    self.scanner
+                                        .active_paths()
+                                        .first()
+                                        .map(|path| components::elide_path_left(path, 40))
+                                        .unwrap_or_default()
+                                )),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(6.0))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(theme.tokens.muted_foreground)
+                            .child(format!("Depth {}", depth)),
+                    )
+                    .child(
+                        Button::new("explore_depth_down", "−")
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .disabled(busy || depth <= 1)
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                this.set_explorer_depth(depth - 1, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("explore_depth_up", "+")
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .disabled(busy || depth >= EXPLORER_MAX_DEPTH)
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                this.set_explorer_depth(depth + 1, cx);
+                            })),
+                    ),
+            );
+
+        let Some(current) = current else {
+            return div().size_full().flex().flex_col().child(toolbar).child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_size(px(14.0))
+                    .text_color(theme.tokens.muted_foreground)
+                    .child("Choose a folder to see which directories take up the most space"),
+            );
+        };
+
+        let root = self.explorer.as_ref().map(|tree| tree.path.clone());
+        let breadcrumbs = (0..=self.explorer_trail.len()).filter_map(|level| {
+            let node = self
+                .explorer
+                .as_ref()?
+                .descend(&self.explorer_trail[..level])?;
+            let is_current = level == self.explorer_trail.len();
+            Some(
+                Button::new(SharedString::from(format!("crumb-{}", level)), node.name())
+                    .variant(if is_current {
+                        ButtonVariant::Secondary
+                    } else {
+                        ButtonVariant::Ghost
+                    })
+                    .size(ButtonSize::Sm)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.explorer_trail.truncate(level);
+                        cx.notify();
+                    })),
+            )
+        });
+
+        let max_size = current
+            .children
+            .first()
+            .map(|child| child.size)
+            .unwrap_or(0)
+            .max(1);
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .child(toolbar)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .px(px(24.0))
+                    .py(px(10.0))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(4.0))
+                            .children(breadcrumbs),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(13.0))
+                            .text_color(theme.tokens.muted_foreground)
+                            .child(format!(
+                                "{} total • {} in files directly here",
+                                bytesize::ByteSize(current.size),
+                                bytesize::ByteSize(current.direct_size())
+                            )),
+                    ),
+            )
+            .child(
+                div().flex_1().overflow_hidden().child(scrollable_vertical(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(4.0))
+                        .px(px(24.0))
+                        .pb(px(16.0))
+                        .children(
+                            current
+                                .children
+                                .iter()
+                                .take(EXPLORER_TOP_N)
+                                .enumerate()
+                                .map(|(index, child)| {
+                                    let path = child.path.clone();
+                                    let size = child.size;
+                                    let can_drill = !child.children.is_empty();
+                                    let share = child.size as f32 / max_size as f32;
+                                    let is_root = root.as_ref() == Some(&child.path);
+
+                                    div()
+                                        .id(SharedString::from(format!(
+                                            "explore-{}",
+                                            child.path.to_string_lossy()
+                                        )))
+                                        .flex()
+                                        .items_center()
+                                        .gap(px(12.0))
+                                        .px(px(16.0))
+                                        .py(px(10.0))
+                                        .bg(theme.tokens.card)
+                                        .border_1()
+                                        .border_color(theme.tokens.border)
+                                        .rounded(px(8.0))
+                                        .when(can_drill, |row| {
+                                            row.cursor_pointer()
+                                                .hover(|s| s.bg(theme.tokens.muted.opacity(0.5)))
+                                                .on_click(cx.listener(
+                                                    move |this, _, _window, cx| {
+                                                        this.explorer_trail.push(index);
+                                                        cx.notify();
+                                                    },
+                                                ))
+                                        })
+                                        .child(
+                                            Icon::new("hard-drive")
+                                                .size(px(16.0))
+                                                .color(theme.tokens.muted_foreground),
+                                        )
+                                        .child(
+                                            div()
+                                                .flex_1()
+                                                .flex()
+                                                .flex_col()
+                                                .gap(px(4.0))
+                                                .overflow_hidden()
+                                                .child(
+                                                    div()
+                                                        .text_size(px(14.0))
+                                                        .font_weight(FontWeight::SEMIBOLD)
+                                                        .text_color(theme.tokens.foreground)
+                                                        .child(child.name()),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .h(px(4.0))
+                                                        .w(relative(share))
+                                                        .rounded(px(2.0))
+                                                        .bg(theme.tokens.primary.opacity(0.6)),
+                                                ),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_size(px(14.0))
+                                                .font_weight(FontWeight::BOLD)
+                                                .text_color(theme.tokens.primary)
+                                                .child(bytesize::ByteSize(size).to_string()),
+                                        )
+                                        .when(!is_root && !audit::is_enabled(), |row| {
+                                            row.child(
+                                                IconButton::new("trash-2")
+                                                    .variant(ButtonVariant::Ghost)
+                                                    .size(px(28.0))
+                                                    .icon_size(px(14.0))
+                                                    .disabled(self.is_deleting)
+                                                    .on_click(cx.listener(
+                                                        move |this, _, window, cx| {
+                                                            cx.stop_propagation();
+                                                            this.request_explorer_delete(
+                                                                path.clone(),
+                                                                size,
+                                                                window,
+                                                                cx,
+                                                            );
+                                                        },
+                                                    )),
+                                            )
+                                        })
+                                }),
+                        ),
+                )),
+            )
+    }
+
+    pub(super) fn render_explorer_delete_dialog(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let (path, size) = self.explorer_delete.as_ref()?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        let confirmed = self.confirm_input.read(cx).content() == name;
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!("Delete {}?", name)),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!(
+                                    "{} ({}) is not a cleanup candidate. Type the folder name to confirm.",
+                                    path.display(),
+                                    bytesize::ByteSize(*size)
+                                )),
+                        )
+                        .child(Input::new(&self.confirm_input).placeholder(name.clone()))
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("cancel_explore_delete", "Cancel")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.explorer_delete = None;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("confirm_explore_delete", "Delete Folder")
+                                        .variant(ButtonVariant::Destructive)
+                                        .icon("trash-2")
+                                        .disabled(!confirmed || self.is_deleting)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.delete_explored(cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,1211 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn filtered_items(&self) -> Vec<&ScannedItem> {
+        let mut items: Vec<&ScannedItem> = match self.active_tab {
+            FilterTab::All => self.store.items().iter().collect(),
+            FilterTab::Largest => {
+                let mut sorted: Vec<_> = self.store.items().iter().collect();
+                sorted.sort_by_key(|i| std::cmp::Reverse(i.size));
+                sorted
+            }
+            FilterTab::DevArtifacts => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| {
+                    matches!(
+                        i.category,
+                        FileCategory::DevArtifact | FileCategory::GlobalPackage
+                    )
+                })
+                .collect(),
+            FilterTab::Caches => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| {
+                    matches!(
+                        i.category,
+                        FileCategory::PackageCache
+                            | FileCategory::IdeCache
+                            | FileCategory::BrowserCache
+                            | FileCategory::SystemCache
+                    )
+                })
+                .collect(),
+            FilterTab::TempLogs => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| matches!(i.category, FileCategory::TempFile | FileCategory::LogFile))
+                .collect(),
+            FilterTab::Downloads => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| i.category == FileCategory::OldDownload)
+                .collect(),
+            FilterTab::Duplicates => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| i.category == FileCategory::Duplicate)
+                .collect(),
+            FilterTab::LargeFiles => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| i.category == FileCategory::LargeFile)
+                .collect(),
+            FilterTab::Review => self
+                .store
+                .items()
+                .iter()
+                .filter(|i| self.review_later.contains(&i.path))
+                .collect(),
+        };
+        if self.active_tab != FilterTab::Review && !self.review_later.is_empty() {
+            items.retain(|item| !self.review_later.contains(&item.path));
+        }
+
+        if let Some(mount) = &self.volume_filter {
+            items.retain(|item| {
+                self.volumes
+                    .volume_for(&item.path)
+                    .is_some_and(|volume| &volume.mount_point == mount)
+            });
+        }
+
+        if !self.search_query.is_empty() {
+            items.retain(|item| {
+                item.name.to_lowercase().contains(&self.search_query)
+                    || item
+                        .path
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&self.search_query)
+            });
+        }
+
+        if self.active_tab == FilterTab::Largest {
+            return items;
+        }
+        if self.sort_order == SortOrder::Suggested {
+            items.sort_by_key(|i| std::cmp::Reverse(i.size));
+            let ranked = suggest::rank(&items);
+            return ranked.into_iter().map(|index| items[index]).collect();
+        }
+
+        // sort_by is stable and the path tiebreak keeps equal keys from
+        // swapping places as streamed items arrive.
+        items.sort_by(|a, b| {
+            let ordering = self.sort_order.compare(a, b);
+            let ordering = if self.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+            ordering.then_with(|| a.path.cmp(&b.path))
+        });
+        items
+    }
+
+    pub(super) fn set_sort(&mut self, order: SortOrder, cx: &mut Context<Self>) {
+        if self.sort_order == order {
+            self.sort_descending = !self.sort_descending;
+        } else {
+            self.sort_order = order;
+            self.sort_descending = order.default_descending();
+        }
+        cx.notify();
+    }
+
+    pub(super) fn volume_pressure(&self) -> Vec<VolumePressure> {
+        if self.viewing_bundle.is_some() {
+            return Vec::new();
+        }
+        self.volumes.pressure(
+            self.store
+                .items()
+                .iter()
+                .map(|item| (item.path.as_path(), item.size)),
+            self.config.full_volume_percent,
+        )
+    }
+
+    pub(super) fn set_volume_filter(&mut self, mount: Option<PathBuf>, cx: &mut Context<Self>) {
+        self.volume_filter = mount;
+        cx.notify();
+    }
+
+    pub(super) fn select_recommended_on(&mut self, mount: PathBuf, cx: &mut Context<Self>) {
+        self.volume_filter = Some(mount);
+        self.select_recommended(cx);
+    }
+
+    pub(super) fn hide_dismissed(&mut self) {
+        let config = &self.config;
+        let hidden = self
+            .store
+            .retain(|item| config.dismissed_key(&item.path).is_none());
+        for item in &hidden {
+            self.selected.remove(&item.path);
+        }
+        self.dismissed_items.extend(hidden);
+    }
+
+    pub(super) fn dismiss_item(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.viewing_bundle.is_some() {
+            return;
+        }
+        self.config.dismiss(&path);
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        if !self.config.show_dismissed {
+            self.hide_dismissed();
+        }
+        cx.notify();
+    }
+
+    /// Adds the item to the ignore list so later scans skip it and drops
+    /// it, along with anything under it, from the current results.
+    pub(super) fn ignore_item(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.viewing_bundle.is_some() {
+            return;
+        }
+        if self.is_scanning {
+            self.notice = Some("Items can't be ignored during a scan".to_string());
+            cx.notify();
+            return;
+        }
+        if !self.update_config(|config| config.ignore(&path), cx) {
+            return;
+        }
+        let config = &self.config;
+        let removed = self.store.retain(|item| !config.is_ignored(&item.path));
+        self.dismissed_items
+            .retain(|item| !config.is_ignored(&item.path));
+        for item in &removed {
+            self.selected.remove(&item.path);
+        }
+        self.pinned.retain(|pinned| !config.is_ignored(pinned));
+        self.notice = Some(format!(
+            "Ignoring {}; remove it under Ignored paths in settings to scan it again",
+            path.display()
+        ));
+        cx.notify();
+    }
+
+    pub(super) fn unignore_path(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.update_config(|config| config.unignore(&path), cx) {
+            self.notice = Some(format!(
+                "{} will be scanned again on the next scan",
+                path.display()
+            ));
+        }
+    }
+
+    pub(super) fn undismiss_item(&mut self, key: PathBuf, cx: &mut Context<Self>) {
+        self.config.undismiss(&key);
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        for item in std::mem::take(&mut self.dismissed_items) {
+            if self.config.dismissed_key(&item.path).is_some() {
+                self.dismissed_items.push(item);
+            } else {
+                self.store.push(item);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(super) fn set_show_dismissed(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.config.show_dismissed = show;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        if show {
+            for item in std::mem::take(&mut self.dismissed_items) {
+                self.store.push(item);
+            }
+        } else {
+            self.hide_dismissed();
+        }
+        cx.notify();
+    }
+
+    pub(super) fn toggle_pin(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if let Some(index) = self.pinned.iter().position(|pinned| pinned == &path) {
+            self.pinned.remove(index);
+        } else {
+            self.pinned.push(path);
+        }
+        cx.notify();
+    }
+
+    pub(super) fn toggle_review_later(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.review_later.remove(&path) {
+            cx.notify();
+            return;
+        }
+        self.change_selection(cx, |app| {
+            app.selected.remove(&path);
+        });
+        self.review_later.insert(path);
+    }
+
+    pub(super) fn review_hovered(
+        &mut self,
+        _: &ToggleReviewLater,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(path) = self.hovered_item.clone() {
+            self.toggle_review_later(path, cx);
+        }
+    }
+
+    /// Moves the review items in view back to their tabs, selected.
+    pub(super) fn promote_review(&mut self, cx: &mut Context<Self>) {
+        let paths: Vec<PathBuf> = self
+            .filtered_items()
+            .iter()
+            .map(|item| item.path.clone())
+            .collect();
+        for path in &paths {
+            self.review_later.remove(path);
+        }
+        self.notice = Some(format!("Selected {} reviewed items", paths.len()));
+        self.change_selection(cx, |app| app.selected.extend(paths));
+    }
+
+    pub(super) fn dismiss_review(&mut self, cx: &mut Context<Self>) {
+        let paths: Vec<PathBuf> = self
+            .filtered_items()
+            .iter()
+            .map(|item| item.path.clone())
+            .collect();
+        for path in &paths {
+            self.review_later.remove(path);
+            self.config.dismiss(path);
+        }
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        if !self.config.show_dismissed {
+            self.hide_dismissed();
+        }
+        cx.notify();
+    }
+
+    pub(super) fn pinned_items(&self) -> Vec<&ScannedItem> {
+        self.pinned
+            .iter()
+            .filter_map(|path| self.store.items().iter().find(|item| &item.path == path))
+            .collect()
+    }
+
+    pub(super) fn render_tabs(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let tabs = [
+            FilterTab::All,
+            FilterTab::Largest,
+            FilterTab::DevArtifacts,
+            FilterTab::Caches,
+            FilterTab::TempLogs,
+            FilterTab::Downloads,
+            FilterTab::Duplicates,
+            FilterTab::LargeFiles,
+            FilterTab::Review,
+        ];
+
+        div()
+            .flex()
+            .items_center()
+            .gap(px(4.0))
+            .px(px(24.0))
+            .py(px(12.0))
+            .children(tabs.into_iter().map(|tab| {
+                let is_active = self.active_tab == tab;
+                let bg = if is_active {
+                    theme.tokens.primary
+                } else {
+                    gpui::transparent_black()
+                };
+                let fg = if is_active {
+                    theme.tokens.primary_foreground
+                } else {
+                    theme.tokens.muted_foreground
+                };
+
+                div()
+                    .id(SharedString::from(tab.label()))
+                    .flex()
+                    .items_center()
+                    .gap(px(6.0))
+                    .px(px(14.0))
+                    .py(px(8.0))
+                    .bg(bg)
+                    .text_color(fg)
+                    .text_size(px(13.0))
+                    .font_weight(if is_active {
+                        FontWeight::SEMIBOLD
+                    } else {
+                        FontWeight::NORMAL
+                    })
+                    .rounded(px(8.0))
+                    .cursor_pointer()
+                    .hover(|s| {
+                        s.bg(if is_active {
+                            theme.tokens.primary
+                        } else {
+                            theme.tokens.muted.opacity(0.5)
+                        })
+                    })
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.active_tab = tab;
+                        this.list_limit = LIST_PAGE_SIZE;
+                        cx.notify();
+                    }))
+                    .child(Icon::new(tab.icon()).size(px(14.0)).color(fg))
+                    .child(tab.label())
+            }))
+    }
+
+    pub(super) fn render_search(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let sortable = self.active_tab != FilterTab::Largest;
+
+        div()
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .px(px(24.0))
+            .pb(px(12.0))
+            .child(
+                Icon::new("search")
+                    .size(px(14.0))
+                    .color(theme.tokens.muted_foreground),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .max_w(px(420.0))
+                    .child(Input::new(&self.search_input).placeholder("Filter by name or path")),
+            )
+            .when(!self.search_query.is_empty(), |row| {
+                row.child(
+                    Button::new("clear_search", "Clear")
+                        .variant(ButtonVariant::Ghost)
+                        .size(ButtonSize::Sm)
+                        .icon("x")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.search_input
+                                .update(cx, |input, cx| input.set_value("", window, cx));
+                        })),
+                )
+            })
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(4.0))
+                    .ml_auto()
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(theme.tokens.muted_foreground)
+                            .child(if sortable {
+                                "Sort by"
+                            } else {
+                                "Sorted by size"
+                            }),
+                    )
+                    .when(sortable, |row| {
+                        row.children(SortOrder::COLUMNS.into_iter().map(|order| {
+                            let active = self.sort_order == order;
+                            let mut button = Button::new(
+                                SharedString::from(format!("sort_{}", order.label())),
+                                order.label(),
+                            )
+                            .variant(if active {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .size(ButtonSize::Sm)
+                            .on_click(cx.listener(
+                                move |this, _, _window, cx| {
+                                    this.set_sort(order, cx);
+                                },
+                            ));
+                            if active {
+                                button = button.icon(if self.sort_descending {
+                                    "arrow-down"
+                                } else {
+                                    "arrow-up"
+                                });
+                            }
+                            button
+                        }))
+                    }),
+            )
+    }
+
+    pub(super) fn render_list(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let items = self.filtered_items();
+        let pinned = self.pinned_items();
+
+        if items.is_empty() && pinned.is_empty() && !self.is_scanning {
+            return div()
+                .flex_1()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .gap(px(20.0))
+                        .child(
+                            div()
+                                .size(px(80.0))
+                                .rounded_full()
+                                .bg(theme.tokens.muted.opacity(0.5))
+                                .flex()
+                                .items_center()
+                                .justify_center()
+                                .child(
+                                    Icon::new("inbox")
+                                        .size(px(40.0))
+                                        .color(theme.tokens.muted_foreground),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child("No items found"),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(14.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Click 'Rescan Disk' to search for cleanup candidates"),
+                        )
+                        .child(
+                            Button::new("scan_empty", "Start Scanning")
+                                .variant(ButtonVariant::Default)
+                                .icon("search")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.start_scan(cx);
+                                })),
+                        ),
+                )
+                .into_any_element();
+        }
+
+        let density = self.config.list_density;
+        let pinned_section = (!pinned.is_empty()).then(|| {
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .px(px(24.0))
+                .pt(px(16.0))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .px(px(4.0))
+                        .child(
+                            Icon::new("pin")
+                                .size(px(14.0))
+                                .color(theme.tokens.muted_foreground),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!("Pinned ({})", pinned.len())),
+                        ),
+                )
+                .children(
+                    pinned
+                        .iter()
+                        .map(|item| self.render_item_row(item, false, None, density, cx)),
+                )
+        });
+
+        let largest = items.iter().map(|item| item.size).max().unwrap_or(0);
+        let mut more = None;
+        let content = if self.active_tab == FilterTab::Duplicates {
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(16.0))
+                .px(px(24.0))
+                .py(px(16.0))
+                .children(
+                    self.duplicate_groups()
+                        .iter()
+                        .map(|group| self.render_duplicate_group(group, cx)),
+                )
+        } else {
+            let total = items
+                .iter()
+                .filter(|item| !self.pinned.contains(&item.path))
+                .count();
+            more = (total > self.list_limit).then(|| self.render_show_more(total, cx));
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .px(px(24.0))
+                .py(px(16.0))
+                .children(
+                    items
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(_, item)| !self.pinned.contains(&item.path))
+                        .take(self.list_limit)
+                        .map(|(position, item)| {
+                            let suggestion = (self.sort_order == SortOrder::Suggested
+                                && self.active_tab != FilterTab::Largest)
+                                .then(|| suggest::describe(item, largest, position));
+                            self.render_item_row(item, false, suggestion, density, cx)
+                        }),
+                )
+        };
+
+        scrollable_vertical(div().children(pinned_section).child(content).children(more))
+            .into_any_element()
+    }
+
+    pub(super) fn render_show_more(&self, total: usize, cx: &Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let next = LIST_PAGE_SIZE.min(total - self.list_limit);
+
+        div()
+            .flex()
+            .items_center()
+            .justify_center()
+            .gap(px(12.0))
+            .px(px(24.0))
+            .pb(px(24.0))
+            .child(
+                div()
+                    .text_size(px(13.0))
+                    .text_color(theme.tokens.muted_foreground)
+                    .child(format!("Showing {} of {}", self.list_limit, total)),
+            )
+            .child(
+                Button::new("show_more", format!("Show {} more", next))
+                    .variant(ButtonVariant::Outline)
+                    .size(ButtonSize::Sm)
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.list_limit += LIST_PAGE_SIZE;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("show_all", "Show all")
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::Sm)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.list_limit = total;
+                        cx.notify();
+                    })),
+            )
+    }
+
+    pub(super) fn render_duplicate_group(
+        &self,
+        group: &DuplicateGroup,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = use_theme();
+        let hash = group.hash.to_string();
+        let keeper_name: String = group.keeper().name.clone().into();
+        let all_rest_selected = group
+            .removable()
+            .all(|item| self.selected.contains(&item.path));
+        let is_sampled = group.is_sampled();
+        let is_verifying = self.verifying.contains(group.hash);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(4.0))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .px(px(4.0))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(10.0))
+                            .child(
+                                Icon::new("copy")
+                                    .size(px(14.0))
+                                    .color(theme.tokens.muted_foreground),
+                            )
+                            .child(
+                                div()
+                                    .text_size(px(13.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(theme.tokens.foreground)
+                                    .child(format!(
+                                        "{} copies of {}",
+                                        group.members.len(),
+                                        keeper_name
+                                    )),
+                            )
+                            .child(
+                                Badge::new(format!(
+                                    "{} reclaimable",
+                                    bytesize::ByteSize(group.wasted_size())
+                                ))
+                                .variant(BadgeVariant::Outline),
+                            )
+                            .when(is_sampled, |header| {
+                                header.child(
+                                    Badge::new("Probable duplicates (sampled)")
+                                        .variant(BadgeVariant::Warning),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(6.0))
+                            .when(is_sampled, |actions| {
+                                let hash = hash.clone();
+                                actions.child(
+                                    Button::new(
+                                        SharedString::from(format!("dup-verify-{}", hash)),
+                                        if is_verifying {
+                                            "Verifying…"
+                                        } else {
+                                            "Verify"
+                                        },
+                                    )
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .icon("shield-check")
+                                    .disabled(is_verifying || self.is_scanning)
+                                    .on_click(cx.listener(
+                                        move |this, _, _window, cx| {
+                                            this.verify_duplicate_group(hash.clone(), cx);
+                                        },
+                                    )),
+                                )
+                            })
+                            .child(
+                                Button::new(
+                                    SharedString::from(format!("dup-rest-{}", hash)),
+                                    "Delete the rest",
+                                )
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("square-check")
+                                .disabled(self.is_deleting || all_rest_selected)
+                                .on_click(cx.listener(
+                                    move |this, _, _window, cx| {
+                                        this.select_duplicate_rest(Some(&hash), cx);
+                                    },
+                                )),
+                            ),
+                    ),
+            )
+            .children(group.members.iter().map(|item| {
+                self.render_item_row(
+                    item,
+                    group.is_keeper(&item.path),
+                    None,
+                    self.config.list_density,
+                    cx,
+                )
+            }))
+    }
+
+    pub(super) fn render_item_row(
+        &self,
+        item: &ScannedItem,
+        protected: bool,
+        suggestion: Option<String>,
+        density: ListDensity,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = use_theme();
+        let compact = density == ListDensity::Compact;
+        let path = item.path.clone();
+        let is_selected = self.selected.contains(&path);
+        let managed_store = item.managed_store();
+        let needs_admin = item.elevation_required().is_some();
+        let locked = protected || item.is_keeper || managed_store.is_some() || needs_admin;
+        let name: String = item.name.clone().into();
+        let category = item.category;
+        let category_name = category.name();
+        let path_str = item.path.to_string_lossy().to_string();
+        let size_str = item.size_formatted();
+        let confidence = item.confidence_percent();
+        let confidence_overridden = item.confidence_overridden;
+        let age_badge = if item.recently_active {
+            Some(Badge::new("Recently active").variant(BadgeVariant::Outline))
+        } else {
+            components::age_badge(item)
+        };
+        let regenerates_quickly = item.regenerates_quickly;
+        let is_outdated = self.outdated.contains(&item.path);
+        let show_tooltips = !cx.has_active_drag();
+        let is_pinned = self.pinned.contains(&item.path);
+        let is_file = item.file_count.is_none() && item.members.is_empty();
+        let reveal_path = item.path.clone();
+        let open_path = item.path.clone();
+        let pin_path = item.path.clone();
+        let dismiss_path = item.path.clone();
+        let ignore_path = item.path.clone();
+        let review_path = item.path.clone();
+        let hover_path = item.path.clone();
+        let in_review = self.review_later.contains(&item.path);
+        let dismissed_key = if self.config.show_dismissed {
+            self.config.dismissed_key(&item.path)
+        } else {
+            None
+        };
+        let size = item.size;
+        let modified = item
+            .modified
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let reasons = item.confidence_reasons();
+        let explanation = SharedString::from(explain::explain(item));
+        let scanned_at = item
+            .scanned_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M");
+        let detail = if category == FileCategory::Duplicate {
+            format!(
+                "{} • modified {} • scanned {}",
+                path_str,
+                item.modified.format("%Y-%m-%d"),
+                scanned_at
+            )
+        } else if let Some(folder) = item
+            .path
+            .file_name()
+            .map(|folder| folder.to_string_lossy())
+            .filter(|folder| item.source == ScanSource::SystemCacheChild && **folder != *item.name)
+        {
+            format!("{} • {} • scanned {}", folder, path_str, scanned_at)
+        } else {
+            format!("{} • scanned {}", path_str, scanned_at)
+        };
+
+        let bg = if is_selected {
+            theme.tokens.primary.opacity(0.1)
+        } else {
+            theme.tokens.card
+        };
+
+        let border_color = if is_selected {
+            theme.tokens.primary.opacity(0.5)
+        } else {
+            theme.tokens.border
+        };
+
+        let header = div()
+            .flex()
+            .items_center()
+            .gap(px(10.0))
+            .child(
+                div()
+                    .text_size(px(14.0))
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(theme.tokens.foreground)
+                    .child(name),
+            )
+            .child(Badge::new(category_name).variant(BadgeVariant::Secondary))
+            .when(protected, |header| {
+                header.child(Badge::new("Keep").variant(BadgeVariant::Default))
+            })
+            .when_some(age_badge, |header, badge| {
+                header.child(
+                    div()
+                        .id(SharedString::from(format!("age-{}", path_str)))
+                        .when(show_tooltips, |age| {
+                            let modified = modified.clone();
+                            age.tooltip(move |_window, cx| {
+                                InfoTooltip::view(vec![format!("Last modified {}", modified)], cx)
+                            })
+                        })
+                        .child(badge),
+                )
+            })
+            .when_some(item.managed_by.clone(), |header, owner| {
+                header.child(
+                    Badge::new(format!("Managed by {}", owner)).variant(BadgeVariant::Warning),
+                )
+            })
+            .when(needs_admin, |header| {
+                header.child(Badge::new("Needs admin").variant(BadgeVariant::Warning))
+            })
+            .when(regenerates_quickly, |header| {
+                header.child(Badge::new("Regenerates quickly").variant(BadgeVariant::Warning))
+            })
+            .when(in_review, |header| {
+                header.child(Badge::new("Review later").variant(BadgeVariant::Outline))
+            })
+            .when(dismissed_key.is_some(), |header| {
+                header.child(Badge::new("Dismissed").variant(BadgeVariant::Outline))
+            })
+            .when(is_outdated, |header| {
+                let path = item.path.clone();
+                header.child(
+                    Button::new(
+                        SharedString::from(format!("refresh-{}", path_str)),
+                        "May be outdated — refresh",
+                    )
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::Sm)
+                    .icon("refresh-cw")
+                    .disabled(self.is_scanning)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        cx.stop_propagation();
+                        this.rescan_paths(vec![path.clone()], cx);
+                    })),
+                )
+            });
+
+        let detail = div()
+            .id(SharedString::from(format!("path-{}", path_str)))
+            .when(show_tooltips, |detail| {
+                let mut lines = vec![
+                    path_str.clone(),
+                    format!("Found by: {}", item.source.name()),
+                ];
+                lines.extend(
+                    item.origin
+                        .as_ref()
+                        .map(|url| format!("Downloaded from: {}", url)),
+                );
+                lines.extend(suggestion);
+                detail.tooltip(move |_window, cx| InfoTooltip::view(lines.clone(), cx))
+            })
+            .flex_1()
+            .text_size(px(12.0))
+            .text_color(theme.tokens.muted_foreground)
+            .overflow_hidden()
+            .text_ellipsis()
+            .whitespace_nowrap()
+            .child(detail);
+        let confidence = div()
+            .id(SharedString::from(format!("confidence-{}", path_str)))
+            .when(show_tooltips, |confidence| {
+                confidence.tooltip(move |_window, cx| InfoTooltip::view(reasons.clone(), cx))
+            })
+            .flex()
+            .items_center()
+            .gap(px(4.0))
+            .child(
+                Icon::new("gauge")
+                    .size(px(12.0))
+                    .color(theme.tokens.muted_foreground),
+            )
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(theme.tokens.muted_foreground)
+                    .child(if confidence_overridden {
+                        format!("{}% (custom)", confidence)
+                    } else {
+                        format!("{}%", confidence)
+                    }),
+            );
+
+        div()
+            .id(SharedString::from(path_str.clone()))
+            .flex()
+            .items_center()
+            .gap(px(if compact { 10.0 } else { 16.0 }))
+            .px(px(if compact { 12.0 } else { 16.0 }))
+            .py(px(if compact { 6.0 } else { 14.0 }))
+            .bg(bg)
+            .border_1()
+            .border_color(border_color)
+            .rounded(px(if compact { 6.0 } else { 8.0 }))
+            .on_hover(cx.listener(move |this, hovered: &bool, _window, _cx| {
+                if *hovered {
+                    this.hovered_item = Some(hover_path.clone());
+                } else if this.hovered_item.as_ref() == Some(&hover_path) {
+                    this.hovered_item = None;
+                }
+            }))
+            .when(!locked, |row| {
+                row.hover(|s| s.bg(theme.tokens.muted.opacity(0.5)).shadow_md())
+                    .cursor_pointer()
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.toggle_selection(path.clone(), cx);
+                    }))
+            })
+            .child(
+                Checkbox::new(SharedString::from(format!("check-{}", path_str)))
+                    .checked(is_selected && !locked)
+                    .indeterminate(in_review && !is_selected && !locked)
+                    .indeterminate_icon("clock")
+                    .disabled(locked),
+            )
+            .child(
+                div()
+                    .size(px(if compact { 28.0 } else { 40.0 }))
+                    .rounded(px(if compact { 6.0 } else { 8.0 }))
+                    .bg(theme.tokens.muted.opacity(0.5))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .child(
+                        Icon::new(category_icon(category))
+                            .size(px(if compact { 14.0 } else { 20.0 }))
+                            .color(theme.tokens.primary),
+                    ),
+            )
+            .child(if compact {
+                div()
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .gap(px(12.0))
+                    .overflow_hidden()
+                    .child(header)
+                    .child(detail)
+                    .into_any_element()
+            } else {
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .gap(px(6.0))
+                    .overflow_hidden()
+                    .child(header)
+                    .child(detail)
+                    .into_any_element()
+            })
+            .child(
+                div()
+                    .flex()
+                    .when(compact, |meta| {
+                        meta.flex_row_reverse().items_center().gap(px(12.0))
+                    })
+                    .when(!compact, |meta| meta.flex_col().items_end().gap(px(6.0)))
+                    .child(
+                        div()
+                            .id(SharedString::from(format!("size-{}", path_str)))
+                            .when(show_tooltips, |label| {
+                                let path = item.path.clone();
+                                label.tooltip(move |_window, cx| {
+                                    let mut lines = vec![format!("{} bytes", size)];
+                                    if let Some(allocated) = scanner::allocated_size(&path) {
+                                        lines.push(format!(
+                                            "{} on disk ({} bytes)",
+                                            bytesize::ByteSize(allocated),
+                                            allocated
+                                        ));
+                                    }
+                                    InfoTooltip::view(lines, cx)
+                                })
+                            })
+                            .text_size(px(if compact { 14.0 } else { 16.0 }))
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(theme.tokens.primary)
+                            .child(size_str),
+                    )
+                    .when_some(item.file_count, |meta, files| {
+                        meta.child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(components::file_count_label(files)),
+                        )
+                    })
+                    .child(confidence),
+            )
+            .when_some(managed_store, |row, store| {
+                row.child(
+                    Button::new(
+                        SharedString::from(format!("copy-command-{}", path_str)),
+                        "Copy Command",
+                    )
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::Sm)
+                    .icon("copy")
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        cx.stop_propagation();
+                        cx.write_to_clipboard(ClipboardItem::new_string(store.command.to_string()));
+                        this.notice = Some(format!(
+                            "Copied `{}`. Run it in a terminal to clean the {} store",
+                            store.command, store.tool
+                        ));
+                        cx.notify();
+                    })),
+                )
+            })
+            .child(
+                div()
+                    .id(SharedString::from(format!("explain-{}", path_str)))
+                    .on_click(|_, _window, cx| cx.stop_propagation())
+                    .child(
+                        Popover::new(SharedString::from(format!("explain-popover-{}", path_str)))
+                            .anchor(Corner::TopRight)
+                            .trigger(
+                                IconButton::new("circle-help")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(px(if compact { 22.0 } else { 28.0 }))
+                                    .icon_size(px(14.0)),
+                            )
+                            .content(move |window, cx| {
+                                let explanation = explanation.clone();
+                                cx.new(|cx| {
+                                    PopoverContent::new(window, cx, move |_window, _cx| {
+                                        div()
+                                            .w(px(320.0))
+                                            .text_size(px(12.0))
+                                            .line_height(px(18.0))
+                                            .child(explanation.clone())
+                                            .into_any_element()
+                                    })
+                                })
+                            }),
+                    ),
+            )
+            .child(
+                div()
+                    .id(SharedString::from(format!("reveal-{}", path_str)))
+                    .when(show_tooltips, |button| {
+                        button.tooltip(|_window, cx| {
+                            InfoTooltip::view(vec!["Reveal in file manager".to_string()], cx)
+                        })
+                    })
+                    .child(
+                        IconButton::new("folder-open")
+                            .variant(ButtonVariant::Ghost)
+                            .size(px(if compact { 22.0 } else { 28.0 }))
+                            .icon_size(px(14.0))
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                cx.stop_propagation();
+                                this.reveal_item(reveal_path.clone(), cx);
+                            })),
+                    ),
+            )
+            .when(is_file, |row| {
+                row.child(
+                    div()
+                        .id(SharedString::from(format!("open-{}", path_str)))
+                        .when(show_tooltips, |button| {
+                            button.tooltip(|_window, cx| {
+                                InfoTooltip::view(vec!["Open with the default app".to_string()], cx)
+                            })
+                        })
+                        .child(
+                            IconButton::new("external-link")
+                                .variant(ButtonVariant::Ghost)
+                                .size(px(if compact { 22.0 } else { 28.0 }))
+                                .icon_size(px(14.0))
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    cx.stop_propagation();
+                                    this.open_item(open_path.clone(), cx);
+                                })),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .id(SharedString::from(format!("review-{}", path_str)))
+                    .when(show_tooltips, |button| {
+                        button.tooltip(move |_window, cx| {
+                            InfoTooltip::view(
+                                vec![if in_review {
+                                    "Done reviewing (R)".to_string()
+                                } else {
+                                    "Review later (R)".to_string()
+                                }],
+                                cx,
+                            )
+                        })
+                    })
+                    .child(
+                        IconButton::new("clock")
+                            .variant(if in_review {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .size(px(if compact { 22.0 } else { 28.0 }))
+                            .icon_size(px(14.0))
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                cx.stop_propagation();
+                                this.toggle_review_later(review_path.clone(), cx);
+                            })),
+                    ),
+            )
+            .child(
+                IconButton::new(if is_pinned { "pin-off" } else { "pin" })
+                    .variant(ButtonVariant::Ghost)
+                    .size(px(if compact { 22.0 } else { 28.0 }))
+                    .icon_size(px(14.0))
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        cx.stop_propagation();
+                        this.toggle_pin(pin_path.clone(), cx);
+                    })),
+            )
+            .child(
+                IconButton::new(if dismissed_key.is_some() {
+                    "eye"
+                } else {
+                    "eye-off"
+                })
+                .variant(ButtonVariant::Ghost)
+                .size(px(if compact { 22.0 } else { 28.0 }))
+                .icon_size(px(14.0))
+                .on_click(cx.listener(move |this, _, _window, cx| {
+                    cx.stop_propagation();
+                    match &dismissed_key {
+                        Some(key) => this.undismiss_item(key.clone(), cx),
+                        None => this.dismiss_item(dismiss_path.clone(), cx),
+                    }
+                })),
+            )
+            .child(
+                div()
+                    .id(SharedString::from(format!("ignore-{}", path_str)))
+                    .when(show_tooltips, |button| {
+                        button.tooltip(|_window, cx| {
+                            InfoTooltip::view(vec!["Ignore in future scans".to_string()], cx)
+                        })
+                    })
+                    .child(
+                        IconButton::new("ban")
+                            .variant(ButtonVariant::Ghost)
+                            .size(px(if compact { 22.0 } else { 28.0 }))
+                            .icon_size(px(14.0))
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                cx.stop_propagation();
+                                this.ignore_item(ignore_path.clone(), cx);
+                            })),
+                    ),
+            )
+    }
+}
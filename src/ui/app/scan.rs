@@ -0,0 +1,356 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn set_scan_scope(
+        &mut self,
+        scan_scope: Option<Vec<PathBuf>>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_scanning {
+            return;
+        }
+        self.scanner = Arc::new(Self::build_scanner(&self.config, scan_scope.as_ref()));
+        self.scan_scope = scan_scope;
+        cx.notify();
+    }
+
+    pub(super) fn scan_folders(&mut self, scope: Vec<PathBuf>, cx: &mut Context<Self>) {
+        if self.is_scanning {
+            self.notice = Some("A scan is already running".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.scope_history.retain(|previous| previous != &scope);
+        self.scope_history.insert(0, scope.clone());
+        self.scope_history.truncate(SCOPE_HISTORY_LIMIT);
+
+        self.set_scan_scope(Some(scope), cx);
+        self.start_scan(cx);
+    }
+
+    pub(super) fn handle_dropped_paths(&mut self, paths: &[PathBuf], cx: &mut Context<Self>) {
+        let scope = scanner::normalize_scope(paths);
+        let skipped = paths.iter().filter(|p| !p.is_dir()).count();
+
+        if scope.is_empty() {
+            self.notice = Some("Only folders can be dropped to scan".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.notice = if skipped > 0 {
+            Some(format!(
+                "Skipped {} dropped items that aren't folders",
+                skipped
+            ))
+        } else {
+            None
+        };
+        self.scan_folders(scope, cx);
+    }
+
+    pub(super) fn start_scan(&mut self, cx: &mut Context<Self>) {
+        self.is_scanning = true;
+        self.resume_polling(cx);
+        self.viewing_bundle = None;
+        self.local_store = None;
+        self.store.clear();
+        self.selected.clear();
+        self.selection_history.clear();
+        self.outdated.clear();
+        self.volume_filter = None;
+        self.list_limit = LIST_PAGE_SIZE;
+        self.scan_progress = ScanProgress::default();
+        self.scan_started = Some(Instant::now());
+        self.scan_cursor = ItemCursor::default();
+        self.scan_cancelled = false;
+        self.scan_delta = None;
+        self.last_deleted = None;
+        cx.notify();
+
+        let scanner = self.scanner.clone();
+        let metrics_path = self.config.metrics_path.clone();
+        cx.spawn(async move |this, cx| {
+            let (store, scan_error, metrics_error) = cx
+                .background_executor()
+                .spawn(async move {
+                    if let Err(e) = scanner.scan() {
+                        return (
+                            scanner.snapshot(),
+                            Some(format!("Scan failed: {}", e)),
+                            None,
+                        );
+                    }
+                    let store = scanner.snapshot();
+                    let metrics_error = metrics_path.and_then(|path| {
+                        metrics::write(&path, &store).err().map(|e| {
+                            format!("Could not write metrics to {}: {}", path.display(), e)
+                        })
+                    });
+                    (store, None, metrics_error)
+                })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                app.is_scanning = false;
+                let full_scan = app.scan_started.take().is_some() && app.scan_scope.is_none();
+                if full_scan {
+                    app.history.last_scan_paths = app.scanner.files_scanned();
+                }
+                let finished = scan_error.is_none() && !app.scan_cancelled;
+                let complete = full_scan && finished;
+                if scan_error.is_some() || metrics_error.is_some() {
+                    app.notice = scan_error.or(metrics_error);
+                }
+                if let Some(disk) = app.pending_disk_config.take() {
+                    app.apply_disk_config(disk, cx);
+                    app.notice = Some("Config changed on disk — applied".to_string());
+                }
+                app.receive_snapshot(store, finished);
+                app.scan_delta = if complete {
+                    app.record_scan_totals()
+                } else {
+                    None
+                };
+                app.pinned.retain(|path| path.exists());
+                app.scan_progress = ScanProgress::default();
+                app.scan_sites.clear();
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn record_scan_totals(&mut self) -> Option<ScanDelta> {
+        let stats = self.store.stats();
+        let delta = self.history.record_scan(ScanSummary {
+            at: Utc::now(),
+            total_size: stats.total_size,
+            total_items: stats.total_items,
+        });
+        if let Err(e) = self.history.save() {
+            log::warn!("Failed to save history: {}", e);
+        }
+        delta
+    }
+
+    /// Runs one tick of the UI poll: scan progress, control requests, the
+    /// deletion grace period and the undo timeout.
+    pub(super) fn poll(&mut self, cx: &mut Context<Self>) {
+        if self.scanner.is_scanning() {
+            self.is_scanning = true;
+            self.scan_progress = self.scanner.progress();
+            self.scan_sites = self.scanner.active_paths();
+            self.receive_streamed_items();
+            cx.notify();
+        } else if self.is_scanning {
+            self.is_scanning = false;
+            let store = self.scanner.snapshot();
+            self.receive_snapshot(store, false);
+            self.pinned.retain(|path| path.exists());
+            self.scan_progress = ScanProgress::default();
+            self.scan_sites.clear();
+            cx.notify();
+        }
+
+        let requests: Vec<ControlRequest> = self
+            .control
+            .as_ref()
+            .map(|control| control.requests.try_iter().collect())
+            .unwrap_or_default();
+        for request in requests {
+            self.handle_control(request, cx);
+        }
+
+        if self.report_export.is_some() {
+            cx.notify();
+        }
+
+        if self.quit_when_idle && !self.is_deleting {
+            self.quit(cx);
+        }
+        if self.compact.is_some() && !self.is_busy() {
+            self.exit_compact(cx);
+        }
+
+        if self
+            .last_deleted
+            .as_ref()
+            .is_some_and(|undo| undo.expires <= Instant::now())
+        {
+            self.last_deleted = None;
+            cx.notify();
+        }
+
+        if let Some(pending) = &self.pending_clean {
+            if pending.is_due(Instant::now()) {
+                if let Some(pending) = self.pending_clean.take() {
+                    self.run_clean_job(pending.into_job(), cx);
+                }
+            }
+            cx.notify();
+        }
+    }
+
+    /// Work the poll loop has to watch at full speed.
+    pub(super) fn needs_fast_poll(&self) -> bool {
+        self.is_busy()
+            || self.report_export.is_some()
+            || self.last_deleted.is_some()
+            || self.quit_when_idle
+            || self.compact.is_some()
+    }
+
+    /// Starts the poll loop if it has stopped. It ticks every 100 ms while
+    /// there is work, backs off to once a second when idle, and stops after a
+    /// few idle seconds unless the control socket needs reading. Anything that
+    /// starts work calls this.
+    pub(super) fn resume_polling(&mut self, cx: &mut Context<Self>) {
+        if self.polling {
+            return;
+        }
+        self.polling = true;
+
+        cx.spawn(async move |this, cx| {
+            let mut cadence = PollCadence::default();
+            loop {
+                cx.background_executor().timer(cadence.interval()).await;
+
+                let Ok(keep_polling) = this.update(cx, |app, cx| {
+                    app.poll(cx);
+                    let keep_polling = cadence.tick(app.needs_fast_poll(), app.control.is_some());
+                    if !keep_polling {
+                        app.polling = false;
+                    }
+                    keep_polling
+                }) else {
+                    break;
+                };
+                if !keep_polling {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    pub(super) fn receive_snapshot(&mut self, store: ItemStore, reconcile: bool) {
+        self.store = store;
+        self.dismissed_items.clear();
+
+        let scope = self.scan_scope.clone();
+        if let Some(pruned) =
+            self.config
+                .reconcile_scan(self.store.items(), scope.as_deref(), reconcile)
+        {
+            if let Err(e) = self.save_config() {
+                self.notice = Some(format!("Could not save settings: {}", e));
+            } else if pruned > 0 {
+                self.notice = Some(format!(
+                    "Forgot {} dismissed items that no longer exist",
+                    pruned
+                ));
+            }
+        }
+
+        if !self.config.show_dismissed {
+            self.hide_dismissed();
+        }
+        self.volumes = VolumeTable::current();
+    }
+
+    pub(super) fn receive_streamed_items(&mut self) {
+        if self.viewing_bundle.is_some() {
+            return;
+        }
+        for item in self.scanner.items_after(&mut self.scan_cursor) {
+            if self.config.show_dismissed || self.config.dismissed_key(&item.path).is_none() {
+                self.store.push(item);
+            }
+        }
+    }
+
+    pub(super) fn render_scope_history(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if self.scope_history.is_empty() {
+            return None;
+        }
+
+        let theme = use_theme();
+        let is_scanning = self.is_scanning;
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .gap(px(6.0))
+                .px(px(24.0))
+                .pt(px(12.0))
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Recent scopes"),
+                )
+                .children(self.scope_history.iter().enumerate().map(|(index, scope)| {
+                    let is_active = self.scan_scope.as_ref() == Some(scope);
+                    let label = scope
+                        .iter()
+                        .map(|p| {
+                            p.file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_else(|| p.to_string_lossy().to_string())
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" + ");
+                    let scope = scope.clone();
+
+                    Button::new(SharedString::from(format!("scope-{}", index)), label)
+                        .variant(if is_active {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        })
+                        .size(ButtonSize::Sm)
+                        .icon("refresh-cw")
+                        .disabled(is_scanning)
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            this.scan_folders(scope.clone(), cx);
+                        }))
+                })),
+        )
+    }
+
+    pub(super) fn render_drop_overlay(&self) -> impl IntoElement {
+        let theme = use_theme();
+
+        div()
+            .absolute()
+            .inset_0()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(theme.tokens.background.opacity(0.85))
+            .border_2()
+            .border_color(theme.tokens.primary)
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .gap(px(12.0))
+                    .child(
+                        Icon::new("hard-drive")
+                            .size(px(40.0))
+                            .color(theme.tokens.primary),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(18.0))
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(theme.tokens.foreground)
+                            .child("Drop folders to scan them"),
+                    ),
+            )
+    }
+}
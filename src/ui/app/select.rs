@@ -0,0 +1,551 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn selection_target(&self, cx: &App) -> Option<u64> {
+        let content = self.target_input.read(cx).content();
+        if content.trim().is_empty() {
+            return None;
+        }
+        config::parse_size(content).ok().filter(|size| *size > 0)
+    }
+
+    pub(super) fn toggle_selection(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.change_selection(cx, |app| {
+            if app.selected.contains(&path) {
+                app.selected.remove(&path);
+            } else {
+                app.selected.insert(path);
+            }
+        });
+    }
+
+    pub(super) fn change_selection(
+        &mut self,
+        cx: &mut Context<Self>,
+        change: impl FnOnce(&mut Self),
+    ) {
+        let before = self.selected.clone();
+        change(self);
+        // Duplicate keepers show up in every tab, so guard them here rather
+        // than in each way of selecting.
+        let keepers = self.duplicate_keepers();
+        self.selected.retain(|path| {
+            categories::CategoryPatterns::managed_store(path).is_none()
+                && categories::CategoryPatterns::elevation_required(path).is_none()
+                && !keepers.contains(path)
+        });
+        self.selection_history.push(&before, &self.selected);
+        cx.notify();
+    }
+
+    /// The copy each duplicate set keeps across the whole scan, worked out
+    /// again only when the items change rather than on every click.
+    pub(super) fn duplicate_keepers(&mut self) -> Arc<HashSet<PathBuf>> {
+        let revision = self.store.revision();
+        if let Some((cached, keepers)) = &self.keepers_cache {
+            if *cached == revision {
+                return keepers.clone();
+            }
+        }
+        let items: Vec<&ScannedItem> = self.store.items().iter().collect();
+        let keepers = Arc::new(duplicates::keepers(&items, dirs::download_dir().as_deref()));
+        self.keepers_cache = Some((revision, keepers.clone()));
+        keepers
+    }
+
+    pub(super) fn undo_selection(
+        &mut self,
+        _: &UndoSelection,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selection_history.undo(&mut self.selected) {
+            self.drop_missing_selection();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn redo_selection(
+        &mut self,
+        _: &RedoSelection,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selection_history.redo(&mut self.selected) {
+            self.drop_missing_selection();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn drop_missing_selection(&mut self) {
+        let present: HashSet<&PathBuf> = self.store.items().iter().map(|item| &item.path).collect();
+        self.selected.retain(|path| present.contains(path));
+    }
+
+    pub(super) fn duplicate_groups(&self) -> Vec<DuplicateGroup<'_>> {
+        let downloads = dirs::download_dir();
+        duplicates::group_duplicates(&self.filtered_items(), downloads.as_deref())
+    }
+
+    pub(super) fn select_all(&mut self, cx: &mut Context<Self>) {
+        if self.active_tab == FilterTab::Duplicates {
+            self.select_duplicate_rest(None, cx);
+            return;
+        }
+
+        let paths: Vec<PathBuf> = self
+            .filtered_items()
+            .iter()
+            .map(|i| i.path.clone())
+            .collect();
+        self.change_selection(cx, |app| app.selected.extend(paths));
+    }
+
+    /// Adds the items in view that match `preset` to the selection and
+    /// returns how many were added. Duplicate keepers are never added.
+    pub(super) fn apply_selection_preset(
+        &mut self,
+        preset: SelectionPreset,
+        cx: &mut Context<Self>,
+    ) -> usize {
+        let keepers: HashSet<PathBuf> = if self.active_tab == FilterTab::Duplicates {
+            self.duplicate_groups()
+                .iter()
+                .map(|group| group.keeper().path.clone())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let downloads = dirs::download_dir();
+        let paths = preset.additions(
+            &self.filtered_items(),
+            downloads.as_deref(),
+            &keepers,
+            &self.selected,
+        );
+        let before = self.selected.len();
+        self.change_selection(cx, |app| app.selected.extend(paths));
+        let added = self.selected.len().saturating_sub(before);
+        self.notice = Some(match added {
+            0 => format!("{}: nothing more to select", preset.label()),
+            1 => format!("{}: selected 1 more item", preset.label()),
+            n => format!("{}: selected {} more items", preset.label(), n),
+        });
+        added
+    }
+
+    pub(super) fn select_recommended(&mut self, cx: &mut Context<Self>) {
+        let target = self.selection_target(cx);
+        let keepers: HashSet<PathBuf> = if self.active_tab == FilterTab::Duplicates {
+            self.duplicate_groups()
+                .iter()
+                .map(|group| group.keeper().path.clone())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let include_quick_regen = self.config.recommend_quick_regen;
+
+        let candidates: Vec<&ScannedItem> = self
+            .filtered_items()
+            .into_iter()
+            .filter(|i| i.confidence >= RECOMMENDED_CONFIDENCE)
+            .filter(|i| include_quick_regen || !i.regenerates_quickly)
+            .filter(|i| !keepers.contains(&i.path))
+            .collect();
+        let paths: Vec<PathBuf> = match target {
+            Some(target) => {
+                let ranked = suggest::rank(&candidates);
+                suggest::select_until(&candidates, &ranked, target)
+                    .into_iter()
+                    .map(|index| candidates[index].path.clone())
+                    .collect()
+            }
+            None => candidates.iter().map(|i| i.path.clone()).collect(),
+        };
+        self.change_selection(cx, |app| app.selected.extend(paths));
+    }
+
+    pub(super) fn select_duplicate_rest(&mut self, hash: Option<&str>, cx: &mut Context<Self>) {
+        let (keepers, removable): (Vec<PathBuf>, Vec<PathBuf>) = {
+            let groups: Vec<DuplicateGroup> = self
+                .duplicate_groups()
+                .into_iter()
+                .filter(|group| hash.is_none_or(|hash| group.hash == hash))
+                .collect();
+            (
+                groups.iter().map(|g| g.keeper().path.clone()).collect(),
+                duplicates::safe_selection(&groups),
+            )
+        };
+
+        self.change_selection(cx, |app| {
+            for path in keepers {
+                app.selected.remove(&path);
+            }
+            app.selected.extend(removable);
+        });
+    }
+
+    pub(super) fn fully_selected_duplicates(&self) -> Vec<(String, usize, PathBuf)> {
+        let items: Vec<&ScannedItem> = self
+            .store
+            .items()
+            .iter()
+            .filter(|item| item.category == FileCategory::Duplicate)
+            .collect();
+        let downloads = dirs::download_dir();
+        let groups = duplicates::group_duplicates(&items, downloads.as_deref());
+        duplicates::fully_selected(&groups, &self.selected)
+            .into_iter()
+            .map(|group| {
+                let newest = group.newest();
+                (
+                    newest.name.to_string(),
+                    group.members.len(),
+                    newest.path.clone(),
+                )
+            })
+            .collect()
+    }
+
+    pub(super) fn verify_selected_sizes(&mut self, cx: &mut Context<Self>) {
+        if self.size_check.as_ref().is_some_and(|check| check.running) {
+            return;
+        }
+        let items: Vec<ScannedItem> = self.selected_items().cloned().collect();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.size_check = Some(SizeCheck {
+            total: items.len(),
+            results: Vec::with_capacity(items.len()),
+            cancel: cancel.clone(),
+            running: true,
+        });
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            for item in items {
+                if cancel.load(Ordering::SeqCst) {
+                    break;
+                }
+                let reported = item.size;
+                let path = item.path.clone();
+                let measured = cx
+                    .background_executor()
+                    .spawn(async move { item.refreshed().map(|item| item.size) })
+                    .await;
+                let updated = this.update(cx, |app, cx| {
+                    let Some(check) = app.size_check.as_mut() else {
+                        return false;
+                    };
+                    check.results.push((path, reported, measured));
+                    cx.notify();
+                    true
+                });
+                if !matches!(updated, Ok(true)) {
+                    return;
+                }
+            }
+            let _ = this.update(cx, |app, cx| {
+                if let Some(check) = app.size_check.as_mut() {
+                    check.running = false;
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn cancel_size_check(&mut self) {
+        if let Some(check) = self.size_check.take() {
+            check.cancel.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub(super) fn deselect_shrunk(&mut self, cx: &mut Context<Self>) {
+        let Some(check) = &self.size_check else {
+            return;
+        };
+        let flagged: Vec<PathBuf> = check.flagged().into_iter().cloned().collect();
+        self.change_selection(cx, |app| {
+            for path in &flagged {
+                app.selected.remove(path);
+            }
+        });
+        if let Some(check) = self.size_check.as_mut() {
+            check.results.retain(|(path, _, _)| !flagged.contains(path));
+            check.total = check.total.saturating_sub(flagged.len());
+        }
+    }
+
+    pub(super) fn keep_newest_duplicates(&mut self, cx: &mut Context<Self>) {
+        let newest: Vec<PathBuf> = self
+            .fully_selected_duplicates()
+            .into_iter()
+            .map(|(_, _, path)| path)
+            .collect();
+        self.change_selection(cx, |app| {
+            for path in &newest {
+                app.selected.remove(path);
+            }
+        });
+    }
+
+    pub(super) fn verify_duplicate_group(&mut self, hash: String, cx: &mut Context<Self>) {
+        if self.viewing_bundle.is_some() {
+            return;
+        }
+        if !self.verifying.insert(hash.clone()) {
+            return;
+        }
+        let members: Vec<ScannedItem> = self
+            .store
+            .items()
+            .iter()
+            .filter(|item| item.hash.as_deref() == Some(hash.as_str()))
+            .cloned()
+            .collect();
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let verified: Vec<ScannedItem> = cx
+                .background_executor()
+                .spawn(async move {
+                    let should_stop = AtomicBool::new(false);
+                    members
+                        .into_iter()
+                        .filter_map(|mut item| {
+                            item.hash = Some(hashing::full_hash(&item.path, &should_stop).ok()??);
+                            item.sampled_hash = false;
+                            Some(item)
+                        })
+                        .collect()
+                })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                app.verifying.remove(&hash);
+                let hashes: HashSet<&str> = verified
+                    .iter()
+                    .filter_map(|item| item.hash.as_deref())
+                    .collect();
+                app.notice = Some(if hashes.len() == 1 && verified.len() > 1 {
+                    "Verified: all copies are identical".to_string()
+                } else {
+                    "Verification found copies that differ; the group was split".to_string()
+                });
+                app.store
+                    .retain(|item| item.hash.as_deref() != Some(hash.as_str()));
+                for item in verified {
+                    app.store.push(item);
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn deselect_all(&mut self, cx: &mut Context<Self>) {
+        self.change_selection(cx, |app| app.selected.clear());
+    }
+
+    pub(super) fn selected_items(&self) -> impl Iterator<Item = &ScannedItem> {
+        self.store
+            .items()
+            .iter()
+            .filter(|i| self.selected.contains(&i.path))
+    }
+
+    /// Size of the selection as the cleaner will see it, with items inside
+    /// other selected items counted once, and how many were merged.
+    pub(super) fn selected_totals(&self) -> (u64, usize) {
+        let (targets, merged) = cleaner::normalize_selection(
+            self.selected_items()
+                .flat_map(CleanTarget::for_item)
+                .collect(),
+        );
+        (targets.iter().map(|target| target.size).sum(), merged)
+    }
+
+    pub(super) fn selected_size(&self) -> u64 {
+        self.selected_totals().0
+    }
+
+    pub(super) fn render_selection_presets(
+        &self,
+        disabled: bool,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let trigger = Button::new("selection_presets", "Select…")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::Sm)
+            .icon("filter");
+        if disabled {
+            return trigger.disabled(true).into_any_element();
+        }
+        let app = cx.entity().downgrade();
+        Popover::new("selection-presets")
+            .anchor(Corner::TopLeft)
+            .trigger(trigger)
+            .content(move |window, cx| {
+                let app = app.clone();
+                cx.new(|cx| {
+                    PopoverContent::new(window, cx, move |_window, _cx| {
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.0))
+                            .children(SelectionPreset::ALL.into_iter().map(|preset| {
+                                let app = app.clone();
+                                Button::new(
+                                    SharedString::from(format!("preset-{:?}", preset)),
+                                    preset.label(),
+                                )
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .on_click(move |_, _window, cx| {
+                                    let _ = app.update(cx, |app, cx| {
+                                        app.apply_selection_preset(preset, cx);
+                                    });
+                                })
+                            }))
+                            .into_any_element()
+                    })
+                })
+            })
+            .into_any_element()
+    }
+
+    pub(super) fn render_size_check(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let check = self.size_check.as_ref()?;
+        let theme = use_theme();
+        let flagged = check.flagged().len();
+        let changed: Vec<_> = check
+            .results
+            .iter()
+            .map(|(path, reported, measured)| {
+                (
+                    path,
+                    *reported,
+                    *measured,
+                    SizeDrift::classify(*reported, *measured),
+                )
+            })
+            .filter(|(_, _, _, drift)| *drift != SizeDrift::Unchanged)
+            .collect();
+        let status = if check.running {
+            format!("Verifying sizes… {}/{}", check.results.len(), check.total)
+        } else if changed.is_empty() {
+            format!("All {} sizes match the scan", check.results.len())
+        } else {
+            format!(
+                "{} of {} sizes changed since the scan",
+                changed.len(),
+                check.results.len()
+            )
+        };
+
+        Some(
+            div()
+                .p(px(12.0))
+                .bg(theme.tokens.muted.opacity(0.3))
+                .rounded(px(8.0))
+                .flex()
+                .flex_col()
+                .gap(px(6.0))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .when(check.running, |row| row.child(Spinner::new()))
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.foreground)
+                                        .child(status),
+                                ),
+                        )
+                        .when(check.running, |row| {
+                            row.child(
+                                Button::new("cancel_size_check", "Stop")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.cancel_size_check();
+                                        cx.notify();
+                                    })),
+                            )
+                        }),
+                )
+                .children(
+                    changed
+                        .iter()
+                        .take(6)
+                        .map(|(path, reported, measured, drift)| {
+                            let name = path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+                            let new_size = match measured {
+                                Some(size) if *drift != SizeDrift::Gone => {
+                                    bytesize::ByteSize(*size).to_string()
+                                }
+                                _ => "gone".to_string(),
+                            };
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .overflow_hidden()
+                                        .text_ellipsis()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.foreground)
+                                        .child(name),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child(format!(
+                                            "{} → {}",
+                                            bytesize::ByteSize(*reported),
+                                            new_size
+                                        )),
+                                )
+                                .when(drift.flagged(), |row| {
+                                    row.child(Badge::new("Shrank").variant(BadgeVariant::Warning))
+                                })
+                        }),
+                )
+                .when(changed.len() > 6, |list| {
+                    list.child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(theme.tokens.muted_foreground)
+                            .child(format!("and {} more", changed.len() - 6)),
+                    )
+                })
+                .when(flagged > 0, |list| {
+                    list.child(
+                        div().flex().justify_end().child(
+                            Button::new("deselect_shrunk", format!("Deselect {} Shrunk", flagged))
+                                .variant(ButtonVariant::Secondary)
+                                .size(ButtonSize::Sm)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.deselect_shrunk(cx);
+                                })),
+                        ),
+                    )
+                }),
+        )
+    }
+}
@@ -0,0 +1,1464 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn save_config(&mut self) -> error::Result<()> {
+        self.config.save()?;
+        self.synced_config = self.config.clone();
+        self.config_modified = Config::disk_modified();
+        Ok(())
+    }
+
+    pub(super) fn config_changed_on_disk(
+        &mut self,
+        modified: Option<SystemTime>,
+        disk: error::Result<Config>,
+        cx: &mut Context<Self>,
+    ) {
+        self.config_modified = modified;
+        let disk = match disk {
+            Ok(disk) => disk,
+            Err(e) => {
+                self.notice = Some(format!(
+                    "config.json on disk is invalid, keeping the current settings: {}",
+                    e
+                ));
+                cx.notify();
+                return;
+            }
+        };
+
+        match Config::reload_decision(&self.synced_config, &self.config, &disk) {
+            ReloadDecision::Unchanged => {
+                self.synced_config = disk;
+            }
+            ReloadDecision::Conflict => {
+                self.config_conflict = Some(disk);
+            }
+            ReloadDecision::Apply { affects_scan } if affects_scan && self.is_scanning => {
+                self.pending_disk_config = Some(disk);
+                self.notice = Some(
+                    "Config changed on disk — it will apply when the current scan finishes"
+                        .to_string(),
+                );
+            }
+            ReloadDecision::Apply { .. } => {
+                self.notice = Some("Config changed on disk — applied".to_string());
+                self.apply_disk_config(disk, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(super) fn apply_disk_config(&mut self, disk: Config, cx: &mut Context<Self>) {
+        let show_dismissed_changed = disk.show_dismissed != self.config.show_dismissed;
+        let control_changed = disk.control_server != self.config.control_server;
+        let scoring_changed = disk.confidence_overrides != self.config.confidence_overrides
+            || disk.stale_days_threshold != self.config.stale_days_threshold;
+
+        if disk.audit_mode {
+            audit::enable();
+        }
+        self.config = disk.clone();
+        self.synced_config = disk;
+        self.config_conflict = None;
+        if !self.is_scanning {
+            self.scanner = Arc::new(Self::build_scanner(&self.config, self.scan_scope.as_ref()));
+        }
+
+        if show_dismissed_changed {
+            if self.config.show_dismissed {
+                for item in std::mem::take(&mut self.dismissed_items) {
+                    self.store.push(item);
+                }
+            } else {
+                self.hide_dismissed();
+            }
+        }
+        if control_changed {
+            if self.config.control_server {
+                self.start_control_server(cx);
+            } else {
+                self.control = None;
+            }
+        }
+        if scoring_changed {
+            self.reclassify_results();
+        }
+        self.sync_delete_mode(cx);
+    }
+
+    pub(super) fn sync_delete_mode(&mut self, cx: &mut Context<Self>) {
+        let mode = self.config.delete_mode();
+        if !self.mode_guard.update(mode) {
+            return;
+        }
+        cx.background_executor()
+            .spawn(async move {
+                let mut log = ActivityLog::load();
+                if log.record_mode(mode, Utc::now()) {
+                    if let Err(e) = log.save() {
+                        log::warn!("Failed to save activity log: {}", e);
+                    }
+                }
+            })
+            .detach();
+    }
+
+    pub(super) fn switch_to_trash(&mut self, cx: &mut Context<Self>) {
+        self.config.use_trash = true;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        self.sync_delete_mode(cx);
+        cx.notify();
+    }
+
+    pub(super) fn resolve_config_conflict(&mut self, use_disk: bool, cx: &mut Context<Self>) {
+        let Some(disk) = self.config_conflict.take() else {
+            return;
+        };
+        if !use_disk {
+            if let Err(e) = self.save_config() {
+                self.notice = Some(format!("Could not save settings: {}", e));
+            }
+        } else if self.is_scanning && self.config.affects_scan(&disk) {
+            self.pending_disk_config = Some(disk);
+        } else {
+            self.apply_disk_config(disk, cx);
+        }
+        cx.notify();
+    }
+
+    pub(super) fn set_metrics_export(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.config.metrics_path = enabled.then(Config::default_metrics_path);
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        } else if let Some(path) = &self.config.metrics_path {
+            self.notice = Some(format!(
+                "Scan metrics will be written to {}",
+                path.display()
+            ));
+        }
+        cx.notify();
+    }
+
+    pub(super) fn set_deletion_grace_period(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.config.deletion_grace_period = enabled;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        cx.notify();
+    }
+
+    pub(super) fn adjust_confidence_override(
+        &mut self,
+        category: FileCategory,
+        delta: Option<f32>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_scanning {
+            return;
+        }
+        let value = delta.map(|delta| self.config.base_confidence(category) + delta);
+        self.config.set_confidence_override(category, value);
+        self.scanner = Arc::new(Self::build_scanner(&self.config, self.scan_scope.as_ref()));
+        self.reclassify_results();
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        cx.notify();
+    }
+
+    pub(super) fn reclassify_results(&mut self) {
+        if self.is_scanning || self.viewing_bundle.is_some() || self.store.is_empty() {
+            return;
+        }
+        self.store.reclassify(&self.config);
+        self.notice = Some(
+            "Confidence recalculated from new settings — sizes unchanged until next scan"
+                .to_string(),
+        );
+    }
+
+    pub(super) fn select_threshold_category(
+        &mut self,
+        category: FileCategory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.threshold_category = Some(category);
+        let current = bytesize::ByteSize(self.config.min_size(category)).to_string();
+        self.threshold_input
+            .update(cx, |input, cx| input.set_value(current, window, cx));
+        cx.notify();
+    }
+
+    pub(super) fn apply_threshold(&mut self, cx: &mut Context<Self>) {
+        let Some(category) = self.threshold_category else {
+            return;
+        };
+        if self.is_scanning {
+            return;
+        }
+        match config::parse_size(self.threshold_input.read(cx).content()) {
+            Ok(bytes) => {
+                self.config.min_sizes.insert(category, bytes);
+                self.scanner =
+                    Arc::new(Self::build_scanner(&self.config, self.scan_scope.as_ref()));
+                self.threshold_category = None;
+                if let Err(e) = self.save_config() {
+                    self.notice = Some(format!("Could not save settings: {}", e));
+                }
+            }
+            Err(e) => self.notice = Some(format!("Invalid size: {}", e)),
+        }
+        cx.notify();
+    }
+
+    pub(super) fn reset_scan_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let values = [
+            (&self.max_depth_input, self.config.max_depth.to_string()),
+            (
+                &self.large_file_input,
+                bytesize::ByteSize(self.config.large_file_threshold).to_string(),
+            ),
+            (
+                &self.download_days_input,
+                self.config.old_download_days.to_string(),
+            ),
+            (&self.scan_path_input, String::new()),
+        ];
+        for (input, value) in values {
+            input.update(cx, |input, cx| input.set_value(value, window, cx));
+        }
+        self.settings_error = None;
+    }
+
+    /// Applies `change` to a copy of the config and keeps it only if it
+    /// validates; the scanner is rebuilt so the next scan uses it.
+    pub(super) fn update_config(
+        &mut self,
+        change: impl FnOnce(&mut Config),
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.is_scanning {
+            self.settings_error = Some("Scan settings can't change during a scan".to_string());
+            cx.notify();
+            return false;
+        }
+        let mut config = self.config.clone();
+        change(&mut config);
+        if let Err(message) = config.validate() {
+            self.settings_error = Some(message);
+            cx.notify();
+            return false;
+        }
+
+        self.config = config;
+        self.settings_error = None;
+        self.scanner = Arc::new(Self::build_scanner(&self.config, self.scan_scope.as_ref()));
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        self.sync_delete_mode(cx);
+        cx.notify();
+        true
+    }
+
+    pub(super) fn add_scan_path(&mut self, path: PathBuf, cx: &mut Context<Self>) -> bool {
+        let path = match path.strip_prefix("~") {
+            Ok(rest) => dirs::home_dir().map_or_else(|| path.clone(), |home| home.join(rest)),
+            Err(_) => path,
+        };
+        let problem = if path.as_os_str().is_empty() {
+            Some("Enter a folder to scan".to_string())
+        } else if !path.exists() {
+            Some(format!("{} does not exist", path.display()))
+        } else if !path.is_dir() {
+            Some(format!("{} is not a folder", path.display()))
+        } else if self.config.scan_paths.contains(&path) {
+            Some(format!("{} is already scanned", path.display()))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            self.settings_error = Some(problem);
+            cx.notify();
+            return false;
+        }
+        self.update_config(|config| config.scan_paths.push(path), cx)
+    }
+
+    pub(super) fn browse_scan_path(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Add".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            if let Some(path) = paths.into_iter().next() {
+                let _ = this.update(cx, |app, cx| app.add_scan_path(path, cx));
+            }
+        })
+        .detach();
+    }
+
+    pub(super) fn remove_scan_path(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.config.scan_paths.len() == 1 {
+            self.settings_error = Some("Keep at least one folder to scan".to_string());
+            cx.notify();
+            return;
+        }
+        self.update_config(|config| config.scan_paths.retain(|p| *p != path), cx);
+    }
+
+    pub(super) fn apply_scan_limits(&mut self, cx: &mut Context<Self>) {
+        let max_depth = match self
+            .max_depth_input
+            .read(cx)
+            .content()
+            .trim()
+            .parse::<u32>()
+        {
+            Ok(depth) => depth,
+            Err(_) => {
+                self.settings_error = Some("Max depth must be a whole number".to_string());
+                cx.notify();
+                return;
+            }
+        };
+        let large_file_threshold =
+            match config::parse_size(self.large_file_input.read(cx).content()) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    self.settings_error = Some(format!("Invalid large file size: {}", e));
+                    cx.notify();
+                    return;
+                }
+            };
+        let old_download_days = match self
+            .download_days_input
+            .read(cx)
+            .content()
+            .trim()
+            .parse::<u64>()
+        {
+            Ok(days) => days,
+            Err(_) => {
+                self.settings_error = Some("Old download age must be a number of days".to_string());
+                cx.notify();
+                return;
+            }
+        };
+        self.update_config(
+            |config| {
+                config.max_depth = max_depth;
+                config.large_file_threshold = large_file_threshold;
+                config.old_download_days = old_download_days;
+            },
+            cx,
+        );
+    }
+
+    pub(super) fn set_list_density(&mut self, density: ListDensity, cx: &mut Context<Self>) {
+        self.config.list_density = density;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        cx.notify();
+    }
+
+    pub(super) fn refresh_data_usage(&mut self, cx: &mut Context<Self>) {
+        let config = self.config.clone();
+        cx.spawn(async move |this, cx| {
+            let usage = cx
+                .background_executor()
+                .spawn(async move {
+                    DataStore::ALL
+                        .iter()
+                        .map(|store| (*store, store.size(&config)))
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                app.data_usage = usage;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn clear_data_store(&mut self, store: DataStore, cx: &mut Context<Self>) {
+        self.confirm_clear_quarantine = false;
+        match store.clear(&self.config) {
+            Ok(()) => {
+                self.notice = Some(format!("Cleared {}", store.label().to_lowercase()));
+                if store == DataStore::Profiles {
+                    self.profiles = Profile::list_saved();
+                    self.active_profile = None;
+                }
+                if store == DataStore::History {
+                    self.history = History::default();
+                }
+            }
+            Err(e) => {
+                self.notice = Some(format!(
+                    "Could not clear {}: {}",
+                    store.label().to_lowercase(),
+                    e
+                ))
+            }
+        }
+        self.refresh_data_usage(cx);
+        cx.notify();
+    }
+
+    pub(super) fn render_preferences(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let check_for_updates = self.config.check_for_updates;
+        let grace_period = self.config.deletion_grace_period;
+        let control_server = self.config.control_server;
+        let metrics_export = self.config.metrics_path.is_some();
+        let crash_report_paths = self.config.crash_report_paths;
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .id("check_for_updates")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_check_for_updates(!check_for_updates, cx);
+                                }))
+                                .child(
+                                    Checkbox::new("check_for_updates_box")
+                                        .checked(check_for_updates),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("Check for updates on startup"),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("deletion_grace_period")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_deletion_grace_period(!grace_period, cx);
+                                }))
+                                .child(
+                                    Checkbox::new("deletion_grace_period_box")
+                                        .checked(grace_period),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("Countdown before deleting"),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("control_server")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_control_server(!control_server, cx);
+                                }))
+                                .child(Checkbox::new("control_server_box").checked(control_server))
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("Local control socket"),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("metrics_export")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_metrics_export(!metrics_export, cx);
+                                }))
+                                .child(Checkbox::new("metrics_export_box").checked(metrics_export))
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("Export scan metrics"),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("crash_report_paths")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.config.crash_report_paths = !crash_report_paths;
+                                    if let Err(e) = this.save_config() {
+                                        this.notice =
+                                            Some(format!("Could not save settings: {}", e));
+                                    }
+                                    cx.notify();
+                                }))
+                                .child(
+                                    Checkbox::new("crash_report_paths_box")
+                                        .checked(crash_report_paths),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("File paths in crash reports"),
+                                ),
+                        ),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Density"),
+                        )
+                        .children(
+                            [
+                                (ListDensity::Comfortable, "Comfortable"),
+                                (ListDensity::Compact, "Compact"),
+                            ]
+                            .into_iter()
+                            .map(|(density, label)| {
+                                Button::new(SharedString::from(format!("density-{}", label)), label)
+                                    .variant(if self.config.list_density == density {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Ghost
+                                    })
+                                    .size(ButtonSize::Sm)
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.set_list_density(density, cx);
+                                    }))
+                            }),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_config_toggle(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        checked: bool,
+        apply: fn(&mut Config, bool),
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = use_theme();
+
+        div()
+            .id(id)
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .cursor_pointer()
+            .on_click(cx.listener(move |this, _, _window, cx| {
+                this.update_config(|config| apply(config, !checked), cx);
+            }))
+            .child(Checkbox::new(SharedString::from(format!("{}_box", id))).checked(checked))
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(theme.tokens.muted_foreground)
+                    .child(label),
+            )
+    }
+
+    pub(super) fn render_scan_settings(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let is_scanning = self.is_scanning;
+        let single_path = self.config.scan_paths.len() == 1;
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(8.0))
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Scan folders (applies to the next scan)"),
+                )
+                .child(div().flex().flex_wrap().gap(px(6.0)).children(
+                    self.config.scan_paths.iter().map(|path| {
+                        let remove = path.clone();
+                        div()
+                            .id(SharedString::from(format!("scan-path-{}", path.display())))
+                            .flex()
+                            .items_center()
+                            .gap(px(4.0))
+                            .pl(px(8.0))
+                            .rounded(px(6.0))
+                            .bg(theme.tokens.muted.opacity(0.5))
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.tokens.foreground)
+                                    .child(path.display().to_string()),
+                            )
+                            .child(
+                                IconButton::new("x")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(px(24.0))
+                                    .icon_size(px(12.0))
+                                    .disabled(is_scanning || single_path)
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.remove_scan_path(remove.clone(), cx);
+                                    })),
+                            )
+                    }),
+                ))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            div()
+                                .w(px(320.0))
+                                .child(Input::new(&self.scan_path_input).placeholder("~/Projects")),
+                        )
+                        .child(
+                            Button::new("add_scan_path", "Add")
+                                .variant(ButtonVariant::Default)
+                                .size(ButtonSize::Sm)
+                                .disabled(is_scanning)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    let path = PathBuf::from(
+                                        this.scan_path_input.read(cx).content().trim(),
+                                    );
+                                    if this.add_scan_path(path, cx) {
+                                        this.scan_path_input.update(cx, |input, cx| {
+                                            input.set_value("", window, cx)
+                                        });
+                                    }
+                                })),
+                        )
+                        .child(
+                            Button::new("browse_scan_path", "Browse…")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .disabled(is_scanning)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.browse_scan_path(cx);
+                                })),
+                        ),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(16.0))
+                        .child(self.render_config_toggle(
+                            "use_trash",
+                            "Move to the trash",
+                            self.config.use_trash,
+                            |config, value| config.use_trash = value,
+                            cx,
+                        ))
+                        .child(self.render_config_toggle(
+                            "dry_run",
+                            "Dry run",
+                            self.config.dry_run,
+                            |config, value| config.dry_run = value,
+                            cx,
+                        ))
+                        .child(self.render_config_toggle(
+                            "follow_symlinks",
+                            "Follow symlinks",
+                            self.config.follow_symlinks,
+                            |config, value| config.follow_symlinks = value,
+                            cx,
+                        ))
+                        .child(self.render_config_toggle(
+                            "same_filesystem_only",
+                            "Stay on one filesystem",
+                            self.config.same_filesystem_only,
+                            |config, value| config.same_filesystem_only = value,
+                            cx,
+                        )),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Max depth"),
+                        )
+                        .child(div().w(px(64.0)).child(Input::new(&self.max_depth_input)))
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Large files ≥"),
+                        )
+                        .child(
+                            div()
+                                .w(px(100.0))
+                                .child(Input::new(&self.large_file_input).placeholder("100 MB")),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Old downloads after (days)"),
+                        )
+                        .child(
+                            div()
+                                .w(px(64.0))
+                                .child(Input::new(&self.download_days_input)),
+                        )
+                        .child(
+                            Button::new("apply_scan_limits", "Apply")
+                                .variant(ButtonVariant::Default)
+                                .size(ButtonSize::Sm)
+                                .disabled(is_scanning)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.apply_scan_limits(cx);
+                                })),
+                        ),
+                )
+                .child(div().flex().flex_wrap().gap(px(12.0)).children(
+                    FileCategory::ALL.into_iter().map(|category| {
+                        let enabled = self.config.enabled_categories.contains(&category);
+                        div()
+                            .id(SharedString::from(format!("enable-{:?}", category)))
+                            .flex()
+                            .items_center()
+                            .gap(px(6.0))
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                this.update_config(
+                                    |config| {
+                                        if enabled {
+                                            config.enabled_categories.remove(&category);
+                                        } else {
+                                            config.enabled_categories.insert(category);
+                                        }
+                                    },
+                                    cx,
+                                );
+                            }))
+                            .child(
+                                Checkbox::new(SharedString::from(format!(
+                                    "enable-{:?}-box",
+                                    category
+                                )))
+                                .checked(enabled),
+                            )
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.tokens.muted_foreground)
+                                    .child(category.name()),
+                            )
+                    }),
+                ))
+                .when_some(self.settings_error.as_ref(), |panel, error| {
+                    panel.child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(theme.tokens.destructive)
+                            .child(error.clone()),
+                    )
+                }),
+        )
+    }
+
+    pub(super) fn render_confidence_overrides(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let is_scanning = self.is_scanning;
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(8.0))
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Confidence per category"),
+                )
+                .child(div().flex().flex_wrap().gap(px(8.0)).children(
+                    FileCategory::ALL.into_iter().map(|category| {
+                        let overridden = self.config.confidence_override(category).is_some();
+                        let percent = (self.config.base_confidence(category) * 100.0).round() as u8;
+                        let id = format!("{:?}", category);
+
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(4.0))
+                            .px(px(8.0))
+                            .py(px(2.0))
+                            .rounded(px(6.0))
+                            .border_1()
+                            .border_color(if overridden {
+                                theme.tokens.primary.opacity(0.5)
+                            } else {
+                                theme.tokens.border
+                            })
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.tokens.foreground)
+                                    .child(format!("{} {}%", category.name(), percent)),
+                            )
+                            .child(
+                                Button::new(SharedString::from(format!("conf-down-{}", id)), "−")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .disabled(is_scanning || percent == 0)
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.adjust_confidence_override(
+                                            category,
+                                            Some(-CONFIDENCE_OVERRIDE_STEP),
+                                            cx,
+                                        );
+                                    })),
+                            )
+                            .child(
+                                Button::new(SharedString::from(format!("conf-up-{}", id)), "+")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .disabled(is_scanning || percent >= 100)
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.adjust_confidence_override(
+                                            category,
+                                            Some(CONFIDENCE_OVERRIDE_STEP),
+                                            cx,
+                                        );
+                                    })),
+                            )
+                            .when(overridden, |chip| {
+                                chip.child(
+                                    Button::new(
+                                        SharedString::from(format!("conf-reset-{}", id)),
+                                        "Reset",
+                                    )
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .icon("rotate-ccw")
+                                    .disabled(is_scanning)
+                                    .on_click(cx.listener(
+                                        move |this, _, _window, cx| {
+                                            this.adjust_confidence_override(category, None, cx);
+                                        },
+                                    )),
+                                )
+                            })
+                    }),
+                )),
+        )
+    }
+
+    pub(super) fn render_dismissed(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let show_dismissed = self.config.show_dismissed;
+        let mut dismissed: Vec<&PathBuf> = self.config.dismissed.keys().collect();
+        dismissed.sort();
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(8.0))
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!("Dismissed items ({})", dismissed.len())),
+                        )
+                        .child(
+                            div()
+                                .id("show_dismissed")
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .cursor_pointer()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_show_dismissed(!show_dismissed, cx);
+                                }))
+                                .child(Checkbox::new("show_dismissed_box").checked(show_dismissed))
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("Show dismissed items"),
+                                ),
+                        ),
+                )
+                .children(dismissed.into_iter().map(|path| {
+                    let key = path.clone();
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .gap(px(8.0))
+                        .child(
+                            div()
+                                .flex_1()
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.foreground)
+                                .child(path.to_string_lossy().to_string()),
+                        )
+                        .child(
+                            Button::new(
+                                SharedString::from(format!("undismiss-{}", path.display())),
+                                "Restore",
+                            )
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .icon("eye")
+                            .on_click(cx.listener(
+                                move |this, _, _window, cx| {
+                                    this.undismiss_item(key.clone(), cx);
+                                },
+                            )),
+                        )
+                })),
+        )
+    }
+
+    pub(super) fn render_ignored(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings || self.config.ignored_paths.is_empty() {
+            return None;
+        }
+
+        let theme = use_theme();
+        let is_scanning = self.is_scanning;
+        let mut ignored: Vec<&PathBuf> = self.config.ignored_paths.iter().collect();
+        ignored.sort();
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(8.0))
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child(format!(
+                            "Ignored paths ({}, applies to the next scan)",
+                            ignored.len()
+                        )),
+                )
+                .children(ignored.into_iter().map(|path| {
+                    let remove = path.clone();
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .gap(px(8.0))
+                        .child(
+                            div()
+                                .flex_1()
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.foreground)
+                                .child(path.to_string_lossy().to_string()),
+                        )
+                        .child(
+                            Button::new(
+                                SharedString::from(format!("unignore-{}", path.display())),
+                                "Remove",
+                            )
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .icon("x")
+                            .disabled(is_scanning)
+                            .on_click(cx.listener(
+                                move |this, _, _window, cx| {
+                                    this.unignore_path(remove.clone(), cx);
+                                },
+                            )),
+                        )
+                })),
+        )
+    }
+
+    pub(super) fn render_size_thresholds(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let is_scanning = self.is_scanning;
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(8.0))
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Minimum size per category (applies to the next scan)"),
+                )
+                .child(div().flex().flex_wrap().gap(px(8.0)).children(
+                    FileCategory::ALL.into_iter().map(|category| {
+                        let selected = self.threshold_category == Some(category);
+                        let label = format!(
+                            "{} ≥ {}",
+                            category.name(),
+                            bytesize::ByteSize(self.config.min_size(category))
+                        );
+
+                        Button::new(
+                            SharedString::from(format!("min-size-{:?}", category)),
+                            label,
+                        )
+                        .variant(if selected {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Outline
+                        })
+                        .size(ButtonSize::Sm)
+                        .disabled(is_scanning)
+                        .on_click(cx.listener(
+                            move |this, _, window, cx| {
+                                this.select_threshold_category(category, window, cx);
+                            },
+                        ))
+                    }),
+                ))
+                .when_some(self.threshold_category, |panel, category| {
+                    panel.child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(8.0))
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.tokens.foreground)
+                                    .child(category.name()),
+                            )
+                            .child(
+                                div()
+                                    .w(px(160.0))
+                                    .child(Input::new(&self.threshold_input).placeholder("250 MB")),
+                            )
+                            .child(
+                                Button::new("apply_min_size", "Apply")
+                                    .variant(ButtonVariant::Default)
+                                    .size(ButtonSize::Sm)
+                                    .disabled(is_scanning)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.apply_threshold(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("cancel_min_size", "Cancel")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Sm)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.threshold_category = None;
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
+                }),
+        )
+    }
+
+    pub(super) fn render_exclusions(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child(format!(
+                            "{} exclusion patterns",
+                            self.config.exclude_patterns.len()
+                        )),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            Button::new("import_exclusions", "Import Exclusions…")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("upload")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.import_exclusions(cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("export_exclusions", "Export Exclusions…")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("download")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.export_exclusions(cx);
+                                })),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_bundles(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let can_export = !self.store.is_empty() && self.viewing_bundle.is_none();
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Scan bundles compare results across machines"),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            Button::new("open_bundle", "Open Bundle…")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("upload")
+                                .disabled(self.is_scanning)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.open_bundle(cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("export_bundle", "Export Bundle…")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("download")
+                                .disabled(!can_export)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.export_bundle(cx);
+                                })),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_custom_rules(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+        let rules = &self.custom_rules;
+        let summary = if rules.is_empty() && rules.warnings.is_empty() {
+            format!("No custom rules. Add them to {}", RuleSet::path().display())
+        } else {
+            format!(
+                "{} custom rules from {}",
+                rules.rules.len(),
+                RuleSet::path().display()
+            )
+        };
+
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .child(summary),
+                        )
+                        .child(
+                            Button::new("reload_rules", "Reload")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("refresh-cw")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.custom_rules = RuleSet::load();
+                                    cx.notify();
+                                })),
+                        ),
+                )
+                .children(rules.warnings.iter().map(|warning| {
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            Icon::new("triangle-alert")
+                                .size(px(12.0))
+                                .color(theme.tokens.destructive),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.destructive)
+                                .child(warning.clone()),
+                        )
+                })),
+        )
+    }
+
+    pub(super) fn render_data_usage(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings || self.data_usage.is_empty() {
+            return None;
+        }
+
+        let theme = use_theme();
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .child("Sweeper data"),
+                )
+                .child(div().flex().items_center().gap(px(16.0)).children(
+                    self.data_usage.iter().map(|(store, size)| {
+                        let store = *store;
+                        let configured = store.location(&self.config).is_some();
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(6.0))
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.tokens.foreground)
+                                    .child(if configured {
+                                        format!("{}: {}", store.label(), bytesize::ByteSize(*size))
+                                    } else {
+                                        format!("{}: not configured", store.label())
+                                    }),
+                            )
+                            .when(
+                                store.clearable() && configured && !audit::is_enabled(),
+                                |row| {
+                                    row.child(
+                                        Button::new(
+                                            SharedString::from(format!(
+                                                "clear-{}",
+                                                store.label().to_lowercase()
+                                            )),
+                                            "Clear",
+                                        )
+                                        .variant(ButtonVariant::Ghost)
+                                        .size(ButtonSize::Sm)
+                                        .disabled(*size == 0)
+                                        .on_click(
+                                            cx.listener(move |this, _, _window, cx| {
+                                                if store == DataStore::Quarantine {
+                                                    this.confirm_clear_quarantine = true;
+                                                    cx.notify();
+                                                } else {
+                                                    this.clear_data_store(store, cx);
+                                                }
+                                            }),
+                                        ),
+                                    )
+                                },
+                            )
+                    }),
+                )),
+        )
+    }
+
+    pub(super) fn render_config_conflict(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        self.config_conflict.as_ref()?;
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.muted.opacity(0.4))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Icon::new("triangle-alert")
+                                .size(px(14.0))
+                                .color(theme.tokens.muted_foreground),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.foreground)
+                                .child("config.json changed on disk, but settings here have unsaved changes"),
+                        ),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            Button::new("keep_app_config", "Keep Mine")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.resolve_config_conflict(false, cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("use_disk_config", "Use File on Disk")
+                                .variant(ButtonVariant::Outline)
+                                .size(ButtonSize::Sm)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.resolve_config_conflict(true, cx);
+                                })),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,713 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn review_profile(&mut self, profile: Profile, cx: &mut Context<Self>) {
+        let changes = profile.diff(&self.config);
+        self.pending_profile = Some((profile, changes));
+        cx.notify();
+    }
+
+    pub(super) fn apply_pending_profile(&mut self, cx: &mut Context<Self>) {
+        let Some((profile, _)) = self.pending_profile.take() else {
+            return;
+        };
+        if self.is_scanning {
+            self.notice = Some("Wait for the scan to finish before applying a profile".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.config = profile.apply(&self.config);
+        self.scanner = Arc::new(Self::build_scanner(&self.config, self.scan_scope.as_ref()));
+        self.sync_delete_mode(cx);
+        self.notice = match self.save_config() {
+            Ok(()) => Some(format!("Applied profile \"{}\"", profile.name)),
+            Err(e) => Some(format!(
+                "Applied profile \"{}\" for this session, but saving settings failed: {}",
+                profile.name, e
+            )),
+        };
+        self.active_profile = Some(profile.name);
+        cx.notify();
+    }
+
+    pub(super) fn import_profile(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import Profile".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+
+            let imported = Profile::load(&path).and_then(|profile| {
+                profile.save(&Profile::profiles_dir().join(profile.file_name()))?;
+                Ok(profile)
+            });
+
+            let _ = this.update(cx, |app, cx| match imported {
+                Ok(profile) => {
+                    app.profiles = Profile::list_saved();
+                    app.review_profile(profile, cx);
+                }
+                Err(e) => {
+                    app.notice = Some(format!("Could not import {}: {}", path.display(), e));
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn export_profile(&mut self, cx: &mut Context<Self>) {
+        let name = self
+            .active_profile
+            .clone()
+            .unwrap_or_else(|| "My cleanup profile".to_string());
+        let profile = Profile::from_config(name, &self.config);
+        let directory = Profile::profiles_dir();
+        let _ = std::fs::create_dir_all(&directory);
+        let path = cx.prompt_for_new_path(&directory, Some(&profile.file_name()));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+
+            let saved = profile.save(&path);
+            let _ = this.update(cx, |app, cx| {
+                app.notice = Some(match saved {
+                    Ok(()) => format!("Exported profile to {}", path.display()),
+                    Err(e) => format!("Could not export profile: {}", e),
+                });
+                app.profiles = Profile::list_saved();
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn export_bundle(&mut self, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+        let directory = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let bundle = ScanBundle::capture(&store);
+        let path = cx.prompt_for_new_path(&directory, Some(&bundle.file_name()));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+
+            let target = path.clone();
+            let saved = cx
+                .background_executor()
+                .spawn(async move { bundle.save(&target) })
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                app.notice = Some(match saved {
+                    Ok(()) => format!("Exported scan bundle to {}", path.display()),
+                    Err(e) => format!("Could not export scan bundle: {}", e),
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn export_report(&mut self, cx: &mut Context<Self>) {
+        if self.report_export.is_some() {
+            return;
+        }
+        let items = self.store.items().to_vec();
+        let stats = self.store.stats().clone();
+        let selected = self.selected.clone();
+        let directory = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let path = cx.prompt_for_new_path(&directory, Some(&report::default_file_name()));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+
+            let progress = Arc::new(ExportProgress::default());
+            let started = this.update(cx, |app, cx| {
+                app.report_export = Some(ReportExport {
+                    total: items.len(),
+                    progress: progress.clone(),
+                });
+                app.resume_polling(cx);
+                cx.notify();
+            });
+            if started.is_err() {
+                return;
+            }
+
+            let target = path.clone();
+            let saved = cx
+                .background_executor()
+                .spawn(async move {
+                    ScanReport::capture(&items, &stats, &selected).save(&target, &progress)
+                })
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                app.report_export = None;
+                app.notice = Some(match saved {
+                    Ok(Some(count)) => {
+                        format!("Exported a report of {} items to {}", count, path.display())
+                    }
+                    Ok(None) => "Report export stopped".to_string(),
+                    Err(e) => format!("Could not export the report: {}", e),
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn open_bundle(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Open Bundle".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+
+            let target = path.clone();
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { ScanBundle::load(&target) })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                match loaded {
+                    Ok(bundle) => app.view_bundle(bundle),
+                    Err(e) => {
+                        app.notice = Some(format!("Could not open {}: {}", path.display(), e));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn view_bundle(&mut self, bundle: LoadedBundle) {
+        if self.is_scanning {
+            return;
+        }
+        self.notice = if bundle.is_newer() {
+            Some(format!(
+                "This bundle was written by a newer version of Sweeper (format {}); some details may be missing",
+                bundle.version
+            ))
+        } else if bundle.skipped > 0 {
+            Some(format!(
+                "Skipped {} unreadable items in the bundle",
+                bundle.skipped
+            ))
+        } else {
+            None
+        };
+
+        let local = std::mem::replace(&mut self.store, bundle.store);
+        if self.local_store.is_none() {
+            self.local_store = Some(local);
+        }
+        self.viewing_bundle = Some((bundle.host, bundle.created_at));
+        self.selected.clear();
+        self.outdated.clear();
+    }
+
+    pub(super) fn close_bundle(&mut self, cx: &mut Context<Self>) {
+        self.viewing_bundle = None;
+        self.store = self.local_store.take().unwrap_or_default();
+        self.selected.clear();
+        cx.notify();
+    }
+
+    pub(super) fn rescan_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        if self.is_scanning || paths.is_empty() || self.viewing_bundle.is_some() {
+            return;
+        }
+
+        let paths: HashSet<PathBuf> = paths.into_iter().collect();
+        let stale: Vec<ScannedItem> = self
+            .store
+            .items()
+            .iter()
+            .filter(|item| paths.contains(&item.path))
+            .cloned()
+            .collect();
+
+        cx.spawn(async move |this, cx| {
+            let refreshed: Vec<ScannedItem> = cx
+                .background_executor()
+                .spawn(async move { stale.iter().filter_map(ScannedItem::refreshed).collect() })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                if app.is_scanning {
+                    return;
+                }
+                app.store.retain(|item| !paths.contains(&item.path));
+                for item in refreshed {
+                    app.store.push(item);
+                }
+                app.selected.retain(|path| {
+                    !paths.contains(path) || app.store.items().iter().any(|item| &item.path == path)
+                });
+                app.outdated.retain(|path| !paths.contains(path));
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn import_exclusions(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import Exclusions".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+
+            let contents = std::fs::read_to_string(&path);
+            let _ = this.update(cx, |app, cx| {
+                match contents {
+                    Ok(contents) => {
+                        app.pending_exclusions = Some(ExclusionImport::plan(
+                            &app.config.exclude_patterns,
+                            &contents,
+                        ));
+                    }
+                    Err(e) => {
+                        app.notice = Some(format!("Could not read {}: {}", path.display(), e));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn apply_pending_exclusions(&mut self, cx: &mut Context<Self>) {
+        let Some(import) = self.pending_exclusions.take() else {
+            return;
+        };
+        if self.is_scanning {
+            self.notice =
+                Some("Wait for the scan to finish before changing exclusions".to_string());
+            cx.notify();
+            return;
+        }
+
+        let added = import.added.len();
+        self.config.exclude_patterns.extend(import.added);
+        self.scanner = Arc::new(Self::build_scanner(&self.config, self.scan_scope.as_ref()));
+        self.notice = Some(match self.save_config() {
+            Ok(()) => format!("Added {} exclusions", added),
+            Err(e) => format!(
+                "Added {} exclusions for this session, but saving settings failed: {}",
+                added, e
+            ),
+        });
+        cx.notify();
+    }
+
+    pub(super) fn export_exclusions(&mut self, cx: &mut Context<Self>) {
+        let contents = exclusions::export(&self.config.exclude_patterns);
+        let directory = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let path = cx.prompt_for_new_path(&directory, Some("sweeper-exclusions.txt"));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+
+            let written = std::fs::write(&path, contents);
+            let _ = this.update(cx, |app, cx| {
+                app.notice = Some(match written {
+                    Ok(()) => format!("Exported exclusions to {}", path.display()),
+                    Err(e) => format!("Could not export exclusions: {}", e),
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn render_profiles(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_settings {
+            return None;
+        }
+
+        let theme = use_theme();
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(10.0))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(if self.profiles.is_empty() {
+                                    "No saved profiles"
+                                } else {
+                                    "Profiles"
+                                }),
+                        )
+                        .children(
+                            self.profiles
+                                .iter()
+                                .enumerate()
+                                .map(|(index, (_, profile))| {
+                                    let is_active =
+                                        self.active_profile.as_ref() == Some(&profile.name);
+                                    let profile = profile.clone();
+
+                                    Button::new(
+                                        SharedString::from(format!("profile-{}", index)),
+                                        profile.name.clone(),
+                                    )
+                                    .variant(if is_active {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Ghost
+                                    })
+                                    .size(ButtonSize::Sm)
+                                    .on_click(cx.listener(
+                                        move |this, _, _window, cx| {
+                                            this.review_profile(profile.clone(), cx);
+                                        },
+                                    ))
+                                }),
+                        ),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            Button::new("import_profile", "Import")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("upload")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.import_profile(cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("export_profile", "Export Current")
+                                .variant(ButtonVariant::Ghost)
+                                .size(ButtonSize::Sm)
+                                .icon("download")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.export_profile(cx);
+                                })),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_bundle_banner(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let (host, created_at) = self.viewing_bundle.as_ref()?;
+
+        let age = Utc::now() - *created_at;
+        let ago = if age.num_days() > 0 {
+            format!("{} days ago", age.num_days())
+        } else if age.num_hours() > 0 {
+            format!("{} hours ago", age.num_hours())
+        } else if age.num_minutes() > 0 {
+            format!("{} minutes ago", age.num_minutes())
+        } else {
+            "just now".to_string()
+        };
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.primary.opacity(0.12))
+                .border_b_1()
+                .border_color(theme.tokens.primary.opacity(0.4))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Icon::new("hard-drive")
+                                .size(px(14.0))
+                                .color(theme.tokens.primary),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!("Viewing results from {} ({})", host.hostname, ago)),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!(
+                                    "{} • {} free of {} • read-only",
+                                    host.os,
+                                    bytesize::ByteSize(host.free_disk),
+                                    bytesize::ByteSize(host.total_disk)
+                                )),
+                        ),
+                )
+                .child(
+                    Button::new("close_bundle", "Back to this machine")
+                        .variant(ButtonVariant::Outline)
+                        .size(ButtonSize::Sm)
+                        .icon("x")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.close_bundle(cx);
+                        })),
+                ),
+        )
+    }
+
+    pub(super) fn render_exclusions_dialog(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let import = self.pending_exclusions.as_ref()?;
+
+        let section = |title: String, patterns: &[String]| {
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .child(
+                    div()
+                        .text_size(px(13.0))
+                        .font_weight(FontWeight::MEDIUM)
+                        .text_color(theme.tokens.foreground)
+                        .child(title),
+                )
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.tokens.muted_foreground)
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .child(patterns.join(", ")),
+                )
+        };
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(520.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child("Import Exclusions"),
+                        )
+                        .child(section(
+                            format!("{} new patterns will be added", import.added.len()),
+                            &import.added,
+                        ))
+                        .when(!import.duplicates.is_empty(), |dialog| {
+                            dialog.child(section(
+                                format!("{} already excluded, skipped", import.duplicates.len()),
+                                &import.duplicates,
+                            ))
+                        })
+                        .when(!import.unsupported.is_empty(), |dialog| {
+                            dialog.child(section(
+                                format!(
+                                    "{} negated patterns aren't supported, skipped",
+                                    import.unsupported.len()
+                                ),
+                                &import.unsupported,
+                            ))
+                        })
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("cancel_exclusions", "Cancel")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.pending_exclusions = None;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("apply_exclusions", "Add Exclusions")
+                                        .variant(ButtonVariant::Default)
+                                        .icon("check")
+                                        .disabled(self.is_scanning || import.added.is_empty())
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.apply_pending_exclusions(cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_profile_dialog(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let (profile, changes) = self.pending_profile.as_ref()?;
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(560.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(20.0))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(4.0))
+                                .child(
+                                    div()
+                                        .text_size(px(20.0))
+                                        .font_weight(FontWeight::BOLD)
+                                        .text_color(theme.tokens.foreground)
+                                        .child(format!("Apply \"{}\"?", profile.name)),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(14.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child(if changes.is_empty() {
+                                            "This profile matches your current settings".to_string()
+                                        } else {
+                                            format!("{} settings will change", changes.len())
+                                        }),
+                                ),
+                        )
+                        .children(changes.iter().map(|change| {
+                            div()
+                                .p(px(12.0))
+                                .bg(theme.tokens.muted.opacity(0.3))
+                                .rounded(px(8.0))
+                                .flex()
+                                .flex_col()
+                                .gap(px(4.0))
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .text_color(theme.tokens.foreground)
+                                        .child(change.field),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .overflow_hidden()
+                                        .text_ellipsis()
+                                        .child(format!("{} → {}", change.from, change.to)),
+                                )
+                        }))
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("cancel_profile", "Cancel")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.pending_profile = None;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("apply_profile", "Apply Profile")
+                                        .variant(ButtonVariant::Default)
+                                        .icon("check")
+                                        .disabled(self.is_scanning)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.apply_pending_profile(cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,446 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn open_trash_log(&mut self, cx: &mut Context<Self>) {
+        self.trash_log = Some(ActivityLog::default());
+        self.trash_selected.clear();
+        self.trash_busy = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let log = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut log = ActivityLog::load();
+                    if log.mark_unrecoverable() > 0 {
+                        if let Err(e) = log.save() {
+                            log::warn!("Failed to save activity log: {}", e);
+                        }
+                    }
+                    log
+                })
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                if app.trash_log.is_some() {
+                    app.trash_log = Some(log);
+                }
+                app.trash_busy = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn restore_trash_selected(&mut self, cx: &mut Context<Self>) {
+        let Some(log) = &self.trash_log else {
+            return;
+        };
+        let entries: Vec<TrashedEntry> = log
+            .entries
+            .iter()
+            .filter(|entry| {
+                entry.status == TrashStatus::InTrash
+                    && self
+                        .trash_selected
+                        .contains(&(entry.batch, entry.path.clone()))
+            })
+            .cloned()
+            .collect();
+        self.restore_trash_entries(entries, None, cx);
+    }
+
+    pub(super) fn restore_trash_entries(
+        &mut self,
+        entries: Vec<TrashedEntry>,
+        destination: Option<PathBuf>,
+        cx: &mut Context<Self>,
+    ) {
+        if entries.is_empty() {
+            return;
+        }
+        self.trash_busy = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let (log, restored, homeless, failed) = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut log = ActivityLog::load();
+                    let mut restored = 0;
+                    let mut homeless = Vec::new();
+                    let mut failed = Vec::new();
+                    for entry in entries {
+                        match activity::restore(&entry, destination.as_deref()) {
+                            Ok(_) => {
+                                log.set_status(entry.batch, &entry.path, TrashStatus::Restored);
+                                restored += 1;
+                            }
+                            Err(RestoreError::ParentMissing) => homeless.push(entry),
+                            Err(RestoreError::Unrecoverable) => {
+                                log.set_status(
+                                    entry.batch,
+                                    &entry.path,
+                                    TrashStatus::Unrecoverable,
+                                );
+                                failed.push(format!(
+                                    "{}: {}",
+                                    entry.path.display(),
+                                    RestoreError::Unrecoverable
+                                ));
+                            }
+                            Err(e) => failed.push(format!("{}: {}", entry.path.display(), e)),
+                        }
+                    }
+                    if let Err(e) = log.save() {
+                        log::warn!("Failed to save activity log: {}", e);
+                    }
+                    (log, restored, homeless, failed)
+                })
+                .await;
+
+            let _ = this.update(cx, |app, cx| {
+                app.trash_busy = false;
+                app.trash_log = Some(log);
+                app.trash_selected.clear();
+                app.notice = Some(if failed.is_empty() {
+                    format!("Restored {} items from the trash", restored)
+                } else {
+                    format!(
+                        "Restored {} items; could not restore {}: {}",
+                        restored,
+                        failed.len(),
+                        failed.join("; ")
+                    )
+                });
+                if !homeless.is_empty() {
+                    app.prompt_restore_destination(homeless, cx);
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn prompt_restore_destination(
+        &mut self,
+        entries: Vec<TrashedEntry>,
+        cx: &mut Context<Self>,
+    ) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(
+                format!(
+                    "Original folder is gone — restore {} items to",
+                    entries.len()
+                )
+                .into(),
+            ),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(destination) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update(cx, |app, cx| {
+                app.restore_trash_entries(entries, Some(destination), cx);
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn reveal_trash(&mut self, cx: &mut Context<Self>) {
+        match activity::trash_dir() {
+            Ok(dir) => cx.open_url(&format!("file://{}", dir.display())),
+            Err(e) => self.notice = Some(format!("Could not open the trash: {}", e)),
+        }
+        cx.notify();
+    }
+
+    pub(super) fn render_trash_log_dialog(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let log = self.trash_log.as_ref()?;
+        let theme = use_theme();
+        let batches = log.batches();
+        let restorable = log
+            .entries
+            .iter()
+            .any(|entry| entry.status == TrashStatus::InTrash);
+
+        let batch_section = |batch: u64, entries: Vec<&TrashedEntry>| {
+            let trashed_at = entries
+                .first()
+                .map(|entry| {
+                    entry
+                        .trashed_at
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            let total: u64 = entries.iter().map(|entry| entry.size).sum();
+            div()
+                .flex()
+                .flex_col()
+                .gap(px(4.0))
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .font_weight(FontWeight::MEDIUM)
+                        .text_color(theme.tokens.muted_foreground)
+                        .child(format!(
+                            "Cleanup #{} • {} • {} items, {}",
+                            batch,
+                            trashed_at,
+                            entries.len(),
+                            bytesize::ByteSize(total)
+                        )),
+                )
+                .children(entries.into_iter().map(|entry| {
+                    let key = (entry.batch, entry.path.clone());
+                    let checked = self.trash_selected.contains(&key);
+                    let in_trash = entry.status == TrashStatus::InTrash;
+                    div()
+                        .id(SharedString::from(format!(
+                            "trash-entry-{}-{}",
+                            entry.batch,
+                            entry.path.display()
+                        )))
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .when(in_trash && activity::TARGETED_RESTORE, |row| {
+                            row.cursor_pointer().on_click(cx.listener(
+                                move |this, _, _window, cx| {
+                                    if !this.trash_selected.remove(&key) {
+                                        this.trash_selected.insert(key.clone());
+                                    }
+                                    cx.notify();
+                                },
+                            ))
+                        })
+                        .when(activity::TARGETED_RESTORE, |row| {
+                            row.child(
+                                Checkbox::new(SharedString::from(format!(
+                                    "trash-check-{}-{}",
+                                    entry.batch,
+                                    entry.path.display()
+                                )))
+                                .checked(checked && in_trash)
+                                .disabled(!in_trash),
+                            )
+                        })
+                        .child(
+                            div()
+                                .flex_1()
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.foreground)
+                                .child(entry.path.to_string_lossy().to_string()),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(bytesize::ByteSize(entry.size).to_string()),
+                        )
+                        .child(match entry.status {
+                            TrashStatus::InTrash => {
+                                Badge::new("In trash").variant(BadgeVariant::Secondary)
+                            }
+                            TrashStatus::Restored => {
+                                Badge::new("Restored").variant(BadgeVariant::Outline)
+                            }
+                            TrashStatus::Unrecoverable => {
+                                Badge::new("Unrecoverable").variant(BadgeVariant::Destructive)
+                            }
+                        })
+                }))
+        };
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(640.0))
+                        .max_h(px(560.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child("Trash by Sweeper"),
+                        )
+                        .child(if self.trash_busy && batches.is_empty() {
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(8.0))
+                                .child(Spinner::new())
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .text_color(theme.tokens.muted_foreground)
+                                        .child("Checking the trash…"),
+                                )
+                                .into_any_element()
+                        } else if batches.is_empty() {
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child("Nothing has been moved to the trash by Sweeper yet.")
+                                .into_any_element()
+                        } else {
+                            div()
+                                .flex_1()
+                                .min_h(px(0.0))
+                                .overflow_hidden()
+                                .child(scrollable_vertical(
+                                    div().flex().flex_col().gap(px(16.0)).children(
+                                        batches
+                                            .into_iter()
+                                            .map(|(batch, entries)| batch_section(batch, entries)),
+                                    ),
+                                ))
+                                .into_any_element()
+                        })
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("close_trash_log", "Close")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.trash_log = None;
+                                            this.trash_selected.clear();
+                                            cx.notify();
+                                        })),
+                                )
+                                .when(!activity::TARGETED_RESTORE, |row| {
+                                    row.child(
+                                        Button::new("reveal_trash", "Reveal in Trash")
+                                            .variant(ButtonVariant::Default)
+                                            .icon("external-link")
+                                            .disabled(!restorable)
+                                            .on_click(cx.listener(|this, _, _window, cx| {
+                                                this.reveal_trash(cx);
+                                            })),
+                                    )
+                                })
+                                .when(activity::TARGETED_RESTORE && !audit::is_enabled(), |row| {
+                                    row.child(
+                                        Button::new("restore_trash", "Restore Selected")
+                                            .variant(ButtonVariant::Default)
+                                            .icon("rotate-ccw")
+                                            .disabled(
+                                                self.trash_busy || self.trash_selected.is_empty(),
+                                            )
+                                            .on_click(cx.listener(|this, _, _window, cx| {
+                                                this.restore_trash_selected(cx);
+                                            })),
+                                    )
+                                }),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_clear_quarantine_dialog(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<impl IntoElement> {
+        if !self.confirm_clear_quarantine {
+            return None;
+        }
+
+        let theme = use_theme();
+        let size = self
+            .data_usage
+            .iter()
+            .find(|(store, _)| *store == DataStore::Quarantine)
+            .map(|(_, size)| *size)
+            .unwrap_or(0);
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::black().opacity(0.6))
+                .child(
+                    div()
+                        .w(px(480.0))
+                        .p(px(28.0))
+                        .bg(theme.tokens.card)
+                        .border_1()
+                        .border_color(theme.tokens.border)
+                        .rounded(px(16.0))
+                        .shadow_xl()
+                        .flex()
+                        .flex_col()
+                        .gap(px(16.0))
+                        .child(
+                            div()
+                                .text_size(px(20.0))
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(theme.tokens.foreground)
+                                .child("Clear quarantine?"),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(theme.tokens.muted_foreground)
+                                .child(format!(
+                                    "This permanently deletes {} of quarantined items. They can no longer be restored.",
+                                    bytesize::ByteSize(size)
+                                )),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .justify_end()
+                                .gap(px(12.0))
+                                .child(
+                                    Button::new("cancel_clear_quarantine", "Cancel")
+                                        .variant(ButtonVariant::Ghost)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_clear_quarantine = false;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("confirm_clear_quarantine", "Clear Quarantine")
+                                        .variant(ButtonVariant::Destructive)
+                                        .icon("trash-2")
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.clear_data_store(DataStore::Quarantine, cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+}
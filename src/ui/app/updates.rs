@@ -0,0 +1,111 @@
+use super::*;
+
+impl SweeperApp {
+    pub(super) fn check_for_updates(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    update::check(&HttpManifestSource::default(), env!("CARGO_PKG_VERSION"))
+                })
+                .await;
+
+            match result {
+                Ok(Some(manifest)) => {
+                    let _ = this.update(cx, |app, cx| {
+                        app.available_update = Some(manifest);
+                        cx.notify();
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => log::debug!("{}", e),
+            }
+        })
+        .detach();
+    }
+
+    pub(super) fn set_check_for_updates(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.config.check_for_updates = enabled;
+        if let Err(e) = self.save_config() {
+            self.notice = Some(format!("Could not save settings: {}", e));
+        }
+        if enabled && self.available_update.is_none() {
+            self.check_for_updates(cx);
+        }
+        cx.notify();
+    }
+
+    pub(super) fn render_update_banner(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let theme = use_theme();
+        let manifest = self.available_update.as_ref()?;
+        let download_url = manifest.download_url.clone();
+        let excerpt = manifest.changelog_excerpt();
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .gap(px(12.0))
+                .px(px(24.0))
+                .py(px(8.0))
+                .bg(theme.tokens.primary.opacity(0.08))
+                .border_b_1()
+                .border_color(theme.tokens.border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .overflow_hidden()
+                        .child(
+                            Icon::new("circle-arrow-up")
+                                .size(px(14.0))
+                                .color(theme.tokens.primary),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .font_weight(FontWeight::MEDIUM)
+                                .text_color(theme.tokens.foreground)
+                                .child(format!("Sweeper {} is available", manifest.version)),
+                        )
+                        .when(!excerpt.is_empty(), |banner| {
+                            banner.child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.tokens.muted_foreground)
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .child(excerpt),
+                            )
+                        }),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(6.0))
+                        .child(
+                            Button::new("download_update", "Download")
+                                .variant(ButtonVariant::Secondary)
+                                .size(ButtonSize::Sm)
+                                .icon("external-link")
+                                .on_click(move |_, _window, cx| {
+                                    cx.open_url(&download_url);
+                                }),
+                        )
+                        .child(
+                            IconButton::new("x")
+                                .variant(ButtonVariant::Ghost)
+                                .size(px(24.0))
+                                .icon_size(px(14.0))
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.available_update = None;
+                                    cx.notify();
+                                })),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,177 @@
+use super::app::SweeperApp;
+use adabraka_ui::components::button::{Button, ButtonSize, ButtonVariant};
+use adabraka_ui::components::icon::Icon;
+use adabraka_ui::components::spinner::Spinner;
+use adabraka_ui::prelude::*;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use std::time::Duration;
+
+pub const SIZE: Size<Pixels> = Size {
+    width: px(340.0),
+    height: px(150.0),
+};
+const SCREEN_MARGIN: f32 = 24.0;
+const MAX_ESTIMATED_FRACTION: f32 = 0.99;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactStatus {
+    pub title: String,
+    pub detail: String,
+    pub fraction: Option<f32>,
+    pub eta: Option<Duration>,
+    pub can_cancel: bool,
+}
+
+pub fn estimate(done: u64, expected: u64, elapsed: Duration) -> (Option<f32>, Option<Duration>) {
+    if expected == 0 {
+        return (None, None);
+    }
+    let fraction = (done as f32 / expected as f32).min(MAX_ESTIMATED_FRACTION);
+    let eta = (done > 0 && done < expected)
+        .then(|| elapsed.mul_f64((expected - done) as f64 / done as f64));
+    (Some(fraction), eta)
+}
+
+pub fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    if secs < 60 {
+        format!("about {}s left", secs.max(1))
+    } else {
+        format!("about {} min left", secs.div_ceil(60))
+    }
+}
+
+pub fn window_options(cx: &App) -> WindowOptions {
+    let origin = cx
+        .primary_display()
+        .map(|display| {
+            let bounds = display.bounds();
+            point(
+                bounds.origin.x + bounds.size.width - SIZE.width - px(SCREEN_MARGIN),
+                bounds.origin.y + px(SCREEN_MARGIN),
+            )
+        })
+        .unwrap_or_default();
+
+    WindowOptions {
+        titlebar: Some(TitlebarOptions {
+            title: Some("Sweeper".into()),
+            ..Default::default()
+        }),
+        window_bounds: Some(WindowBounds::Windowed(Bounds { origin, size: SIZE })),
+        kind: WindowKind::PopUp,
+        is_resizable: false,
+        is_minimizable: false,
+        focus: false,
+        ..Default::default()
+    }
+}
+
+pub struct CompactView {
+    app: Entity<SweeperApp>,
+}
+
+impl CompactView {
+    pub fn new(app: Entity<SweeperApp>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        cx.observe(&app, |_, _, cx| cx.notify()).detach();
+        let closing = app.clone();
+        window.on_window_should_close(cx, move |_window, cx| {
+            closing.update(cx, |app, cx| app.compact_closed(cx));
+            true
+        });
+        Self { app }
+    }
+}
+
+impl Render for CompactView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+        let status = self.app.read(cx).compact_status();
+        let restore = self.app.clone();
+        let cancel = self.app.clone();
+
+        div()
+            .id("compact_card")
+            .size_full()
+            .p(px(16.0))
+            .bg(theme.tokens.card)
+            .flex()
+            .flex_col()
+            .gap(px(10.0))
+            .cursor_pointer()
+            .on_click(move |_, _window, cx| {
+                restore.update(cx, |app, cx| app.exit_compact(cx));
+            })
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap(px(12.0))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(10.0))
+                            .child(Spinner::new())
+                            .child(
+                                div()
+                                    .text_size(px(14.0))
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(theme.tokens.foreground)
+                                    .child(status.title),
+                            ),
+                    )
+                    .child(
+                        Icon::new("maximize-2")
+                            .size(px(14.0))
+                            .color(theme.tokens.muted_foreground),
+                    ),
+            )
+            .child(
+                div()
+                    .h(px(6.0))
+                    .w_full()
+                    .rounded_full()
+                    .bg(theme.tokens.muted)
+                    .when_some(status.fraction, |bar, fraction| {
+                        bar.child(
+                            div()
+                                .h_full()
+                                .w(relative(fraction))
+                                .rounded_full()
+                                .bg(theme.tokens.primary),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap(px(12.0))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(theme.tokens.muted_foreground)
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .child(match status.eta {
+                                Some(eta) => format!("{} · {}", status.detail, format_eta(eta)),
+                                None => status.detail,
+                            }),
+                    )
+                    .child(
+                        Button::new("compact_cancel", "Cancel")
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Sm)
+                            .disabled(!status.can_cancel)
+                            .on_click(move |_, _window, cx| {
+                                cx.stop_propagation();
+                                cancel.update(cx, |app, cx| app.cancel_operation(cx));
+                            }),
+                    ),
+            )
+    }
+}
@@ -1 +1,228 @@
+use crate::categories::FileCategory;
+use crate::scanner::ScannedItem;
+use adabraka_ui::display::badge::{Badge, BadgeVariant};
+use adabraka_ui::prelude::*;
+use gpui::*;
+use std::path::Path;
 
+const ANCIENT_AFTER_DAYS: u64 = 3 * 365;
+
+pub struct InfoTooltip {
+    lines: Vec<SharedString>,
+}
+
+impl InfoTooltip {
+    pub fn view(lines: Vec<String>, cx: &mut App) -> AnyView {
+        cx.new(|_| Self {
+            lines: lines.into_iter().map(SharedString::from).collect(),
+        })
+        .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeTier {
+    Months(u64),
+    Year,
+    Ancient(u64),
+}
+
+impl AgeTier {
+    pub fn of(category: FileCategory, age_days: u64) -> Option<Self> {
+        if age_days >= ANCIENT_AFTER_DAYS {
+            Some(Self::Ancient(age_days / 365))
+        } else if age_days >= 365 {
+            Some(Self::Year)
+        } else if age_days >= unused_after_days(category) {
+            Some(Self::Months((age_days / 30).max(1)))
+        } else {
+            None
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Months(months) => format!("Unused {} mo", months),
+            Self::Year => "Unused 1 yr+".to_string(),
+            Self::Ancient(years) => format!("Ancient ({} yr)", years),
+        }
+    }
+
+    fn variant(&self) -> BadgeVariant {
+        match self {
+            Self::Months(_) => BadgeVariant::Secondary,
+            Self::Year => BadgeVariant::Warning,
+            Self::Ancient(_) => BadgeVariant::Destructive,
+        }
+    }
+}
+
+fn unused_after_days(category: FileCategory) -> u64 {
+    match category {
+        FileCategory::LogFile | FileCategory::TempFile | FileCategory::OldDownload => 30,
+        FileCategory::DevArtifact
+        | FileCategory::PackageCache
+        | FileCategory::IdeCache
+        | FileCategory::BrowserCache
+        | FileCategory::SystemCache
+        | FileCategory::GlobalPackage => 90,
+        FileCategory::LargeFile
+        | FileCategory::Duplicate
+        | FileCategory::Unused
+        | FileCategory::AppContainer => 180,
+    }
+}
+
+pub fn elide_path_left(path: &Path, max_chars: usize) -> String {
+    let display = match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~{}{}", std::path::MAIN_SEPARATOR, rest.display()),
+        None => path.display().to_string(),
+    };
+    let len = display.chars().count();
+    if len <= max_chars {
+        return display;
+    }
+    let tail: String = display.chars().skip(len + 1 - max_chars.max(1)).collect();
+    format!("…{}", tail)
+}
+
+pub fn file_count_label(files: u64) -> String {
+    match files {
+        1 => "1 file".to_string(),
+        0..=999 => format!("{} files", files),
+        1_000..=9_999 => format!("{:.1}k files", (files / 100) as f64 / 10.0),
+        10_000..=999_999 => format!("{}k files", files / 1_000),
+        _ => format!("{:.1}M files", (files / 100_000) as f64 / 10.0),
+    }
+}
+
+pub fn age_badge(item: &ScannedItem) -> Option<Badge> {
+    let tier = AgeTier::of(item.category, item.age_days())?;
+    Some(Badge::new(tier.label()).variant(tier.variant()))
+}
+
+impl Render for InfoTooltip {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = use_theme();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .max_w(px(480.0))
+            .px(px(8.0))
+            .py(px(6.0))
+            .bg(theme.tokens.popover)
+            .text_color(theme.tokens.popover_foreground)
+            .border_1()
+            .border_color(theme.tokens.border)
+            .rounded(theme.tokens.radius_sm)
+            .shadow_md()
+            .text_size(px(12.0))
+            .children(self.lines.iter().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{elide_path_left, file_count_label, unused_after_days, AgeTier};
+    use crate::categories::FileCategory;
+    use std::path::Path;
+
+    #[test]
+    fn age_tiers_depend_on_category_and_age() {
+        let cases = [
+            (FileCategory::TempFile, 29, None),
+            (FileCategory::TempFile, 30, Some(AgeTier::Months(1))),
+            (FileCategory::LogFile, 95, Some(AgeTier::Months(3))),
+            (FileCategory::SystemCache, 89, None),
+            (FileCategory::SystemCache, 90, Some(AgeTier::Months(3))),
+            (FileCategory::Duplicate, 179, None),
+            (FileCategory::Duplicate, 364, Some(AgeTier::Months(12))),
+            (FileCategory::Duplicate, 365, Some(AgeTier::Year)),
+            (FileCategory::DevArtifact, 3 * 365 - 1, Some(AgeTier::Year)),
+            (
+                FileCategory::DevArtifact,
+                3 * 365,
+                Some(AgeTier::Ancient(3)),
+            ),
+            (
+                FileCategory::AppContainer,
+                10 * 365 + 5,
+                Some(AgeTier::Ancient(10)),
+            ),
+        ];
+        for (category, days, expected) in cases {
+            assert_eq!(
+                AgeTier::of(category, days),
+                expected,
+                "{:?} {}",
+                category,
+                days
+            );
+        }
+    }
+
+    #[test]
+    fn every_category_gets_a_badge_within_a_year() {
+        for category in FileCategory::ALL {
+            assert!(unused_after_days(category) < 365, "{:?}", category);
+            assert_eq!(AgeTier::of(category, 0), None);
+        }
+    }
+
+    #[test]
+    fn age_tier_labels() {
+        assert_eq!(AgeTier::Months(4).label(), "Unused 4 mo");
+        assert_eq!(AgeTier::Year.label(), "Unused 1 yr+");
+        assert_eq!(AgeTier::Ancient(5).label(), "Ancient (5 yr)");
+    }
+
+    #[test]
+    fn long_paths_keep_their_tail() {
+        assert_eq!(elide_path_left(Path::new("/srv/data"), 20), "/srv/data");
+        assert_eq!(
+            elide_path_left(Path::new("/srv/data/projects/app"), 10),
+            "…jects/app"
+        );
+        assert_eq!(
+            elide_path_left(Path::new("/srv/dätä/ünïcode"), 8),
+            "…ünïcode"
+        );
+        assert_eq!(elide_path_left(Path::new("/srv"), 0), "…");
+    }
+
+    #[test]
+    fn paths_under_home_are_shortened_to_a_tilde() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        assert_eq!(elide_path_left(&home, 20), "~");
+        let sep = std::path::MAIN_SEPARATOR;
+        assert_eq!(
+            elide_path_left(&home.join("code"), 20),
+            format!("~{}code", sep)
+        );
+    }
+
+    #[test]
+    fn file_counts_are_abbreviated() {
+        let cases = [
+            (0, "0 files"),
+            (1, "1 file"),
+            (999, "999 files"),
+            (1_000, "1.0k files"),
+            (1_250, "1.2k files"),
+            (9_999, "9.9k files"),
+            (10_000, "10k files"),
+            (999_999, "999k files"),
+            (1_000_000, "1.0M files"),
+            (2_500_000, "2.5M files"),
+            (9_999_999, "9.9M files"),
+        ];
+        for (files, expected) in cases {
+            assert_eq!(file_count_label(files), expected);
+        }
+    }
+}
@@ -1,4 +1,8 @@
 mod app;
+mod compact;
 mod components;
+mod mode;
+mod poll;
+mod selection;
 
-pub use app::SweeperApp;
+pub use app::{RedoSelection, SweeperApp, ToggleReviewLater, UndoSelection};
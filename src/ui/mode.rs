@@ -0,0 +1,85 @@
+use crate::config::DeleteMode;
+use adabraka_ui::display::badge::BadgeVariant;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModeGuard {
+    mode: Option<DeleteMode>,
+    acknowledged: bool,
+}
+
+impl ModeGuard {
+    pub fn update(&mut self, mode: DeleteMode) -> bool {
+        let changed = self.mode != Some(mode);
+        self.mode = Some(mode);
+        changed
+    }
+
+    pub fn needs_acknowledgment(&self) -> bool {
+        self.mode == Some(DeleteMode::Permanent) && !self.acknowledged
+    }
+
+    pub fn acknowledge(&mut self) {
+        if self.mode == Some(DeleteMode::Permanent) {
+            self.acknowledged = true;
+        }
+    }
+
+    pub fn badge(&self) -> Option<(&'static str, BadgeVariant)> {
+        match self.mode? {
+            DeleteMode::DryRun => Some(("Dry run", BadgeVariant::Outline)),
+            DeleteMode::Trash => None,
+            DeleteMode::Permanent => Some(("Permanent delete mode", BadgeVariant::Destructive)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_report_only_real_mode_changes() {
+        let mut guard = ModeGuard::default();
+        assert!(guard.update(DeleteMode::Trash));
+        assert!(!guard.update(DeleteMode::Trash));
+        assert!(guard.update(DeleteMode::DryRun));
+    }
+
+    #[test]
+    fn permanent_mode_needs_acknowledging_once_per_session() {
+        let mut guard = ModeGuard::default();
+        assert!(!guard.needs_acknowledgment());
+
+        guard.update(DeleteMode::Permanent);
+        assert!(guard.needs_acknowledgment());
+        guard.acknowledge();
+        assert!(!guard.needs_acknowledgment());
+
+        guard.update(DeleteMode::Trash);
+        guard.update(DeleteMode::Permanent);
+        assert!(!guard.needs_acknowledgment());
+    }
+
+    #[test]
+    fn acknowledging_another_mode_does_not_carry_over_to_permanent() {
+        let mut guard = ModeGuard::default();
+        guard.acknowledge();
+        guard.update(DeleteMode::Trash);
+        guard.acknowledge();
+        guard.update(DeleteMode::Permanent);
+        assert!(guard.needs_acknowledgment());
+    }
+
+    #[test]
+    fn only_non_default_modes_get_a_badge() {
+        let badge = |mode| {
+            let mut guard = ModeGuard::default();
+            guard.update(mode);
+            guard.badge().map(|(label, _)| label)
+        };
+        assert_eq!(ModeGuard::default().badge(), None);
+        assert_eq!(badge(DeleteMode::Trash), None);
+        assert_eq!(badge(DeleteMode::DryRun), Some("Dry run"));
+        assert_eq!(badge(DeleteMode::Permanent), Some("Permanent delete mode"));
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+pub const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+pub const IDLE_POLLS_BEFORE_STOP: u32 = 5;
+
+/// Paces the UI poll: fast while work is running, once a second after the
+/// first quiet tick, and off after a few quiet ticks in a row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PollCadence {
+    idle_ticks: u32,
+}
+
+impl PollCadence {
+    pub fn interval(&self) -> Duration {
+        if self.idle_ticks == 0 {
+            POLL_INTERVAL
+        } else {
+            IDLE_POLL_INTERVAL
+        }
+    }
+
+    /// Records one poll and returns whether to keep polling. An open control
+    /// socket keeps the poll alive however long the app stays idle.
+    pub fn tick(&mut self, busy: bool, control_open: bool) -> bool {
+        if busy {
+            self.idle_ticks = 0;
+        } else {
+            self.idle_ticks += 1;
+        }
+        self.idle_ticks < IDLE_POLLS_BEFORE_STOP || control_open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polls_fast_while_busy() {
+        let mut cadence = PollCadence::default();
+        for _ in 0..20 {
+            assert!(cadence.tick(true, false));
+            assert_eq!(cadence.interval(), POLL_INTERVAL);
+        }
+    }
+
+    #[test]
+    fn backs_off_then_stops_after_the_idle_period() {
+        let mut cadence = PollCadence::default();
+        assert_eq!(cadence.interval(), POLL_INTERVAL);
+
+        for _ in 1..IDLE_POLLS_BEFORE_STOP {
+            assert!(cadence.tick(false, false));
+            assert_eq!(cadence.interval(), IDLE_POLL_INTERVAL);
+        }
+        assert!(!cadence.tick(false, false));
+    }
+
+    #[test]
+    fn work_resets_the_idle_period() {
+        let mut cadence = PollCadence::default();
+        for _ in 1..IDLE_POLLS_BEFORE_STOP {
+            cadence.tick(false, false);
+        }
+        assert!(cadence.tick(true, false));
+        assert_eq!(cadence.interval(), POLL_INTERVAL);
+
+        for _ in 1..IDLE_POLLS_BEFORE_STOP {
+            assert!(cadence.tick(false, false));
+        }
+        assert!(!cadence.tick(false, false));
+    }
+
+    #[test]
+    fn an_open_control_socket_keeps_polling() {
+        let mut cadence = PollCadence::default();
+        for _ in 0..IDLE_POLLS_BEFORE_STOP * 3 {
+            assert!(cadence.tick(false, true));
+            assert_eq!(cadence.interval(), IDLE_POLL_INTERVAL);
+        }
+        assert!(!cadence.tick(false, false));
+    }
+}
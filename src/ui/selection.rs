@@ -0,0 +1,272 @@
+use crate::categories::FileCategory;
+use crate::scanner::ScannedItem;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+const HISTORY_LIMIT: usize = 100;
+pub const HIGH_CONFIDENCE: f32 = 0.9;
+
+/// One-click selections that add the matching items in view to the
+/// current selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionPreset {
+    HighConfidence,
+    StaleOnly,
+    CachesOnly,
+    ExceptDownloads,
+}
+
+impl SelectionPreset {
+    pub const ALL: [Self; 4] = [
+        Self::HighConfidence,
+        Self::StaleOnly,
+        Self::CachesOnly,
+        Self::ExceptDownloads,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::HighConfidence => "High confidence only",
+            Self::StaleOnly => "Stale items only",
+            Self::CachesOnly => "Caches only",
+            Self::ExceptDownloads => "Everything except Downloads",
+        }
+    }
+
+    pub fn matches(&self, item: &ScannedItem, downloads: Option<&Path>) -> bool {
+        match self {
+            Self::HighConfidence => item.confidence >= HIGH_CONFIDENCE,
+            Self::StaleOnly => item.is_stale,
+            Self::CachesOnly => matches!(
+                item.category,
+                FileCategory::PackageCache
+                    | FileCategory::IdeCache
+                    | FileCategory::BrowserCache
+                    | FileCategory::SystemCache
+            ),
+            Self::ExceptDownloads => {
+                item.category != FileCategory::OldDownload
+                    && downloads.is_none_or(|downloads| !item.path.starts_with(downloads))
+            }
+        }
+    }
+
+    /// The paths among `items` this preset would add: matching ones that
+    /// are neither kept duplicates nor already selected.
+    pub fn additions(
+        &self,
+        items: &[&ScannedItem],
+        downloads: Option<&Path>,
+        keepers: &HashSet<PathBuf>,
+        selected: &HashSet<PathBuf>,
+    ) -> Vec<PathBuf> {
+        items
+            .iter()
+            .filter(|item| self.matches(item, downloads))
+            .filter(|item| !keepers.contains(&item.path) && !selected.contains(&item.path))
+            .map(|item| item.path.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SelectionChange {
+    added: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+}
+
+impl SelectionChange {
+    fn apply(&self, selected: &mut HashSet<PathBuf>) {
+        for path in &self.removed {
+            selected.remove(path);
+        }
+        selected.extend(self.added.iter().cloned());
+    }
+
+    fn revert(&self, selected: &mut HashSet<PathBuf>) {
+        for path in &self.added {
+            selected.remove(path);
+        }
+        selected.extend(self.removed.iter().cloned());
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SelectionHistory {
+    undo: VecDeque<SelectionChange>,
+    redo: Vec<SelectionChange>,
+}
+
+impl SelectionHistory {
+    pub fn push(&mut self, before: &HashSet<PathBuf>, after: &HashSet<PathBuf>) {
+        let change = SelectionChange {
+            added: after.difference(before).cloned().collect(),
+            removed: before.difference(after).cloned().collect(),
+        };
+        if change.added.is_empty() && change.removed.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        self.undo.push_back(change);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn undo(&mut self, selected: &mut HashSet<PathBuf>) -> bool {
+        let Some(change) = self.undo.pop_back() else {
+            return false;
+        };
+        change.revert(selected);
+        self.redo.push(change);
+        true
+    }
+
+    pub fn redo(&mut self, selected: &mut HashSet<PathBuf>) -> bool {
+        let Some(change) = self.redo.pop() else {
+            return false;
+        };
+        change.apply(selected);
+        self.undo.push_back(change);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn set(paths: &[&str]) -> HashSet<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    fn item(path: &str, category: FileCategory, confidence: f32, stale: bool) -> ScannedItem {
+        let mut item = ScannedItem::new(path.into(), 1, category, Utc::now());
+        item.confidence = confidence;
+        item.is_stale = stale;
+        item
+    }
+
+    fn items() -> Vec<ScannedItem> {
+        vec![
+            item("/p/target", FileCategory::DevArtifact, 0.95, false),
+            item("/c/npm", FileCategory::PackageCache, 0.5, true),
+            item("/c/chrome", FileCategory::BrowserCache, 0.9, false),
+            item("/home/dl/a.zip", FileCategory::OldDownload, 0.7, true),
+            item("/home/dl/b.iso", FileCategory::LargeFile, 0.6, false),
+            item("/x/copy.bin", FileCategory::Duplicate, 0.95, true),
+        ]
+    }
+
+    fn additions(preset: SelectionPreset, keepers: &[&str], selected: &[&str]) -> Vec<PathBuf> {
+        let items = items();
+        let items: Vec<&ScannedItem> = items.iter().collect();
+        let mut paths = preset.additions(
+            &items,
+            Some(Path::new("/home/dl")),
+            &set(keepers),
+            &set(selected),
+        );
+        paths.sort();
+        paths
+    }
+
+    fn paths(list: &[&str]) -> Vec<PathBuf> {
+        list.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn each_preset_adds_what_it_matches() {
+        let cases = [
+            (
+                SelectionPreset::HighConfidence,
+                paths(&["/c/chrome", "/p/target", "/x/copy.bin"]),
+            ),
+            (
+                SelectionPreset::StaleOnly,
+                paths(&["/c/npm", "/home/dl/a.zip", "/x/copy.bin"]),
+            ),
+            (SelectionPreset::CachesOnly, paths(&["/c/chrome", "/c/npm"])),
+            (
+                SelectionPreset::ExceptDownloads,
+                paths(&["/c/chrome", "/c/npm", "/p/target", "/x/copy.bin"]),
+            ),
+        ];
+        for (preset, expected) in cases {
+            assert_eq!(additions(preset, &[], &[]), expected, "{:?}", preset);
+        }
+    }
+
+    #[test]
+    fn presets_skip_keepers_and_what_is_already_selected() {
+        let added = additions(
+            SelectionPreset::HighConfidence,
+            &["/x/copy.bin"],
+            &["/p/target"],
+        );
+        assert_eq!(added, paths(&["/c/chrome"]));
+        assert!(additions(SelectionPreset::CachesOnly, &[], &["/c/npm", "/c/chrome"]).is_empty());
+    }
+
+    #[test]
+    fn undo_then_redo_restores_the_selection() {
+        let mut history = SelectionHistory::default();
+        let before = set(&["/a", "/b"]);
+        let after = set(&["/b", "/c"]);
+        history.push(&before, &after);
+
+        let mut selected = after.clone();
+        assert!(history.undo(&mut selected));
+        assert_eq!(selected, before);
+        assert!(history.redo(&mut selected));
+        assert_eq!(selected, after);
+        assert!(!history.redo(&mut selected));
+    }
+
+    #[test]
+    fn a_new_change_clears_the_redo_stack() {
+        let mut history = SelectionHistory::default();
+        let mut selected = set(&["/a"]);
+        history.push(&set(&[]), &selected);
+        assert!(history.undo(&mut selected));
+        assert!(selected.is_empty());
+
+        history.push(&selected, &set(&["/b"]));
+        selected = set(&["/b"]);
+        assert!(!history.redo(&mut selected));
+        assert_eq!(selected, set(&["/b"]));
+    }
+
+    #[test]
+    fn unchanged_selections_are_not_recorded() {
+        let mut history = SelectionHistory::default();
+        let mut selected = set(&["/a"]);
+        history.push(&selected, &selected.clone());
+        assert!(!history.undo(&mut selected));
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_changes() {
+        let mut history = SelectionHistory::default();
+        let mut selected = HashSet::new();
+        for index in 0..HISTORY_LIMIT + 5 {
+            let before = selected.clone();
+            selected.insert(PathBuf::from(format!("/{}", index)));
+            history.push(&before, &selected);
+        }
+
+        let mut undone = 0;
+        while history.undo(&mut selected) {
+            undone += 1;
+        }
+        assert_eq!(undone, HISTORY_LIMIT);
+        assert_eq!(selected.len(), 5);
+    }
+}
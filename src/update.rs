@@ -0,0 +1,152 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+pub const MANIFEST_URL: &str =
+    "https://github.com/augani/sweeper-rust/releases/latest/download/latest.json";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const CHANGELOG_EXCERPT_LINES: usize = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateError {
+    #[error("update check failed: {0}")]
+    Network(String),
+    #[error("release manifest is malformed: {0}")]
+    Manifest(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReleaseManifest {
+    pub version: String,
+    #[serde(default)]
+    pub changelog: String,
+    pub download_url: String,
+}
+
+impl ReleaseManifest {
+    pub fn changelog_excerpt(&self) -> String {
+        self.changelog
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(CHANGELOG_EXCERPT_LINES)
+            .collect::<Vec<_>>()
+            .join(" • ")
+    }
+}
+
+pub trait ManifestSource {
+    fn fetch(&self) -> Result<String, UpdateError>;
+}
+
+pub struct HttpManifestSource {
+    url: String,
+    timeout: Duration,
+}
+
+impl Default for HttpManifestSource {
+    fn default() -> Self {
+        Self {
+            url: MANIFEST_URL.to_string(),
+            timeout: CHECK_TIMEOUT,
+        }
+    }
+}
+
+impl ManifestSource for HttpManifestSource {
+    fn fetch(&self) -> Result<String, UpdateError> {
+        ureq::get(&self.url)
+            .timeout(self.timeout)
+            .call()
+            .map_err(|e| UpdateError::Network(e.to_string()))?
+            .into_string()
+            .map_err(|e| UpdateError::Network(e.to_string()))
+    }
+}
+
+pub fn check(
+    source: &impl ManifestSource,
+    current_version: &str,
+) -> Result<Option<ReleaseManifest>, UpdateError> {
+    let manifest: ReleaseManifest = serde_json::from_str(&source.fetch()?)?;
+    Ok(is_newer(&manifest.version, current_version).then_some(manifest))
+}
+
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeSource(Result<&'static str, &'static str>);
+
+    impl ManifestSource for FakeSource {
+        fn fetch(&self) -> Result<String, UpdateError> {
+            self.0
+                .map(str::to_string)
+                .map_err(|e| UpdateError::Network(e.to_string()))
+        }
+    }
+
+    const MANIFEST: &str = r#"{
+        "version": "v0.4.0",
+        "changelog": "Faster scans\n\n  Nix store support\nCrash reports\nMore",
+        "download_url": "https://example.com/sweeper-0.4.0"
+    }"#;
+
+    #[test]
+    fn newer_releases_are_offered() {
+        let manifest = check(&FakeSource(Ok(MANIFEST)), "0.3.0").unwrap().unwrap();
+        assert_eq!(manifest.version, "v0.4.0");
+        assert_eq!(
+            manifest.changelog_excerpt(),
+            "Faster scans • Nix store support • Crash reports"
+        );
+    }
+
+    #[test]
+    fn same_or_older_releases_are_not_offered() {
+        assert!(check(&FakeSource(Ok(MANIFEST)), "0.4.0").unwrap().is_none());
+        assert!(check(&FakeSource(Ok(MANIFEST)), "1.0.0").unwrap().is_none());
+    }
+
+    #[test]
+    fn network_and_manifest_errors_are_reported() {
+        assert!(matches!(
+            check(&FakeSource(Err("timed out")), "0.3.0"),
+            Err(UpdateError::Network(message)) if message == "timed out"
+        ));
+        assert!(matches!(
+            check(&FakeSource(Ok("<html>")), "0.3.0"),
+            Err(UpdateError::Manifest(_))
+        ));
+        assert!(matches!(
+            check(&FakeSource(Ok(r#"{"version": "1.0"}"#)), "0.3.0"),
+            Err(UpdateError::Manifest(_))
+        ));
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_newer("0.10.0", "0.9.9"));
+        assert!(is_newer("1", "0.99"));
+        assert!(is_newer("v1.2.4-beta", "1.2.3"));
+        assert!(!is_newer("1.2.3+build", "1.2.3"));
+        assert!(!is_newer("latest", "0.1.0"));
+        assert!(!is_newer("1.0.0", "garbage"));
+    }
+}
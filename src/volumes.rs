@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Volume {
+    pub mount_point: PathBuf,
+    pub name: String,
+    pub total: u64,
+    pub available: u64,
+}
+
+impl Volume {
+    pub fn used_percent(&self) -> u8 {
+        if self.total == 0 {
+            return 0;
+        }
+        let used = self.total.saturating_sub(self.available) as f64;
+        (used / self.total as f64 * 100.0).round().min(100.0) as u8
+    }
+
+    pub fn is_system(&self) -> bool {
+        self.mount_point == system_root()
+    }
+
+    pub fn label(&self) -> String {
+        if self.is_system() {
+            "system disk".to_string()
+        } else if self.name.is_empty() {
+            self.mount_point.display().to_string()
+        } else {
+            format!("{} ({})", self.name, self.mount_point.display())
+        }
+    }
+}
+
+fn system_root() -> PathBuf {
+    #[cfg(windows)]
+    {
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        PathBuf::from(format!("{}\\", drive))
+    }
+    #[cfg(not(windows))]
+    {
+        PathBuf::from("/")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capacity {
+    Fits,
+    TooSmall { available: u64 },
+    Unknown,
+}
+
+pub fn capacity(source: Option<&Volume>, destination: Option<&Volume>, size: u64) -> Capacity {
+    let Some(destination) = destination else {
+        return Capacity::Unknown;
+    };
+    if source.is_some_and(|source| source.mount_point == destination.mount_point) {
+        return Capacity::Fits;
+    }
+    if destination.available >= size {
+        Capacity::Fits
+    } else {
+        Capacity::TooSmall {
+            available: destination.available,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VolumeTable {
+    volumes: Vec<Volume>,
+}
+
+impl VolumeTable {
+    pub fn new(volumes: Vec<Volume>) -> Self {
+        Self { volumes }
+    }
+
+    pub fn current() -> Self {
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        Self::new(
+            disks
+                .list()
+                .iter()
+                .map(|disk| Volume {
+                    mount_point: disk.mount_point().to_path_buf(),
+                    name: disk.name().to_string_lossy().to_string(),
+                    total: disk.total_space(),
+                    available: disk.available_space(),
+                })
+                .collect(),
+        )
+    }
+
+    pub fn volume_for(&self, path: &Path) -> Option<&Volume> {
+        self.volumes
+            .iter()
+            .filter(|volume| path.starts_with(&volume.mount_point))
+            .max_by_key(|volume| volume.mount_point.as_os_str().len())
+    }
+
+    pub fn capacity_for(&self, path: &Path, size: u64, destination: Option<&Path>) -> Capacity {
+        capacity(
+            self.volume_for(path),
+            destination.and_then(|destination| self.volume_for(destination)),
+            size,
+        )
+    }
+
+    pub fn pressure<'a>(
+        &self,
+        items: impl IntoIterator<Item = (&'a Path, u64)>,
+        full_percent: u8,
+    ) -> Vec<VolumePressure> {
+        let mut found: HashMap<&Path, (u64, usize)> = HashMap::new();
+        for (path, size) in items {
+            if let Some(volume) = self.volume_for(path) {
+                let entry = found.entry(volume.mount_point.as_path()).or_default();
+                entry.0 += size;
+                entry.1 += 1;
+            }
+        }
+
+        let mut full: Vec<VolumePressure> = self
+            .volumes
+            .iter()
+            .filter(|volume| volume.used_percent() >= full_percent)
+            .filter_map(|volume| {
+                let (bytes, items) = found.get(volume.mount_point.as_path())?;
+                Some(VolumePressure {
+                    volume: volume.clone(),
+                    bytes: *bytes,
+                    items: *items,
+                })
+            })
+            .collect();
+        full.sort_by(|a, b| {
+            b.volume
+                .used_percent()
+                .cmp(&a.volume.used_percent())
+                .then_with(|| b.bytes.cmp(&a.bytes))
+        });
+        full
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumePressure {
+    pub volume: Volume,
+    pub bytes: u64,
+    pub items: usize,
+}
+
+impl VolumePressure {
+    pub fn summary(&self) -> String {
+        format!(
+            "Your {} is {}% full — {} of the findings are on it",
+            self.volume.label(),
+            self.volume.used_percent(),
+            bytesize::ByteSize(self.bytes)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(mount_point: &str, total: u64, available: u64) -> Volume {
+        Volume {
+            mount_point: PathBuf::from(mount_point),
+            name: String::new(),
+            total,
+            available,
+        }
+    }
+
+    fn table() -> VolumeTable {
+        VolumeTable::new(vec![
+            volume("/", 1000, 100),
+            volume("/mnt/backup", 1000, 500),
+            volume("/mnt/backup2", 1000, 10),
+        ])
+    }
+
+    #[test]
+    fn capacity_checks_the_destination_volume() {
+        let root = volume("/", 1000, 100);
+        let backup = volume("/mnt/backup", 1000, 500);
+        let cases = [
+            (Some(&root), Some(&backup), 500, Capacity::Fits),
+            (
+                Some(&root),
+                Some(&backup),
+                501,
+                Capacity::TooSmall { available: 500 },
+            ),
+            // Moving within one volume needs no free space.
+            (Some(&root), Some(&root), 10_000, Capacity::Fits),
+            (None, Some(&backup), 0, Capacity::Fits),
+            (
+                None,
+                Some(&backup),
+                600,
+                Capacity::TooSmall { available: 500 },
+            ),
+            (Some(&root), None, 1, Capacity::Unknown),
+        ];
+        for (index, (source, destination, size, expected)) in cases.into_iter().enumerate() {
+            assert_eq!(
+                capacity(source, destination, size),
+                expected,
+                "case {}",
+                index
+            );
+        }
+    }
+
+    #[test]
+    fn paths_resolve_to_the_deepest_matching_mount() {
+        let table = table();
+        let mount = |path: &str| {
+            table
+                .volume_for(Path::new(path))
+                .map(|volume| volume.mount_point.clone())
+        };
+        assert_eq!(mount("/home/me"), Some(PathBuf::from("/")));
+        assert_eq!(mount("/mnt/backup/x"), Some(PathBuf::from("/mnt/backup")));
+        // A sibling whose name extends a mount point is a different volume.
+        assert_eq!(mount("/mnt/backup2/x"), Some(PathBuf::from("/mnt/backup2")));
+        assert_eq!(VolumeTable::default().volume_for(Path::new("/x")), None);
+
+        assert_eq!(
+            table.capacity_for(
+                Path::new("/home/me/a"),
+                50,
+                Some(Path::new("/mnt/backup2/t"))
+            ),
+            Capacity::TooSmall { available: 10 }
+        );
+        assert_eq!(
+            table.capacity_for(Path::new("/home/me/a"), 5_000, Some(Path::new("/tmp"))),
+            Capacity::Fits
+        );
+        assert_eq!(
+            table.capacity_for(Path::new("/home/me/a"), 50, None),
+            Capacity::Unknown
+        );
+    }
+
+    #[test]
+    fn used_percent_rounds_and_handles_empty_volumes() {
+        assert_eq!(volume("/", 1000, 100).used_percent(), 90);
+        assert_eq!(volume("/", 1000, 1000).used_percent(), 0);
+        assert_eq!(volume("/", 3, 1).used_percent(), 67);
+        assert_eq!(volume("/", 0, 0).used_percent(), 0);
+        assert_eq!(volume("/", 100, 500).used_percent(), 0);
+    }
+
+    #[test]
+    fn pressure_lists_full_volumes_that_hold_findings() {
+        let table = table();
+        let items = [
+            (Path::new("/home/me/a"), 300),
+            (Path::new("/home/me/b"), 200),
+            (Path::new("/mnt/backup/c"), 900),
+            (Path::new("/mnt/backup2/d"), 50),
+            (Path::new("/elsewhere"), 7),
+        ];
+
+        let full = table.pressure(items, 90);
+        let mounts: Vec<&Path> = full
+            .iter()
+            .map(|pressure| pressure.volume.mount_point.as_path())
+            .collect();
+        // Fullest first; the half-empty backup volume is left out.
+        assert_eq!(mounts, [Path::new("/mnt/backup2"), Path::new("/")]);
+        assert_eq!((full[1].bytes, full[1].items), (507, 3));
+        assert_eq!((full[0].bytes, full[0].items), (50, 1));
+
+        assert!(table.pressure(items, 100).is_empty());
+        // A full volume without findings isn't reported.
+        assert!(table
+            .pressure([(Path::new("/mnt/backup/c"), 1)], 90)
+            .is_empty());
+    }
+
+    #[test]
+    fn pressure_summaries_name_the_volume() {
+        let named = Volume {
+            name: "Backups".to_string(),
+            ..volume("/mnt/backup", 1000, 50)
+        };
+        let pressure = VolumePressure {
+            volume: named,
+            bytes: 2048,
+            items: 3,
+        };
+        assert_eq!(
+            pressure.summary(),
+            "Your Backups (/mnt/backup) is 95% full — 2.0 KB of the findings are on it"
+        );
+        assert_eq!(volume("/mnt/x", 1, 1).label(), "/mnt/x");
+    }
+}
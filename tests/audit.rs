@@ -0,0 +1,69 @@
+//! Audit mode is a process-wide switch that can't be turned off again, so
+//! these checks live in their own test binary and run as one test.
+
+use chrono::Utc;
+use sweeper_rust::activity::{self, RestoreError, TrashStatus, TrashedEntry};
+use sweeper_rust::audit;
+use sweeper_rust::cleaner::{CleanJob, CleanTarget};
+use sweeper_rust::config::Config;
+use sweeper_rust::error::SweeperError;
+use sweeper_rust::fixture::{self, FixtureSpec};
+use sweeper_rust::storage::DataStore;
+
+#[test]
+fn audit_mode_blocks_every_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let fixture = fixture::generate(&root, &FixtureSpec::small(31)).unwrap();
+    let targets: Vec<CleanTarget> = fixture
+        .artifacts
+        .iter()
+        .chain(&fixture.temp_files)
+        .map(|path| CleanTarget {
+            path: path.clone(),
+            size: 1,
+            check_git: false,
+        })
+        .collect();
+    let quarantine = root.join("documents");
+    let config = Config {
+        quarantine_dir: Some(quarantine.clone()),
+        ..Config::default()
+    };
+
+    // A job built before the switch must still refuse to run after it.
+    let early = CleanJob::new(targets.clone(), false, false)
+        .unwrap()
+        .with_manifest_dir(Some(root.join("manifests")));
+    let before = fixture.snapshot().unwrap();
+    audit::enable();
+    assert!(audit::is_enabled());
+
+    assert!(matches!(
+        CleanJob::new(targets.clone(), true, false),
+        Err(SweeperError::AuditMode)
+    ));
+
+    let report = early.run();
+    assert!(report.deleted.is_empty());
+    assert!(report.trashed.is_empty());
+    assert_eq!(report.blocked.len(), targets.len());
+    assert!(!root.join("manifests").exists());
+
+    let cleared = DataStore::Quarantine.clear(&config);
+    assert!(cleared.is_err());
+
+    let entry = TrashedEntry {
+        batch: 1,
+        path: fixture.temp_files[0].clone(),
+        size: 1,
+        trashed_at: Utc::now(),
+        status: TrashStatus::InTrash,
+    };
+    match activity::restore(&entry, None) {
+        Err(RestoreError::Failed(message)) => assert!(message.contains("audit mode")),
+        other => panic!("restore ran in audit mode: {:?}", other),
+    }
+
+    assert_eq!(fixture.snapshot().unwrap(), before);
+}
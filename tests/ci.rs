@@ -0,0 +1,125 @@
+//! Runs `sweeper --ci` against a generated workspace and checks the contract
+//! build agents rely on: what gets removed, the JSON summary on stdout, and
+//! the exit code for `--require-free`.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::SystemTime;
+use sweeper_rust::ci::{EXIT_ERROR, EXIT_INSUFFICIENT, EXIT_OK};
+use sweeper_rust::fixture::{self, Fixture, FixtureSpec};
+
+fn workspace() -> (tempfile::TempDir, Fixture) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("_work");
+    std::fs::create_dir(&root).unwrap();
+    let fixture = fixture::generate(&root.canonicalize().unwrap(), &FixtureSpec::small(3)).unwrap();
+    (dir, fixture)
+}
+
+/// Runs the binary with its user folders inside `home`, returning the exit
+/// code and the parsed stdout summary.
+fn sweeper(home: &Path, args: &[&str]) -> (i32, Option<serde_json::Value>) {
+    let output = Command::new(env!("CARGO_BIN_EXE_sweeper"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .output()
+        .unwrap();
+    let summary = serde_json::from_slice(&output.stdout).ok();
+    (output.status.code().unwrap(), summary)
+}
+
+#[test]
+fn dry_run_reports_without_deleting() {
+    let (dir, fixture) = workspace();
+    let before = fixture.snapshot().unwrap();
+
+    let root = fixture.root.to_str().unwrap();
+    let (code, summary) = sweeper(dir.path(), &["--ci", root, "--dry-run"]);
+    let summary = summary.unwrap();
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(summary["dry_run"], true);
+    assert_eq!(summary["success"], true);
+    assert_eq!(summary["workspace"], root);
+    assert!(summary["items_found"].as_u64().unwrap() >= fixture.artifacts.len() as u64);
+    assert_eq!(fixture.snapshot().unwrap(), before);
+}
+
+#[test]
+fn removes_every_artifact_and_temp_file_regardless_of_age() {
+    let (dir, fixture) = workspace();
+    // A project edited just now still has its artifacts cleaned.
+    let source = &fixture.sources[0];
+    std::fs::File::options()
+        .write(true)
+        .open(source)
+        .unwrap()
+        .set_modified(SystemTime::now())
+        .unwrap();
+
+    let root = fixture.root.to_str().unwrap();
+    let (code, summary) = sweeper(dir.path(), &["--ci", root, "--require-free", "1MB"]);
+    let summary = summary.unwrap();
+    assert_eq!(code, EXIT_OK, "{:#}", summary);
+    assert_eq!(summary["success"], true);
+    assert_eq!(summary["items_deleted"], summary["items_found"]);
+    assert_eq!(summary["freed_bytes"], summary["found_bytes"]);
+    assert_eq!(summary["failed"], serde_json::json!([]));
+
+    for artifact in &fixture.artifacts {
+        assert!(!artifact.exists(), "{} survived", artifact.display());
+    }
+    for temp in &fixture.temp_files {
+        assert!(!temp.exists(), "{} survived", temp.display());
+    }
+    // Nothing outside those categories is touched, and nothing is trashed.
+    let kept = fixture
+        .sources
+        .iter()
+        .chain(&fixture.plain)
+        .chain(fixture.duplicates.iter().flat_map(|(a, b)| [a, b]));
+    for path in kept {
+        assert!(path.exists(), "{} was removed", path.display());
+    }
+    assert!(!dir.path().join("data").join("Trash").exists());
+}
+
+#[test]
+fn too_little_freed_exits_with_insufficient() {
+    let (dir, fixture) = workspace();
+
+    let root = fixture.root.to_str().unwrap();
+    let (code, summary) = sweeper(dir.path(), &["--ci", root, "--require-free", "1TB"]);
+    let summary = summary.unwrap();
+    assert_eq!(code, EXIT_INSUFFICIENT);
+    assert_eq!(summary["success"], false);
+    assert_eq!(summary["required_bytes"], 1_000_000_000_000u64);
+    assert!(summary["freed_bytes"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn missing_workspace_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing");
+
+    let (code, summary) = sweeper(dir.path(), &["--ci", missing.to_str().unwrap()]);
+    assert_eq!(code, EXIT_ERROR);
+    assert!(summary.is_none());
+}
+
+#[test]
+fn audit_lock_in_the_user_config_blocks_deletion() {
+    let (dir, fixture) = workspace();
+    let config_dir = dir.path().join("config").join("sweeper");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.json"), r#"{ "audit_mode": true }"#).unwrap();
+    let before = fixture.snapshot().unwrap();
+
+    let root = fixture.root.to_str().unwrap();
+    let (code, summary) = sweeper(dir.path(), &["--ci", root]);
+    assert_eq!(code, EXIT_ERROR);
+    assert!(summary.is_none());
+    assert_eq!(fixture.snapshot().unwrap(), before);
+}
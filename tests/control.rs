@@ -0,0 +1,206 @@
+#![cfg(unix)]
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use sweeper_rust::categories::FileCategory;
+use sweeper_rust::cleaner::CleanJob;
+use sweeper_rust::config::Config;
+use sweeper_rust::control::{self, Call, CleanMode, ControlServer, SOCKET_NAME, TOKEN_NAME};
+use sweeper_rust::fixture::{self, FixtureSpec};
+use sweeper_rust::scanner::{ScannedItem, Scanner};
+
+/// Stands in for the app: answers control requests from a real scanner.
+fn serve_app(server: ControlServer, config: Config) {
+    let mut items: Vec<ScannedItem> = Vec::new();
+    let mut running: Option<(Arc<Scanner>, Arc<AtomicBool>)> = None;
+
+    for request in server.requests.iter() {
+        if let Some((scanner, done)) = &running {
+            if done.load(Ordering::SeqCst) {
+                items = scanner.snapshot().items().to_vec();
+                running = None;
+            }
+        }
+        let reply = match request.call {
+            Call::Status => Ok(json!({ "scanning": running.is_some(), "items": items.len() })),
+            Call::Scan { scope } => {
+                let scanner = Arc::new(Scanner::with_scope(
+                    config.clone(),
+                    scope.unwrap_or_default(),
+                ));
+                let done = Arc::new(AtomicBool::new(false));
+                let (thread_scanner, thread_done) = (scanner.clone(), done.clone());
+                std::thread::spawn(move || {
+                    let _ = thread_scanner.scan();
+                    thread_done.store(true, Ordering::SeqCst);
+                });
+                running = Some((scanner, done));
+                Ok(json!({ "started": true }))
+            }
+            Call::Items(filter) => Ok(control::list_items(&items, &filter)),
+            Call::Clean {
+                paths,
+                filter,
+                mode,
+            } => {
+                let targets = control::clean_targets(&items, &paths, filter.as_ref(), None);
+                CleanJob::new(targets, mode == CleanMode::Trash, false)
+                    .map(|job| {
+                        let report = job.run();
+                        items.retain(|item| !item.removed_by(&report.deleted));
+                        json!({ "deleted": report.deleted, "freed": report.freed })
+                    })
+                    .map_err(|e| e.to_string())
+            }
+        };
+        let _ = request.reply.send(reply);
+    }
+}
+
+struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+}
+
+impl Client {
+    fn connect(server: &ControlServer) -> Self {
+        let writer = UnixStream::connect(server.socket_path()).unwrap();
+        let reader = BufReader::new(writer.try_clone().unwrap());
+        Self {
+            reader,
+            writer,
+            next_id: 0,
+        }
+    }
+
+    /// Sends a call and returns its response, skipping progress
+    /// notifications.
+    fn call(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let request =
+            json!({ "jsonrpc": "2.0", "id": self.next_id, "method": method, "params": params });
+        writeln!(self.writer, "{}", request).unwrap();
+        loop {
+            let mut line = String::new();
+            assert!(
+                self.reader.read_line(&mut line).unwrap() > 0,
+                "socket closed"
+            );
+            let message: Value = serde_json::from_str(&line).unwrap();
+            if message["id"] == json!(self.next_id) {
+                return message;
+            }
+            assert_eq!(message["method"], "progress");
+        }
+    }
+}
+
+fn paths(reply: &Value) -> Vec<PathBuf> {
+    reply["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| PathBuf::from(item["path"].as_str().unwrap()))
+        .collect()
+}
+
+#[test]
+fn scan_list_and_clean_over_the_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let home = dir.path().join("home");
+    std::fs::create_dir(&home).unwrap();
+    let home = home.canonicalize().unwrap();
+    let fixture = fixture::generate(&home, &FixtureSpec::small(11)).unwrap();
+
+    let server = ControlServer::start(&dir.path().join("control")).unwrap();
+    let token = std::fs::read_to_string(dir.path().join("control").join(TOKEN_NAME)).unwrap();
+    let mut client = Client::connect(&server);
+
+    let mut config = Config::default();
+    config.enabled_categories.insert(FileCategory::Duplicate);
+    std::thread::spawn(move || serve_app(server, config));
+
+    let refused = client.call("items", json!({}));
+    assert!(refused["error"].is_object());
+    let auth = client.call("auth", json!({ "token": token.trim() }));
+    assert_eq!(auth["result"]["authenticated"], true);
+
+    let scan = client.call("scan", json!({ "scope": [home] }));
+    assert_eq!(scan["result"]["scanning"], false);
+
+    let artifacts = client.call("items", json!({ "categories": ["DevArtifact"] }));
+    let mut listed = paths(&artifacts);
+    listed.sort();
+    let mut expected = fixture.artifacts.clone();
+    expected.sort();
+    assert_eq!(listed, expected);
+
+    let empty = client.call("clean", json!({ "filter": {}, "mode": "delete" }));
+    assert!(empty["error"].is_object());
+
+    let before = fixture.snapshot().unwrap();
+    let cleaned = client.call("clean", json!({ "paths": listed, "mode": "delete" }));
+    assert_eq!(cleaned["result"]["deleted"].as_array().unwrap().len(), 3);
+    for artifact in &fixture.artifacts {
+        assert!(!artifact.exists());
+    }
+    let after = fixture.snapshot().unwrap();
+    let untouched: Vec<_> = before
+        .into_iter()
+        .filter(|(path, _)| !fixture.artifacts.iter().any(|dir| path.starts_with(dir)))
+        .collect();
+    assert_eq!(after, untouched);
+
+    // Cleaning every duplicate by filter still leaves one copy of each.
+    let duplicates = client.call(
+        "clean",
+        json!({ "filter": { "categories": ["Duplicate"] }, "mode": "delete" }),
+    );
+    assert_eq!(
+        duplicates["result"]["deleted"].as_array().unwrap().len(),
+        fixture.duplicates.len()
+    );
+    for (original, copy) in &fixture.duplicates {
+        assert!(original.exists() != copy.exists());
+    }
+
+    let remaining = client.call(
+        "items",
+        json!({ "categories": ["DevArtifact", "Duplicate"] }),
+    );
+    assert_eq!(paths(&remaining).len(), fixture.duplicates.len());
+}
+
+#[test]
+fn socket_and_token_are_private_from_the_start() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let control = dir.path().join("control");
+    // A stale staging folder from a crash is replaced, not reused.
+    std::fs::create_dir_all(control.join(".control.staging")).unwrap();
+    std::fs::set_permissions(
+        control.join(".control.staging"),
+        std::fs::Permissions::from_mode(0o777),
+    )
+    .unwrap();
+
+    let server = ControlServer::start(&control).unwrap();
+    let mode =
+        |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(server.socket_path(), control.join(SOCKET_NAME));
+    assert_eq!(mode(server.socket_path()), 0o600);
+    assert_eq!(mode(&control.join(TOKEN_NAME)), 0o600);
+    assert!(!control.join(".control.staging").exists());
+    UnixStream::connect(server.socket_path()).unwrap();
+
+    // Restarting replaces the old socket.
+    drop(server);
+    let server = ControlServer::start(&control).unwrap();
+    UnixStream::connect(server.socket_path()).unwrap();
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use sweeper_rust::categories::FileCategory;
+use sweeper_rust::cleaner::CleanTarget;
+use sweeper_rust::config::Config;
+use sweeper_rust::control::{self, ItemFilter};
+use sweeper_rust::duplicates;
+use sweeper_rust::fixture::{self, FixtureSpec};
+use sweeper_rust::scanner::persist::ItemRecordV1;
+use sweeper_rust::scanner::{hashing, Scanner};
+
+#[test]
+fn scan_marks_exactly_one_keeper_per_duplicate_set() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let fixture = fixture::generate(&root, &FixtureSpec::small(1)).unwrap();
+
+    let mut config = Config::default();
+    config.enabled_categories.insert(FileCategory::Duplicate);
+    let scanner = Scanner::with_scope(config, vec![root.clone()]);
+    let items = scanner.scan().unwrap();
+    let duplicates: Vec<_> = items
+        .iter()
+        .filter(|item| item.category == FileCategory::Duplicate)
+        .collect();
+    assert_eq!(duplicates.len(), fixture.duplicates.len() * 2);
+
+    for (original, copy) in &fixture.duplicates {
+        let pair: Vec<_> = duplicates
+            .iter()
+            .filter(|item| &item.path == original || &item.path == copy)
+            .collect();
+        assert_eq!(pair.len(), 2);
+        assert_eq!(pair.iter().filter(|item| item.is_keeper).count(), 1);
+    }
+
+    // Selecting every duplicate still leaves one copy of each.
+    let targets: HashSet<PathBuf> = duplicates
+        .iter()
+        .flat_map(|item| CleanTarget::for_item(item))
+        .map(|target| target.path)
+        .collect();
+    assert_eq!(targets.len(), fixture.duplicates.len());
+}
+
+#[test]
+fn every_copy_is_reported_and_only_the_keeper_is_flagged() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let fixture = fixture::generate(&root, &FixtureSpec::small(2)).unwrap();
+
+    let mut config = Config::default();
+    config.enabled_categories.insert(FileCategory::Duplicate);
+    let items = Scanner::with_scope(config, vec![root]).scan().unwrap();
+    let duplicates: Vec<_> = items
+        .iter()
+        .filter(|item| item.category == FileCategory::Duplicate)
+        .collect();
+
+    // Both copies of every set are reported, and the JSON output says which
+    // one stays.
+    for (original, copy) in &fixture.duplicates {
+        let flags: Vec<bool> = [original, copy]
+            .iter()
+            .map(|path| {
+                let item = duplicates
+                    .iter()
+                    .find(|item| &&item.path == path)
+                    .expect("every copy is reported");
+                let record = serde_json::to_value(ItemRecordV1::from(*item)).unwrap();
+                record["is_keeper"].as_bool().unwrap_or(false)
+            })
+            .collect();
+        assert_eq!(flags.iter().filter(|kept| **kept).count(), 1);
+    }
+
+    // Grouping agrees with the scanner, and the keeper is never counted as
+    // reclaimable.
+    let groups = duplicates::group_duplicates(&duplicates, None);
+    assert_eq!(groups.len(), fixture.duplicates.len());
+    for group in &groups {
+        assert!(group.keeper().is_keeper);
+        assert!(group.removable().all(|item| !item.is_keeper));
+        assert_eq!(group.wasted_size(), group.keeper().size);
+    }
+}
+
+#[test]
+fn sampled_lookalikes_are_never_cleaned_outside_the_app() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let (a, b) = fixture::sampled_lookalikes(&root).unwrap();
+
+    let mut config = Config::default();
+    config.enabled_categories.insert(FileCategory::Duplicate);
+    let items = Scanner::with_scope(config, vec![root.clone()])
+        .scan()
+        .unwrap();
+    let pair: Vec<_> = items
+        .iter()
+        .filter(|item| item.category == FileCategory::Duplicate)
+        .collect();
+    // The samples agree, so the scan pairs them, even though the files differ.
+    assert_eq!(pair.len(), 2);
+    assert!(pair.iter().all(|item| item.sampled_hash));
+    assert_eq!(pair[0].hash, pair[1].hash);
+    let should_stop = AtomicBool::new(false);
+    assert_ne!(
+        hashing::full_hash(&a, &should_stop).unwrap(),
+        hashing::full_hash(&b, &should_stop).unwrap()
+    );
+
+    let filter = ItemFilter {
+        categories: Some(vec![FileCategory::Duplicate]),
+        ..ItemFilter::default()
+    };
+    assert!(
+        control::clean_targets(&items, &[a.clone(), b.clone()], Some(&filter), None).is_empty()
+    );
+}
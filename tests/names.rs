@@ -0,0 +1,55 @@
+//! Counts heap allocations in a real scan of a tree full of files that match
+//! nothing. Classifying such a file (dev artifact names, temp and log
+//! suffixes, excludes) must not allocate, so the cost of each extra file
+//! stays at what the directory walk itself needs.
+//!
+//! The count covers every thread, so this binary holds a single test.
+
+mod support;
+
+use std::path::Path;
+use support::CountingAlloc;
+use sweeper_rust::config::Config;
+use sweeper_rust::scanner::Scanner;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Files in mixed case so the case-folding branch runs too, spread over
+/// a fixed number of folders so only the file count changes.
+fn populate(root: &Path, files: usize) {
+    for index in 0..files {
+        let dir = root.join(format!("Project-{}", index % 20));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("Report-{}.PDF", index)), b"x").unwrap();
+    }
+}
+
+fn scan_allocations(files: usize) -> usize {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    populate(&root, files);
+    let scanner = Scanner::with_scope(Config::default(), vec![root]);
+
+    let (items, allocations) = support::count_process_allocations(|| scanner.scan().unwrap());
+    assert!(items.is_empty(), "{:?}", items);
+    allocations
+}
+
+#[test]
+fn classifying_files_that_match_nothing_does_not_allocate() {
+    // The first scan warms anything built lazily, such as regex caches.
+    scan_allocations(10);
+
+    let small = scan_allocations(1_000);
+    let large = scan_allocations(5_000);
+    let per_file = large.saturating_sub(small) as f64 / 4_000.0;
+    println!(
+        "{} allocations for 1000 files, {} for 5000: {:.2} per extra file",
+        small, large, per_file
+    );
+
+    // Each file costs 3 allocations in a scan today, none of them from
+    // classifying it. Lowercasing names to classify them made it 4.
+    assert!(per_file < 3.5, "{:.2} allocations per file", per_file);
+}
@@ -0,0 +1,132 @@
+//! Exports a very large scan and checks the report is streamed: memory held
+//! while writing stays flat no matter how many rows go out, and the file
+//! still parses back row for row.
+
+mod support;
+
+use chrono::{TimeZone, Utc};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use support::CountingAlloc;
+use sweeper_rust::categories::FileCategory;
+use sweeper_rust::report::{ExportProgress, ScanReport};
+use sweeper_rust::scanner::{ScanStats, ScannedItem};
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Far less than a single row per item would need for any of the row counts
+/// below, so passing means rows are not being collected.
+const MEMORY_CEILING: usize = 1024 * 1024;
+
+fn items(rows: usize) -> Vec<ScannedItem> {
+    let modified = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    (0..rows)
+        .map(|index| {
+            let path = PathBuf::from(format!(
+                "/home/me/projects/p{}/target/f{}.o",
+                index / 100,
+                index
+            ));
+            ScannedItem::new(
+                path,
+                4096 + index as u64,
+                FileCategory::DevArtifact,
+                modified,
+            )
+        })
+        .collect()
+}
+
+fn export_within_ceiling(rows: usize) {
+    let items = items(rows);
+    let stats = ScanStats::from_items(&items);
+    let selected: HashSet<PathBuf> = items
+        .iter()
+        .step_by(3)
+        .map(|item| item.path.clone())
+        .collect();
+    let report = ScanReport::capture(&items, &stats, &selected);
+    let dir = tempfile::tempdir().unwrap();
+
+    for name in ["report.json", "report.csv"] {
+        let path = dir.path().join(name);
+        let progress = ExportProgress::default();
+        let (written, usage) = support::measure(|| report.save(&path, &progress).unwrap());
+        assert_eq!(written, Some(rows));
+        assert_eq!(progress.written(), rows);
+        assert!(
+            usage.peak_bytes < MEMORY_CEILING,
+            "{} held {} bytes at once for {} rows",
+            name,
+            usage.peak_bytes,
+            rows
+        );
+
+        let lines = BufReader::new(std::fs::File::open(&path).unwrap()).lines();
+        let parsed = if name.ends_with(".json") {
+            parse_json_rows(lines, &items, &selected)
+        } else {
+            parse_csv_rows(lines, &items, &selected)
+        };
+        assert_eq!(parsed, rows);
+    }
+}
+
+/// Each JSON row sits on a line of its own between the `items` brackets.
+fn parse_json_rows(
+    lines: impl Iterator<Item = std::io::Result<String>>,
+    items: &[ScannedItem],
+    selected: &HashSet<PathBuf>,
+) -> usize {
+    let mut rows = 0;
+    for line in lines {
+        let line = line.unwrap();
+        let Some(row) = line.strip_prefix("    ") else {
+            continue;
+        };
+        let row: serde_json::Value =
+            serde_json::from_str(row.strip_suffix(',').unwrap_or(row)).unwrap();
+        let item = &items[rows];
+        assert_eq!(row["path"], item.path.to_str().unwrap());
+        assert_eq!(row["size"], item.size);
+        assert_eq!(row["selected"], selected.contains(&item.path));
+        rows += 1;
+    }
+    rows
+}
+
+fn parse_csv_rows(
+    lines: impl Iterator<Item = std::io::Result<String>>,
+    items: &[ScannedItem],
+    selected: &HashSet<PathBuf>,
+) -> usize {
+    let mut rows = 0;
+    for line in lines
+        .skip_while(|line| line.as_ref().unwrap().starts_with('#'))
+        .skip(1)
+    {
+        let line = line.unwrap();
+        let fields: Vec<&str> = line.split(',').collect();
+        let item = &items[rows];
+        assert_eq!(fields.len(), 9);
+        assert_eq!(fields[0], item.path.to_str().unwrap());
+        assert_eq!(fields[3], item.size.to_string());
+        assert_eq!(fields[8], selected.contains(&item.path).to_string());
+        rows += 1;
+    }
+    rows
+}
+
+#[test]
+fn large_export_streams_in_flat_memory() {
+    export_within_ceiling(100_000);
+}
+
+/// The full million rows; slow in debug builds, so run with `--ignored`.
+#[test]
+#[ignore]
+fn million_row_export_streams_in_flat_memory() {
+    export_within_ceiling(1_000_000);
+}
@@ -0,0 +1,174 @@
+//! End-to-end guard that a cleanup removes exactly what was selected: scan a
+//! generated home folder, select part of what the scan found, clean it, and
+//! compare every other file byte for byte.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use sweeper_rust::activity::{self, ActivityLog};
+use sweeper_rust::categories::FileCategory;
+use sweeper_rust::cleaner::{CleanJob, CleanTarget};
+use sweeper_rust::config::Config;
+use sweeper_rust::fixture::{self, Fixture, FixtureSpec};
+use sweeper_rust::scanner::{ScannedItem, Scanner};
+
+/// Points the trash and the config folder (which holds the activity log)
+/// into Cargo's scratch folder for tests, so the trash round trip never
+/// touches the real ones. Every test in this binary shares it.
+fn isolate_user_dirs() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("safety");
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::env::set_var("XDG_DATA_HOME", dir.join("data"));
+        std::env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+        dir
+    })
+}
+
+fn home(spec: &FixtureSpec) -> (tempfile::TempDir, Fixture) {
+    let scratch = isolate_user_dirs();
+    let dir = tempfile::tempdir_in(scratch).unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let fixture = fixture::generate(&root, spec).unwrap();
+
+    // A link out of an artifact folder into files that must survive.
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(
+        root.join("documents").join("folder-0"),
+        fixture.artifacts[0].join("linked-docs"),
+    )
+    .unwrap();
+    (dir, fixture)
+}
+
+fn scan(fixture: &Fixture) -> Vec<ScannedItem> {
+    let mut config = Config::default();
+    config.enabled_categories.insert(FileCategory::Duplicate);
+    Scanner::with_scope(config, vec![fixture.root.clone()])
+        .scan()
+        .unwrap()
+}
+
+/// Every artifact folder, every other temp file, every duplicate the scan
+/// offers, plus a file inside an already selected folder to exercise
+/// normalisation.
+fn select(fixture: &Fixture, items: &[ScannedItem]) -> Vec<CleanTarget> {
+    let temp: HashSet<&PathBuf> = fixture.temp_files.iter().step_by(2).collect();
+    let mut targets: Vec<CleanTarget> = items
+        .iter()
+        .filter(|item| match item.category {
+            FileCategory::DevArtifact | FileCategory::Duplicate => true,
+            FileCategory::TempFile => temp.contains(&item.path),
+            _ => false,
+        })
+        .flat_map(CleanTarget::for_item)
+        .collect();
+    targets.push(CleanTarget {
+        path: fixture.artifacts[0].join("pkg-0"),
+        size: 0,
+        check_git: false,
+    });
+    targets
+}
+
+fn selected(path: &Path, targets: &[CleanTarget]) -> bool {
+    targets.iter().any(|target| path.starts_with(&target.path))
+}
+
+fn assert_only_selection_removed(
+    before: Vec<(PathBuf, Vec<u8>)>,
+    fixture: &Fixture,
+    targets: &[CleanTarget],
+) {
+    for target in targets {
+        assert!(
+            std::fs::symlink_metadata(&target.path).is_err(),
+            "{} was selected but still exists",
+            target.path.display()
+        );
+    }
+    let expected: Vec<(PathBuf, Vec<u8>)> = before
+        .into_iter()
+        .filter(|(path, _)| !selected(path, targets))
+        .collect();
+    let after = fixture.snapshot().unwrap();
+    assert_eq!(
+        after.len(),
+        expected.len(),
+        "a file outside the selection went missing"
+    );
+    for ((path, bytes), (expected_path, expected_bytes)) in after.iter().zip(&expected) {
+        assert_eq!(path, expected_path);
+        assert!(bytes == expected_bytes, "{} changed", path.display());
+    }
+}
+
+fn clean_permanently(spec: FixtureSpec) {
+    let (_dir, fixture) = home(&spec);
+    let items = scan(&fixture);
+    let targets = select(&fixture, &items);
+    assert!(targets.len() > fixture.artifacts.len());
+
+    let before = fixture.snapshot().unwrap();
+    let report = CleanJob::new(targets.clone(), false, false).unwrap().run();
+    assert_eq!(report.failures().count(), 0);
+    assert!(report.blocked.is_empty(), "{:?}", report.blocked);
+
+    assert_only_selection_removed(before, &fixture, &targets);
+    // One copy of every duplicate survives even though all were selected.
+    for (original, copy) in &fixture.duplicates {
+        assert!(original.exists() != copy.exists());
+    }
+}
+
+#[test]
+fn permanent_clean_removes_only_the_selection() {
+    clean_permanently(FixtureSpec::small(21));
+}
+
+#[test]
+#[ignore = "writes about 100 MB; run with --ignored"]
+fn permanent_clean_removes_only_the_selection_in_a_large_home() {
+    clean_permanently(FixtureSpec {
+        artifact_bytes: 1536 * 1024,
+        ..FixtureSpec::bench(22)
+    });
+}
+
+#[test]
+fn dry_run_touches_nothing() {
+    let (_dir, fixture) = home(&FixtureSpec::small(23));
+    let items = scan(&fixture);
+    let targets = select(&fixture, &items);
+
+    let before = fixture.snapshot().unwrap();
+    let report = CleanJob::new(targets, false, true).unwrap().run();
+    assert!(!report.deleted.is_empty());
+    assert_eq!(fixture.snapshot().unwrap(), before);
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn trash_then_undo_restores_everything() {
+    let (_dir, fixture) = home(&FixtureSpec::small(24));
+    let items = scan(&fixture);
+    let targets = select(&fixture, &items);
+
+    let before = fixture.snapshot().unwrap();
+    let job = CleanJob::new(targets.clone(), true, false).unwrap();
+    let report = activity::run_logged(job);
+    assert_eq!(report.failures().count(), 0);
+    assert!(report.untrashable.is_empty());
+    assert_only_selection_removed(before.clone(), &fixture, &targets);
+
+    let batch = report.batch.expect("trashed items are logged");
+    let log = ActivityLog::load();
+    let entries = log.batch(batch);
+    assert_eq!(entries.len(), report.trashed.len());
+    for entry in &entries {
+        activity::restore(entry, None).unwrap();
+    }
+    assert_eq!(fixture.snapshot().unwrap(), before);
+}
@@ -0,0 +1,81 @@
+//! A global allocator that counts what each thread allocates, for the tests
+//! that put a ceiling on memory or allocations. Counts are per thread so
+//! tests running side by side in the same binary don't see each other.
+
+// Each test binary includes this module and uses only part of it.
+#![allow(dead_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+/// Allocations made by every thread, for work that fans out to a pool.
+static PROCESS_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// What the current thread allocated while a closure ran.
+#[derive(Debug, Clone, Copy)]
+pub struct Usage {
+    pub allocations: usize,
+    /// Highest number of bytes held at once, above what was live when the
+    /// closure started.
+    pub peak_bytes: usize,
+}
+
+/// How many allocations every thread in the process made while a closure
+/// ran. Anything else running in the binary at the same time is counted
+/// too, so tests using this must not run beside others.
+pub fn count_process_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = PROCESS_ALLOCATIONS.load(Ordering::SeqCst);
+    let value = f();
+    (value, PROCESS_ALLOCATIONS.load(Ordering::SeqCst) - before)
+}
+
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let allocations = ALLOCATIONS.with(Cell::get);
+    let live = LIVE.with(Cell::get);
+    PEAK.with(|peak| peak.set(live));
+    let value = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.with(Cell::get) - allocations,
+        peak_bytes: (PEAK.with(Cell::get) - live).max(0) as usize,
+    };
+    (value, usage)
+}
+
+fn record(bytes: isize, allocation: bool) {
+    // `try_with` because the allocator also runs while thread locals are
+    // being torn down.
+    let _ = LIVE.try_with(|live| {
+        live.set(live.get() + bytes);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(live.get())));
+    });
+    if allocation {
+        PROCESS_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size() as isize, true);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(-(layout.size() as isize), false);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size as isize - layout.size() as isize, true);
+        System.realloc(ptr, layout, new_size)
+    }
+}